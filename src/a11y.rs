@@ -0,0 +1,24 @@
+use eframe::egui::{self, Response, Ui, WidgetInfo, WidgetType};
+
+pub const MIN_SCALE: f32 = 0.75;
+pub const MAX_SCALE: f32 = 3.0;
+
+pub fn icon_button(ui: &mut Ui, icon: &str, label: &str) -> Response {
+    labeled(ui.button(icon), label)
+}
+
+/// Gives an icon-only widget a readable name for screen readers and a matching tooltip.
+pub fn labeled(response: Response, label: &str) -> Response {
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, label));
+    response.on_hover_text(label)
+}
+
+pub fn apply(ctx: &egui::Context, ui_scale: f32, text_scale: f32) {
+    ctx.set_zoom_factor(ui_scale.clamp(MIN_SCALE, MAX_SCALE));
+
+    let mut style = egui::Style::default();
+    for font in style.text_styles.values_mut() {
+        font.size *= text_scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+    ctx.set_style(style);
+}
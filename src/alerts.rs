@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    dump,
+    i18n::{self, tr},
+    scan::{self, ScanOptions},
+};
+
+const TOP_OFFENDERS: usize = 5;
+const SEND_TIMEOUT_SECS: &str = "30";
+pub const DEFAULT_BREACH_TEMPLATE: &str =
+    "{path} on {host} is {size}, {over} over its {budget} budget.\n\nLargest entries:\n{top}";
+pub const DEFAULT_SCAN_TEMPLATE: &str = "Scan of {path} on {host} finished: {size} in {entries} items.\n\nLargest entries:\n{top}";
+
+/// Where budget breaches and finished monitor scans are reported besides the desktop.
+/// Both channels go through `curl`, which also ships with Windows 10 and later.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct AlertSettings {
+    /// Receives a JSON POST; its `text` field suits Slack and Mattermost incoming webhooks.
+    pub webhook_url: String,
+    /// e.g. `smtps://smtp.example.com:465`; empty turns email off.
+    pub smtp_url: String,
+    pub smtp_user: String,
+    pub smtp_password: String,
+    pub mail_from: String,
+    /// Comma-separated recipients.
+    pub mail_to: String,
+    pub on_scan_finished: bool,
+    /// Message templates; empty uses the defaults above.
+    pub breach_template: String,
+    pub scan_template: String,
+}
+
+impl AlertSettings {
+    pub fn enabled(&self) -> bool {
+        !self.webhook_url.trim().is_empty() || self.email_enabled()
+    }
+
+    fn email_enabled(&self) -> bool {
+        !self.smtp_url.trim().is_empty() && !self.recipients().is_empty()
+    }
+
+    fn recipients(&self) -> Vec<&str> {
+        self.mail_to.split(',').map(str::trim).filter(|to| !to.is_empty()).collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlertKind {
+    OverBudget,
+    ScanFinished,
+}
+
+pub struct Alert {
+    pub kind: AlertKind,
+    pub path: PathBuf,
+    pub size: u64,
+    pub entries: u64,
+    pub budget: Option<u64>,
+}
+
+/// The biggest entries directly inside `path`, measured on demand since monitoring only
+/// keeps totals.
+fn largest_entries(path: &Path, options: &ScanOptions) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut sizes: Vec<(PathBuf, u64)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_type = entry.file_type().ok()?;
+            let size = if file_type.is_dir() {
+                scan::measure_dir(&entry.path(), options).size
+            } else {
+                entry.metadata().ok()?.len()
+            };
+            Some((entry.path(), size))
+        })
+        .collect();
+    sizes.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+    sizes.truncate(TOP_OFFENDERS);
+    sizes
+}
+
+fn render(template: &str, alert: &Alert, host: &str, top: &[(PathBuf, u64)]) -> String {
+    let budget = alert.budget.unwrap_or_default();
+    let top = top
+        .iter()
+        .map(|(path, size)| format!("  {}  {}", i18n::format_bytes(*size), path.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    template
+        .replace("{path}", &alert.path.display().to_string())
+        .replace("{host}", host)
+        .replace("{size}", &i18n::format_bytes(alert.size))
+        .replace("{entries}", &i18n::format_count(alert.entries))
+        .replace("{budget}", &i18n::format_bytes(budget))
+        .replace("{over}", &i18n::format_bytes(alert.size.saturating_sub(budget)))
+        .replace("{top}", &top)
+}
+
+/// Runs curl with `input` on its standard input.
+fn curl(args: &[&str], input: &[u8]) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", SEND_TIMEOUT_SECS])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Error running curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| format!("Error running curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Error running curl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Quotes a value for a curl config file.
+fn config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Makes `text` safe for a mail header: control characters, line breaks among them, are
+/// dropped, and anything beyond ASCII is sent as RFC 2047 encoded words.
+fn header_text(text: &str) -> String {
+    let text: String = text.chars().filter(|c| !c.is_control()).collect();
+    if text.is_ascii() {
+        return text;
+    }
+    // 45 bytes encode to 60 characters, keeping each encoded word under the 75 allowed.
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = (start + 45).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        words.push(format!("=?UTF-8?B?{}?=", base64(&text.as_bytes()[start..end])));
+        start = end;
+    }
+    words.join("\r\n ")
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn send_email(settings: &AlertSettings, subject: &str, body: &str) -> Result<(), String> {
+    let recipients = settings.recipients();
+    let from = if settings.mail_from.trim().is_empty() { recipients[0] } else { settings.mail_from.trim() };
+    let message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+        from,
+        recipients.join(", "),
+        header_text(subject),
+        body.replace('\n', "\r\n")
+    );
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |age| age.as_nanos());
+    let message_path = std::env::temp_dir().join(format!("disk-analyzer-alert-{}.eml", stamp));
+    fs::write(&message_path, message).map_err(|e| format!("Error writing {}: {}", message_path.display(), e))?;
+
+    let mut config = format!(
+        "url = {}\nmail-from = {}\nupload-file = {}\n",
+        config_value(settings.smtp_url.trim()),
+        config_value(from),
+        config_value(&message_path.to_string_lossy())
+    );
+    for to in &recipients {
+        config.push_str(&format!("mail-rcpt = {}\n", config_value(to)));
+    }
+    if !settings.smtp_user.is_empty() {
+        let user = format!("{}:{}", settings.smtp_user, settings.smtp_password);
+        config.push_str(&format!("user = {}\n", config_value(&user)));
+    }
+    // Read from standard input so the password doesn't show up in the process list.
+    let result = curl(&["--config", "-"], config.as_bytes());
+    let _ = fs::remove_file(&message_path);
+    result.map_err(|e| format!("Error sending alert email: {}", e))
+}
+
+/// Reports the alert to every configured channel; the message lists the largest entries.
+pub fn send(settings: &AlertSettings, alert: &Alert, options: &ScanOptions) -> Result<(), String> {
+    if !settings.enabled() {
+        return Ok(());
+    }
+    let host = dump::host_name();
+    let top = largest_entries(&alert.path, options);
+    let (event, template, default, subject) = match alert.kind {
+        AlertKind::OverBudget => {
+            ("over_budget", &settings.breach_template, DEFAULT_BREACH_TEMPLATE, tr("Directory over budget"))
+        }
+        AlertKind::ScanFinished => ("scan_finished", &settings.scan_template, DEFAULT_SCAN_TEMPLATE, tr("Scan finished")),
+    };
+    let template = if template.trim().is_empty() { default } else { template.as_str() };
+    let message = render(template, alert, &host, &top);
+    let subject = format!("{}: {}", subject, alert.path.display());
+
+    let mut errors = Vec::new();
+    if !settings.webhook_url.trim().is_empty() {
+        let payload = json!({
+            "event": event,
+            "host": host,
+            "path": alert.path,
+            "size": alert.size,
+            "entries": alert.entries,
+            "budget": alert.budget,
+            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs()),
+            "text": message,
+            "top_offenders": top.iter().map(|(path, size)| json!({ "path": path, "size": size })).collect::<Vec<_>>(),
+        });
+        let args = ["--header", "Content-Type: application/json", "--data-binary", "@-", settings.webhook_url.trim()];
+        if let Err(e) = curl(&args, payload.to_string().as_bytes()) {
+            errors.push(format!("Error calling webhook: {}", e));
+        }
+    }
+    if settings.email_enabled() {
+        if let Err(e) = send_email(settings, &subject, &message) {
+            errors.push(e);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
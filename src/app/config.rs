@@ -0,0 +1,235 @@
+use eframe::egui::{self, Color32, RichText};
+
+use super::DiskAnalyzer;
+use crate::{
+    a11y, columns,
+    config::Preferences,
+    i18n::{self, tr, trf},
+    integration, profiles,
+};
+
+/// Settings that outlive a session: preferences, scan profiles and list columns.
+pub struct ConfigState {
+    pub preferences: Preferences,
+    pub profiles: profiles::ProfileStore,
+    pub active_profile: String,
+    pub new_profile_name: String,
+    pub columns: columns::ColumnLayouts,
+    pub show_settings: bool,
+    pub settings_message: Option<Result<String, String>>,
+    pub read_only_forced: bool,
+}
+
+impl Default for ConfigState {
+    fn default() -> Self {
+        Self {
+            preferences: Preferences::load(),
+            profiles: profiles::ProfileStore::load(),
+            active_profile: profiles::Profile::default().name,
+            new_profile_name: String::new(),
+            columns: columns::ColumnLayouts::load(),
+            show_settings: false,
+            settings_message: None,
+            read_only_forced: false,
+        }
+    }
+}
+
+impl DiskAnalyzer {
+    pub(super) fn read_only(&self) -> bool {
+        self.config.read_only_forced || self.config.preferences.read_only || self.scan.imported.is_some()
+    }
+
+    fn current_profile(&self, name: String) -> profiles::Profile {
+        profiles::Profile {
+            name,
+            scan: self.scan.options.clone(),
+            show_hidden: self.scan.show_hidden,
+            show_all: self.scan.show_all,
+            min_size: self.scan.min_size_filter,
+            directory_filter: self.scan.directory_filter,
+            sort_by_size: self.view.sort_by_size,
+            highlight_names: self.view.highlight_names.clone(),
+            group_by: self.view.group_by,
+            show_owners: self.view.show_owners,
+            age_heatmap: self.view.age_heatmap,
+        }
+    }
+
+    pub(super) fn apply_profile(&mut self, profile: profiles::Profile) {
+        self.config.active_profile = profile.name;
+        self.scan.options = profile.scan;
+        self.scan.show_hidden = profile.show_hidden;
+        self.scan.show_all = profile.show_all;
+        self.scan.min_size_filter = profile.min_size;
+        self.scan.directory_filter = profile.directory_filter;
+        self.view.sort_by_size = profile.sort_by_size;
+        self.view.highlight_names = profile.highlight_names;
+        self.view.group_by = profile.group_by;
+        self.view.collapsed_groups.clear();
+        self.view.show_owners = profile.show_owners;
+        self.view.age_heatmap = profile.age_heatmap;
+        self.rescan();
+    }
+
+    pub(super) fn render_settings(&mut self, ctx: &egui::Context) {
+        let mut open = self.config.show_settings;
+        egui::Window::new(tr("Settings"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let preferences = &mut self.config.preferences;
+                let previous_preferences = preferences.clone();
+                ui.horizontal(|ui| {
+                    ui.label(tr("Language"));
+                    egui::ComboBox::from_id_source("locale")
+                        .selected_text(preferences.locale.name())
+                        .show_ui(ui, |ui| {
+                            for locale in i18n::Locale::ALL {
+                                ui.selectable_value(&mut preferences.locale, locale, locale.name());
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Units"));
+                    ui.radio_value(&mut preferences.decimal_units, false, tr("Binary (KiB, MiB, GiB)"));
+                    ui.radio_value(&mut preferences.decimal_units, true, tr("Decimal (kB, MB, GB)"));
+                });
+                ui.checkbox(&mut preferences.show_exact_bytes, tr("Show exact byte counts"));
+                ui.checkbox(&mut preferences.show_diagnostics, tr("Show scan diagnostics"));
+                ui.add_enabled_ui(!self.config.read_only_forced, |ui| {
+                    ui.checkbox(&mut preferences.read_only, tr("Read-only mode"))
+                        .on_hover_text(tr("Disables deleting, wiping, pruning and cleaning"))
+                        .on_disabled_hover_text(tr("Turned on by --read-only"));
+                });
+                ui.checkbox(&mut preferences.confirm_trash, tr("Ask before moving to the trash"));
+                ui.horizontal(|ui| {
+                    ui.checkbox(
+                        &mut preferences.typed_confirmation,
+                        tr("Type the folder name to delete folders over"),
+                    );
+                    let mut gib = preferences.typed_confirmation_size / (1024 * 1024 * 1024);
+                    let drag = egui::DragValue::new(&mut gib).suffix(" GiB").clamp_range(0..=u64::MAX >> 30);
+                    if ui.add_enabled(preferences.typed_confirmation, drag).changed() {
+                        preferences.typed_confirmation_size = gib * 1024 * 1024 * 1024;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(
+                        &mut preferences.notify_long_scans,
+                        tr("Notify in the background after scans longer than"),
+                    );
+                    let drag =
+                        egui::DragValue::new(&mut preferences.long_scan_secs).suffix(" s").clamp_range(0..=86_400);
+                    ui.add_enabled(preferences.notify_long_scans, drag);
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut preferences.sniff_content, tr("Detect file types from contents for files over"))
+                        .on_hover_text(tr("Reads the first bytes of large files so misnamed and extensionless files land in the right category"));
+                    let mut mib = preferences.sniff_min_size / (1024 * 1024);
+                    let drag = egui::DragValue::new(&mut mib).suffix(" MiB").clamp_range(0..=u64::MAX >> 20);
+                    if ui.add_enabled(preferences.sniff_content, drag).changed() {
+                        preferences.sniff_min_size = mib * 1024 * 1024;
+                    }
+                });
+                ui.collapsing(tr("Scan throttling"), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Max worker threads"));
+                        ui.add(egui::DragValue::new(&mut preferences.max_workers).clamp_range(0..=256));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Max file operations per second"));
+                        ui.add(
+                            egui::DragValue::new(&mut preferences.max_operations_per_second)
+                                .speed(100.0)
+                                .clamp_range(0..=1_000_000),
+                        );
+                    });
+                    ui.label(RichText::new(tr("0 means no limit")).small().color(Color32::GRAY));
+                    ui.checkbox(&mut preferences.low_priority_io, tr("Low-priority background scans"))
+                        .on_hover_text(tr("Scans use idle I/O and the lowest CPU priority so other programs stay responsive"));
+                });
+                ui.add(
+                    egui::Slider::new(&mut preferences.ui_scale, a11y::MIN_SCALE..=a11y::MAX_SCALE)
+                        .text(tr("UI scale")),
+                );
+                ui.add(
+                    egui::Slider::new(&mut preferences.text_scale, a11y::MIN_SCALE..=a11y::MAX_SCALE)
+                        .text(tr("Text size")),
+                );
+                if *preferences != previous_preferences {
+                    preferences.apply();
+                    if (preferences.sniff_content, preferences.sniff_min_size)
+                        != (previous_preferences.sniff_content, previous_preferences.sniff_min_size)
+                    {
+                        self.summary.invalidate();
+                    }
+                    a11y::apply(ctx, preferences.ui_scale, preferences.text_scale);
+                    self.config.settings_message = preferences.save().err().map(Err);
+                }
+
+                ui.separator();
+                ui.heading(tr("Context menu"));
+                ui.label(trf(
+                    "Adds \"{}\" to the file manager's folder menu.",
+                    &[&integration::MENU_LABEL],
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Install")).clicked() {
+                        self.config.settings_message = Some(integration::install().map(|paths| {
+                            trf("Installed {} entries", &[&paths.len()])
+                        }));
+                    }
+                    if ui.button(tr("Remove")).clicked() {
+                        self.config.settings_message = Some(integration::uninstall().map(|paths| {
+                            trf("Removed {} entries", &[&paths.len()])
+                        }));
+                    }
+                });
+
+                ui.separator();
+                ui.heading(tr("Scan profiles"));
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.config.new_profile_name);
+                    let name = self.config.new_profile_name.trim().to_string();
+                    let is_builtin = profiles::builtin().iter().any(|p| p.name == name);
+                    if ui
+                        .add_enabled(!name.is_empty() && !is_builtin, egui::Button::new(tr("Save current settings")))
+                        .clicked()
+                    {
+                        let profile = self.current_profile(name.clone());
+                        self.config.profiles.upsert(profile);
+                        self.config.active_profile = name;
+                        self.config.settings_message =
+                            Some(self.config.profiles.save().map(|_| tr("Profile saved").to_string()));
+                    }
+                });
+                let mut removed = None;
+                for profile in &self.config.profiles.user {
+                    ui.horizontal(|ui| {
+                        ui.label(&profile.name);
+                        if a11y::labeled(ui.small_button("🗑"), tr("Delete profile")).clicked() {
+                            removed = Some(profile.name.clone());
+                        }
+                    });
+                }
+                if let Some(name) = removed {
+                    self.config.profiles.remove(&name);
+                    self.config.settings_message =
+                        Some(self.config.profiles.save().map(|_| tr("Profile removed").to_string()));
+                }
+
+                match &self.config.settings_message {
+                    Some(Ok(message)) => {
+                        ui.label(RichText::new(message).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+            });
+        self.config.show_settings = open;
+    }
+}
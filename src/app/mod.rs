@@ -0,0 +1,487 @@
+mod config;
+mod ops;
+pub mod scan;
+mod ui;
+
+use eframe::egui;
+use std::{fs, time::{Duration, Instant}};
+
+use crate::{
+    a11y, archive, artifacts, bookmarks, browsers, caches, checkpoint, cli, cloud, cloud_sync, cold, compare,
+    crashes, dashboard, deletion, diskimage, docker, games, git, histogram, i18n::tr, icicle, icons, layout,
+    linux_apps, logs, mail, media, openfiles, operations, orphans, photos, plugins, quick_open, quotas, reclaim,
+    saved_reports, script, security_report, session, similar, snapshots, storage, summary, tags, telemetry, toasts,
+    treemap, users_report, windows_cleanup,
+};
+
+/// The desktop app. Scanning, presentation, file operations and settings each keep their state
+/// in their own module; the report and tool windows are fields of their own.
+pub struct DiskAnalyzer {
+    scan: scan::ScanState,
+    view: ui::ViewState,
+    ops: ops::OpsState,
+    config: config::ConfigState,
+    layout: layout::Layout,
+    tags: tags::TagStore,
+    bookmarks: bookmarks::BookmarkStore,
+    pending_session: Option<session::Session>,
+    quick_open: quick_open::QuickOpen,
+    icons: icons::Icons,
+    icicle: icicle::Icicle,
+    treemap: treemap::Treemap,
+    diagnostics: telemetry::Diagnostics,
+    toasts: toasts::Toasts,
+    compare: compare::CompareState,
+    orphans: orphans::OrphanState,
+    users_report: users_report::UsersReportState,
+    docker: docker::DockerState,
+    caches: caches::CachesState,
+    dashboard: dashboard::DashboardState,
+    windows_cleanup: windows_cleanup::WindowsCleanupState,
+    linux_apps: linux_apps::LinuxAppsState,
+    storage: storage::StorageState,
+    low_value: media::LowValueState,
+    photos: photos::PhotosState,
+    git_repos: git::GitReposState,
+    artifacts: artifacts::ArtifactsState,
+    logs: logs::LogsState,
+    crashes: crashes::CrashesState,
+    cold: cold::ColdState,
+    archive: archive::ArchiveState,
+    cloud: cloud::CloudState,
+    cloud_sync: cloud_sync::CloudSyncState,
+    plugins: plugins::PluginsState,
+    console: script::ConsoleState,
+    browsers: browsers::BrowsersState,
+    mail: mail::MailState,
+    games: games::GamesState,
+    snapshots: snapshots::SnapshotState,
+    quotas: quotas::QuotaState,
+    saved_reports: saved_reports::SavedReportsState,
+    security_report: security_report::SecurityReportState,
+    histogram: histogram::HistogramState,
+    summary: summary::SummaryState,
+    similar: similar::SimilarState,
+    disk_image: diskimage::DiskImageState,
+    open_files: openfiles::OpenFilesState,
+}
+
+impl Default for DiskAnalyzer {
+    fn default() -> Self {
+        Self {
+            scan: scan::ScanState::default(),
+            view: ui::ViewState::default(),
+            ops: ops::OpsState::default(),
+            config: config::ConfigState::default(),
+            layout: layout::Layout::load(),
+            tags: tags::TagStore::load(),
+            bookmarks: bookmarks::BookmarkStore::load(),
+            pending_session: None,
+            quick_open: quick_open::QuickOpen::default(),
+            icons: icons::Icons::default(),
+            icicle: icicle::Icicle::default(),
+            treemap: treemap::Treemap::default(),
+            diagnostics: telemetry::Diagnostics::default(),
+            toasts: toasts::Toasts::default(),
+            compare: compare::CompareState::default(),
+            orphans: orphans::OrphanState::default(),
+            users_report: users_report::UsersReportState::default(),
+            docker: docker::DockerState::default(),
+            caches: caches::CachesState::default(),
+            dashboard: dashboard::DashboardState::default(),
+            windows_cleanup: windows_cleanup::WindowsCleanupState::default(),
+            linux_apps: linux_apps::LinuxAppsState::default(),
+            storage: storage::StorageState::default(),
+            low_value: media::LowValueState::default(),
+            photos: photos::PhotosState::default(),
+            git_repos: git::GitReposState::default(),
+            artifacts: artifacts::ArtifactsState::default(),
+            logs: logs::LogsState::default(),
+            crashes: crashes::CrashesState::default(),
+            cold: cold::ColdState::default(),
+            archive: archive::ArchiveState::default(),
+            cloud: cloud::CloudState::default(),
+            cloud_sync: cloud_sync::CloudSyncState::default(),
+            plugins: plugins::PluginsState::load(),
+            console: script::ConsoleState::default(),
+            browsers: browsers::BrowsersState::default(),
+            mail: mail::MailState::default(),
+            games: games::GamesState::default(),
+            snapshots: snapshots::SnapshotState::default(),
+            quotas: quotas::QuotaState::default(),
+            saved_reports: saved_reports::SavedReportsState::default(),
+            security_report: security_report::SecurityReportState::default(),
+            histogram: histogram::HistogramState::default(),
+            summary: summary::SummaryState::default(),
+            similar: similar::SimilarState::default(),
+            disk_image: diskimage::DiskImageState::default(),
+            open_files: openfiles::OpenFilesState::default(),
+        }
+    }
+}
+
+impl DiskAnalyzer {
+    pub fn new(cc: &eframe::CreationContext, options: cli::LaunchOptions) -> Self {
+        let app = Self::with_launch_options(options);
+        let preferences = &app.config.preferences;
+        a11y::apply(&cc.egui_ctx, preferences.ui_scale, preferences.text_scale);
+        app
+    }
+
+    fn with_launch_options(options: cli::LaunchOptions) -> Self {
+        let mut app = Self::default();
+        app.config.preferences.apply();
+        app.view.show_tree = app.layout.is_open(layout::Panel::Tree);
+        app.view.show_details = app.layout.is_open(layout::Panel::Details);
+        if let Some(min_size) = options.min_size {
+            app.scan.min_size_filter = min_size;
+        }
+        app.scan.show_all = options.show_all;
+        app.scan.show_hidden = options.show_hidden;
+        app.scan.options.respect_ignores = options.respect_ignores;
+        app.scan.options.network_mode = options.network;
+        app.scan.options.async_io = options.async_io;
+        app.scan.options.summarize_below = options.summarize_below.unwrap_or_default();
+        app.config.read_only_forced = options.read_only;
+        app.view.sort_by_size = !options.sort_by_name;
+        if let Some(path) = options.path {
+            let path = fs::canonicalize(&path).unwrap_or(path);
+            app.open_root(path);
+        } else {
+            app.pending_session = session::Session::load();
+            app.dashboard.open = true;
+        }
+        app
+    }
+
+    fn resume_session(&mut self, mut session: session::Session) {
+        self.scan.cache = session.take_cache();
+        self.scan.root_path = session.root_path;
+        self.scan.current_path = session.current_path.or_else(|| self.scan.root_path.clone());
+        self.view.restore_scroll = Some(session.scroll_offset);
+        self.view.show_tree = session.show_tree;
+        self.view.expanded = session.expanded.into_iter().collect();
+        self.scan_current_directory();
+        self.scan.rebuild_index();
+    }
+
+    fn save_session(&self) -> Result<(), String> {
+        // An imported scan can't be resumed by rescanning its root, so the last real session stays.
+        if self.scan.imported.is_some() {
+            return Ok(());
+        }
+        let mut session = session::Session {
+            root_path: self.scan.root_path.clone(),
+            current_path: self.scan.current_path.clone(),
+            scroll_offset: self.view.scroll_offset,
+            show_tree: self.view.show_tree,
+            expanded: self.view.expanded.iter().cloned().collect(),
+            listings: Vec::new(),
+        };
+        if session.root_path.is_some() {
+            session.capture_cache(&self.scan.cache);
+        }
+        session.save()
+    }
+
+    fn render_session_prompt(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.pending_session else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(tr("Resume Last Session"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if let Some(path) = session.current_path.as_ref().or(session.root_path.as_ref()) {
+                    ui.label(path.display().to_string());
+                }
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Resume")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Start Fresh")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => {
+                if let Some(session) = self.pending_session.take() {
+                    self.resume_session(session);
+                }
+            }
+            Some(false) => {
+                self.pending_session = None;
+                checkpoint::discard();
+            }
+            None => {}
+        }
+    }
+
+    /// The report and tool windows that are open.
+    fn show_windows(&mut self, ctx: &egui::Context) {
+        let read_only = self.read_only();
+
+        if self.compare.open {
+            self.compare.show(ctx);
+        }
+
+        if self.orphans.open {
+            self.orphans.show(ctx, self.scan.root_path.as_deref());
+        }
+
+        if self.users_report.open {
+            self.users_report.show(ctx);
+        }
+
+        if self.docker.open {
+            self.docker.show(ctx, read_only);
+        }
+
+        if self.dashboard.open {
+            match self.dashboard.show(ctx) {
+                Some(dashboard::DashboardAction::Open(path)) => {
+                    self.dashboard.open = false;
+                    self.open_root(path);
+                }
+                Some(dashboard::DashboardAction::ShowCaches) => self.caches.open = true,
+                None => {}
+            }
+        }
+
+        if self.caches.open {
+            self.caches.show(ctx, read_only);
+        }
+
+        if self.browsers.open {
+            self.browsers.show(ctx, read_only);
+        }
+
+        if self.mail.open {
+            if let Some(path) = self.mail.show(ctx) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.games.open {
+            if let Some(path) = self.games.show(ctx) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.windows_cleanup.open {
+            self.windows_cleanup.show(ctx, read_only);
+        }
+
+        if self.linux_apps.open {
+            let delete = self.linux_apps.show(ctx, self.scan.index.as_ref(), read_only);
+            if !delete.is_empty() {
+                self.ops.typed_confirmation.clear();
+                self.ops.delete_confirmation = delete
+                    .into_iter()
+                    .map(|(path, is_dir, size)| {
+                        scan::FileInfo::selected(path, reclaim::Selected { is_dir, size, logical: size })
+                    })
+                    .collect();
+            }
+        }
+
+        if self.storage.open {
+            self.storage.show(ctx);
+        }
+
+        if self.snapshots.open {
+            self.snapshots.show(ctx, self.scan.root_path.as_deref(), read_only);
+        }
+
+        let current = self.scan.current_path.as_deref();
+        if self.security_report.open {
+            self.security_report.show(ctx, current);
+        }
+        if self.saved_reports.open {
+            self.saved_reports.show(ctx, current);
+        }
+        if self.quotas.open {
+            self.quotas.show(ctx, current);
+        }
+
+        if self.histogram.open {
+            self.histogram.show(ctx, self.scan.index.as_ref());
+        }
+
+        if self.disk_image.open {
+            self.disk_image.show(ctx);
+        }
+
+        if self.ops.deletion_failures.open {
+            match self.ops.deletion_failures.show(ctx) {
+                Some(deletion::Outcome::ShowProcesses(path)) => self.open_files.inspect(path),
+                Some(deletion::Outcome::Finished(finished)) => self.finish_deletion(finished),
+                None => {}
+            }
+        }
+
+        if self.open_files.open {
+            self.open_files.show(ctx);
+        }
+
+        if self.similar.open {
+            if let Some(path) = self.similar.show(ctx, self.scan.index.as_ref()) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.low_value.open {
+            if let Some(path) = self.low_value.show(ctx, self.scan.index.as_ref()) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.photos.open {
+            if let Some(path) = self.photos.show(ctx, self.scan.index.as_ref()) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.git_repos.open {
+            if let Some(path) = self.git_repos.show(ctx, self.scan.index.as_ref()) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.artifacts.open {
+            for (path, size) in self.artifacts.show(ctx, self.scan.index.as_ref(), read_only) {
+                self.queue(operations::Action::Delete, path, true, size);
+            }
+        }
+
+        if self.logs.open {
+            let compressed = self.logs.show(ctx, self.scan.index.as_ref(), read_only);
+            if !compressed.is_empty() {
+                self.ops.audit_log.invalidate();
+                for (path, saved) in compressed {
+                    self.ops.freed.record(path.clone(), saved, false);
+                    self.forget_item(&path);
+                }
+                self.scan_current_directory();
+            }
+        }
+
+        if self.crashes.open {
+            for (path, size) in self.crashes.show(ctx, self.scan.index.as_ref(), read_only) {
+                let is_dir = path.is_dir();
+                self.queue(operations::Action::Delete, path, is_dir, size);
+            }
+        }
+
+        if self.cold.open {
+            if let Some(path) = self.cold.show(ctx, self.scan.index.as_ref(), &mut self.archive) {
+                self.navigate_to(path);
+            }
+        }
+
+        if self.archive.open {
+            for (action, path, size) in self.archive.show(ctx, read_only) {
+                let is_dir = path.is_dir();
+                self.queue(action, path, is_dir, size);
+            }
+        }
+
+        if self.plugins.show(ctx) {
+            self.scan_current_directory();
+        }
+
+        if self.cloud_sync.open {
+            self.cloud_sync.show(ctx, read_only);
+        }
+
+        if self.cloud.open {
+            let current = self.scan.current_path.as_deref();
+            if let Some((header, index)) = self.cloud.show(ctx, self.scan.index.as_ref(), current) {
+                self.open_imported(header, index);
+            }
+        }
+
+        if self.quick_open.open {
+            let root = self.scan.root_path.as_deref();
+            if let Some(path) = self.quick_open.show(ctx, self.scan.index.as_ref(), root) {
+                self.navigate_to(path);
+            }
+        }
+
+        let audit_errors = [
+            self.docker.audit_error.take(),
+            self.caches.audit_error.take(),
+            self.browsers.audit_error.take(),
+            self.windows_cleanup.audit_error.take(),
+            self.linux_apps.audit_error.take(),
+            self.snapshots.audit_error.take(),
+            self.ops.trash.audit_error.take(),
+        ];
+        for error in audit_errors.into_iter().flatten() {
+            self.report_error(error);
+        }
+    }
+}
+
+impl eframe::App for DiskAnalyzer {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.render_session_prompt(ctx);
+        self.poll_scan(ctx);
+        for error in self.quotas.poll(ctx) {
+            self.report_error(error);
+        }
+        self.saved_reports.poll(ctx, &self.scan.options, self.scan.show_hidden);
+        self.poll_operations(ctx);
+        if ctx.input_mut(|i| i.consume_shortcut(&quick_open::SHORTCUT)) {
+            self.quick_open.toggle();
+        }
+        self.handle_history_shortcuts(ctx);
+        let view_before = self.view_snapshot();
+        self.handle_dropped_files(ctx);
+
+        self.render_top_panel(ctx);
+        self.render_status_bar(ctx);
+        self.render_dock(ctx);
+        self.render_confirmations(ctx);
+
+        if self.view.show_freed_summary {
+            self.render_freed_summary(ctx);
+        }
+
+        self.show_windows(ctx);
+
+        self.render_tag_editor(ctx);
+
+        if self.config.show_settings {
+            self.render_settings(ctx);
+        }
+
+        if self.config.preferences.show_diagnostics {
+            self.diagnostics.show(ctx);
+        }
+        self.toasts.show(ctx);
+
+        if !std::mem::take(&mut self.view.history_applied) {
+            let view_after = self.view_snapshot();
+            let coalescable = view_before.only_search_differs(&view_after);
+            self.view.history.observe(view_before, &view_after, coalescable);
+        }
+
+        if self.scan.auto_refresh && self.scan.last_refresh.elapsed() > Duration::from_secs(30) {
+            self.scan_current_directory();
+            self.scan.last_refresh = Instant::now();
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.save_session() {
+            eprintln!("{}", e);
+        }
+        if let Err(e) = self.layout.save() {
+            eprintln!("{}", e);
+        }
+    }
+}
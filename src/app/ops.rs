@@ -0,0 +1,580 @@
+use eframe::egui::{self, Color32, RichText};
+use std::path::{Path, PathBuf};
+
+use super::{scan::FileInfo, DiskAnalyzer};
+use crate::{
+    audit, deletion,
+    error::Error,
+    freed,
+    i18n::{self, tr, trf},
+    layout, operations, paths, script, snapshots, trash, wipe,
+};
+
+/// Everything that changes files on disk, and what it has freed so far.
+pub struct OpsState {
+    pub operations: operations::OperationQueue,
+    pub freed: freed::FreedTracker,
+    pub deletion_failures: deletion::DeletionFailuresState,
+    pub audit_log: audit::AuditLog,
+    pub trash: trash::TrashState,
+    pub secure_delete: bool,
+    /// Entries waiting for the user to confirm their deletion: one row, or the selection.
+    pub delete_confirmation: Vec<FileInfo>,
+    pub trash_confirmation: Option<FileInfo>,
+    /// A row dropped on a folder, and that folder.
+    pub move_confirmation: Option<(FileInfo, PathBuf)>,
+    /// Operations a console script asked for, until the user confirms or drops them.
+    pub script_confirmation: Vec<script::Queued>,
+    /// Tasks held back because their name is taken where they're going, oldest first.
+    pub conflicts: Vec<Conflict>,
+    pub apply_to_all: bool,
+    pub typed_confirmation: String,
+}
+
+/// A task waiting for the user to say what happens to the item already at `target`.
+pub struct Conflict {
+    action: operations::Action,
+    path: PathBuf,
+    target: PathBuf,
+    is_dir: bool,
+    size: u64,
+}
+
+impl Default for OpsState {
+    fn default() -> Self {
+        Self {
+            operations: operations::OperationQueue::default(),
+            freed: freed::FreedTracker::load(),
+            deletion_failures: deletion::DeletionFailuresState::default(),
+            audit_log: audit::AuditLog::default(),
+            trash: trash::TrashState::default(),
+            secure_delete: false,
+            delete_confirmation: Vec::new(),
+            trash_confirmation: None,
+            move_confirmation: None,
+            script_confirmation: Vec::new(),
+            conflicts: Vec::new(),
+            apply_to_all: false,
+            typed_confirmation: String::new(),
+        }
+    }
+}
+
+impl DiskAnalyzer {
+    fn delete_item(&mut self, item: &FileInfo) -> Result<(), Error> {
+        if self.read_only() {
+            return Err(Error::ReadOnly);
+        }
+        let path = &item.path;
+        let failures = deletion::delete_tree(path, &operations::Progress::default())?;
+        if !failures.is_empty() {
+            // Auditing waits until the user has decided what to do with the leftovers.
+            self.ops.deletion_failures.add(path.clone(), item.is_dir, item.size, failures);
+            self.scan_current_directory();
+            return Ok(());
+        }
+        if let Err(error) = audit::record_path(audit::DELETE, path, item.size, None) {
+            self.report_error(error);
+        }
+        self.ops.audit_log.invalidate();
+
+        self.ops.freed.record(path.clone(), item.size, item.is_dir);
+        self.forget_item(path);
+        Ok(())
+    }
+
+    pub(super) fn finish_deletion(&mut self, finished: deletion::Finished) {
+        let error = (!finished.removed).then(|| format!("{} items were skipped", finished.skipped));
+        if let Err(error) = audit::record_path(audit::DELETE, &finished.path, finished.size, error.as_ref()) {
+            self.report_error(error);
+        }
+        self.ops.audit_log.invalidate();
+        if finished.removed {
+            self.ops.freed.record(finished.path.clone(), finished.size, finished.is_dir);
+            self.forget_item(&finished.path);
+        } else {
+            self.scan_current_directory();
+        }
+    }
+
+    pub(super) fn trash_item(&mut self, item: &FileInfo) -> Result<(), Error> {
+        if self.read_only() {
+            return Err(Error::ReadOnly);
+        }
+        let result = trash::move_to_trash(&item.path);
+        let error = result.as_ref().err().map(ToString::to_string);
+        if let Err(error) = audit::record_path(audit::TRASH, &item.path, item.size, error.as_ref()) {
+            self.report_error(error);
+        }
+        self.ops.audit_log.invalidate();
+        self.ops.trash.invalidate();
+        result?;
+
+        self.forget_item(&item.path);
+        Ok(())
+    }
+
+    pub(super) fn forget_item(&mut self, path: &Path) {
+        if self.scan.forget(path) {
+            self.index_changed();
+        }
+        self.close_details(path);
+    }
+
+    /// Updates the list and the scan for an entry moved to `target` without listing again.
+    fn relocate_item(&mut self, path: &Path, target: &Path) {
+        if self.scan.relocate(path, target) {
+            self.index_changed();
+        }
+        self.close_details(path);
+    }
+
+    /// Updates the list and the scan for a copy of `path` made at `target`.
+    fn duplicate_item(&mut self, path: &Path, target: &Path, size: u64) {
+        if self.scan.duplicate(path, target, size) {
+            self.index_changed();
+        }
+        self.update_search();
+    }
+
+    /// Closes what the details panel shows about an entry that is gone from `path`.
+    fn close_details(&mut self, path: &Path) {
+        if self.view.checksums.as_ref().is_some_and(|checksums| checksums.path == path) {
+            self.view.checksums = None;
+        }
+        if self.view.media_details.as_ref().is_some_and(|media| media.path == path) {
+            self.view.media_details = None;
+        }
+        self.update_search();
+    }
+
+    /// Adds a task to the operations queue unless the path already has one, and shows the queue.
+    /// Tasks whose name is taken where they're going wait for the conflict window instead.
+    pub(super) fn queue(&mut self, action: operations::Action, path: PathBuf, is_dir: bool, size: u64) {
+        if self.ops.operations.contains(&path) || self.ops.conflicts.iter().any(|conflict| conflict.path == path) {
+            return;
+        }
+        if let Some(target) = action.target(&path).filter(|target| target.exists()) {
+            self.ops.conflicts.push(Conflict { action, path, target, is_dir, size });
+            return;
+        }
+        self.ops.operations.push(action, path, is_dir, size);
+        self.layout.set_open(layout::Panel::Operations, true);
+    }
+
+    pub(super) fn queue_operation(&mut self, action: operations::Action, item: &FileInfo) {
+        if self.read_only() {
+            return;
+        }
+        self.queue(action, item.path.clone(), item.is_dir, item.size);
+    }
+
+    /// Asks to confirm deleting every selected entry; they leave the selection once queued.
+    pub(super) fn confirm_selection_deletion(&mut self) {
+        self.ops.typed_confirmation.clear();
+        self.ops.delete_confirmation = self
+            .view
+            .selection
+            .items()
+            .map(|(path, selected)| FileInfo::selected(path.clone(), selected))
+            .collect();
+    }
+
+    /// Queues the action for every selected entry and empties the selection.
+    pub(super) fn queue_selection(&mut self, action: operations::Action) {
+        for (path, selected) in self.view.selection.take() {
+            self.queue(action.clone(), path, selected.is_dir, selected.size);
+        }
+    }
+
+    pub(super) fn poll_operations(&mut self, ctx: &egui::Context) {
+        for finished in self.ops.operations.poll(ctx) {
+            self.finish_operation(finished);
+        }
+    }
+
+    fn finish_operation(&mut self, finished: operations::Finished) {
+        let deletes = matches!(
+            finished.action,
+            operations::Action::Delete | operations::Action::Archive { delete_originals: true, .. }
+        );
+        if deletes && !finished.failures.is_empty() {
+            self.ops.deletion_failures.add(finished.path, finished.is_dir, finished.size, finished.failures);
+            self.scan_current_directory();
+            return;
+        }
+        let method = match &finished.action {
+            operations::Action::Delete => Some(audit::DELETE),
+            operations::Action::SecureDelete => Some(audit::SECURE_DELETE),
+            operations::Action::Move(_) | operations::Action::MoveAs { .. } | operations::Action::Restore { .. } => {
+                Some(audit::MOVE)
+            }
+            operations::Action::Copy(_)
+            | operations::Action::CopyAs { .. }
+            | operations::Action::Compress
+            | operations::Action::CompressInPlace => None,
+            operations::Action::Archive { delete_originals, .. } => delete_originals.then_some(audit::ARCHIVE),
+        };
+        if let Some(method) = method {
+            let error = finished.result.as_ref().err().map(ToString::to_string);
+            if let Err(error) = audit::record_path(method, &finished.path, finished.size, error.as_ref()) {
+                self.report_error(error);
+            }
+            self.ops.audit_log.invalidate();
+        }
+        match (&finished.action, finished.result) {
+            (operations::Action::Delete | operations::Action::SecureDelete, Ok(())) => {
+                self.ops.freed.record(finished.path.clone(), finished.size, finished.is_dir);
+                self.forget_item(&finished.path);
+            }
+            (operations::Action::Move(destination), Ok(())) => match finished.path.file_name() {
+                Some(name) => self.relocate_item(&finished.path, &destination.join(name)),
+                None => self.forget_item(&finished.path),
+            },
+            (operations::Action::MoveAs { target, replace }, Ok(())) => {
+                if *replace {
+                    self.forget_item(target);
+                }
+                self.relocate_item(&finished.path, target);
+            }
+            (operations::Action::Copy(destination), Ok(())) => {
+                if let Some(name) = finished.path.file_name() {
+                    self.duplicate_item(&finished.path, &destination.join(name), finished.size);
+                }
+            }
+            (operations::Action::CopyAs { target, replace }, Ok(())) => {
+                if *replace {
+                    self.forget_item(target);
+                }
+                self.duplicate_item(&finished.path, target, finished.size);
+            }
+            (operations::Action::Compress, Ok(())) => self.scan_current_directory(),
+            // btrfs doesn't report compressed sizes, so a rescan would show nothing new.
+            (operations::Action::CompressInPlace, Ok(())) => {}
+            (operations::Action::Restore { .. }, Ok(())) => {
+                self.ops.trash.invalidate();
+                self.scan_current_directory();
+            }
+            (operations::Action::Archive { delete_originals, .. }, Ok(())) => {
+                if *delete_originals {
+                    self.ops.freed.record(finished.path.clone(), finished.size, finished.is_dir);
+                    self.forget_item(&finished.path);
+                }
+            }
+            (_, Err(Error::Cancelled)) => self.scan_current_directory(),
+            (_, Err(error)) => {
+                self.report_error(error);
+                self.scan_current_directory();
+            }
+        }
+    }
+
+    /// Asks what to do about the oldest conflict, or all of them at once.
+    fn render_conflict(&mut self, ctx: &egui::Context) {
+        let Some(conflict) = self.ops.conflicts.first() else {
+            return;
+        };
+        let remaining = self.ops.conflicts.len();
+        let mut choice = None;
+        egui::Window::new(tr("Name Conflict"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let name = paths::display_name(conflict.target.file_name().unwrap_or_default());
+                let folder = conflict.target.parent().map(paths::display_path).unwrap_or_default();
+                ui.label(trf("{} already exists in {}.", &[&name, &folder]));
+                ui.label(
+                    RichText::new(tr("Overwriting replaces the existing item, with everything in it if it's a folder."))
+                        .color(Color32::GRAY),
+                );
+                if remaining > 1 {
+                    ui.checkbox(
+                        &mut self.ops.apply_to_all,
+                        trf("Apply to all {} conflicts", &[&remaining]),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    for resolution in [
+                        operations::Resolution::Overwrite,
+                        operations::Resolution::Rename,
+                        operations::Resolution::Skip,
+                    ] {
+                        if ui.button(resolution.label()).clicked() {
+                            choice = Some(resolution);
+                        }
+                    }
+                });
+            });
+
+        let Some(resolution) = choice else {
+            return;
+        };
+        let count = if self.ops.apply_to_all { remaining } else { 1 };
+        let resolved: Vec<Conflict> = self.ops.conflicts.drain(..count).collect();
+        for conflict in resolved {
+            if let Some(action) = conflict.action.resolve(&conflict.target, resolution) {
+                self.ops.operations.push(action, conflict.path, conflict.is_dir, conflict.size);
+                self.layout.set_open(layout::Panel::Operations, true);
+            }
+        }
+        if self.ops.conflicts.is_empty() {
+            self.ops.apply_to_all = false;
+        }
+    }
+
+    /// Lists what a script asked for; nothing is queued until the user confirms all of it.
+    fn render_script_confirmation(&mut self, ctx: &egui::Context) {
+        if self.ops.script_confirmation.is_empty() {
+            return;
+        }
+        let mut choice = None;
+        egui::Window::new(tr("Confirm Script Operations"))
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let queued = &self.ops.script_confirmation;
+                let size: u64 = queued.iter().map(|queued| queued.size).sum();
+                ui.label(trf(
+                    "The script asks for {} operations on {}:",
+                    &[&i18n::format_count(queued.len() as u64), &i18n::format_bytes(size)],
+                ));
+                let deletes = queued.iter().filter(|queued| queued.action == operations::Action::Delete).count();
+                if deletes > 0 {
+                    let warning = trf("{} items will be permanently deleted.", &[&i18n::format_count(deletes as u64)]);
+                    ui.label(RichText::new(warning).color(Color32::YELLOW));
+                    if self.scan.snapshot_fs.is_some() {
+                        ui.label(RichText::new(tr(snapshots::SNAPSHOT_WARNING)).color(Color32::YELLOW));
+                    }
+                }
+                let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                egui::ScrollArea::vertical().max_height(300.0).show_rows(ui, row_height, queued.len(), |ui, rows| {
+                    for queued in &queued[rows] {
+                        ui.label(format!(
+                            "{}  {} ({})",
+                            queued.action.label(),
+                            paths::display_path(&queued.path),
+                            i18n::format_bytes(queued.size)
+                        ));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Queue All")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => {
+                for queued in std::mem::take(&mut self.ops.script_confirmation) {
+                    self.queue(queued.action, queued.path, queued.is_dir, queued.size);
+                }
+            }
+            Some(false) => self.ops.script_confirmation.clear(),
+            None => {}
+        }
+    }
+
+    /// The trash, move, conflict and delete confirmation windows, while one is pending.
+    pub(super) fn render_confirmations(&mut self, ctx: &egui::Context) {
+        if self.read_only() {
+            self.ops.delete_confirmation.clear();
+            self.ops.trash_confirmation = None;
+            self.ops.move_confirmation = None;
+            self.ops.script_confirmation.clear();
+            self.ops.conflicts.clear();
+        }
+        if let Some(item) = self.ops.trash_confirmation.take() {
+            let mut choice = None;
+            egui::Window::new(tr("Move to Trash"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf("Move {} to the trash?", &[&paths::display_name(&item.name)]));
+                    ui.label(
+                        RichText::new(tr("It keeps using disk space until the trash is emptied."))
+                            .color(Color32::GRAY),
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Move to Trash")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("Cancel")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            match choice {
+                Some(true) => {
+                    if let Err(error) = self.trash_item(&item) {
+                        self.report_error(error);
+                    }
+                }
+                Some(false) => {}
+                None => self.ops.trash_confirmation = Some(item),
+            }
+        }
+        if let Some((item, destination)) = self.ops.move_confirmation.take() {
+            let mut choice = None;
+            egui::Window::new(tr("Move"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf(
+                        "Move {} into {}?",
+                        &[&paths::display_name(&item.name), &paths::display_path(&destination)],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Move")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("Cancel")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            match choice {
+                Some(true) => self.queue_operation(operations::Action::Move(destination), &item),
+                Some(false) => {}
+                None => self.ops.move_confirmation = Some((item, destination)),
+            }
+        }
+        self.render_script_confirmation(ctx);
+        self.render_conflict(ctx);
+        if !self.ops.delete_confirmation.is_empty() {
+            let items = std::mem::take(&mut self.ops.delete_confirmation);
+            let size: u64 = items.iter().map(|item| item.size).sum();
+            let mut keep = true;
+            egui::Window::new(tr("Confirm Deletion"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    match items.as_slice() {
+                        [item] => {
+                            ui.label(trf("Are you sure you want to delete {}?", &[&paths::display_name(&item.name)]))
+                        }
+                        _ => ui.label(trf(
+                            "Are you sure you want to delete {} items ({})?",
+                            &[&i18n::format_count(items.len() as u64), &i18n::format_bytes(size)],
+                        )),
+                    };
+                    if items.len() > 1 {
+                        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                        let list = egui::ScrollArea::vertical().max_height(200.0);
+                        list.show_rows(ui, row_height, items.len(), |ui, rows| {
+                            for item in &items[rows] {
+                                let size = i18n::format_bytes(item.size);
+                                ui.label(format!("{} ({})", paths::display_path(&item.path), size));
+                            }
+                        });
+                    }
+                    if self.scan.snapshot_fs.is_some() {
+                        ui.label(RichText::new(tr(snapshots::SNAPSHOT_WARNING)).color(Color32::YELLOW));
+                    }
+                    ui.checkbox(&mut self.ops.secure_delete, tr("Secure delete (overwrite before removing)"));
+                    if self.ops.secure_delete {
+                        ui.label(RichText::new(tr(wipe::SSD_WARNING)).color(Color32::YELLOW));
+                    }
+                    let preferences = &self.config.preferences;
+                    let mut confirmed = true;
+                    if let [item] = items.as_slice() {
+                        if preferences.needs_typed_confirmation(item.is_dir, item.size) {
+                            ui.label(trf(
+                                "This permanently deletes {}. Type the folder name to confirm:",
+                                &[&i18n::format_bytes(item.size)],
+                            ));
+                            ui.text_edit_singleline(&mut self.ops.typed_confirmation);
+                            confirmed = self.ops.typed_confirmation == paths::display_name(&item.name);
+                        }
+                    } else if items.iter().any(|item| preferences.needs_typed_confirmation(item.is_dir, item.size)) {
+                        ui.label(trf(
+                            "This permanently deletes {}. Type the number of items to confirm:",
+                            &[&i18n::format_bytes(size)],
+                        ));
+                        ui.text_edit_singleline(&mut self.ops.typed_confirmation);
+                        confirmed = self.ops.typed_confirmation.trim() == items.len().to_string();
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(confirmed, egui::Button::new(tr("Yes"))).clicked() {
+                            keep = false;
+                            match items.as_slice() {
+                                [item] if !self.ops.secure_delete => {
+                                    if let Err(error) = self.delete_item(item) {
+                                        self.report_error(error);
+                                        keep = true;
+                                    }
+                                }
+                                _ => {
+                                    let action = match self.ops.secure_delete {
+                                        true => operations::Action::SecureDelete,
+                                        false => operations::Action::Delete,
+                                    };
+                                    for item in &items {
+                                        self.view.selection.set(item.path.clone(), None);
+                                        self.queue_operation(action.clone(), item);
+                                    }
+                                }
+                            }
+                            return;
+                        }
+                        if items.len() == 1
+                            && !self.ops.secure_delete
+                            && ui.add_enabled(confirmed, egui::Button::new(tr("Delete in background"))).clicked()
+                        {
+                            self.queue_operation(operations::Action::Delete, &items[0]);
+                            keep = false;
+                            return;
+                        }
+                        if ui.button(tr("No")).clicked() {
+                            keep = false;
+                        }
+                    });
+                });
+            if keep {
+                self.ops.delete_confirmation = items;
+            }
+        }
+    }
+
+    pub(super) fn render_freed_summary(&mut self, ctx: &egui::Context) {
+        let freed = &self.ops.freed;
+        let mut open = self.view.show_freed_summary;
+        egui::Window::new(tr("Session Summary"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "Removed {} items, freeing {}",
+                    &[
+                        &i18n::format_count(freed.session.len() as u64),
+                        &i18n::format_bytes(freed.session_bytes()),
+                    ],
+                ));
+                ui.label(trf(
+                    "All time: {} items, {}",
+                    &[
+                        &i18n::format_count(freed.lifetime.items_removed),
+                        &i18n::format_bytes(freed.lifetime.bytes_freed),
+                    ],
+                ));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for item in freed.session.iter().rev() {
+                        let icon = if item.is_dir { "📁" } else { "📄" };
+                        let ago = item.removed_at.elapsed().unwrap_or_default().as_secs() / 60;
+                        ui.label(format!(
+                            "{} {} - {} ({})",
+                            icon,
+                            item.path.display(),
+                            i18n::format_bytes(item.size),
+                            trf("{} min ago", &[&ago])
+                        ));
+                    }
+                });
+            });
+        self.view.show_freed_summary = open;
+    }
+}
@@ -0,0 +1,594 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::DiskAnalyzer;
+use crate::{
+    backend::{self, Backend},
+    category, columns, dialogs, dump,
+    error::Error,
+    filter,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job, ncdu, network, notify, paths, reclaim,
+    scan::{IgnoredTotal, ScanOptions},
+    snapshots, telemetry, volume,
+};
+
+pub const MIN_SIZE_FILTER: u64 = 1024 * 100;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Bytes before compression; see `scan::logical_size`.
+    #[serde(default)]
+    pub logical: u64,
+    pub own_size: u64,
+    pub is_dir: bool,
+    #[serde(deserialize_with = "paths::deserialize_name")]
+    pub name: OsString,
+    pub volatile: bool,
+    pub modified: Option<SystemTime>,
+    pub owner: Option<u32>,
+    #[serde(default)]
+    pub unreachable: bool,
+    #[serde(default)]
+    pub online_only: bool,
+    #[serde(default)]
+    pub entries: u64,
+    #[serde(default)]
+    pub accessed: Option<SystemTime>,
+}
+
+impl FileInfo {
+    /// A ticked entry as far as the selection knows it, which is enough to act on it.
+    pub fn selected(path: PathBuf, selected: reclaim::Selected) -> Self {
+        FileInfo {
+            name: path.file_name().unwrap_or_default().to_os_string(),
+            path,
+            size: selected.size,
+            logical: selected.logical,
+            own_size: 0,
+            is_dir: selected.is_dir,
+            volatile: false,
+            modified: None,
+            owner: None,
+            unreachable: false,
+            online_only: false,
+            entries: 0,
+            accessed: None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CacheEntry {
+    pub file_list: Vec<FileInfo>,
+    pub total_size: u64,
+    pub ignored: IgnoredTotal,
+    pub timestamp: Instant,
+}
+
+type Entries = Result<(Vec<FileInfo>, IgnoredTotal), Error>;
+
+/// What a scan worker sends back to the UI thread when it's done. Each request carries the
+/// number it was started with, so answers to superseded requests can be told apart.
+pub enum ScanMessage {
+    Listed { request: u64, path: PathBuf, result: Entries },
+    Indexed { request: u64, index: ScanIndex, elapsed: Duration },
+    Imported(Result<(dump::Header, ScanIndex), String>),
+}
+
+/// Reads and measures the children of `dir`; runs on a worker thread.
+fn read_entries(
+    backend: &dyn Backend,
+    dir: &Path,
+    options: &ScanOptions,
+    show_hidden: bool,
+    size_filter: filter::SizeFilter,
+) -> Entries {
+    let listing = backend.list(dir, options, show_hidden)?;
+    let files = listing
+        .entries
+        .into_iter()
+        .filter(|entry| !size_filter.hides(entry.is_dir, entry.size, || entry.own_size))
+        .inspect(|entry| {
+            if !entry.is_dir {
+                category::sniff_file(&entry.path, entry.size);
+            }
+        })
+        .map(|entry| FileInfo {
+            path: entry.path,
+            size: entry.size,
+            logical: entry.logical,
+            own_size: entry.own_size,
+            is_dir: entry.is_dir,
+            name: entry.name,
+            volatile: entry.changed,
+            modified: entry.modified,
+            owner: entry.owner,
+            unreachable: false,
+            online_only: entry.online_only,
+            entries: entry.entries,
+            accessed: entry.accessed,
+        })
+        .collect();
+    Ok((files, listing.ignored))
+}
+
+/// The scanned tree and the folder being looked at. Only the UI thread touches it; the
+/// workers it starts report through its channel.
+pub struct ScanState {
+    pub root_path: Option<PathBuf>,
+    pub current_path: Option<PathBuf>,
+    pub options: ScanOptions,
+    pub show_hidden: bool,
+    pub min_size_filter: u64,
+    pub show_all: bool,
+    pub directory_filter: filter::DirectoryFilter,
+    pub backend: Arc<dyn Backend>,
+    pub cache: HashMap<PathBuf, CacheEntry>,
+    /// The current folder's entries, in display order.
+    pub file_list: Vec<FileInfo>,
+    pub total_size: u64,
+    pub ignored: IgnoredTotal,
+    pub index: Option<ScanIndex>,
+    /// Where the current scan came from when it was opened from a scan file.
+    pub imported: Option<dump::Header>,
+    pub snapshot_fs: Option<snapshots::SnapshotFs>,
+    pub volume_details: Option<volume::VolumeDetails>,
+    /// Gathers `volume_details`, which reads every file's extents, off the UI thread.
+    pub volume_job: Option<job::Job<volume::VolumeDetails>>,
+    pub inode_usage: bool,
+    pub inodes: Option<volume::Inodes>,
+    pub auto_refresh: bool,
+    pub last_refresh: Instant,
+    sender: Sender<ScanMessage>,
+    receiver: Receiver<ScanMessage>,
+    next_request: u64,
+    /// The listing and index being waited for, by request number.
+    listing: Option<u64>,
+    indexing: Option<u64>,
+    importing: bool,
+}
+
+impl Default for ScanState {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            root_path: None,
+            current_path: None,
+            options: ScanOptions::default(),
+            show_hidden: false,
+            min_size_filter: MIN_SIZE_FILTER,
+            show_all: false,
+            directory_filter: filter::DirectoryFilter::default(),
+            backend: Arc::new(backend::LocalBackend),
+            cache: HashMap::new(),
+            file_list: Vec::new(),
+            total_size: 0,
+            ignored: IgnoredTotal::default(),
+            index: None,
+            imported: None,
+            snapshot_fs: None,
+            volume_details: None,
+            volume_job: None,
+            inode_usage: false,
+            inodes: None,
+            auto_refresh: false,
+            last_refresh: Instant::now(),
+            sender,
+            receiver,
+            next_request: 0,
+            listing: None,
+            indexing: None,
+            importing: false,
+        }
+    }
+}
+
+impl ScanState {
+    pub fn size_filter(&self) -> filter::SizeFilter {
+        filter::SizeFilter {
+            min_size: self.min_size_filter,
+            show_all: self.show_all,
+            directories: self.directory_filter,
+        }
+    }
+
+    /// Imported scans have nothing on disk to read, so they always list from the index.
+    pub fn lists_from_index(&self) -> bool {
+        self.options.lists_from_index() || self.imported.is_some()
+    }
+
+    /// The current folder is still being read.
+    pub fn scanning(&self) -> bool {
+        self.listing.is_some()
+    }
+
+    pub fn indexing(&self) -> bool {
+        self.indexing.is_some()
+    }
+
+    pub fn importing(&self) -> bool {
+        self.importing
+    }
+
+    /// Starts a worker and returns the request number it was given.
+    fn spawn(&mut self, work: impl FnOnce(u64) -> ScanMessage + Send + 'static) -> u64 {
+        self.next_request += 1;
+        let request = self.next_request;
+        job::send_when_done(self.sender.clone(), move || work(request));
+        request
+    }
+
+    fn request_listing(&mut self, dir: PathBuf) {
+        let (backend, options) = (Arc::clone(&self.backend), self.options.clone());
+        let (show_hidden, size_filter) = (self.show_hidden, self.size_filter());
+        self.listing = Some(self.spawn(move |request| {
+            let result = read_entries(backend.as_ref(), &dir, &options, show_hidden, size_filter);
+            ScanMessage::Listed { request, path: dir, result }
+        }));
+    }
+
+    /// Scans the whole tree below the root again in the background; the current index stays
+    /// until the new one is done.
+    pub fn rebuild_index(&mut self) {
+        let Some(root) = self.root_path.clone().filter(|_| self.imported.is_none()) else {
+            return;
+        };
+        let (options, show_hidden) = (self.options.clone(), self.show_hidden);
+        self.indexing = Some(self.spawn(move |request| {
+            let started = Instant::now();
+            let index = ScanIndex::build_resumable(&root, &options, show_hidden);
+            ScanMessage::Indexed { request, index, elapsed: started.elapsed() }
+        }));
+    }
+
+    /// Stops waiting for the index being built; it's dropped when it arrives.
+    pub fn cancel_index(&mut self) {
+        self.indexing = None;
+    }
+
+    pub fn open_scan_file(&mut self, file: PathBuf) {
+        self.spawn(move |_| ScanMessage::Imported(dump::import(&file)));
+        self.importing = true;
+    }
+
+    /// Takes what the workers sent since the last frame, leaving out answers to requests
+    /// that were superseded in the meantime.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Vec<ScanMessage> {
+        let mut messages = Vec::new();
+        while let Ok(message) = self.receiver.try_recv() {
+            let awaited = match &message {
+                ScanMessage::Listed { request, .. } => self.listing.take_if(|awaited| awaited == request).is_some(),
+                ScanMessage::Indexed { request, .. } => self.indexing.take_if(|awaited| awaited == request).is_some(),
+                ScanMessage::Imported(_) => std::mem::take(&mut self.importing),
+            };
+            if awaited {
+                messages.push(message);
+            }
+        }
+        if self.scanning() {
+            ctx.request_repaint();
+        } else if self.indexing() || self.importing {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+        messages
+    }
+
+    /// Builds a listing from the scan index instead of touching the filesystem again, which
+    /// is what network mode relies on. The index doesn't keep access times, so they're only
+    /// read when `read_accessed` asks for them.
+    pub fn entries_from_index(&self, dir: &Path, read_accessed: bool) -> Option<Vec<FileInfo>> {
+        let index = self.index.as_ref()?;
+        let id = index.find_dir(dir)?;
+        let size_filter = self.size_filter();
+        let files = index.nodes[id]
+            .children
+            .iter()
+            .filter_map(|&child| {
+                let node = &index.nodes[child];
+                let own_size = if node.is_dir { index.own_size(child) } else { node.size };
+                if size_filter.hides(node.is_dir, node.size, || own_size) {
+                    return None;
+                }
+                Some(FileInfo {
+                    path: node.path.clone(),
+                    size: node.size,
+                    logical: node.logical,
+                    own_size,
+                    is_dir: node.is_dir,
+                    name: node.name.clone(),
+                    volatile: false,
+                    modified: node.modified,
+                    owner: None,
+                    unreachable: node.unreachable,
+                    online_only: node.online_only,
+                    entries: if node.is_dir { node.entries } else { 1 },
+                    accessed: read_accessed
+                        .then(|| fs::symlink_metadata(&node.path).and_then(|metadata| metadata.accessed()).ok())
+                        .flatten(),
+                })
+            })
+            .collect();
+        Some(files)
+    }
+
+    /// Files low-memory mode counted into `dir` without listing them, as (count, bytes).
+    pub fn small_files(&self, dir: &Path) -> (u64, u64) {
+        let Some(index) = &self.index else {
+            return (0, 0);
+        };
+        index.find_dir(dir).map_or((0, 0), |id| (index.nodes[id].small_files, index.nodes[id].small_size))
+    }
+
+    /// Drops a removed path from the listing, its cache entry and the index. Returns true when
+    /// the index changed.
+    pub fn forget(&mut self, path: &Path) -> bool {
+        if let Some(current_path) = &self.current_path {
+            self.cache.remove(current_path);
+        }
+        self.file_list.retain(|f| f.path != path);
+        self.total_size = self.file_list.iter().map(|f| f.size).sum();
+        let Some(index) = &mut self.index else {
+            return false;
+        };
+        index.remove(path);
+        true
+    }
+
+    /// Like `forget`, for an entry that was moved to `target`: listed folders holding the
+    /// target grow by its size instead. Returns whether the scan index changed.
+    pub fn relocate(&mut self, path: &Path, target: &Path) -> bool {
+        self.cache.retain(|dir, _| !path.starts_with(dir) && !target.starts_with(dir));
+        if let Some(position) = self.file_list.iter().position(|f| f.path == path) {
+            let moved = self.file_list.remove(position);
+            self.grow_folders(target, moved.size, moved.logical);
+        }
+        let Some(index) = &mut self.index else {
+            return false;
+        };
+        index.relocate(path, target);
+        true
+    }
+
+    /// Like `relocate`, for a copy of `path` made at `target`; `size` is used when `path`
+    /// isn't listed.
+    pub fn duplicate(&mut self, path: &Path, target: &Path, size: u64) -> bool {
+        self.cache.retain(|dir, _| !target.starts_with(dir));
+        let (size, logical) =
+            self.file_list.iter().find(|f| f.path == path).map_or((size, size), |f| (f.size, f.logical));
+        self.grow_folders(target, size, logical);
+        let Some(index) = &mut self.index else {
+            return false;
+        };
+        index.duplicate(path, target);
+        true
+    }
+
+    /// Adds an entry that arrived at `target` to the listed folders holding it.
+    fn grow_folders(&mut self, target: &Path, size: u64, logical: u64) {
+        for folder in self.file_list.iter_mut().filter(|f| f.is_dir && target.starts_with(&f.path)) {
+            folder.size += size;
+            folder.logical += logical;
+        }
+        self.total_size = self.file_list.iter().map(|f| f.size).sum();
+    }
+}
+
+impl DiskAnalyzer {
+    pub(super) fn scan_current_directory(&mut self) {
+        let current_path = match &self.scan.current_path {
+            Some(path) => path.clone(),
+            None => return,
+        };
+
+        self.scan.listing = None;
+        self.scan.file_list.clear();
+        self.scan.volume_details = None;
+        self.scan.volume_job = None;
+        self.scan.inodes = if self.scan.inode_usage { volume::inodes(&current_path) } else { None };
+
+        if let Some(cache_entry) = self.scan.cache.get(&current_path) {
+            if cache_entry.timestamp.elapsed() < Duration::from_secs(300) {
+                telemetry::record_cache(true);
+                self.scan.file_list = cache_entry.file_list.clone();
+                self.scan.total_size = cache_entry.total_size;
+                self.scan.ignored = cache_entry.ignored;
+                self.sort_files();
+                self.update_search();
+                return;
+            }
+        }
+
+        telemetry::record_cache(false);
+        if self.scan.lists_from_index() {
+            let read_accessed = self.visible_columns().contains(&columns::Column::Accessed);
+            self.scan.file_list = self.scan.entries_from_index(&current_path, read_accessed).unwrap_or_default();
+            self.scan.ignored = IgnoredTotal::default();
+            self.sort_files();
+            self.scan.total_size =
+                self.scan.file_list.iter().map(|f| f.size).sum::<u64>() + self.scan.small_files(&current_path).1;
+        } else {
+            self.scan.request_listing(current_path);
+        }
+        self.update_search();
+    }
+
+    fn finish_listing(&mut self, path: PathBuf, result: Entries) {
+        match result {
+            Ok((files, ignored)) => {
+                self.scan.file_list = files;
+                self.scan.ignored = ignored;
+                self.sort_files();
+                self.scan.total_size = self.scan.file_list.iter().map(|f| f.size).sum();
+
+                self.scan.cache.insert(path, CacheEntry {
+                    file_list: self.scan.file_list.clone(),
+                    total_size: self.scan.total_size,
+                    ignored: self.scan.ignored,
+                    timestamp: Instant::now(),
+                });
+            }
+            Err(e) => self.report_error(e),
+        }
+        self.update_search();
+    }
+
+    /// Applies what the scan workers finished since the last frame.
+    pub(super) fn poll_scan(&mut self, ctx: &egui::Context) {
+        for message in self.scan.poll(ctx) {
+            match message {
+                ScanMessage::Listed { path, result, .. } => self.finish_listing(path, result),
+                ScanMessage::Indexed { index, elapsed, .. } => {
+                    self.notify_scan_finished(ctx, &index, elapsed);
+                    self.scan.index = Some(index);
+                    self.index_changed();
+                    self.refresh_inline();
+                    if self.scan.lists_from_index() {
+                        self.apply_filter();
+                    } else {
+                        self.update_search();
+                    }
+                }
+                ScanMessage::Imported(Ok((header, index))) => self.open_imported(header, index),
+                ScanMessage::Imported(Err(error)) => self.report_error(error),
+            }
+        }
+    }
+
+    /// Drops what the reports worked out from the previous index.
+    pub(super) fn index_changed(&mut self) {
+        self.histogram.invalidate();
+        self.summary.invalidate();
+        self.cold.invalidate();
+        self.treemap.invalidate();
+        self.console.invalidate();
+    }
+
+    pub(super) fn apply_filter(&mut self) {
+        if let Some(current_path) = &self.scan.current_path {
+            self.scan.cache.remove(current_path);
+        }
+        self.scan_current_directory();
+    }
+
+    /// Reads the current folder and the whole tree again, after options that change what a
+    /// scan finds.
+    pub(super) fn rescan(&mut self) {
+        self.scan.cache.clear();
+        self.scan_current_directory();
+        self.scan.rebuild_index();
+    }
+
+    /// Browses an index that was loaded rather than scanned here, read-only.
+    pub(super) fn open_imported(&mut self, header: dump::Header, index: ScanIndex) {
+        self.pending_session = None;
+        self.scan.cancel_index();
+        self.scan.cache.clear();
+        self.view.selection.clear();
+        self.scan.snapshot_fs = None;
+        self.scan.root_path = Some(header.root.clone());
+        self.view.expanded = HashSet::from([header.root.clone()]);
+        self.scan.current_path = Some(header.root.clone());
+        self.scan.imported = Some(header);
+        self.scan.index = Some(index);
+        self.index_changed();
+        self.scan_current_directory();
+    }
+
+    pub(super) fn navigate_to(&mut self, path: PathBuf) {
+        self.view.inline_expanded.clear();
+        self.scan.current_path = Some(path);
+        self.scan_current_directory();
+    }
+
+    pub(super) fn open_root(&mut self, path: PathBuf) {
+        self.pending_session = None;
+        self.scan.imported = None;
+        if network::is_network_path(&path) {
+            self.scan.options.network_mode = true;
+        }
+        self.scan.snapshot_fs = snapshots::detect(&path).map(|(fs, _)| fs);
+        self.view.selection.clear();
+        self.scan.root_path = Some(path.clone());
+        self.scan.index = None;
+        self.view.expanded = HashSet::from([path.clone()]);
+        self.navigate_to(path);
+        self.scan.rebuild_index();
+    }
+
+    pub(super) fn go_up(&mut self) {
+        if let Some(current) = &self.scan.current_path {
+            if let Some(parent) = current.parent() {
+                if self.scan.root_path.as_ref().map_or(true, |root| parent.starts_with(root)) {
+                    self.navigate_to(parent.to_path_buf());
+                }
+            }
+        }
+    }
+
+    pub(super) fn export_scan(&mut self) {
+        let Some(index) = &self.scan.index else {
+            return;
+        };
+        let Some(path) = dialogs::save_file(dump::DEFAULT_FILE_NAME) else {
+            return;
+        };
+        let options = self.scan.imported.as_ref().map_or(&self.scan.options, |header| &header.options);
+        if let Err(error) = dump::export(index, options, self.scan.show_hidden, &path) {
+            self.report_error(error);
+        }
+    }
+
+    pub(super) fn export_ncdu(&mut self) {
+        let Some(index) = &self.scan.index else {
+            return;
+        };
+        let Some(path) = dialogs::save_file("scan.ncdu.json") else {
+            return;
+        };
+        if let Err(error) = ncdu::export(index, &path) {
+            self.report_error(error);
+        }
+    }
+
+    /// Lets the user know a long scan is done when they switched to something else meanwhile.
+    fn notify_scan_finished(&mut self, ctx: &egui::Context, index: &ScanIndex, elapsed: Duration) {
+        let preferences = &self.config.preferences;
+        if !preferences.notify_long_scans || elapsed.as_secs() < preferences.long_scan_secs {
+            return;
+        }
+        let background = ctx.input(|i| {
+            let viewport = i.viewport();
+            viewport.minimized == Some(true) || viewport.focused == Some(false)
+        });
+        let Some(root) = index.nodes.first().filter(|_| background) else {
+            return;
+        };
+        let body = trf(
+            "{}: {} in {} items, scanned in {} s",
+            &[
+                &paths::display_path(&root.path),
+                &i18n::format_bytes(root.size),
+                &i18n::format_count(root.entries),
+                &elapsed.as_secs(),
+            ],
+        );
+        if let Err(e) = notify::send(tr("Scan finished"), &body) {
+            self.report_error(e);
+        }
+    }
+}
@@ -0,0 +1,1720 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use super::{
+    scan::{FileInfo, ScanState},
+    DiskAnalyzer,
+};
+use crate::{
+    a11y, audit, bookmarks, cloud, columns,
+    config::Preferences,
+    dialogs, diskimage,
+    error::Error,
+    filter, grouping, hashes, heatmap, history, html_report,
+    i18n::{self, tr, trf},
+    icons,
+    job::Job,
+    layout, list_row, media, mounts, operations, owner, paths, plugins, query, quotas, reclaim, scan, summary, tags,
+    tree, volume,
+};
+
+const MAX_TREE_RESULTS: usize = 1000;
+/// Indentation per level, and the width of the chevron, of folders expanded in the list.
+const INLINE_INDENT: f32 = 18.0;
+
+/// What undo and redo step through.
+#[derive(Clone, PartialEq)]
+pub struct ViewSnapshot {
+    root_path: Option<PathBuf>,
+    current_path: Option<PathBuf>,
+    search_query: String,
+    search_everywhere: bool,
+    sort_by_size: bool,
+    sort_by_own_size: bool,
+    show_hidden: bool,
+    show_all: bool,
+    min_size_filter: u64,
+    directory_filter: filter::DirectoryFilter,
+    tag_filter: Option<String>,
+}
+
+impl ViewSnapshot {
+    pub fn only_search_differs(&self, other: &ViewSnapshot) -> bool {
+        self.search_query != other.search_query
+            && ViewSnapshot { search_query: other.search_query.clone(), ..self.clone() } == *other
+    }
+}
+
+/// How the scan is presented: search, sorting, grouping, what's expanded and selected.
+pub struct ViewState {
+    /// Positions in `ScanState::file_list` of the entries the search and tag filter let through.
+    pub filtered: Vec<usize>,
+    pub search_query: String,
+    pub search_everywhere: bool,
+    pub tree_results: Vec<usize>,
+    pub query: String,
+    /// The query bar's results are what the list shows.
+    pub query_active: bool,
+    pub query_error: Option<String>,
+    pub tag_filter: Option<String>,
+    pub tag_editor: Option<(PathBuf, tags::PathTags)>,
+    pub new_tag: String,
+    pub sort_by_size: bool,
+    pub sort_by_own_size: bool,
+    pub group_by: grouping::GroupBy,
+    pub collapsed_groups: HashSet<grouping::GroupKey>,
+    pub age_heatmap: bool,
+    pub highlight_names: Vec<String>,
+    pub show_owners: bool,
+    pub show_ignored_size: bool,
+    pub show_tree: bool,
+    pub show_details: bool,
+    pub show_freed_summary: bool,
+    pub expanded: HashSet<PathBuf>,
+    /// Folders of the file list opened in place, with their sorted children.
+    pub inline_expanded: HashMap<PathBuf, Vec<FileInfo>>,
+    /// Entries ticked in the file list.
+    pub selection: reclaim::Selection,
+    /// The row being dragged onto a folder to move it there.
+    pub dragging: Option<FileInfo>,
+    pub scroll_offset: f32,
+    pub restore_scroll: Option<f32>,
+    pub history: history::History<ViewSnapshot>,
+    pub history_applied: bool,
+    pub checksums: Option<hashes::Checksums>,
+    pub media_details: Option<media::MediaDetails>,
+    pub errors: Vec<String>,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            filtered: Vec::new(),
+            search_query: String::new(),
+            search_everywhere: false,
+            tree_results: Vec::new(),
+            query: String::new(),
+            query_active: false,
+            query_error: None,
+            tag_filter: None,
+            tag_editor: None,
+            new_tag: String::new(),
+            sort_by_size: true,
+            sort_by_own_size: false,
+            group_by: grouping::GroupBy::None,
+            collapsed_groups: HashSet::new(),
+            age_heatmap: false,
+            highlight_names: Vec::new(),
+            show_owners: false,
+            show_ignored_size: true,
+            show_tree: false,
+            show_details: false,
+            show_freed_summary: false,
+            expanded: HashSet::new(),
+            inline_expanded: HashMap::new(),
+            selection: reclaim::Selection::default(),
+            dragging: None,
+            scroll_offset: 0.0,
+            restore_scroll: None,
+            history: history::History::default(),
+            history_applied: false,
+            checksums: None,
+            media_details: None,
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// Something a click in the file list asked for. Rows only read the app while they're drawn;
+/// what they ask for is applied once the list is done.
+enum RowAction {
+    Navigate(PathBuf),
+    ToggleInline(PathBuf),
+    ToggleGroup(grouping::GroupKey),
+    Select(PathBuf, Option<reclaim::Selected>),
+    ToggleBookmark(PathBuf, PathBuf),
+    Trash(FileInfo),
+    ConfirmDelete(FileInfo),
+    Queue(operations::Action, FileInfo),
+    Archive(PathBuf, u64),
+    ShowProcesses(PathBuf),
+    BrowseImage(PathBuf),
+    Details(FileInfo),
+    EditTags(PathBuf),
+    StartDrag(FileInfo),
+}
+
+/// What drawing a file row needs from the app.
+struct Rows<'a> {
+    scan: &'a ScanState,
+    view: &'a ViewState,
+    preferences: &'a Preferences,
+    bookmarks: &'a bookmarks::BookmarkStore,
+    tags: &'a tags::TagStore,
+    quotas: &'a quotas::QuotaState,
+    plugins: &'a mut plugins::PluginsState,
+    icons: &'a mut icons::Icons,
+    columns: Vec<columns::Column>,
+    read_only: bool,
+    /// Rows shown so far, for striping.
+    shown: usize,
+    actions: Vec<RowAction>,
+    /// Folder rows a dragged row can be dropped on, while one is being dragged.
+    drop_targets: Vec<(PathBuf, egui::Rect)>,
+}
+
+impl Rows<'_> {
+    fn column_text(&self, column: columns::Column, item: &FileInfo) -> String {
+        let time = |time: Option<SystemTime>| {
+            time.and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or_else(|| "-".to_string(), |age| audit::format_timestamp(age.as_secs()))
+        };
+        match column {
+            columns::Column::Size if self.scan.inode_usage && item.is_dir => {
+                trf("{} items", &[&i18n::format_count(item.entries)])
+            }
+            columns::Column::Size => i18n::format_bytes(item.size),
+            columns::Column::OwnSize if item.is_dir => i18n::format_bytes(item.own_size),
+            columns::Column::Logical => i18n::format_bytes(item.logical),
+            columns::Column::Compression if item.size > 0 && item.logical > item.size => {
+                format!("{:.1}×", item.logical as f64 / item.size as f64)
+            }
+            columns::Column::Percent if self.scan.total_size > 0 => {
+                format!("{:.1}%", item.size as f64 * 100.0 / self.scan.total_size as f64)
+            }
+            columns::Column::Items if item.is_dir => i18n::format_count(item.entries),
+            columns::Column::Modified => time(item.modified),
+            columns::Column::Accessed => time(item.accessed),
+            columns::Column::Owner => owner::display_owner(item.owner),
+            columns::Column::Extension if !item.is_dir => Path::new(&item.name)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    /// One entry of the file list, followed by its children when it's expanded in place.
+    fn show(&mut self, ui: &mut egui::Ui, item: &FileInfo, depth: usize) {
+        let view = self.view;
+        let row = list_row::ListRow::new(self.shown).selected(view.selection.contains(&item.path));
+        self.shown += 1;
+        let row = row.show(ui, |ui| {
+            if self.scan.imported.is_none() {
+                let mut selected = view.selection.contains(&item.path);
+                if a11y::labeled(ui.checkbox(&mut selected, ""), tr("Select")).changed() {
+                    let info = reclaim::Selected { is_dir: item.is_dir, size: item.size, logical: item.logical };
+                    self.actions.push(RowAction::Select(item.path.clone(), selected.then_some(info)));
+                }
+            }
+            for &column in &self.columns {
+                let text = self.column_text(column, item);
+                columns::cell(ui, column, |ui| ui.label(RichText::new(text).color(Color32::GRAY)));
+            }
+
+            ui.add_space(depth as f32 * INLINE_INDENT);
+            let chevron = egui::vec2(INLINE_INDENT, ui.spacing().interact_size.y);
+            if item.is_dir && self.scan.index.is_some() {
+                let expanded = view.inline_expanded.contains_key(&item.path);
+                let (icon, label) = if expanded { ("⏷", tr("Collapse")) } else { ("⏵", tr("Expand in place")) };
+                let toggle = ui.add_sized(chevron, egui::Button::new(icon).frame(false));
+                if a11y::labeled(toggle, label).clicked() {
+                    self.actions.push(RowAction::ToggleInline(item.path.clone()));
+                }
+            } else {
+                ui.allocate_space(chevron);
+            }
+
+            self.icons.show(ui, &item.path, item.is_dir);
+            let color = if item.is_dir && self.quotas.over_budget(&item.path, item.size) {
+                Color32::RED
+            } else if view.age_heatmap {
+                heatmap::age_color(item.modified)
+            } else if view.highlight_names.iter().any(|name| OsStr::new(name) == item.name) {
+                Color32::from_rgb(255, 165, 0)
+            } else if item.is_dir {
+                Color32::LIGHT_BLUE
+            } else {
+                Color32::WHITE
+            };
+            let text = RichText::new(paths::display_name(&item.name)).color(color);
+
+            let draggable = !self.read_only && self.scan.imported.is_none();
+            let response = match (item.is_dir, draggable) {
+                (true, true) => ui.add(egui::Button::new(text).sense(egui::Sense::click_and_drag())),
+                (true, false) => ui.button(text),
+                (false, true) => ui.add(egui::Label::new(text).sense(egui::Sense::drag())),
+                (false, false) => ui.label(text),
+            };
+            if response.drag_started() {
+                self.actions.push(RowAction::StartDrag(item.clone()));
+            }
+            let exact = self.preferences.show_exact_bytes;
+            let index = self.scan.index.as_ref().filter(|_| item.is_dir);
+            let breakdown = index.and_then(|index| Some((index, index.find_dir(&item.path)?)));
+            let response = if exact || breakdown.is_some() {
+                response.on_hover_ui(|ui| {
+                    if exact {
+                        ui.label(i18n::format_exact_bytes(item.size));
+                    }
+                    if let Some((index, dir)) = breakdown {
+                        summary::show_breakdown(ui, index, dir);
+                    }
+                })
+            } else {
+                response
+            };
+            if item.is_dir && response.clicked() {
+                self.actions.push(RowAction::Navigate(item.path.clone()));
+            }
+
+            if let Some(root) = self.scan.root_path.as_ref().filter(|_| item.is_dir) {
+                let starred = self.bookmarks.contains(root, &item.path);
+                let (icon, label) = if starred { ("★", tr("Remove bookmark")) } else { ("☆", tr("Bookmark")) };
+                if a11y::icon_button(ui, icon, label).clicked() {
+                    self.actions.push(RowAction::ToggleBookmark(root.clone(), item.path.clone()));
+                }
+            }
+
+            if let Some(entry) = self.tags.get(&item.path) {
+                for tag in &entry.tags {
+                    ui.label(RichText::new(format!("[{}]", tag)).small().color(tags::tag_color(tag)));
+                }
+                if !entry.note.trim().is_empty() {
+                    ui.label("📝").on_hover_text(&entry.note);
+                }
+            }
+
+            if item.unreachable {
+                ui.label(RichText::new("⚠").color(Color32::RED))
+                    .on_hover_text(tr("Unreachable or timed out; size is incomplete"));
+            }
+
+            if item.online_only {
+                ui.label(RichText::new(tr("online-only")).small().color(Color32::LIGHT_BLUE))
+                    .on_hover_text(tr("Stored in the cloud; takes no space on this device until downloaded"));
+            }
+
+            if item.volatile {
+                ui.label(RichText::new("⚠").color(Color32::YELLOW))
+                    .on_hover_text(tr("Changed while scanning; size may be out of date"));
+            }
+
+            if !self.read_only && a11y::icon_button(ui, "♻", tr("Move to Trash")).clicked() {
+                self.actions.push(RowAction::Trash(item.clone()));
+            }
+
+            if !self.read_only && a11y::icon_button(ui, "🗑️", tr("Delete permanently")).clicked() {
+                self.actions.push(RowAction::ConfirmDelete(item.clone()));
+            }
+
+            let read_only = self.read_only;
+            ui.menu_button("⋯", |ui| {
+                if !read_only {
+                    if ui.button(tr("Move to...")).clicked() {
+                        ui.close_menu();
+                        if let Some(destination) = dialogs::pick_folder() {
+                            self.actions.push(RowAction::Queue(operations::Action::Move(destination), item.clone()));
+                        }
+                    }
+                    if ui.button(tr("Copy with verification...")).clicked() {
+                        ui.close_menu();
+                        if let Some(destination) = dialogs::pick_folder() {
+                            self.actions.push(RowAction::Queue(operations::Action::Copy(destination), item.clone()));
+                        }
+                    }
+                    if ui.button(tr("Compress")).clicked() {
+                        ui.close_menu();
+                        self.actions.push(RowAction::Queue(operations::Action::Compress, item.clone()));
+                    }
+                    if mounts::mount_of(&item.path).is_some_and(|mount| mount.fs_type == "btrfs")
+                        && ui
+                            .button(tr("Compress in place"))
+                            .on_hover_text(tr("Turns on btrfs compression for it and recompresses what it holds"))
+                            .clicked()
+                    {
+                        ui.close_menu();
+                        self.actions.push(RowAction::Queue(operations::Action::CompressInPlace, item.clone()));
+                    }
+                }
+                if ui.button(tr("Archive...")).clicked() {
+                    ui.close_menu();
+                    self.actions.push(RowAction::Archive(item.path.clone(), item.size));
+                }
+                self.plugins.context_menu(ui, &item.path, item.is_dir, read_only);
+                if ui.button(tr("Show processes using this")).clicked() {
+                    ui.close_menu();
+                    self.actions.push(RowAction::ShowProcesses(item.path.clone()));
+                }
+            });
+
+            if !item.is_dir
+                && diskimage::is_disk_image(&item.path)
+                && a11y::icon_button(ui, "💿", tr("Browse image contents")).clicked()
+            {
+                self.actions.push(RowAction::BrowseImage(item.path.clone()));
+            }
+
+            if a11y::icon_button(ui, "ℹ️", tr("Details")).clicked() {
+                self.actions.push(RowAction::Details(item.clone()));
+            }
+
+            if a11y::icon_button(ui, "🏷", tr("Tags & notes")).clicked() {
+                self.actions.push(RowAction::EditTags(item.path.clone()));
+            }
+        });
+        if item.is_dir && view.dragging.is_some() {
+            self.drop_targets.push((item.path.clone(), row.response.rect));
+        }
+        if let Some(children) = view.inline_expanded.get(&item.path) {
+            for child in children {
+                self.show(ui, child, depth + 1);
+            }
+        }
+    }
+}
+
+struct Panels<'a> {
+    app: &'a mut DiskAnalyzer,
+}
+
+impl egui_dock::TabViewer for Panels<'_> {
+    type Tab = layout::Panel;
+
+    fn title(&mut self, panel: &mut layout::Panel) -> egui::WidgetText {
+        tr(panel.label()).into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, panel: &mut layout::Panel) {
+        let app = &mut *self.app;
+        match panel {
+            layout::Panel::Files => app.render_files(ui),
+            layout::Panel::Tree => {
+                if app.scan.root_path.is_some() {
+                    app.render_tree(ui);
+                }
+            }
+            layout::Panel::Details => app.render_details(ui),
+            layout::Panel::Errors => app.render_errors(ui),
+            layout::Panel::Reports => {
+                app.render_report_buttons(ui);
+            }
+            layout::Panel::History => app.ops.audit_log.show(ui),
+            layout::Panel::Operations => app.ops.operations.show(ui),
+            layout::Panel::Summary => {
+                app.summary.show(ui, app.scan.index.as_ref(), app.scan.current_path.as_deref());
+            }
+            layout::Panel::Console => {
+                let read_only = app.read_only();
+                let queued = app.console.show(ui, app.scan.index.as_ref(), read_only);
+                if !queued.is_empty() {
+                    app.ops.script_confirmation = queued;
+                }
+            }
+            layout::Panel::Bookmarks => {
+                let (root, current) = (app.scan.root_path.as_deref(), app.scan.current_path.as_deref());
+                if let Some(path) = app.bookmarks.show(ui, root, current) {
+                    app.navigate_to(path);
+                }
+            }
+            layout::Panel::Icicle | layout::Panel::Treemap => {
+                let Some(index) = &app.scan.index else {
+                    ui.label(RichText::new(tr("Available once the tree has been scanned")).color(Color32::GRAY));
+                    return;
+                };
+                let open = match panel {
+                    layout::Panel::Icicle => app.icicle.show(ui, index),
+                    _ => app.treemap.show(ui, index),
+                };
+                if let Some(path) = open {
+                    app.navigate_to(path);
+                }
+            }
+        }
+    }
+
+    fn closeable(&mut self, panel: &mut layout::Panel) -> bool {
+        *panel != layout::Panel::Files
+    }
+
+    fn on_close(&mut self, panel: &mut layout::Panel) -> bool {
+        match panel {
+            layout::Panel::Tree => self.app.view.show_tree = false,
+            layout::Panel::Details => self.app.view.show_details = false,
+            _ => {}
+        }
+        true
+    }
+}
+
+impl DiskAnalyzer {
+    /// Shows a toast for the error and keeps it in the Errors panel.
+    pub(super) fn report_error(&mut self, error: impl Into<Error>) {
+        let error = error.into();
+        self.view.errors.push(error.to_string());
+        self.toasts.error(&error);
+    }
+
+    /// Folders first, then by the chosen order.
+    fn order_entries(&self, files: &mut [FileInfo]) {
+        if self.scan.inode_usage {
+            files.sort_by_key(|f| (!f.is_dir, std::cmp::Reverse(f.entries)));
+        } else if self.view.sort_by_size {
+            let own = self.view.sort_by_own_size;
+            let key = |f: &FileInfo| if own { f.own_size } else { f.size };
+            files.sort_by(|a, b| {
+                if a.is_dir == b.is_dir {
+                    key(b).cmp(&key(a))
+                } else {
+                    b.is_dir.cmp(&a.is_dir)
+                }
+            });
+        } else {
+            files.sort_by(|a, b| {
+                if a.is_dir == b.is_dir {
+                    a.name.to_string_lossy().to_lowercase().cmp(&b.name.to_string_lossy().to_lowercase())
+                } else {
+                    b.is_dir.cmp(&a.is_dir)
+                }
+            });
+        }
+    }
+
+    pub(super) fn sort_files(&mut self) {
+        let mut files = std::mem::take(&mut self.scan.file_list);
+        self.order_entries(&mut files);
+        self.scan.file_list = files;
+        self.refresh_inline();
+
+        let group_by = self.view.group_by;
+        if group_by != grouping::GroupBy::None {
+            let key = |f: &FileInfo| grouping::key(group_by, &f.path, &f.name, f.is_dir, f.size, f.modified);
+            let mut subtotals: HashMap<Option<grouping::GroupKey>, u64> = HashMap::new();
+            for file in &self.scan.file_list {
+                *subtotals.entry(key(file)).or_default() += file.size;
+            }
+            // Largest groups first when sorting by size; Folders always lead.
+            let by_size = self.view.sort_by_size;
+            self.scan.file_list.sort_by_cached_key(|f| {
+                let group = key(f);
+                let subtotal = if by_size && group != Some(grouping::GroupKey::Folders) { subtotals[&group] } else { 0 };
+                (group != Some(grouping::GroupKey::Folders), std::cmp::Reverse(subtotal), group)
+            });
+        }
+    }
+
+    pub(super) fn update_search(&mut self) {
+        let search_query = self.view.search_query.to_lowercase();
+        let (view, tags) = (&mut self.view, &self.tags);
+        view.filtered = self
+            .scan
+            .file_list
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                item.name.to_string_lossy().to_lowercase().contains(&search_query)
+                    && view.tag_filter.as_ref().is_none_or(|tag| tags.has_tag(&item.path, tag))
+            })
+            .map(|(position, _)| position)
+            .collect();
+
+        if self.view.query_active {
+            self.run_query();
+            return;
+        }
+        self.view.tree_results = match &self.scan.index {
+            Some(index) if self.whole_tree_search() => index
+                .search(&search_query)
+                .into_iter()
+                .filter(|&id| {
+                    self.view
+                        .tag_filter
+                        .as_ref()
+                        .is_none_or(|tag| self.tags.has_tag(&index.nodes[id].path, tag))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    fn whole_tree_search(&self) -> bool {
+        let view = &self.view;
+        view.query_active || (view.search_everywhere && (!view.search_query.is_empty() || view.tag_filter.is_some()))
+    }
+
+    fn run_query(&mut self) {
+        let Some(index) = &self.scan.index else {
+            self.view.query_error = Some(tr("Available once the tree has been scanned").to_string());
+            return;
+        };
+        match query::Query::parse(&self.view.query) {
+            Ok(query) => {
+                self.view.tree_results = query.run(index);
+                self.view.query_active = true;
+                self.view.query_error = None;
+            }
+            Err(error) => self.view.query_error = Some(error),
+        }
+    }
+
+    fn clear_query(&mut self) {
+        self.view.query_active = false;
+        self.view.query_error = None;
+        self.update_search();
+    }
+
+    fn export_query_results(&mut self) {
+        let Some(index) = &self.scan.index else {
+            return;
+        };
+        let Some(path) = dialogs::save_file("query-results.csv") else {
+            return;
+        };
+        let csv = query::to_csv(index, &self.view.tree_results);
+        if let Err(e) = fs::write(&path, csv) {
+            self.report_error(format!("Error writing {}: {}", path.display(), e));
+        }
+    }
+
+    /// The list columns for the current root; the profile's owner setting adds the owner.
+    pub(super) fn visible_columns(&self) -> Vec<columns::Column> {
+        let mut visible = self.config.columns.for_root(self.scan.root_path.as_deref()).to_vec();
+        if self.view.show_owners && !visible.contains(&columns::Column::Owner) {
+            visible.push(columns::Column::Owner);
+        }
+        visible
+    }
+
+    fn children_of(&self, path: &Path) -> Option<Vec<FileInfo>> {
+        let read_accessed = self.visible_columns().contains(&columns::Column::Accessed);
+        let mut children = self.scan.entries_from_index(path, read_accessed)?;
+        self.order_entries(&mut children);
+        Some(children)
+    }
+
+    /// Expands a folder of the list in place, or collapses it along with what's open inside.
+    fn toggle_inline(&mut self, path: &Path) {
+        if self.view.inline_expanded.remove(path).is_some() {
+            self.view.inline_expanded.retain(|expanded, _| !expanded.starts_with(path));
+        } else if let Some(children) = self.children_of(path) {
+            self.view.inline_expanded.insert(path.to_path_buf(), children);
+        }
+    }
+
+    /// Lists the expanded folders again after the index or the sort order changed.
+    pub(super) fn refresh_inline(&mut self) {
+        let paths: Vec<PathBuf> = self.view.inline_expanded.keys().cloned().collect();
+        for path in paths {
+            match self.children_of(&path) {
+                Some(children) => {
+                    self.view.inline_expanded.insert(path, children);
+                }
+                None => {
+                    self.view.inline_expanded.remove(&path);
+                }
+            }
+        }
+    }
+
+    pub(super) fn view_snapshot(&self) -> ViewSnapshot {
+        ViewSnapshot {
+            root_path: self.scan.root_path.clone(),
+            current_path: self.scan.current_path.clone(),
+            search_query: self.view.search_query.clone(),
+            search_everywhere: self.view.search_everywhere,
+            sort_by_size: self.view.sort_by_size,
+            sort_by_own_size: self.view.sort_by_own_size,
+            show_hidden: self.scan.show_hidden,
+            show_all: self.scan.show_all,
+            min_size_filter: self.scan.min_size_filter,
+            directory_filter: self.scan.directory_filter,
+            tag_filter: self.view.tag_filter.clone(),
+        }
+    }
+
+    fn restore_view(&mut self, snapshot: ViewSnapshot) {
+        let filters_changed = snapshot.show_hidden != self.scan.show_hidden
+            || snapshot.show_all != self.scan.show_all
+            || snapshot.min_size_filter != self.scan.min_size_filter
+            || snapshot.directory_filter != self.scan.directory_filter;
+        let path_changed = snapshot.current_path != self.scan.current_path;
+        self.view.history_applied = true;
+
+        self.scan.root_path = snapshot.root_path;
+        self.scan.current_path = snapshot.current_path;
+        self.view.search_query = snapshot.search_query;
+        self.view.search_everywhere = snapshot.search_everywhere;
+        self.view.sort_by_size = snapshot.sort_by_size;
+        self.view.sort_by_own_size = snapshot.sort_by_own_size;
+        self.scan.show_hidden = snapshot.show_hidden;
+        self.scan.show_all = snapshot.show_all;
+        self.scan.min_size_filter = snapshot.min_size_filter;
+        self.scan.directory_filter = snapshot.directory_filter;
+        self.view.tag_filter = snapshot.tag_filter;
+
+        if filters_changed {
+            if let Some(current_path) = &self.scan.current_path {
+                self.scan.cache.remove(current_path);
+            }
+        }
+        if filters_changed || path_changed {
+            self.scan_current_directory();
+        } else {
+            self.sort_files();
+            self.update_search();
+        }
+    }
+
+    fn undo_view(&mut self) {
+        if let Some(snapshot) = self.view.history.undo(self.view_snapshot()) {
+            self.restore_view(snapshot);
+        }
+    }
+
+    fn redo_view(&mut self) {
+        if let Some(snapshot) = self.view.history.redo(self.view_snapshot()) {
+            self.restore_view(snapshot);
+        }
+    }
+
+    pub(super) fn handle_history_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|m| m.focus().is_some()) {
+            return;
+        }
+        let redo = egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+            egui::Key::Z,
+        );
+        let redo_alt = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+
+        if ctx.input_mut(|i| i.consume_shortcut(&redo) || i.consume_shortcut(&redo_alt)) {
+            self.redo_view();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo_view();
+        }
+    }
+
+    pub(super) fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let hovering = ctx.input(|i| !i.raw.hovered_files.is_empty());
+        if hovering {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_overlay"),
+            ));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, Color32::from_black_alpha(180));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                tr("Drop a folder to analyze it"),
+                egui::FontId::proportional(24.0),
+                Color32::WHITE,
+            );
+        }
+
+        let dropped = ctx.input(|i| {
+            i.raw.dropped_files.iter().find_map(|file| file.path.clone())
+        });
+        if let Some(path) = dropped {
+            let dir = if path.is_dir() {
+                Some(path)
+            } else {
+                path.parent().map(Path::to_path_buf)
+            };
+            if let Some(dir) = dir {
+                self.open_root(dir);
+            }
+        }
+    }
+
+    pub(super) fn render_top_panel(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            self.render_toolbar(ui);
+            self.render_search_bar(ui);
+            self.render_query_bar(ui);
+
+            if self.scan.current_path.is_some() {
+                self.render_path_bar(ui);
+            }
+
+            if self.view.age_heatmap {
+                heatmap::render_legend(ui);
+            }
+        });
+    }
+
+    fn render_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let undo = ui.add_enabled(self.view.history.can_undo(), egui::Button::new("↶"));
+            if a11y::labeled(undo, tr("Undo (Ctrl+Z)")).clicked() {
+                self.undo_view();
+            }
+            let redo = ui.add_enabled(self.view.history.can_redo(), egui::Button::new("↷"));
+            if a11y::labeled(redo, tr("Redo (Ctrl+Shift+Z)")).clicked() {
+                self.redo_view();
+            }
+
+            if ui.button(tr("Select Directory")).clicked() {
+                if let Some(path) = dialogs::pick_folder() {
+                    self.open_root(path);
+                }
+            }
+
+            if ui.add_enabled(!self.scan.importing(), egui::Button::new(tr("Open Scan File..."))).clicked() {
+                if let Some(file) = dialogs::pick_file() {
+                    self.scan.open_scan_file(file);
+                }
+            }
+
+            if ui.button(tr("Dashboard")).clicked() {
+                self.dashboard.open = true;
+            }
+
+            if ui.button(tr("Compare...")).clicked() {
+                self.compare.open = true;
+            }
+
+            if a11y::icon_button(ui, "🔎", tr("Go to path (Ctrl+P)")).clicked() {
+                self.quick_open.toggle();
+            }
+
+            ui.menu_button(tr("Reports"), |ui| {
+                if self.render_report_buttons(ui) {
+                    ui.close_menu();
+                }
+            });
+
+            ui.menu_button(tr("Panels"), |ui| {
+                for panel in layout::Panel::ALL {
+                    if panel == layout::Panel::Files {
+                        continue;
+                    }
+                    let mut open = self.layout.is_open(panel);
+                    if ui.checkbox(&mut open, tr(panel.label())).changed() {
+                        self.set_panel_open(panel, open);
+                    }
+                }
+                ui.separator();
+                if ui.button(tr("Reset Layout")).clicked() {
+                    self.layout = layout::Layout::default();
+                    self.view.show_tree = true;
+                    self.view.show_details = true;
+                    ui.close_menu();
+                }
+            });
+
+            if a11y::icon_button(ui, "⚙", tr("Settings")).clicked() {
+                self.config.show_settings = true;
+            }
+
+            if self.read_only() {
+                ui.label(RichText::new(format!("🔒 {}", tr("Read-only"))).color(Color32::YELLOW));
+            }
+
+            let mut selected_profile = None;
+            egui::ComboBox::from_id_source("profile")
+                .selected_text(trf("Profile: {}", &[&self.config.active_profile]))
+                .show_ui(ui, |ui| {
+                    for profile in self.config.profiles.all() {
+                        let checked = profile.name == self.config.active_profile;
+                        if ui.selectable_label(checked, &profile.name).clicked() {
+                            selected_profile = Some(profile);
+                        }
+                    }
+                });
+            if let Some(profile) = selected_profile {
+                self.apply_profile(profile);
+            }
+
+            if self.scan.current_path.is_some() {
+                self.render_scan_options(ui);
+            }
+        });
+    }
+
+    fn render_scan_options(&mut self, ui: &mut egui::Ui) {
+        if a11y::icon_button(ui, "🔄", tr("Refresh")).clicked() {
+            self.scan_current_directory();
+            self.scan.rebuild_index();
+        }
+        ui.checkbox(&mut self.scan.auto_refresh, tr("Auto Refresh"));
+        ui.checkbox(&mut self.view.sort_by_size, tr("Sort by Size"));
+        ui.checkbox(&mut self.scan.show_hidden, tr("Show Hidden"));
+        ui.checkbox(&mut self.view.age_heatmap, tr("Age Heatmap"));
+        ui.checkbox(&mut self.view.show_tree, tr("Tree View"));
+        if ui
+            .checkbox(&mut self.scan.options.respect_ignores, tr("Respect .gitignore"))
+            .on_hover_text(tr("Skip entries excluded by .gitignore and .ignore files"))
+            .changed()
+        {
+            self.rescan();
+        }
+        if self.scan.options.respect_ignores {
+            ui.checkbox(&mut self.view.show_ignored_size, tr("Show Ignored Size"));
+        }
+        if ui
+            .checkbox(&mut self.scan.options.network_mode, tr("Network Share Mode"))
+            .on_hover_text(tr("Lists many folders in parallel with timeouts; suited to SMB and NFS mounts"))
+            .changed()
+        {
+            self.scan.cache.clear();
+            self.scan.index = None;
+            self.scan.rebuild_index();
+            self.scan_current_directory();
+        }
+        if ui
+            .checkbox(&mut self.scan.options.async_io, tr("Async I/O"))
+            .on_hover_text(tr("Keeps many file system calls in flight at once; suited to SFTP and cloud mounts"))
+            .changed()
+        {
+            self.scan.cache.clear();
+            self.scan.index = None;
+            self.scan.rebuild_index();
+            self.scan_current_directory();
+        }
+        let mut low_memory = self.scan.options.summarize_below > 0;
+        if ui
+            .checkbox(&mut low_memory, tr("Low-Memory Mode"))
+            .on_hover_text(tr("Keeps only large files in the scan index and counts smaller ones per folder"))
+            .changed()
+        {
+            self.scan.options.summarize_below = if low_memory { scan::DEFAULT_SUMMARY_THRESHOLD } else { 0 };
+            self.scan.index = None;
+            self.scan.rebuild_index();
+        }
+        if low_memory {
+            let previous = self.scan.options.summarize_below;
+            egui::ComboBox::from_id_source("summarize_below")
+                .selected_text(format!("< {}", i18n::format_bytes(previous)))
+                .show_ui(ui, |ui| {
+                    for threshold in scan::SUMMARY_THRESHOLDS {
+                        let label = format!("< {}", i18n::format_bytes(threshold));
+                        ui.selectable_value(&mut self.scan.options.summarize_below, threshold, label);
+                    }
+                })
+                .response
+                .on_hover_text(tr("Files below this size are only counted"));
+            if self.scan.options.summarize_below != previous {
+                self.scan.index = None;
+                self.scan.rebuild_index();
+            }
+        }
+        if ui
+            .checkbox(&mut self.scan.inode_usage, tr("Inode Usage"))
+            .on_hover_text(tr("Rank folders by how many entries they contain instead of by size"))
+            .changed()
+        {
+            self.scan_current_directory();
+        }
+        ui.label(trf("Total Size: {}", &[&i18n::format_bytes(self.scan.total_size)]));
+        if self.scan.inode_usage {
+            let entries: u64 = self.scan.file_list.iter().map(|f| f.entries).sum();
+            ui.label(trf("Entries: {}", &[&i18n::format_count(entries)]));
+            if let Some(inodes) = self.scan.inodes {
+                render_inodes(ui, inodes);
+            }
+        }
+    }
+
+    fn render_search_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Search:"));
+            if ui.text_edit_singleline(&mut self.view.search_query).changed() {
+                self.update_search();
+            }
+            if ui.checkbox(&mut self.view.search_everywhere, tr("Entire tree")).changed() {
+                self.update_search();
+            }
+
+            ui.checkbox(&mut self.scan.show_all, tr("Show All Files"));
+            ui.add_enabled_ui(!self.scan.show_all, |ui| {
+                let mut min_kib = self.scan.min_size_filter / 1024;
+                if ui
+                    .add(egui::DragValue::new(&mut min_kib).suffix(" KiB").speed(16.0))
+                    .on_hover_text(tr("Minimum size"))
+                    .changed()
+                {
+                    self.scan.min_size_filter = min_kib * 1024;
+                }
+                egui::ComboBox::from_id_source("directory_filter")
+                    .selected_text(tr(self.scan.directory_filter.label()))
+                    .show_ui(ui, |ui| {
+                        for mode in filter::DirectoryFilter::ALL {
+                            ui.selectable_value(&mut self.scan.directory_filter, mode, tr(mode.label()));
+                        }
+                    });
+            });
+            if ui.button(tr("Apply Filter")).clicked() {
+                self.apply_filter();
+            }
+
+            let previous_tag_filter = self.view.tag_filter.clone();
+            egui::ComboBox::from_label(tr("Tag"))
+                .selected_text(self.view.tag_filter.as_deref().unwrap_or(tr("Any")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.view.tag_filter, None, tr("Any"));
+                    for tag in self.tags.all_tags() {
+                        ui.selectable_value(&mut self.view.tag_filter, Some(tag.clone()), tag);
+                    }
+                });
+            if self.view.tag_filter != previous_tag_filter {
+                self.update_search();
+            }
+
+            let previous_group_by = self.view.group_by;
+            egui::ComboBox::from_label(tr("Group by"))
+                .selected_text(tr(self.view.group_by.label()))
+                .show_ui(ui, |ui| {
+                    for group_by in grouping::GroupBy::ALL {
+                        ui.selectable_value(&mut self.view.group_by, group_by, tr(group_by.label()));
+                    }
+                });
+            if self.view.group_by != previous_group_by {
+                self.view.collapsed_groups.clear();
+                self.sort_files();
+                self.update_search();
+            }
+        });
+    }
+
+    fn render_query_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Query:"));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.view.query)
+                    .hint_text(query::EXAMPLE)
+                    .desired_width(420.0)
+                    .code_editor(),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            response.on_hover_text(tr(
+                "Fields: size, name, ext, path, type, entries, modified, accessed. Operators: = != > >= < <= and ~ (contains), joined with and, or, not. Dates are YYYY-MM-DD or a number of days ago like 90d.",
+            ));
+            let run = ui
+                .add_enabled(self.scan.index.is_some(), egui::Button::new(tr("Run")))
+                .on_disabled_hover_text(tr("Available once the tree has been scanned"));
+            if submitted || run.clicked() {
+                self.run_query();
+            }
+            if self.view.query_active {
+                if ui.button(tr("Clear")).clicked() {
+                    self.clear_query();
+                }
+                if ui.button(tr("Export CSV...")).clicked() {
+                    self.export_query_results();
+                }
+            }
+            if ui.button(tr("Save as Report...")).clicked() {
+                self.saved_reports.save_query(&self.view.query, self.scan.current_path.as_deref());
+            }
+            if let Some(error) = &self.view.query_error {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+        });
+    }
+
+    fn render_path_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if a11y::icon_button(ui, "⬆️", tr("Up one level")).clicked() {
+                self.go_up();
+            }
+
+            let mut target = None;
+            if let (Some(current), Some(root)) = (&self.scan.current_path, &self.scan.root_path) {
+                let mut components: Vec<_> = current.components().collect();
+                let root_len = root.components().count();
+                while components.len() > root_len {
+                    let name = components
+                        .last()
+                        .map(|c| paths::display_name(c.as_os_str()))
+                        .unwrap_or_default();
+
+                    if ui.button(name.as_ref()).clicked() {
+                        target = Some(components.iter().collect::<PathBuf>());
+                        break;
+                    }
+                    ui.label(">");
+                    components.pop();
+                }
+            }
+            if let Some(path) = target {
+                self.navigate_to(path);
+            }
+
+            if let Some(header) = &self.scan.imported {
+                let host = if header.host.is_empty() { "?" } else { header.host.as_str() };
+                ui.label(
+                    RichText::new(trf(
+                        "Scan file from {}, saved {}",
+                        &[&host, &audit::format_timestamp(header.scanned_at)],
+                    ))
+                    .color(Color32::GRAY),
+                );
+            }
+        });
+    }
+
+    fn render_tree(&mut self, ui: &mut egui::Ui) {
+        let Some(index) = &self.scan.index else {
+            ui.spinner();
+            ui.label(tr("Indexing..."));
+            return;
+        };
+        if let Some(saved_at) = index.resumed_from {
+            let timestamp = saved_at.duration_since(std::time::UNIX_EPOCH).map_or(0, |age| age.as_secs());
+            ui.label(
+                RichText::new(trf("Continued from a checkpoint saved {}", &[&audit::format_timestamp(timestamp)]))
+                    .small()
+                    .color(Color32::GRAY),
+            );
+        }
+        let size_filter = self.scan.size_filter();
+        let current = self.scan.current_path.as_deref();
+        if let Some(path) = tree::render(ui, index, &mut self.view.expanded, current, size_filter, &self.quotas.store) {
+            self.navigate_to(path);
+        }
+    }
+
+    fn render_tree_results(&mut self, ui: &mut egui::Ui) {
+        let (Some(index), Some(root)) = (&self.scan.index, &self.scan.root_path) else {
+            ui.spinner();
+            ui.label(tr("Indexing..."));
+            return;
+        };
+        let results = &self.view.tree_results;
+        ui.label(trf("{} matches in {}", &[&i18n::format_count(results.len() as u64), &root.display()]));
+        let mut target = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing.y = 0.0;
+                for (position, &id) in results.iter().take(MAX_TREE_RESULTS).enumerate() {
+                    let node = &index.nodes[id];
+                    let relative = node.path.strip_prefix(root).unwrap_or(&node.path);
+                    list_row::ListRow::new(position).show(ui, |ui| {
+                        self.icons.show(ui, &node.path, node.is_dir);
+                        let text = format!("{} - {}", paths::display_path(relative), i18n::format_bytes(node.size));
+                        if node.is_dir {
+                            if ui.button(RichText::new(text).color(Color32::LIGHT_BLUE)).clicked() {
+                                target = Some(node.path.clone());
+                            }
+                        } else {
+                            ui.label(text);
+                        }
+                        if a11y::icon_button(ui, "📂", tr("Go to parent folder")).clicked() {
+                            target = node.path.parent().map(Path::to_path_buf);
+                        }
+                    });
+                }
+                if results.len() > MAX_TREE_RESULTS {
+                    ui.label(RichText::new(trf("Showing the {} largest matches", &[&MAX_TREE_RESULTS])).color(Color32::GRAY));
+                }
+            });
+        if let Some(path) = target {
+            self.view.search_query.clear();
+            self.view.query_active = false;
+            self.navigate_to(path);
+        }
+    }
+
+    fn render_details(&mut self, ui: &mut egui::Ui) {
+        if let Some(checksums) = &mut self.view.checksums {
+            checksums.show(ui);
+            ui.separator();
+        }
+        if let Some(media) = &mut self.view.media_details {
+            media.show(ui);
+            ui.separator();
+        }
+        let file_list = &self.scan.file_list;
+        ui.label(tr("Directory Statistics:"));
+        ui.label(trf("Total items: {}", &[&i18n::format_count(file_list.len() as u64)]));
+        ui.label(trf("Total size: {}", &[&i18n::format_bytes(self.scan.total_size)]));
+        if self.config.preferences.show_exact_bytes {
+            ui.label(i18n::format_exact_bytes(self.scan.total_size));
+        }
+        let on_disk: u64 = file_list.iter().map(|i| i.size).sum();
+        let logical: u64 = file_list.iter().map(|i| i.logical).sum();
+        if on_disk > 0 && logical > on_disk {
+            ui.label(trf(
+                "Before compression: {} ({} smaller on disk)",
+                &[&i18n::format_bytes(logical), &format!("{:.1}×", logical as f64 / on_disk as f64)],
+            ))
+            .on_hover_text(tr("Deleting compressed files frees their size on disk, not their size before compression."));
+        }
+
+        let files_count = file_list.iter().filter(|i| !i.is_dir).count();
+        let dirs_count = file_list.iter().filter(|i| i.is_dir).count();
+        ui.label(trf("Files: {}", &[&i18n::format_count(files_count as u64)]));
+        ui.label(trf("Directories: {}", &[&i18n::format_count(dirs_count as u64)]));
+
+        let volatile_count = file_list.iter().filter(|i| i.volatile).count();
+        if volatile_count > 0 {
+            ui.label(trf("Changed during scan: {}", &[&volatile_count]));
+        }
+
+        if let Some(job) = &self.scan.volume_job {
+            match job.poll() {
+                Some(details) => {
+                    self.scan.volume_details = Some(details);
+                    self.scan.volume_job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+        if let Some(details) = &self.scan.volume_details {
+            render_volume_details(ui, details);
+        } else if self.scan.volume_job.is_some() {
+            ui.separator();
+            ui.spinner();
+        } else if let Some(current_path) = self.scan.current_path.clone().filter(|_| !self.scan.scanning()) {
+            let files: Vec<(PathBuf, u64)> =
+                file_list.iter().filter(|i| !i.is_dir).map(|i| (i.path.clone(), i.size)).collect();
+            self.scan.volume_job = Some(Job::spawn(move || {
+                volume::details(&current_path, files.iter().map(|(path, size)| (path.as_path(), *size)))
+            }));
+        }
+        let read_only = self.read_only();
+        for (action, path, is_dir, size) in self.ops.trash.show(ui, read_only) {
+            self.queue(action, path, is_dir, size);
+        }
+    }
+
+    fn render_errors(&mut self, ui: &mut egui::Ui) {
+        if self.view.errors.is_empty() {
+            ui.label(tr("No errors."));
+            return;
+        }
+        if ui.button(tr("Clear")).clicked() {
+            self.view.errors.clear();
+            return;
+        }
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            for error in self.view.errors.iter().rev() {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+        });
+    }
+
+    /// Buttons that open the report windows; returns true when one was clicked.
+    fn render_report_buttons(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut reports = vec![
+            (tr("Hidden Usage"), &mut self.orphans.open),
+            (tr("Usage by User"), &mut self.users_report.open),
+            (tr("Security Report"), &mut self.security_report.open),
+            (tr("Container Storage"), &mut self.docker.open),
+            (tr("Package Caches"), &mut self.caches.open),
+            (tr("Browser Data"), &mut self.browsers.open),
+            (tr("Mail Storage"), &mut self.mail.open),
+            (tr("Game Libraries"), &mut self.games.open),
+            (tr("Cloud Sync Folders"), &mut self.cloud_sync.open),
+            (tr("Storage Categories"), &mut self.storage.open),
+            (tr("Snapshots"), &mut self.snapshots.open),
+            (tr("Directory Budgets"), &mut self.quotas.open),
+            (tr("Size Histogram"), &mut self.histogram.open),
+            (tr("Similar Media"), &mut self.similar.open),
+            (tr("Low-Value Media"), &mut self.low_value.open),
+            (tr("Photo Library"), &mut self.photos.open),
+            (tr("Git Repositories"), &mut self.git_repos.open),
+            (tr("Build Artifacts"), &mut self.artifacts.open),
+            (tr("Log Files"), &mut self.logs.open),
+            (tr("Crash Dumps"), &mut self.crashes.open),
+            (tr("Cold Data"), &mut self.cold.open),
+            (tr("Archive"), &mut self.archive.open),
+        ];
+        if cfg!(windows) {
+            reports.push((tr("Windows Cleanup"), &mut self.windows_cleanup.open));
+        }
+        if cfg!(target_os = "linux") {
+            reports.push((tr("Flatpak, Snap and AppImage"), &mut self.linux_apps.open));
+        }
+        if !cloud::Provider::available().is_empty() {
+            reports.push((tr("Cloud Storage"), &mut self.cloud.open));
+        }
+        reports.push((tr("Plugins"), &mut self.plugins.open));
+        let mut clicked = false;
+        for (label, open) in reports {
+            if ui.button(label).clicked() {
+                *open = true;
+                clicked = true;
+            }
+        }
+        let saved_reports = match self.saved_reports.changed_count() {
+            0 => tr("Saved Reports").to_string(),
+            changed => trf("Saved Reports ({} changed)", &[&changed]),
+        };
+        if ui.button(saved_reports).clicked() {
+            self.saved_reports.open = true;
+            clicked = true;
+        }
+        clicked |= self.plugins.report_buttons(ui, self.scan.root_path.as_deref());
+
+        ui.separator();
+        let has_index = self.scan.index.is_some();
+        let export = ui
+            .add_enabled(has_index, egui::Button::new(tr("Export HTML Report...")))
+            .on_disabled_hover_text(tr("Available once the tree has been scanned"));
+        if export.clicked() {
+            clicked = true;
+            self.export_html_report();
+        }
+        let export = ui
+            .add_enabled(has_index, egui::Button::new(tr("Export Scan...")))
+            .on_hover_text(tr("Saves the whole scan to a file that can be opened on another machine"))
+            .on_disabled_hover_text(tr("Available once the tree has been scanned"));
+        if export.clicked() {
+            clicked = true;
+            self.export_scan();
+        }
+        let export = ui
+            .add_enabled(has_index, egui::Button::new(tr("Export for ncdu...")))
+            .on_disabled_hover_text(tr("Available once the tree has been scanned"));
+        if export.clicked() {
+            clicked = true;
+            self.export_ncdu();
+        }
+        clicked
+    }
+
+    fn export_html_report(&mut self) {
+        let Some(index) = &self.scan.index else {
+            return;
+        };
+        let Some(path) = dialogs::save_file("disk-usage.html") else {
+            return;
+        };
+        let result = html_report::render(index)
+            .and_then(|html| fs::write(&path, html).map_err(|e| format!("Error writing {}: {}", path.display(), e)));
+        if let Err(error) = result {
+            self.report_error(error);
+        }
+    }
+
+    fn set_panel_open(&mut self, panel: layout::Panel, open: bool) {
+        match panel {
+            layout::Panel::Tree => self.view.show_tree = open,
+            layout::Panel::Details => self.view.show_details = open,
+            _ => self.layout.set_open(panel, open),
+        }
+    }
+
+    /// The docked panels, around the file list.
+    pub(super) fn render_dock(&mut self, ctx: &egui::Context) {
+        self.layout.set_open(layout::Panel::Tree, self.view.show_tree);
+        self.layout.set_open(layout::Panel::Details, self.view.show_details);
+        let mut dock = std::mem::replace(&mut self.layout.dock, egui_dock::DockState::new(Vec::new()));
+        egui_dock::DockArea::new(&mut dock)
+            .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
+            .show(ctx, &mut Panels { app: self });
+        self.layout.dock = dock;
+    }
+
+    fn render_files(&mut self, ui: &mut egui::Ui) {
+        let scan = &self.scan;
+        if scan.scanning() || (scan.lists_from_index() && scan.indexing() && scan.file_list.is_empty()) {
+            ui.spinner();
+            ui.heading(tr("Scanning..."));
+        } else if self.whole_tree_search() {
+            self.render_tree_results(ui);
+        } else if !self.view.filtered.is_empty() {
+            self.render_file_list(ui);
+        }
+    }
+
+    pub(super) fn render_status_bar(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let scan = &self.scan;
+                let activity = if scan.scanning() {
+                    Some(tr("Scanning..."))
+                } else if scan.indexing() {
+                    Some(tr("Indexing..."))
+                } else if scan.importing() {
+                    Some(tr("Importing..."))
+                } else {
+                    None
+                };
+                if let Some(activity) = activity {
+                    ui.spinner();
+                    ui.label(activity);
+                    ui.separator();
+                }
+
+                let summary = self.ops.operations.summary();
+                if summary.running > 0 || summary.queued > 0 {
+                    if summary.total > 0 {
+                        let fraction = summary.done as f32 / summary.total as f32;
+                        ui.add(egui::ProgressBar::new(fraction).desired_width(120.0).show_percentage());
+                    } else if summary.running > 0 {
+                        ui.spinner();
+                    }
+                    let text = trf(
+                        "{} running, {} queued",
+                        &[&i18n::format_count(summary.running as u64), &i18n::format_count(summary.queued as u64)],
+                    );
+                    if ui.link(text).clicked() {
+                        self.layout.set_open(layout::Panel::Operations, true);
+                    }
+                    ui.separator();
+                }
+
+                if let Some(delta) = scan.root_path.as_deref().and_then(|root| self.ops.freed.free_space_delta(root)) {
+                    let bytes = i18n::format_bytes(delta.unsigned_abs());
+                    let (text, color) = match delta {
+                        0 => (tr("Free space unchanged").to_string(), Color32::GRAY),
+                        1.. => (trf("Free space +{} this session", &[&bytes]), Color32::GREEN),
+                        _ => (trf("Free space -{} this session", &[&bytes]), Color32::YELLOW),
+                    };
+                    ui.label(RichText::new(text).color(color))
+                        .on_hover_text(tr("Change in free space on this volume, including changes made by other programs"));
+                    ui.separator();
+                }
+
+                let freed = &self.ops.freed;
+                let session = freed.session_bytes();
+                if session > 0 {
+                    ui.label(trf("You've freed {}", &[&i18n::format_bytes(session)]));
+                } else {
+                    ui.label(tr("Nothing freed yet this session"));
+                }
+                ui.label(trf(
+                    "({} all time)",
+                    &[&i18n::format_bytes(freed.lifetime.bytes_freed)],
+                ));
+                if ui.button(tr("Summary")).clicked() {
+                    self.view.show_freed_summary = true;
+                }
+
+                if let Some(error) = self.view.errors.last() {
+                    ui.separator();
+                    let label = egui::Label::new(RichText::new(format!("⚠ {}", error)).color(Color32::RED))
+                        .truncate(true)
+                        .sense(egui::Sense::click());
+                    if ui.add(label).on_hover_text(tr("Show all errors")).clicked() {
+                        self.layout.set_open(layout::Panel::Errors, true);
+                    }
+                }
+            });
+        });
+    }
+
+    pub(super) fn render_tag_editor(&mut self, ctx: &egui::Context) {
+        let Some((path, mut entry)) = self.view.tag_editor.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new(tr("Tags & Notes"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(path.display().to_string());
+                ui.horizontal_wrapped(|ui| {
+                    for tag in self.tags.all_tags() {
+                        let mut selected = entry.tags.contains(&tag);
+                        if ui.toggle_value(&mut selected, &tag).changed() {
+                            if selected {
+                                entry.tags.insert(tag);
+                            } else {
+                                entry.tags.remove(&tag);
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.view.new_tag);
+                    if ui.button(tr("Add Tag")).clicked() && !self.view.new_tag.trim().is_empty() {
+                        entry.tags.insert(self.view.new_tag.trim().to_string());
+                        self.view.new_tag.clear();
+                    }
+                });
+                ui.label(tr("Note:"));
+                ui.text_edit_multiline(&mut entry.note);
+                save = ui.button(tr("Save")).clicked();
+            });
+
+        if save {
+            self.tags.set(&path, entry);
+            let _ = self.tags.save();
+            self.update_search();
+        } else if open {
+            self.view.tag_editor = Some((path, entry));
+        }
+    }
+
+    fn render_sort_header(&mut self, ui: &mut egui::Ui) {
+        let mut columns_changed = false;
+        ui.horizontal(|ui| {
+            let columns = [
+                (tr("Name"), false, false),
+                (tr("Own size"), true, true),
+                (tr("Total size"), true, false),
+            ];
+            for (label, by_size, by_own_size) in columns {
+                let view = &self.view;
+                let selected = view.sort_by_size == by_size && (!by_size || view.sort_by_own_size == by_own_size);
+                if ui.selectable_label(selected, label).clicked() {
+                    self.view.sort_by_size = by_size;
+                    self.view.sort_by_own_size = by_own_size;
+                    self.sort_files();
+                    self.update_search();
+                }
+            }
+            ui.menu_button(tr("Columns"), |ui| {
+                columns_changed |= self.config.columns.chooser(ui, self.scan.root_path.as_deref());
+            });
+        });
+        let visible = self.visible_columns();
+        let sorted = match (self.view.sort_by_size, self.view.sort_by_own_size) {
+            (true, true) => Some(columns::Column::OwnSize),
+            (true, false) => Some(columns::Column::Size),
+            (false, _) => None,
+        };
+        let clicked = ui
+            .horizontal(|ui| {
+                let (changed, clicked) =
+                    self.config.columns.header(ui, self.scan.root_path.as_deref(), &visible, sorted);
+                columns_changed |= changed;
+                clicked
+            })
+            .inner;
+        if let Some(column) = clicked {
+            self.view.sort_by_size = true;
+            self.view.sort_by_own_size = column == columns::Column::OwnSize;
+            self.sort_files();
+            self.update_search();
+        }
+        if columns_changed {
+            let _ = self.config.columns.save();
+            if self.scan.lists_from_index() && self.visible_columns().contains(&columns::Column::Accessed) {
+                self.scan_current_directory();
+            }
+        }
+    }
+
+    fn render_file_list(&mut self, ui: &mut egui::Ui) {
+        let read_only = self.read_only();
+        match self.view.selection.show(ui, read_only) {
+            Some(reclaim::SelectionAction::Delete) => self.confirm_selection_deletion(),
+            Some(reclaim::SelectionAction::Move) => {
+                if let Some(destination) = dialogs::pick_folder() {
+                    self.queue_selection(operations::Action::Move(destination));
+                }
+            }
+            Some(reclaim::SelectionAction::Copy) => {
+                if let Some(destination) = dialogs::pick_folder() {
+                    self.queue_selection(operations::Action::Copy(destination));
+                }
+            }
+            None => {}
+        }
+        self.render_sort_header(ui);
+        let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+        if let Some(offset) = self.view.restore_scroll.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let (scan, view) = (&self.scan, &self.view);
+        let group_by = view.group_by;
+        let group_key = |item: &FileInfo| grouping::key(group_by, &item.path, &item.name, item.is_dir, item.size, item.modified);
+        let mut subtotals: HashMap<grouping::GroupKey, (u64, u64)> = HashMap::new();
+        for &position in &view.filtered {
+            let item = &scan.file_list[position];
+            if let Some(group) = group_key(item) {
+                let subtotal = subtotals.entry(group).or_default();
+                subtotal.0 += 1;
+                subtotal.1 += item.size;
+            }
+        }
+        let columns = self.visible_columns();
+        let mut rows = Rows {
+            scan,
+            view,
+            preferences: &self.config.preferences,
+            bookmarks: &self.bookmarks,
+            tags: &self.tags,
+            quotas: &self.quotas,
+            plugins: &mut self.plugins,
+            icons: &mut self.icons,
+            columns,
+            read_only,
+            shown: 0,
+            actions: Vec::new(),
+            drop_targets: Vec::new(),
+        };
+        let output = scroll_area.show(ui, |ui| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            let mut current_group = None;
+            for &position in &view.filtered {
+                let item = &scan.file_list[position];
+                if let Some(group) = group_key(item) {
+                    let collapsed = view.collapsed_groups.contains(&group);
+                    if current_group.as_ref() != Some(&group) {
+                        let (count, size) = subtotals[&group];
+                        let header = format!(
+                            "{} {} - {}, {}",
+                            if collapsed { "⏵" } else { "⏷" },
+                            group.label(),
+                            trf("{} items", &[&i18n::format_count(count)]),
+                            i18n::format_bytes(size)
+                        );
+                        if ui.selectable_label(false, RichText::new(header).strong()).clicked() {
+                            rows.actions.push(RowAction::ToggleGroup(group.clone()));
+                        }
+                        current_group = Some(group);
+                    }
+                    if collapsed {
+                        continue;
+                    }
+                }
+
+                rows.show(ui, item, 0);
+            }
+
+            if let Some(current_path) = scan.current_path.as_deref().filter(|_| scan.lists_from_index()) {
+                let (count, size) = scan.small_files(current_path);
+                if count > 0 {
+                    ui.label(RichText::new(tree::small_files_label(count, size)).color(Color32::GRAY));
+                }
+            }
+            if scan.options.respect_ignores && view.show_ignored_size && scan.ignored.count > 0 {
+                ui.label(RichText::new(format!(
+                    "🚫 {}",
+                    trf(
+                        "{} ignored entries - {}",
+                        &[&i18n::format_count(scan.ignored.count), &i18n::format_bytes(scan.ignored.size)],
+                    )
+                )).color(Color32::GRAY));
+            }
+        });
+        let (actions, drop_targets) = (rows.actions, rows.drop_targets);
+        self.view.scroll_offset = output.state.offset.y;
+        for action in actions {
+            self.apply_row_action(action);
+        }
+        self.drag_row(ui, &drop_targets);
+    }
+
+    /// Follows a row being dragged; dropping it on another folder's row asks to move it there.
+    fn drag_row(&mut self, ui: &mut egui::Ui, targets: &[(PathBuf, egui::Rect)]) {
+        let Some(item) = &self.view.dragging else {
+            return;
+        };
+        let pointer = ui.input(|i| i.pointer.hover_pos());
+        let target = pointer
+            .and_then(|pointer| targets.iter().find(|(_, rect)| rect.contains(pointer)))
+            .filter(|(folder, _)| Some(folder.as_path()) != item.path.parent() && !folder.starts_with(&item.path));
+        if let Some((_, rect)) = target {
+            ui.painter().rect_stroke(*rect, 2.0, egui::Stroke::new(1.5, Color32::LIGHT_BLUE));
+        }
+        ui.ctx().set_cursor_icon(if target.is_some() { egui::CursorIcon::Grabbing } else { egui::CursorIcon::NoDrop });
+        egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("dragged row"), |ui| {
+            ui.horizontal(|ui| {
+                self.icons.show(ui, &item.path, item.is_dir);
+                ui.label(paths::display_name(&item.name));
+            });
+        });
+
+        if !ui.input(|i| i.pointer.any_released()) {
+            return;
+        }
+        let destination = target.map(|(folder, _)| folder.clone());
+        if let (Some(item), Some(destination)) = (self.view.dragging.take(), destination) {
+            self.ops.move_confirmation = Some((item, destination));
+        }
+    }
+
+    fn apply_row_action(&mut self, action: RowAction) {
+        match action {
+            RowAction::Navigate(path) => self.navigate_to(path),
+            RowAction::ToggleInline(path) => self.toggle_inline(&path),
+            RowAction::ToggleGroup(group) => {
+                if !self.view.collapsed_groups.remove(&group) {
+                    self.view.collapsed_groups.insert(group);
+                }
+            }
+            RowAction::Select(path, selected) => self.view.selection.set(path, selected),
+            RowAction::ToggleBookmark(root, path) => {
+                self.bookmarks.toggle(&root, &path);
+                let _ = self.bookmarks.save();
+            }
+            RowAction::Trash(item) => {
+                if self.config.preferences.confirm_trash {
+                    self.ops.trash_confirmation = Some(item);
+                } else if let Err(error) = self.trash_item(&item) {
+                    self.report_error(error);
+                }
+            }
+            RowAction::ConfirmDelete(item) => {
+                self.ops.typed_confirmation.clear();
+                self.ops.delete_confirmation = vec![item];
+            }
+            RowAction::Queue(action, item) => self.queue_operation(action, &item),
+            RowAction::Archive(path, size) => self.archive.add(path, size),
+            RowAction::ShowProcesses(path) => self.open_files.inspect(path),
+            RowAction::BrowseImage(path) => self.disk_image.inspect(path),
+            RowAction::Details(item) => {
+                self.view.show_details = true;
+                self.view.checksums = (!item.is_dir).then(|| hashes::Checksums::new(item.path.clone(), item.size));
+                self.view.media_details = (!item.is_dir && media::is_media(&item.path, item.size))
+                    .then(|| media::MediaDetails::new(item.path, item.size));
+            }
+            RowAction::EditTags(path) => {
+                let existing = self.tags.get(&path).cloned().unwrap_or_default();
+                self.view.tag_editor = Some((path, existing));
+            }
+            RowAction::StartDrag(item) => self.view.dragging = Some(item),
+        }
+    }
+}
+
+fn render_inodes(ui: &mut egui::Ui, inodes: volume::Inodes) {
+    let fraction = inodes.fraction_used();
+    let text = trf(
+        "Inodes: {} of {} used ({}%)",
+        &[
+            &i18n::format_count(inodes.used()),
+            &i18n::format_count(inodes.total),
+            &((fraction * 100.0).round() as u64),
+        ],
+    );
+    let color = if fraction > 0.9 { Color32::RED } else { Color32::GRAY };
+    ui.label(RichText::new(text).color(color));
+}
+
+fn render_volume_details(ui: &mut egui::Ui, details: &volume::VolumeDetails) {
+    ui.separator();
+    ui.label(tr("Volume:"));
+    if let Some(volume) = &details.volume {
+        if let Some(fs_type) = &volume.fs_type {
+            ui.label(trf("Filesystem: {}", &[fs_type]));
+        }
+        ui.label(trf("Capacity: {}", &[&i18n::format_bytes(volume.total)]));
+        ui.label(trf("Available: {}", &[&i18n::format_bytes(volume.available)]));
+        if let Some(inodes) = volume.inodes {
+            render_inodes(ui, inodes);
+        }
+    }
+    if details.cloned_files > 0 {
+        ui.label(trf(
+            "Cloned files: {} ({} shared with other copies)",
+            &[&i18n::format_count(details.cloned_files), &i18n::format_bytes(details.shared_bytes)],
+        ));
+    }
+    if cfg!(target_os = "macos") {
+        if details.local_snapshots > 0 {
+            ui.label(trf("Local snapshots: {}", &[&details.local_snapshots]));
+        }
+        if let Some(purgeable) = details.purgeable {
+            ui.label(trf("Purgeable: {}", &[&i18n::format_bytes(purgeable)]));
+        }
+        ui.label(RichText::new(tr(volume::PURGEABLE_NOTE)).small().color(Color32::GRAY));
+    }
+}
@@ -0,0 +1,302 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicU64,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    dialogs, dump,
+    error::Error,
+    hashes,
+    i18n::{self, tr, trf},
+    operations::{self, Action, Progress},
+    paths, volume,
+};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Written beside the copy so the archive can be checked and found again later.
+#[derive(Serialize)]
+struct Manifest {
+    source: String,
+    host: String,
+    archived_at: u64,
+    algorithm: &'static str,
+    originals_deleted: bool,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Serialize)]
+pub struct ManifestFile {
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+/// Where the manifest for an archived item goes: `<name>.manifest.json` in the destination.
+pub fn manifest_path(path: &Path, destination: &Path) -> Option<PathBuf> {
+    let mut name = path.file_name()?.to_os_string();
+    name.push(".manifest.json");
+    Some(destination.join(name))
+}
+
+/// Copies the file while hashing what was read, so the source is only read once.
+fn copy_hashed(source: &Path, destination: &Path, progress: &Progress) -> Result<String, Error> {
+    let error = |e| Error::Copy { path: source.to_path_buf(), source: e };
+    let mut input = File::open(paths::extended(source)).map_err(error)?;
+    let mut output = File::create(paths::extended(destination)).map_err(error)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        progress.checkpoint()?;
+        let read = input.read(&mut buffer).map_err(error)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read]).map_err(error)?;
+        progress.advance(read as u64);
+    }
+    output.sync_all().map_err(error)?;
+    let permissions = input.metadata().map_err(error)?.permissions();
+    output.set_permissions(permissions).map_err(error)?;
+    Ok(hashes::hex(&hasher.finalize()))
+}
+
+/// Copies `source` to `target`, then reads the copy back and compares it with SHA-256 hashes
+/// taken while reading the source.
+pub fn copy_and_verify(source: &Path, target: &Path, progress: &Progress) -> Result<Vec<ManifestFile>, Error> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(source).follow_links(false) {
+        progress.checkpoint()?;
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let destination = if relative.as_os_str().is_empty() { target.to_path_buf() } else { target.join(relative) };
+        let error = |source| Error::Write { path: destination.clone(), source };
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(paths::extended(&destination)).map_err(error)?;
+        } else if entry.file_type().is_symlink() {
+            operations::copy_link(entry.path(), &destination).map_err(error)?;
+        } else {
+            let sha256 = copy_hashed(entry.path(), &destination, progress)?;
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+            let name = if relative.as_os_str().is_empty() { Path::new(entry.file_name()) } else { relative };
+            files.push(ManifestFile { path: name.to_string_lossy().replace('\\', "/"), size, sha256 });
+        }
+    }
+
+    // Read the copy back from the destination and compare it with what was read from the source.
+    let mut mismatched = Vec::new();
+    for file in &files {
+        progress.checkpoint()?;
+        let copy = if target.is_dir() { target.join(&file.path) } else { target.to_path_buf() };
+        let sha256 = hashes::hash_file(&copy, hashes::Algorithm::Sha256, &AtomicU64::new(0))?;
+        if sha256 != file.sha256 {
+            mismatched.push(copy);
+        }
+    }
+    if !mismatched.is_empty() {
+        return Err(Error::Verification(mismatched));
+    }
+    Ok(files)
+}
+
+/// Copies `path` into `destination`, verifies the copy against SHA-256 hashes of the source
+/// and writes the manifest. The originals are left alone; the queue deletes them afterwards
+/// when asked to and then calls `record_deletion`.
+pub fn archive(path: &Path, destination: &Path, progress: &Progress) -> Result<(), Error> {
+    let name = path.file_name().ok_or(Error::NoName("archive"))?;
+    if paths::is_within(destination, path) {
+        return Err(Error::IntoItself(path.to_path_buf()));
+    }
+    let target = destination.join(name);
+    let manifest = manifest_path(path, destination).ok_or(Error::NoName("archive"))?;
+    for existing in [&target, &manifest] {
+        if existing.exists() {
+            return Err(Error::Exists(existing.clone()));
+        }
+    }
+
+    let files = match copy_and_verify(path, &target, progress) {
+        Ok(files) => files,
+        Err(e) => {
+            let _ = if target.is_dir() { fs::remove_dir_all(&target) } else { fs::remove_file(&target) };
+            return Err(e);
+        }
+    };
+    let manifest_data = Manifest {
+        source: path.to_string_lossy().into_owned(),
+        host: dump::host_name(),
+        archived_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs()),
+        algorithm: hashes::Algorithm::Sha256.label(),
+        originals_deleted: false,
+        files,
+    };
+    let json = serde_json::to_string_pretty(&manifest_data).map_err(|e| format!("Error writing manifest: {}", e))?;
+    fs::write(&manifest, json).map_err(|source| Error::Write { path: manifest, source })
+}
+
+/// Marks the originals as deleted in the manifest of an archived item, once they are gone.
+pub fn record_deletion(path: &Path, destination: &Path) -> Result<(), Error> {
+    let manifest = manifest_path(path, destination).ok_or(Error::NoName("archive"))?;
+    let error = |source| Error::Write { path: manifest.clone(), source };
+    let json = fs::read_to_string(&manifest).map_err(error)?;
+    let mut data: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| format!("Error reading manifest: {}", e))?;
+    data["originals_deleted"] = serde_json::Value::Bool(true);
+    let json = serde_json::to_string_pretty(&data).map_err(|e| format!("Error writing manifest: {}", e))?;
+    fs::write(&manifest, json).map_err(error)
+}
+
+#[derive(Default)]
+pub struct ArchiveState {
+    pub open: bool,
+    sources: Vec<(PathBuf, u64)>,
+    destination: String,
+    delete_originals: bool,
+    confirm: bool,
+}
+
+impl ArchiveState {
+    /// Adds a folder to the selection and brings the window up.
+    pub fn add(&mut self, path: PathBuf, size: u64) {
+        self.open = true;
+        if !self.sources.iter().any(|(source, _)| *source == path) {
+            self.sources.push((path, size));
+        }
+    }
+
+    fn tasks(&mut self) -> Vec<(Action, PathBuf, u64)> {
+        let action = Action::Archive {
+            destination: PathBuf::from(self.destination.trim()),
+            delete_originals: self.delete_originals,
+        };
+        self.sources.drain(..).map(|(path, size)| (action.clone(), path, size)).collect()
+    }
+
+    /// Draws the window and returns the archive tasks the user confirmed, with the size of
+    /// each source.
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) -> Vec<(Action, PathBuf, u64)> {
+        let mut queued = Vec::new();
+        let mut open = self.open;
+        egui::Window::new(tr("Archive"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(tr(
+                        "Copies folders to an external drive or network share, checks every file against a SHA-256 hash and writes a manifest beside the copy.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.separator();
+                if self.sources.is_empty() {
+                    ui.label(tr("Choose folders to archive from the Cold Data report or an item's menu."));
+                }
+                let mut removed = None;
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    egui::Grid::new("archive_sources").striped(true).show(ui, |ui| {
+                        for (position, (path, size)) in self.sources.iter().enumerate() {
+                            ui.label(paths::display_path(path));
+                            ui.label(i18n::format_bytes(*size));
+                            if ui.small_button(tr("Remove")).clicked() {
+                                removed = Some(position);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if let Some(position) = removed {
+                    self.sources.remove(position);
+                }
+                let total: u64 = self.sources.iter().map(|(_, size)| size).sum();
+                ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("Destination:"));
+                    ui.text_edit_singleline(&mut self.destination);
+                    if ui.button(tr("Browse...")).clicked() {
+                        if let Some(folder) = dialogs::pick_folder() {
+                            self.destination = folder.to_string_lossy().into_owned();
+                        }
+                    }
+                });
+                let destination = PathBuf::from(self.destination.trim());
+                let valid = !self.destination.trim().is_empty() && destination.is_dir();
+                if !self.destination.trim().is_empty() {
+                    match volume::info(&destination).filter(|_| valid) {
+                        Some(info) if info.available < total => {
+                            ui.label(
+                                RichText::new(trf(
+                                    "Only {} free at the destination",
+                                    &[&i18n::format_bytes(info.available)],
+                                ))
+                                .color(Color32::RED),
+                            );
+                        }
+                        Some(info) => {
+                            ui.label(trf("{} free at the destination", &[&i18n::format_bytes(info.available)]));
+                        }
+                        None => {
+                            ui.label(RichText::new(tr("The destination folder doesn't exist")).color(Color32::RED));
+                        }
+                    }
+                }
+                ui.add_enabled(
+                    !read_only,
+                    egui::Checkbox::new(&mut self.delete_originals, tr("Delete originals after verifying")),
+                )
+                .on_disabled_hover_text(tr("Disabled in read-only mode"));
+
+                let button = egui::Button::new(trf(
+                    "Archive {} Folders",
+                    &[&i18n::format_count(self.sources.len() as u64)],
+                ));
+                if ui.add_enabled(valid && !self.sources.is_empty(), button).clicked() {
+                    if self.delete_originals && !read_only {
+                        self.confirm = true;
+                    } else {
+                        self.delete_originals = false;
+                        queued = self.tasks();
+                    }
+                }
+            });
+        self.open = open;
+
+        if self.confirm {
+            let mut choice = None;
+            egui::Window::new(tr("Confirm Archive"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf(
+                        "Archive {} folders to {} and delete the originals once each copy is verified?",
+                        &[&i18n::format_count(self.sources.len() as u64), &self.destination.trim()],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Yes")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("No")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            if choice == Some(true) {
+                queued = self.tasks();
+            }
+            if choice.is_some() {
+                self.confirm = false;
+            }
+        }
+        queued
+    }
+}
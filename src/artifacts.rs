@@ -0,0 +1,252 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    paths,
+};
+
+const MONTH: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const DEFAULT_MONTHS: u32 = 3;
+
+/// Build output folders and the files that mark their parent as a project of that kind, so
+/// an unrelated folder that happens to be called `build` isn't offered for deletion.
+const ARTIFACT_KINDS: [(&str, &[&str]); 5] = [
+    ("target", &["Cargo.toml"]),
+    ("node_modules", &["package.json"]),
+    ("build", &["build.gradle", "build.gradle.kts", "CMakeLists.txt", "package.json", "setup.py", "pyproject.toml"]),
+    (".venv", &["pyproject.toml", "requirements.txt", "setup.py", "Pipfile"]),
+    ("dist", &["package.json", "setup.py", "pyproject.toml"]),
+];
+
+pub struct Artifact {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Newest file inside, i.e. roughly the last build.
+    pub built: Option<SystemTime>,
+    /// Newest file in the project outside its build output.
+    pub touched: Option<SystemTime>,
+}
+
+impl Artifact {
+    fn project(&self) -> &Path {
+        self.path.parent().unwrap_or(&self.path)
+    }
+
+    fn untouched_for(&self, age: Duration) -> bool {
+        heatmap::age(self.touched.or(self.built)).is_some_and(|untouched| untouched >= age)
+    }
+}
+
+fn is_artifact(path: &Path, name: &str) -> bool {
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    ARTIFACT_KINDS
+        .iter()
+        .filter(|(kind, _)| *kind == name)
+        .any(|(_, markers)| markers.iter().any(|marker| parent.join(marker).is_file()))
+}
+
+/// Finds build output folders in the scan, leaving out those nested inside another one.
+pub fn find_artifacts(index: &ScanIndex) -> Vec<Artifact> {
+    let names: HashSet<&str> = ARTIFACT_KINDS.iter().map(|(name, _)| *name).collect();
+    let mut artifacts = Vec::new();
+    let mut pending = vec![0];
+    while let Some(id) = pending.pop() {
+        let node = &index.nodes[id];
+        if !node.is_dir {
+            continue;
+        }
+        if node.name.to_str().is_some_and(|name| names.contains(name) && is_artifact(&node.path, name)) {
+            let touched = node.parent.and_then(|parent| {
+                index.nodes[parent]
+                    .children
+                    .iter()
+                    .map(|&sibling| &index.nodes[sibling])
+                    .filter(|sibling| {
+                        !(sibling.is_dir && sibling.name.to_str().is_some_and(|name| names.contains(name)))
+                    })
+                    .filter_map(|sibling| sibling.modified)
+                    .max()
+            });
+            artifacts.push(Artifact { path: node.path.clone(), size: node.size, built: node.modified, touched });
+            continue;
+        }
+        pending.extend(node.children.iter().copied());
+    }
+    artifacts.sort_by_key(|artifact| std::cmp::Reverse(artifact.size));
+    artifacts
+}
+
+fn age_text(time: Option<SystemTime>) -> String {
+    match heatmap::age(time) {
+        Some(age) => trf("{} days ago", &[&i18n::format_count(age.as_secs() / (24 * 60 * 60))]),
+        None => "-".to_string(),
+    }
+}
+
+pub struct ArtifactsState {
+    pub open: bool,
+    artifacts: Option<Vec<Artifact>>,
+    months: u32,
+    selected: HashSet<PathBuf>,
+    confirm: bool,
+}
+
+impl Default for ArtifactsState {
+    fn default() -> Self {
+        Self { open: false, artifacts: None, months: DEFAULT_MONTHS, selected: HashSet::new(), confirm: false }
+    }
+}
+
+impl ArtifactsState {
+    /// Draws the window and returns the folders the user confirmed for deletion, with their
+    /// sizes.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>, read_only: bool) -> Vec<(PathBuf, u64)> {
+        let mut clean = Vec::new();
+        let mut open = self.open;
+        egui::Window::new(tr("Build Artifacts"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr(
+                        "Finds target, node_modules, build, .venv and dist folders of projects. They can be rebuilt, so cleaning those of dormant projects is safe.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Find Build Artifacts")).clicked() {
+                        self.artifacts = Some(find_artifacts(index));
+                        self.selected.clear();
+                    }
+                    ui.label(tr("Projects untouched for"));
+                    ui.add(egui::DragValue::new(&mut self.months).clamp_range(1..=120).suffix(tr(" months")));
+                });
+                let Some(artifacts) = &self.artifacts else {
+                    return;
+                };
+                if artifacts.is_empty() {
+                    ui.label(tr("No build artifacts found."));
+                    return;
+                }
+                let age = MONTH * self.months;
+                let total: u64 = artifacts.iter().map(|artifact| artifact.size).sum();
+                let dormant: Vec<&Artifact> = artifacts.iter().filter(|artifact| artifact.untouched_for(age)).collect();
+                let dormant_size: u64 = dormant.iter().map(|artifact| artifact.size).sum();
+                ui.label(trf(
+                    "{} folders, {} in total; {} in projects untouched for {} months",
+                    &[
+                        &i18n::format_count(artifacts.len() as u64),
+                        &i18n::format_bytes(total),
+                        &i18n::format_bytes(dormant_size),
+                        &self.months,
+                    ],
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Select Dormant")).clicked() {
+                        self.selected = dormant.iter().map(|artifact| artifact.path.clone()).collect();
+                    }
+                    if ui.button(tr("Select None")).clicked() {
+                        self.selected.clear();
+                    }
+                    let selected_size: u64 = artifacts
+                        .iter()
+                        .filter(|artifact| self.selected.contains(&artifact.path))
+                        .map(|artifact| artifact.size)
+                        .sum();
+                    let button = egui::Button::new(trf(
+                        "Clean {} Selected ({})",
+                        &[&i18n::format_count(self.selected.len() as u64), &i18n::format_bytes(selected_size)],
+                    ));
+                    if ui
+                        .add_enabled(!read_only && !self.selected.is_empty(), button)
+                        .on_disabled_hover_text(if read_only {
+                            tr("Disabled in read-only mode")
+                        } else {
+                            tr("Select the folders to clean first")
+                        })
+                        .clicked()
+                    {
+                        self.confirm = true;
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("build_artifacts").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label(tr("Folder"));
+                        ui.label(tr("Size"));
+                        ui.label(tr("Last build"));
+                        ui.label(tr("Project last changed"));
+                        ui.end_row();
+                        for artifact in artifacts {
+                            let mut selected = self.selected.contains(&artifact.path);
+                            if ui.checkbox(&mut selected, "").changed() {
+                                if selected {
+                                    self.selected.insert(artifact.path.clone());
+                                } else {
+                                    self.selected.remove(&artifact.path);
+                                }
+                            }
+                            let color = if artifact.untouched_for(age) { Color32::YELLOW } else { ui.visuals().text_color() };
+                            ui.label(RichText::new(paths::display_path(&artifact.path)).color(color))
+                                .on_hover_text(paths::display_path(artifact.project()));
+                            ui.label(i18n::format_bytes(artifact.size));
+                            ui.label(age_text(artifact.built));
+                            ui.label(age_text(artifact.touched));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+
+        if self.confirm {
+            let mut choice = None;
+            egui::Window::new(tr("Confirm Cleaning"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf(
+                        "Permanently delete {} build folders? Projects rebuild them when needed.",
+                        &[&i18n::format_count(self.selected.len() as u64)],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Yes")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("No")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            if choice == Some(true) {
+                if let Some(artifacts) = &mut self.artifacts {
+                    let (chosen, kept): (Vec<Artifact>, Vec<Artifact>) =
+                        artifacts.drain(..).partition(|artifact| self.selected.contains(&artifact.path));
+                    *artifacts = kept;
+                    clean = chosen.into_iter().map(|artifact| (artifact.path, artifact.size)).collect();
+                }
+                self.selected.clear();
+            }
+            if choice.is_some() {
+                self.confirm = false;
+            }
+        }
+        clean
+    }
+}
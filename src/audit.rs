@@ -0,0 +1,196 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config,
+    i18n::{self, tr, trf},
+    paths,
+};
+
+const AUDIT_FILE: &str = "audit.jsonl";
+
+pub const DELETE: &str = "delete";
+pub const SECURE_DELETE: &str = "secure delete";
+pub const MOVE: &str = "move";
+pub const TRASH: &str = "trash";
+pub const PRUNE: &str = "prune";
+pub const CLEAN: &str = "clean";
+pub const COMPRESS: &str = "compress";
+pub const ARCHIVE: &str = "archive";
+
+fn method_label(method: &str) -> &str {
+    match method {
+        DELETE => tr("Delete"),
+        SECURE_DELETE => tr("Secure delete"),
+        MOVE => tr("Move"),
+        TRASH => tr("Move to Trash"),
+        PRUNE => tr("Prune"),
+        CLEAN => tr("Clean"),
+        COMPRESS => tr("Compress"),
+        ARCHIVE => tr("Archive"),
+        other => other,
+    }
+}
+
+/// One destructive action, appended to the audit log as a JSON line.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub method: String,
+    pub target: String,
+    pub size: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A cleanup's outcome, and whether its entry made it into the log.
+pub type Audited<T> = (Result<T, String>, Result<(), String>);
+
+pub fn log_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(AUDIT_FILE))
+}
+
+/// Appends one entry to the log; the error says why the entry could not be written.
+pub fn record(method: &str, target: &str, size: Option<u64>, error: Option<&String>) -> Result<(), String> {
+    let entry = AuditEntry {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        method: method.to_string(),
+        target: target.to_string(),
+        size,
+        error: error.cloned(),
+    };
+    append(&entry)
+}
+
+pub fn record_path(method: &str, path: &Path, size: u64, error: Option<&String>) -> Result<(), String> {
+    record(method, &paths::display_path(path), Some(size), error)
+}
+
+fn append(entry: &AuditEntry) -> Result<(), String> {
+    let path = log_path().ok_or("No data directory available")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Error creating {}: {}", dir.display(), e))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| format!("Error serializing audit entry: {}", e))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// Reads the whole log, newest entry first; unreadable lines are skipped.
+pub fn load() -> Vec<AuditEntry> {
+    let Some(contents) = log_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<AuditEntry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Splits seconds since the epoch into a UTC (year, month, day, seconds into the day).
+pub fn civil_date(timestamp: u64) -> (i64, i64, i64, u64) {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+    // Civil-from-days, after Howard Hinnant's date algorithms.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day, seconds)
+}
+
+/// The inverse of `civil_date`: seconds since the epoch for a UTC date and time of day.
+pub fn civil_timestamp(year: i64, month: i64, day: i64, seconds: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    (days.max(0) as u64) * 86_400 + seconds
+}
+
+/// Formats seconds since the epoch as "YYYY-MM-DD HH:MM UTC".
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (year, month, day, seconds) = civil_date(timestamp);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[derive(Default)]
+pub struct AuditLog {
+    entries: Option<Vec<AuditEntry>>,
+}
+
+impl AuditLog {
+    pub fn invalidate(&mut self) {
+        self.entries = None;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(tr("Refresh")).clicked() {
+                self.entries = None;
+            }
+            if let Some(path) = log_path() {
+                ui.label(RichText::new(path.display().to_string()).small().color(Color32::GRAY));
+            }
+        });
+        let entries = self.entries.get_or_insert_with(load);
+        if entries.is_empty() {
+            ui.label(tr("Nothing has been deleted or cleaned yet."));
+            return;
+        }
+        let freed: u64 = entries.iter().filter(|e| e.succeeded()).filter_map(|e| e.size).sum();
+        ui.label(trf("Freed over all recorded actions: {}", &[&i18n::format_bytes(freed)]));
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            egui::Grid::new("audit_log").striped(true).show(ui, |ui| {
+                ui.label(tr("When"));
+                ui.label(tr("Action"));
+                ui.label(tr("Target"));
+                ui.label(tr("Size"));
+                ui.label(tr("Result"));
+                ui.end_row();
+                for entry in entries.iter() {
+                    ui.label(format_timestamp(entry.timestamp));
+                    ui.label(method_label(&entry.method));
+                    ui.label(&entry.target);
+                    ui.label(entry.size.map_or("-".to_string(), i18n::format_bytes));
+                    match &entry.error {
+                        None => ui.label(RichText::new(tr("OK")).color(Color32::GREEN)),
+                        Some(error) => ui.label(RichText::new(error).color(Color32::RED)),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
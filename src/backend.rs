@@ -0,0 +1,120 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{error::Error, owner, paths, scan};
+
+/// A child of a listed folder, already measured by the backend that produced it.
+#[derive(Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub name: OsString,
+    pub is_dir: bool,
+    pub size: u64,
+    /// Bytes before transparent compression; see `scan::logical_size`.
+    pub logical: u64,
+    /// Bytes of the files directly inside a folder; the same as `size` for files.
+    pub own_size: u64,
+    pub entries: u64,
+    pub modified: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    /// The entry changed while it was being measured.
+    pub changed: bool,
+    pub owner: Option<u32>,
+    pub online_only: bool,
+}
+
+#[derive(Default)]
+pub struct Listing {
+    pub entries: Vec<Entry>,
+    /// What ignore files hid from `entries`.
+    pub ignored: scan::IgnoredTotal,
+}
+
+/// Where folder listings come from: the local filesystem on desktop, a folder the user
+/// granted access to in the browser build. Listings run on worker threads.
+pub trait Backend: Send + Sync {
+    fn list(&self, dir: &Path, options: &scan::ScanOptions, show_hidden: bool) -> Result<Listing, Error>;
+}
+
+pub struct LocalBackend;
+
+impl LocalBackend {
+    fn list_once(dir: &Path, options: &scan::ScanOptions, show_hidden: bool) -> Result<Listing, Error> {
+        let entries = fs::read_dir(paths::extended(dir))
+            .map_err(|source| Error::Read { path: dir.to_path_buf(), source })?;
+        let unignored = options
+            .respect_ignores
+            .then(|| scan::unignored_children(dir, options));
+        let root_device = options.root_device(dir);
+        let mut listing = Listing::default();
+        for entry in entries.filter_map(Result::ok) {
+            let path = dir.join(entry.file_name());
+            let name = entry.file_name();
+            if !show_hidden && paths::is_dotfile(&name) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if options.excludes(&entry.file_name()) || scan::crosses_device(&metadata, root_device) {
+                continue;
+            }
+
+            if unignored.as_ref().is_some_and(|set| !set.contains(&path)) {
+                if let Some(measurement) = scan::measure_entry(&path, &metadata, &scan::ScanOptions::default()) {
+                    listing.ignored.count += 1;
+                    listing.ignored.size += measurement.size;
+                }
+                continue;
+            }
+
+            let Some(measurement) = scan::measure_entry(&path, &metadata, options) else {
+                continue;
+            };
+            if metadata.is_dir() && options.respect_ignores {
+                let nested = scan::ignored_within(&path, options);
+                listing.ignored.count += nested.count;
+                listing.ignored.size += nested.size;
+            }
+            let own_size = if metadata.is_dir() {
+                scan::own_size(&path, options)
+            } else {
+                measurement.size
+            };
+            listing.entries.push(Entry {
+                name,
+                is_dir: metadata.is_dir(),
+                size: measurement.size,
+                logical: measurement.logical,
+                own_size,
+                entries: measurement.entries,
+                modified: measurement.modified,
+                accessed: metadata.accessed().ok(),
+                changed: measurement.changed,
+                owner: owner::owner_id(&metadata),
+                online_only: scan::is_online_only(&metadata),
+                path,
+            });
+        }
+        Ok(listing)
+    }
+}
+
+impl Backend for LocalBackend {
+    /// Lists the folder again when it changed during the listing, up to `scan::MAX_RESCANS` times.
+    fn list(&self, dir: &Path, options: &scan::ScanOptions, show_hidden: bool) -> Result<Listing, Error> {
+        let mut attempt = 0;
+        loop {
+            let before = scan::modified(dir);
+            let listing = Self::list_once(dir, options, show_hidden)?;
+            if scan::modified(dir) == before || attempt == scan::MAX_RESCANS {
+                return Ok(listing);
+            }
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{a11y, config, i18n::tr, paths};
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bookmark {
+    pub path: PathBuf,
+    pub note: String,
+}
+
+/// Folders starred inside a scan, kept apart for every scanned root.
+#[derive(Serialize, Deserialize, Default)]
+pub struct BookmarkStore {
+    roots: BTreeMap<PathBuf, Vec<Bookmark>>,
+}
+
+impl BookmarkStore {
+    pub fn load() -> Self {
+        config::load(BOOKMARKS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(BOOKMARKS_FILE, self)
+    }
+
+    pub fn contains(&self, root: &Path, path: &Path) -> bool {
+        self.roots.get(root).is_some_and(|bookmarks| bookmarks.iter().any(|bookmark| bookmark.path == path))
+    }
+
+    pub fn toggle(&mut self, root: &Path, path: &Path) {
+        let bookmarks = self.roots.entry(root.to_path_buf()).or_default();
+        match bookmarks.iter().position(|bookmark| bookmark.path == path) {
+            Some(position) => {
+                bookmarks.remove(position);
+            }
+            None => bookmarks.push(Bookmark { path: path.to_path_buf(), note: String::new() }),
+        }
+        if bookmarks.is_empty() {
+            self.roots.remove(root);
+        }
+    }
+
+    /// Lists the root's bookmarks with their notes editable; returns the one the user jumped to.
+    pub fn show(&mut self, ui: &mut egui::Ui, root: Option<&Path>, current: Option<&Path>) -> Option<PathBuf> {
+        let Some(root) = root else {
+            ui.label(tr("Open a folder to bookmark places in it."));
+            return None;
+        };
+        if let Some(current) = current {
+            let starred = self.contains(root, current);
+            let label = if starred { tr("★ Remove Bookmark Here") } else { tr("☆ Bookmark This Folder") };
+            if ui.button(label).clicked() {
+                self.toggle(root, current);
+                let _ = self.save();
+            }
+        }
+        let Some(bookmarks) = self.roots.get_mut(root) else {
+            ui.label(RichText::new(tr("No bookmarks yet. Star folders to come back to them.")).color(Color32::GRAY));
+            return None;
+        };
+        ui.separator();
+
+        let mut navigate = None;
+        let mut removed = None;
+        let mut changed = false;
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            for (position, bookmark) in bookmarks.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let relative = bookmark.path.strip_prefix(root).unwrap_or(&bookmark.path);
+                    let name = if relative.as_os_str().is_empty() { bookmark.path.as_path() } else { relative };
+                    let name = paths::display_path(name);
+                    if !bookmark.path.exists() {
+                        ui.label(RichText::new(format!("★ {}", name)).color(Color32::GRAY).strikethrough())
+                            .on_hover_text(tr("This folder no longer exists"));
+                    } else if ui.link(format!("★ {}", name)).clicked() {
+                        navigate = Some(bookmark.path.clone());
+                    }
+                    if a11y::icon_button(ui, "✖", tr("Remove bookmark")).clicked() {
+                        removed = Some(position);
+                    }
+                });
+                let note = egui::TextEdit::singleline(&mut bookmark.note).hint_text(tr("Add a note"));
+                if ui.add(note).lost_focus() {
+                    changed = true;
+                }
+            }
+        });
+
+        if let Some(position) = removed {
+            bookmarks.remove(position);
+            if bookmarks.is_empty() {
+                self.roots.remove(root);
+            }
+            changed = true;
+        }
+        if changed {
+            let _ = self.save();
+        }
+        navigate
+    }
+}
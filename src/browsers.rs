@@ -0,0 +1,336 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    job::Job,
+    paths, scan,
+};
+
+const MAX_SITES: usize = 15;
+
+/// Chromium-based browsers and where each platform keeps their user data, relative to the
+/// config folder on Linux and macOS and the local app data folder on Windows.
+const CHROMIUM_BROWSERS: [(&str, &str, &str, &str); 6] = [
+    ("Chrome", "google-chrome", "Google/Chrome", r"Google\Chrome\User Data"),
+    ("Chromium", "chromium", "Chromium", r"Chromium\User Data"),
+    ("Edge", "microsoft-edge", "Microsoft Edge", r"Microsoft\Edge\User Data"),
+    ("Brave", "BraveSoftware/Brave-Browser", "BraveSoftware/Brave-Browser", r"BraveSoftware\Brave-Browser\User Data"),
+    ("Vivaldi", "vivaldi", "Vivaldi", r"Vivaldi\User Data"),
+    ("Opera", "opera", "com.operasoftware.Opera", r"Opera Software\Opera Stable"),
+];
+/// Caches inside a Chromium profile; on Linux and macOS the main cache lives under the
+/// system cache folder instead.
+const CHROMIUM_CACHES: [&str; 5] = ["Cache", "Code Cache", "GPUCache", "DawnCache", "GrShaderCache"];
+
+pub struct BrowserProfile {
+    pub browser: &'static str,
+    pub name: String,
+    pub path: PathBuf,
+    /// Everything the profile uses, including caches kept elsewhere.
+    pub total: u64,
+    pub cache: u64,
+    pub cache_paths: Vec<PathBuf>,
+    pub service_workers: u64,
+    pub indexed_db: u64,
+    /// Site storage (IndexedDB and friends), largest first.
+    pub sites: Vec<(String, u64)>,
+}
+
+fn size(path: &Path) -> u64 {
+    scan::measure_dir(path, &Default::default()).size
+}
+
+fn subdirs(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    entries.filter_map(Result::ok).map(|entry| entry.path()).filter(|path| path.is_dir()).collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+/// `https_www.example.com_0.indexeddb.leveldb` becomes `https://www.example.com`.
+fn chromium_site(name: &str) -> String {
+    let origin = name.split(".indexeddb.").next().unwrap_or(name);
+    let origin = origin.strip_suffix("_0").unwrap_or(origin);
+    origin.replacen('_', "://", 1)
+}
+
+fn chromium_profiles(browser: &'static str, data: &Path, cache_root: Option<PathBuf>) -> Vec<BrowserProfile> {
+    subdirs(data)
+        .into_iter()
+        .filter(|profile| profile.join("Preferences").is_file())
+        .map(|profile| {
+            let name = file_name(&profile);
+            let mut cache_paths: Vec<PathBuf> = CHROMIUM_CACHES.iter().map(|cache| profile.join(cache)).collect();
+            if let Some(cache_root) = &cache_root {
+                cache_paths.extend(CHROMIUM_CACHES.iter().map(|cache| cache_root.join(&name).join(cache)));
+            }
+            cache_paths.retain(|path| path.is_dir());
+            let cache = cache_paths.iter().map(|path| size(path)).sum::<u64>();
+            let outside: u64 = cache_paths.iter().filter(|path| !path.starts_with(&profile)).map(|path| size(path)).sum();
+            let mut sites: Vec<(String, u64)> = subdirs(&profile.join("IndexedDB"))
+                .into_iter()
+                .map(|site| (chromium_site(&file_name(&site)), size(&site)))
+                .collect();
+            sites.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+            BrowserProfile {
+                browser,
+                name,
+                total: size(&profile) + outside,
+                cache,
+                cache_paths,
+                service_workers: size(&profile.join("Service Worker")),
+                indexed_db: sites.iter().map(|(_, size)| size).sum(),
+                sites,
+                path: profile,
+            }
+        })
+        .collect()
+}
+
+/// Firefox keeps profiles in `Profiles` (or the profile folder itself on Linux) and the
+/// network cache in a mirror of it under the local cache folder.
+fn firefox_profiles(profiles: &Path, cache_root: Option<PathBuf>) -> Vec<BrowserProfile> {
+    subdirs(profiles)
+        .into_iter()
+        .filter(|profile| profile.join("prefs.js").is_file())
+        .map(|profile| {
+            let name = file_name(&profile);
+            let mut cache_paths = vec![profile.join("cache2"), profile.join("startupCache")];
+            if let Some(cache_root) = &cache_root {
+                cache_paths.push(cache_root.join(&name).join("cache2"));
+            }
+            cache_paths.retain(|path| path.is_dir());
+            let cache = cache_paths.iter().map(|path| size(path)).sum::<u64>();
+            let outside: u64 = cache_paths.iter().filter(|path| !path.starts_with(&profile)).map(|path| size(path)).sum();
+            let (mut sites, mut service_workers) = (Vec::new(), 0);
+            for site in subdirs(&profile.join("storage").join("default")) {
+                // Cache API storage belongs to service workers; the rest is IndexedDB and local storage.
+                let worker_cache = size(&site.join("cache"));
+                service_workers += worker_cache;
+                sites.push((file_name(&site).replacen("+++", "://", 1), size(&site).saturating_sub(worker_cache)));
+            }
+            sites.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+            BrowserProfile {
+                browser: "Firefox",
+                name,
+                total: size(&profile) + outside,
+                cache,
+                cache_paths,
+                service_workers,
+                indexed_db: sites.iter().map(|(_, size)| size).sum(),
+                sites,
+                path: profile,
+            }
+        })
+        .collect()
+}
+
+pub fn find_profiles() -> Vec<BrowserProfile> {
+    let mut profiles = Vec::new();
+    for (browser, linux, macos, windows) in CHROMIUM_BROWSERS {
+        let (data, cache_root) = if cfg!(windows) {
+            (dirs::data_local_dir().map(|dir| dir.join(windows)), None)
+        } else {
+            let relative = if cfg!(target_os = "macos") { macos } else { linux };
+            (dirs::config_dir().map(|dir| dir.join(relative)), dirs::cache_dir().map(|dir| dir.join(relative)))
+        };
+        if let Some(data) = data {
+            profiles.extend(chromium_profiles(browser, &data, cache_root));
+        }
+    }
+
+    let (firefox, firefox_cache) = if cfg!(windows) {
+        (
+            dirs::config_dir().map(|dir| dir.join(r"Mozilla\Firefox\Profiles")),
+            dirs::data_local_dir().map(|dir| dir.join(r"Mozilla\Firefox\Profiles")),
+        )
+    } else if cfg!(target_os = "macos") {
+        (
+            dirs::config_dir().map(|dir| dir.join("Firefox/Profiles")),
+            dirs::cache_dir().map(|dir| dir.join("Firefox/Profiles")),
+        )
+    } else {
+        (
+            dirs::home_dir().map(|dir| dir.join(".mozilla/firefox")),
+            dirs::cache_dir().map(|dir| dir.join("mozilla/firefox")),
+        )
+    };
+    if let Some(firefox) = firefox {
+        profiles.extend(firefox_profiles(&firefox, firefox_cache));
+    }
+    profiles.sort_by_key(|profile| std::cmp::Reverse(profile.total));
+    profiles
+}
+
+/// Empties the cache folders; the browser recreates them on the next start.
+fn clear_cache(paths: &[PathBuf]) -> Result<(), String> {
+    for path in paths {
+        for entry in fs::read_dir(path).map_err(|e| format!("Error reading {}: {}", path.display(), e))? {
+            let entry = entry.map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+            let removed = if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                fs::remove_dir_all(entry.path())
+            } else {
+                fs::remove_file(entry.path())
+            };
+            removed.map_err(|e| format!("Error removing {}: {}", entry.path().display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct BrowsersState {
+    pub open: bool,
+    job: Option<Job<Vec<BrowserProfile>>>,
+    profiles: Option<Vec<BrowserProfile>>,
+    /// Index of the profile whose cache is about to be cleared.
+    confirm: Option<usize>,
+    clear_job: Option<Job<audit::Audited<()>>>,
+    message: Option<String>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl BrowsersState {
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) {
+        if self.profiles.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(find_profiles));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(profiles) => {
+                    self.profiles = Some(profiles);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.clear_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = result.err();
+                    self.clear_job = None;
+                    self.job = Some(Job::spawn(find_profiles));
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Browser Data"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                if self.job.is_some() || self.clear_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if let Some(message) = &self.message {
+                    ui.label(RichText::new(message).color(Color32::RED));
+                }
+                let Some(profiles) = &self.profiles else {
+                    return;
+                };
+                if profiles.is_empty() {
+                    ui.label(tr("No browser profiles found."));
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(460.0).show(ui, |ui| {
+                    for (position, profile) in profiles.iter().enumerate() {
+                        let title = format!(
+                            "{} - {} - {}",
+                            profile.browser,
+                            profile.name,
+                            i18n::format_bytes(profile.total)
+                        );
+                        egui::CollapsingHeader::new(title).id_source(("browser_profile", &profile.path)).show(ui, |ui| {
+                            ui.label(RichText::new(paths::display_path(&profile.path)).small().color(Color32::GRAY));
+                            egui::Grid::new(("browser_breakdown", &profile.path)).show(ui, |ui| {
+                                ui.label(tr("Cache"));
+                                ui.label(i18n::format_bytes(profile.cache));
+                                let clear = ui
+                                    .add_enabled(
+                                        !read_only && profile.cache > 0,
+                                        egui::Button::new(tr("Clear Cache")),
+                                    )
+                                    .on_disabled_hover_text(tr("Disabled in read-only mode"));
+                                if clear.clicked() {
+                                    self.confirm = Some(position);
+                                }
+                                ui.end_row();
+                                ui.label(tr("Service workers"));
+                                ui.label(i18n::format_bytes(profile.service_workers));
+                                ui.end_row();
+                                ui.label(tr("Site storage"));
+                                ui.label(i18n::format_bytes(profile.indexed_db));
+                                ui.end_row();
+                                ui.label(tr("Everything else"));
+                                let other = profile.total.saturating_sub(
+                                    profile.cache + profile.service_workers + profile.indexed_db,
+                                );
+                                ui.label(i18n::format_bytes(other));
+                                ui.end_row();
+                            });
+                            if !profile.sites.is_empty() {
+                                ui.label(RichText::new(tr("Largest sites")).strong());
+                                for (site, size) in profile.sites.iter().take(MAX_SITES) {
+                                    ui.label(format!("{}  {}", i18n::format_bytes(*size), site));
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        self.render_confirmation(ctx);
+    }
+
+    fn render_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(profile) = self.confirm.and_then(|position| self.profiles.as_ref()?.get(position)) else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(tr("Confirm Clearing"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "Clear {} of cached data from {} ({})? Close the browser first; logins and site data are kept.",
+                    &[&i18n::format_bytes(profile.cache), &profile.browser, &profile.name],
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Yes")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("No")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if choice == Some(true) {
+            let paths = profile.cache_paths.clone();
+            let target = format!("{} cache ({})", profile.browser, profile.name);
+            let size = profile.cache;
+            self.message = None;
+            self.clear_job = Some(Job::spawn(move || {
+                let result = clear_cache(&paths);
+                let audited = audit::record(audit::CLEAN, &target, Some(size), result.as_ref().err());
+                (result, audited)
+            }));
+        }
+        if choice.is_some() {
+            self.confirm = None;
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{path::PathBuf, process::Command};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    job::Job,
+    scan,
+};
+
+pub struct PackageCache {
+    pub manager: &'static str,
+    pub paths: Vec<PathBuf>,
+    pub clean: &'static [&'static str],
+    pub needs_root: bool,
+}
+
+pub struct CacheUsage {
+    pub cache: PackageCache,
+    pub size: u64,
+}
+
+fn home(path: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(path))
+}
+
+fn user_cache(path: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache| cache.join(path))
+}
+
+pub fn known_caches() -> Vec<PackageCache> {
+    let cache = |manager, paths: Vec<Option<PathBuf>>, clean, needs_root| PackageCache {
+        manager,
+        paths: paths.into_iter().flatten().collect(),
+        clean,
+        needs_root,
+    };
+    vec![
+        cache("apt", vec![Some(PathBuf::from("/var/cache/apt/archives"))], &["apt-get", "clean"], true),
+        cache("dnf", vec![Some(PathBuf::from("/var/cache/dnf"))], &["dnf", "clean", "all"], true),
+        cache("pacman", vec![Some(PathBuf::from("/var/cache/pacman/pkg"))], &["paccache", "-r"], true),
+        cache(
+            "brew",
+            vec![user_cache("Homebrew")],
+            &["brew", "cleanup", "--prune=all"],
+            false,
+        ),
+        cache(
+            "cargo",
+            vec![home(".cargo/registry/cache"), home(".cargo/registry/src"), home(".cargo/git/checkouts")],
+            &["cargo", "cache", "--autoclean"],
+            false,
+        ),
+        cache(
+            "npm",
+            vec![home(".npm/_cacache"), dirs::data_local_dir().map(|dir| dir.join("npm-cache"))],
+            &["npm", "cache", "clean", "--force"],
+            false,
+        ),
+        cache("pip", vec![user_cache("pip")], &["pip", "cache", "purge"], false),
+    ]
+}
+
+pub fn scan_caches() -> Vec<CacheUsage> {
+    let mut usages: Vec<CacheUsage> = known_caches()
+        .into_iter()
+        .filter_map(|mut cache| {
+            cache.paths.retain(|path| path.is_dir());
+            let size = cache
+                .paths
+                .iter()
+                .map(|path| scan::measure_dir(path, &Default::default()).size)
+                .sum();
+            (!cache.paths.is_empty()).then_some(CacheUsage { cache, size })
+        })
+        .collect();
+    usages.sort_by_key(|usage| std::cmp::Reverse(usage.size));
+    usages
+}
+
+pub fn command_line(cache: &PackageCache) -> String {
+    let command = cache.clean.join(" ");
+    if cache.needs_root && cfg!(unix) {
+        format!("sudo {}", command)
+    } else {
+        command
+    }
+}
+
+fn run_clean(clean: &'static [&'static str]) -> Result<String, String> {
+    let (program, args) = clean.split_first().ok_or("Empty command")?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(clean.join(" "))
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[derive(Default)]
+pub struct CachesState {
+    pub open: bool,
+    job: Option<Job<Vec<CacheUsage>>>,
+    usages: Option<Vec<CacheUsage>>,
+    clean_job: Option<Job<audit::Audited<String>>>,
+    message: Option<Result<String, String>>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl CachesState {
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) {
+        if self.usages.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(scan_caches));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(usages) => {
+                    self.usages = Some(usages);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.clean_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = Some(result);
+                    self.clean_job = None;
+                    self.job = Some(Job::spawn(scan_caches));
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Package Caches"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                if self.job.is_some() || self.clean_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.message {
+                    Some(Ok(command)) => {
+                        ui.label(RichText::new(trf("Ran {}", &[command])).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+                let Some(usages) = &self.usages else {
+                    return;
+                };
+                if usages.is_empty() {
+                    ui.label(tr("No known package caches found."));
+                    return;
+                }
+
+                let total: u64 = usages.iter().map(|usage| usage.size).sum();
+                ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+                let mut clean = None;
+                egui::Grid::new("package_caches").striped(true).show(ui, |ui| {
+                    for usage in usages {
+                        let command = command_line(&usage.cache);
+                        ui.label(RichText::new(usage.cache.manager).strong());
+                        ui.label(i18n::format_bytes(usage.size)).on_hover_text(
+                            usage.cache.paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join("\n"),
+                        );
+                        ui.label(RichText::new(&command).monospace());
+                        if ui.button(tr("Copy")).clicked() {
+                            ui.output_mut(|output| output.copied_text = command.clone());
+                        }
+                        let run = ui
+                            .add_enabled(!read_only && !usage.cache.needs_root, egui::Button::new(tr("Run")))
+                            .on_disabled_hover_text(if read_only {
+                                tr("Disabled in read-only mode")
+                            } else {
+                                tr("Needs administrator rights; copy the command instead")
+                            });
+                        if run.clicked() {
+                            clean = Some((usage.cache.clean, usage.size));
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some((clean, size)) = clean {
+                    self.message = None;
+                    self.clean_job = Some(Job::spawn(move || {
+                        let result = run_clean(clean);
+                        let audited = audit::record(audit::CLEAN, &clean.join(" "), Some(size), result.as_ref().err());
+                        (result, audited)
+                    }));
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+static SNIFF_CONTENT: AtomicBool = AtomicBool::new(false);
+static SNIFF_MIN_SIZE: AtomicU64 = AtomicU64::new(0);
+/// Sniffed types by path and size, filled in by the scan workers.
+static SNIFFED: Mutex<BTreeMap<(PathBuf, u64), Option<Category>>> = Mutex::new(BTreeMap::new());
+/// Most files `SNIFFED` remembers; past it, entries make room for new ones.
+const MAX_SNIFFED: usize = 200_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Video,
+    Audio,
+    Image,
+    Document,
+    Archive,
+    Code,
+    Executable,
+    Other,
+}
+
+impl Category {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Category::Video => "Video",
+            Category::Audio => "Audio",
+            Category::Image => "Images",
+            Category::Document => "Documents",
+            Category::Archive => "Archives",
+            Category::Code => "Code",
+            Category::Executable => "Executables",
+            Category::Other => "Other",
+        }
+    }
+}
+
+pub fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+pub fn classify(path: &Path) -> Category {
+    match extension(path).as_str() {
+        "mp4" | "mkv" | "avi" | "mov" | "wmv" | "webm" | "flv" | "m4v" | "mpg" | "mpeg" | "ts" => {
+            Category::Video
+        }
+        "mp3" | "flac" | "wav" | "ogg" | "m4a" | "aac" | "opus" | "wma" => Category::Audio,
+        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tif" | "tiff" | "webp" | "heic" | "svg" | "raw"
+        | "cr2" | "nef" | "arw" | "dng" | "psd" => Category::Image,
+        "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "ods" | "txt" | "md"
+        | "rtf" | "epub" => Category::Document,
+        "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" | "zst" | "iso" | "dmg" | "tgz" => {
+            Category::Archive
+        }
+        "rs" | "c" | "h" | "cpp" | "hpp" | "py" | "js" | "go" | "java" | "kt" | "cs"
+        | "rb" | "php" | "swift" | "json" | "toml" | "yaml" | "yml" | "html" | "css" => Category::Code,
+        "exe" | "dll" | "so" | "dylib" | "msi" | "app" | "deb" | "rpm" | "appimage" => {
+            Category::Executable
+        }
+        _ => Category::Other,
+    }
+}
+
+pub fn configure_sniffing(enabled: bool, min_size: u64) {
+    SNIFF_CONTENT.store(enabled, Ordering::Relaxed);
+    SNIFF_MIN_SIZE.store(min_size, Ordering::Relaxed);
+}
+
+/// Looks at the file's first bytes instead of its name. Text formats have no signature, so
+/// they give None and are left to the extension.
+fn sniff(path: &Path) -> Option<Category> {
+    let kind = infer::get_from_path(path).ok()??;
+    Some(match kind.matcher_type() {
+        infer::MatcherType::Video => Category::Video,
+        infer::MatcherType::Audio => Category::Audio,
+        infer::MatcherType::Image => Category::Image,
+        infer::MatcherType::Doc | infer::MatcherType::Book => Category::Document,
+        infer::MatcherType::Archive => Category::Archive,
+        infer::MatcherType::App => Category::Executable,
+        infer::MatcherType::Font | infer::MatcherType::Text | infer::MatcherType::Custom => return None,
+    })
+}
+
+/// Reads the first bytes of a file the scan came across into the cache `classify_file` looks
+/// in, when sniffing is on and the file is big enough. Only scan workers call this, so
+/// drawing a listing never waits on the disk.
+pub fn sniff_file(path: &Path, size: u64) {
+    if !SNIFF_CONTENT.load(Ordering::Relaxed) || size < SNIFF_MIN_SIZE.load(Ordering::Relaxed) {
+        return;
+    }
+    let key = (path.to_path_buf(), size);
+    if SNIFFED.lock().map(|sniffed| sniffed.contains_key(&key)).unwrap_or(true) {
+        return;
+    }
+    let sniffed = sniff(path);
+    if let Ok(mut cache) = SNIFFED.lock() {
+        if cache.len() >= MAX_SNIFFED {
+            cache.pop_first();
+        }
+        cache.insert(key, sniffed);
+    }
+}
+
+/// Like `classify`, but when content sniffing is on, files of at least the configured size
+/// that a scan has sniffed are classified by their contents, which catches extensionless and
+/// misnamed files.
+pub fn classify_file(path: &Path, size: u64) -> Category {
+    if !SNIFF_CONTENT.load(Ordering::Relaxed) || size < SNIFF_MIN_SIZE.load(Ordering::Relaxed) {
+        return classify(path);
+    }
+    let key = (path.to_path_buf(), size);
+    let sniffed = SNIFFED.lock().ok().and_then(|sniffed| sniffed.get(&key).copied()).flatten();
+    sniffed.unwrap_or_else(|| classify(path))
+}
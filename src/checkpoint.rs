@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{config, paths, scan::ScanOptions};
+
+const CHECKPOINT_FILE: &str = "scan_checkpoint.json";
+/// How often a running scan writes its progress out.
+pub const INTERVAL: Duration = Duration::from_secs(120);
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedNode {
+    pub path: PathBuf,
+    #[serde(deserialize_with = "paths::deserialize_name")]
+    pub name: OsString,
+    pub size: u64,
+    #[serde(default)]
+    pub logical: u64,
+    pub is_dir: bool,
+    pub modified: Option<SystemTime>,
+    #[serde(default)]
+    pub accessed: Option<SystemTime>,
+    pub unreachable: bool,
+    pub online_only: bool,
+    #[serde(default)]
+    pub small_files: u64,
+    #[serde(default)]
+    pub small_size: u64,
+    /// Position of the parent within the same subtree; `None` for the subtree's top folder.
+    pub parent: Option<usize>,
+}
+
+/// Progress of an unfinished scan: every folder that had been walked completely, with
+/// everything below it. Partly walked folders are walked again on resume.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub root: PathBuf,
+    pub options: ScanOptions,
+    pub show_hidden: bool,
+    pub saved_at: SystemTime,
+    pub subtrees: Vec<Vec<SavedNode>>,
+}
+
+impl Checkpoint {
+    /// Returns the checkpoint left by an interrupted scan with the same root and settings.
+    pub fn load(root: &Path, options: &ScanOptions, show_hidden: bool) -> Option<Self> {
+        let checkpoint: Option<Checkpoint> = config::load(CHECKPOINT_FILE);
+        checkpoint.filter(|checkpoint| {
+            checkpoint.root == root && checkpoint.options == *options && checkpoint.show_hidden == show_hidden
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(CHECKPOINT_FILE, self)
+    }
+
+    /// Groups the subtrees by the folder they belong in, so each can be put back when the
+    /// walk reaches that folder.
+    pub fn by_parent(self) -> HashMap<PathBuf, Vec<Vec<SavedNode>>> {
+        let mut grouped: HashMap<PathBuf, Vec<Vec<SavedNode>>> = HashMap::new();
+        for subtree in self.subtrees {
+            if let Some(parent) = subtree.first().and_then(|top| top.path.parent()) {
+                grouped.entry(parent.to_path_buf()).or_default().push(subtree);
+            }
+        }
+        grouped
+    }
+}
+
+/// Removes the checkpoint once a scan finishes or the user chooses to start over.
+pub fn discard() {
+    if let Some(dir) = config::data_dir() {
+        let _ = fs::remove_file(dir.join(CHECKPOINT_FILE));
+    }
+}
@@ -0,0 +1,175 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{quotas, scan::ScanOptions, text_tree};
+
+const DEFAULT_TREE_DEPTH: usize = 3;
+
+pub const USAGE: &str = "Usage: disk-analyzer [PATH] [--min-size SIZE] [--show-all] [--show-hidden] \
+[--respect-ignores] [--sort-by-name] [--network] [--async-io] [--low-memory SIZE] [--read-only] [--bench] \
+[--export FILE] [--export-ncdu FILE] \
+[--tree [--depth N] [--color auto|always|never] [--reverse]] \
+[--serve-metrics ADDR [--watch PATH]... [--interval SECS]]
+       disk-analyzer gen-fixture <output> [options]
+
+--network scans with parallel, time-limited directory reads for SMB/NFS shares.
+--async-io scans with asynchronous file I/O for high-latency mounts such as SFTP or cloud storage.
+--low-memory only counts files smaller than SIZE per folder instead of indexing each one.
+--read-only disables every action that deletes or changes files.
+--bench scans PATH without opening a window and prints timings.
+--export scans PATH without opening a window and saves the scan to FILE for opening elsewhere.
+--export-ncdu does the same in ncdu's export format (open it with `ncdu -f FILE`).
+--tree prints PATH as a size-sorted tree with bars and percentages, N levels deep (default 3);
+  --reverse lists the smallest entries first.
+--serve-metrics keeps measuring PATH, every --watch PATH and every folder with a budget, and
+  serves their sizes to Prometheus at http://ADDR/metrics (e.g. 127.0.0.1:9184). Folders are
+  measured every SECS seconds (default 300).
+
+SIZE accepts plain bytes or a K/M/G/T suffix (binary multiples), e.g. 512K or 2G.";
+
+#[derive(Default)]
+pub struct LaunchOptions {
+    pub path: Option<PathBuf>,
+    pub min_size: Option<u64>,
+    pub show_all: bool,
+    pub show_hidden: bool,
+    pub respect_ignores: bool,
+    pub sort_by_name: bool,
+    pub network: bool,
+    pub async_io: bool,
+    pub summarize_below: Option<u64>,
+    pub read_only: bool,
+    pub bench: bool,
+    pub export: Option<PathBuf>,
+    pub export_ncdu: Option<PathBuf>,
+    pub tree: Option<text_tree::TreeOptions>,
+    pub serve_metrics: Option<String>,
+    pub watch: Vec<PathBuf>,
+    pub interval: Option<Duration>,
+}
+
+impl LaunchOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--min-size" => {
+                    let value = iter.next().ok_or("Missing value for --min-size")?;
+                    options.min_size = Some(parse_size(value)?);
+                }
+                "--show-all" => options.show_all = true,
+                "--show-hidden" => options.show_hidden = true,
+                "--respect-ignores" => options.respect_ignores = true,
+                "--sort-by-name" => options.sort_by_name = true,
+                "--network" => options.network = true,
+                "--async-io" => options.async_io = true,
+                "--low-memory" => {
+                    let value = iter.next().ok_or("Missing value for --low-memory")?;
+                    options.summarize_below = Some(parse_size(value)?);
+                }
+                "--read-only" => options.read_only = true,
+                "--bench" => options.bench = true,
+                "--export" => {
+                    let value = iter.next().ok_or("Missing value for --export")?;
+                    options.export = Some(PathBuf::from(value));
+                }
+                "--tree" => {
+                    options.tree.get_or_insert_with(tree_defaults);
+                }
+                "--depth" => {
+                    let value = iter.next().ok_or("Missing value for --depth")?;
+                    options.tree.get_or_insert_with(tree_defaults).depth =
+                        value.parse().map_err(|_| format!("Invalid depth: {}", value))?;
+                }
+                "--color" => {
+                    let value = iter.next().ok_or("Missing value for --color")?;
+                    options.tree.get_or_insert_with(tree_defaults).color = text_tree::ColorChoice::parse(value)?;
+                }
+                "--reverse" => options.tree.get_or_insert_with(tree_defaults).reverse = true,
+                "--export-ncdu" => {
+                    let value = iter.next().ok_or("Missing value for --export-ncdu")?;
+                    options.export_ncdu = Some(PathBuf::from(value));
+                }
+                "--serve-metrics" => {
+                    let value = iter.next().ok_or("Missing value for --serve-metrics")?;
+                    options.serve_metrics = Some(value.clone());
+                }
+                "--watch" => {
+                    let value = iter.next().ok_or("Missing value for --watch")?;
+                    options.watch.push(PathBuf::from(value));
+                }
+                "--interval" => {
+                    let value = iter.next().ok_or("Missing value for --interval")?;
+                    let seconds: u64 = value.parse().map_err(|_| format!("Invalid interval: {}", value))?;
+                    options.interval = Some(Duration::from_secs(seconds.max(1)));
+                }
+                other if other.starts_with("--") => {
+                    return Err(format!("Unknown option: {}", other));
+                }
+                other => {
+                    if options.path.is_some() {
+                        return Err(format!("Unexpected argument: {}", other));
+                    }
+                    options.path = Some(PathBuf::from(other));
+                }
+            }
+        }
+        if options.bench && options.path.is_none() {
+            return Err("--bench needs a PATH".to_string());
+        }
+        if (options.export.is_some() || options.export_ncdu.is_some()) && options.path.is_none() {
+            return Err("--export needs a PATH".to_string());
+        }
+        if options.tree.is_some() && options.path.is_none() {
+            return Err("--tree needs a PATH".to_string());
+        }
+        if (!options.watch.is_empty() || options.interval.is_some()) && options.serve_metrics.is_none() {
+            return Err("--watch and --interval need --serve-metrics".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Folders to keep measuring in `--serve-metrics` mode, besides the budgeted ones.
+    pub fn watched_paths(&self) -> Vec<PathBuf> {
+        self.path.iter().chain(&self.watch).cloned().collect()
+    }
+
+    pub fn monitor_interval(&self) -> Duration {
+        self.interval.unwrap_or(quotas::MONITOR_INTERVAL)
+    }
+
+    /// The scan settings for runs without a window.
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            respect_ignores: self.respect_ignores,
+            network_mode: self.network,
+            async_io: self.async_io,
+            summarize_below: self.summarize_below.unwrap_or_default(),
+            ..ScanOptions::default()
+        }
+    }
+}
+
+fn tree_defaults() -> text_tree::TreeOptions {
+    text_tree::TreeOptions { depth: DEFAULT_TREE_DEPTH, color: text_tree::ColorChoice::default(), reverse: false }
+}
+
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size: {}", value))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches("IB").trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return Err(format!("Invalid size unit: {}", unit)),
+    };
+    Ok((number * multiplier as f64) as u64)
+}
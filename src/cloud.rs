@@ -0,0 +1,431 @@
+use eframe::egui::{self, Color32, RichText};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    audit, dump,
+    i18n::{self, tr, trf},
+    index::{Node, ScanIndex},
+    job::Job,
+    paths,
+};
+
+const MAX_PREFIXES: usize = 50;
+
+/// Object stores the analyzer can list. Each one is a cargo feature (`cloud-s3`, `cloud-gcs`,
+/// `cloud-azure`) and goes through the provider's own command-line tool, so credentials stay
+/// wherever that tool keeps them.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Provider {
+    S3,
+    Gcs,
+    Azure,
+}
+
+impl Provider {
+    const ALL: [Provider; 3] = [Provider::S3, Provider::Gcs, Provider::Azure];
+
+    fn enabled(&self) -> bool {
+        match self {
+            Provider::S3 => cfg!(feature = "cloud-s3"),
+            Provider::Gcs => cfg!(feature = "cloud-gcs"),
+            Provider::Azure => cfg!(feature = "cloud-azure"),
+        }
+    }
+
+    /// The providers this build was compiled with.
+    pub fn available() -> Vec<Provider> {
+        Provider::ALL.into_iter().filter(Provider::enabled).collect()
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Provider::S3 => "Amazon S3",
+            Provider::Gcs => "Google Cloud Storage",
+            Provider::Azure => "Azure Blob Storage",
+        }
+    }
+
+    fn scheme(&self) -> &'static str {
+        match self {
+            Provider::S3 => "s3://",
+            Provider::Gcs => "gs://",
+            Provider::Azure => "az://",
+        }
+    }
+
+    fn location_hint(&self) -> &'static str {
+        match self {
+            Provider::Azure => "account/container/prefix",
+            _ => "bucket/prefix",
+        }
+    }
+
+    /// What objects that don't report a class are stored as.
+    fn default_class(&self) -> &'static str {
+        match self {
+            Provider::Azure => "Hot",
+            _ => "STANDARD",
+        }
+    }
+}
+
+pub struct Object {
+    pub key: String,
+    pub size: u64,
+    pub class: Option<String>,
+    pub modified: Option<SystemTime>,
+}
+
+/// Reads "2024-05-01T12:30:00.000Z"-style times; the stores all report UTC.
+fn parse_time(text: &str) -> Option<SystemTime> {
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    let seconds = (hour * 3600 + minute * 60 + second) as u64;
+    Some(UNIX_EPOCH + Duration::from_secs(audit::civil_timestamp(year, month, day, seconds)))
+}
+
+fn number(value: &Value) -> u64 {
+    value.as_u64().or_else(|| value.as_str().and_then(|text| text.parse().ok())).unwrap_or(0)
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().filter(|text| !text.is_empty()).map(str::to_string)
+}
+
+fn run(program: &str, args: &[String]) -> Result<Value, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&stdout).map_err(|e| format!("Error reading {} output: {}", program, e))
+}
+
+/// Splits "bucket/prefix" into the bucket and the prefix below it.
+fn split_location(location: &str) -> (&str, &str) {
+    let location = location.trim().trim_matches('/');
+    location.split_once('/').unwrap_or((location, ""))
+}
+
+/// Rows of `[key, size, class, modified]`, as the AWS and Azure queries return them.
+fn rows(value: &Value) -> Vec<Object> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| {
+            Some(Object {
+                key: row.get(0)?.as_str()?.to_string(),
+                size: number(&row[1]),
+                class: text(&row[2]),
+                modified: row[3].as_str().and_then(parse_time),
+            })
+        })
+        .collect()
+}
+
+/// Lists every object under the location with the provider's CLI. The CLIs page through large
+/// buckets themselves.
+pub fn list_objects(provider: Provider, location: &str) -> Result<Vec<Object>, String> {
+    let (bucket, prefix) = split_location(location);
+    if bucket.is_empty() {
+        return Err(tr("Enter a bucket name").to_string());
+    }
+    match provider {
+        Provider::S3 => {
+            let mut args: Vec<String> = ["s3api", "list-objects-v2", "--bucket", bucket, "--output", "json"]
+                .map(str::to_string)
+                .to_vec();
+            if !prefix.is_empty() {
+                args.extend(["--prefix".to_string(), format!("{}/", prefix)]);
+            }
+            args.extend(["--query".to_string(), "Contents[].[Key,Size,StorageClass,LastModified]".to_string()]);
+            Ok(rows(&run("aws", &args)?))
+        }
+        Provider::Gcs => {
+            let url = if prefix.is_empty() {
+                format!("gs://{}/**", bucket)
+            } else {
+                format!("gs://{}/{}/**", bucket, prefix)
+            };
+            let args = ["storage", "objects", "list", &url, "--format=json"].map(str::to_string);
+            let value = run("gcloud", &args)?;
+            Ok(value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|object| {
+                    let field = |snake: &str, camel: &str| {
+                        if object[snake].is_null() { object[camel].clone() } else { object[snake].clone() }
+                    };
+                    Some(Object {
+                        key: object["name"].as_str()?.to_string(),
+                        size: number(&object["size"]),
+                        class: text(&field("storage_class", "storageClass")),
+                        modified: field("update_time", "updated").as_str().and_then(parse_time),
+                    })
+                })
+                .collect())
+        }
+        Provider::Azure => {
+            let (container, prefix) = split_location(prefix);
+            if container.is_empty() {
+                return Err(tr("Enter the storage account and container as account/container").to_string());
+            }
+            let mut args: Vec<String> = [
+                "storage",
+                "blob",
+                "list",
+                "--account-name",
+                bucket,
+                "--container-name",
+                container,
+                "--num-results",
+                "*",
+                "--auth-mode",
+                "login",
+                "--output",
+                "json",
+            ]
+            .map(str::to_string)
+            .to_vec();
+            if !prefix.is_empty() {
+                args.extend(["--prefix".to_string(), format!("{}/", prefix)]);
+            }
+            args.extend([
+                "--query".to_string(),
+                "[].[name, properties.contentLength, properties.blobTier, properties.lastModified]".to_string(),
+            ]);
+            Ok(rows(&run("az", &args)?))
+        }
+    }
+}
+
+pub struct Bucket {
+    pub header: dump::Header,
+    pub index: ScanIndex,
+    /// Bytes per storage class under each node of the index.
+    classes: Vec<BTreeMap<String, u64>>,
+}
+
+fn node(path: PathBuf, name: String, is_dir: bool, size: u64, parent: Option<usize>) -> Node {
+    Node {
+        path,
+        name: name.into(),
+        size,
+        logical: size,
+        entries: 0,
+        is_dir,
+        modified: None,
+        accessed: None,
+        unreachable: false,
+        online_only: false,
+        parent,
+        children: Vec::new(),
+        small_files: 0,
+        small_size: 0,
+    }
+}
+
+/// Turns a flat object listing into an index where every `/`-separated prefix is a folder.
+pub fn build(provider: Provider, location: &str, objects: Vec<Object>) -> Bucket {
+    let location = location.trim().trim_matches('/');
+    let root = PathBuf::from(format!("{}{}", provider.scheme(), location));
+    let (_, prefix) = match provider {
+        Provider::Azure => split_location(split_location(location).1),
+        _ => split_location(location),
+    };
+    let root_name = root.file_name().map_or_else(|| location.to_string(), |name| name.to_string_lossy().into_owned());
+    let mut nodes = vec![node(root.clone(), root_name, true, 0, None)];
+    let mut classes: Vec<Option<String>> = vec![None];
+    let mut dirs: HashMap<String, usize> = HashMap::new();
+    for object in objects {
+        let relative = object.key.strip_prefix(prefix).unwrap_or(&object.key).trim_start_matches('/');
+        let mut parent = 0;
+        let mut dir_key = String::new();
+        let mut parts: Vec<&str> = relative.split('/').collect();
+        // Keys ending in "/" are folder markers the consoles create; they only add the folder.
+        let file_name = parts.pop().unwrap_or_default();
+        for part in parts.into_iter().filter(|part| !part.is_empty()) {
+            dir_key.push('/');
+            dir_key.push_str(part);
+            parent = match dirs.get(&dir_key) {
+                Some(&id) => id,
+                None => {
+                    let id = nodes.len();
+                    let path = nodes[parent].path.join(part);
+                    nodes.push(node(path, part.to_string(), true, 0, Some(parent)));
+                    classes.push(None);
+                    nodes[parent].children.push(id);
+                    dirs.insert(dir_key.clone(), id);
+                    id
+                }
+            };
+        }
+        if file_name.is_empty() {
+            continue;
+        }
+        let id = nodes.len();
+        let mut file = node(nodes[parent].path.join(file_name), file_name.to_string(), false, object.size, Some(parent));
+        file.modified = object.modified;
+        file.accessed = object.modified;
+        nodes.push(file);
+        classes.push(Some(object.class.unwrap_or_else(|| provider.default_class().to_string())));
+        nodes[parent].children.push(id);
+    }
+
+    let mut totals: Vec<BTreeMap<String, u64>> = vec![BTreeMap::new(); nodes.len()];
+    for id in (0..nodes.len()).rev() {
+        if let Some(class) = &classes[id] {
+            *totals[id].entry(class.clone()).or_default() += nodes[id].size;
+        }
+        if let Some(parent) = nodes[id].parent {
+            let below = totals[id].clone();
+            for (class, bytes) in below {
+                *totals[parent].entry(class).or_default() += bytes;
+            }
+        }
+    }
+    let header = dump::Header::new(
+        root,
+        provider.label().to_string(),
+        dump::seconds(SystemTime::now()),
+        nodes.len(),
+    );
+    Bucket { header, index: ScanIndex::from_unfinished(nodes), classes: totals }
+}
+
+fn load(provider: Provider, location: String) -> Result<Bucket, String> {
+    let objects = list_objects(provider, &location)?;
+    Ok(build(provider, &location, objects))
+}
+
+#[derive(Default)]
+pub struct CloudState {
+    pub open: bool,
+    provider: Option<Provider>,
+    location: String,
+    job: Option<Job<Result<Bucket, String>>>,
+    error: Option<String>,
+    /// The loaded bucket's root and per-class totals; the index itself goes to the browser.
+    root: Option<PathBuf>,
+    classes: Vec<BTreeMap<String, u64>>,
+}
+
+fn class_summary(classes: &BTreeMap<String, u64>) -> String {
+    let mut classes: Vec<(&String, &u64)> = classes.iter().collect();
+    classes.sort_by_key(|(_, bytes)| std::cmp::Reverse(**bytes));
+    classes
+        .iter()
+        .map(|(class, bytes)| format!("{} {}", class, i18n::format_bytes(**bytes)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl CloudState {
+    /// Draws the window. Returns a freshly listed bucket for the browser to open like an
+    /// imported scan; the storage class breakdown follows `current`, the folder being browsed.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        index: Option<&ScanIndex>,
+        current: Option<&Path>,
+    ) -> Option<(dump::Header, ScanIndex)> {
+        let mut loaded = None;
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(Ok(bucket)) => {
+                    self.root = Some(bucket.header.root.clone());
+                    self.classes = bucket.classes;
+                    loaded = Some((bucket.header, bucket.index));
+                    self.job = None;
+                }
+                Some(Err(error)) => {
+                    self.error = Some(error);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let providers = Provider::available();
+        let mut open = self.open;
+        egui::Window::new(tr("Cloud Storage"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                let Some(first) = providers.first() else {
+                    ui.label(tr("This build has no cloud storage support. Rebuild with the cloud-s3, cloud-gcs or cloud-azure feature."));
+                    return;
+                };
+                let provider = *self.provider.get_or_insert(*first);
+                ui.label(
+                    RichText::new(tr(
+                        "Lists a bucket with the provider's command-line tool (aws, gcloud or az) and opens it like a scan: every prefix is a folder.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("cloud_provider").selected_text(provider.label()).show_ui(ui, |ui| {
+                        for choice in &providers {
+                            ui.selectable_value(&mut self.provider, Some(*choice), choice.label());
+                        }
+                    });
+                    ui.label(provider.scheme());
+                    ui.add(egui::TextEdit::singleline(&mut self.location).hint_text(provider.location_hint()));
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("List Bucket"))).clicked() {
+                        let location = self.location.clone();
+                        self.error = None;
+                        self.job = Some(Job::spawn(move || load(provider, location)));
+                    }
+                    if self.job.is_some() {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                }
+
+                let (Some(root), Some(index)) = (&self.root, index) else {
+                    return;
+                };
+                if index.nodes.first().map(|node| &node.path) != Some(root) || self.classes.len() != index.nodes.len() {
+                    return;
+                }
+                ui.separator();
+                ui.label(trf("By storage class: {}", &[&class_summary(&self.classes[0])]));
+                let Some(id) = current.and_then(|path| index.find_dir(path)) else {
+                    return;
+                };
+                ui.label(RichText::new(trf("Prefixes in {}", &[&index.nodes[id].path.display()])).strong());
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("cloud_prefixes").striped(true).show(ui, |ui| {
+                        for &child in index.nodes[id].children.iter().take(MAX_PREFIXES) {
+                            let node = &index.nodes[child];
+                            let name = paths::display_name(&node.name);
+                            ui.label(if node.is_dir { format!("{}/", name) } else { name.into_owned() });
+                            ui.label(i18n::format_bytes(node.size));
+                            ui.label(class_summary(&self.classes[child]));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+        loaded
+    }
+}
@@ -0,0 +1,347 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use walkdir::WalkDir;
+
+use crate::{
+    i18n::{self, tr, trf},
+    job::Job,
+    paths, scan,
+};
+
+const MAX_FOLDERS: usize = 30;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Provider {
+    OneDrive,
+    Dropbox,
+    GoogleDrive,
+    ICloud,
+}
+
+impl Provider {
+    fn label(&self) -> &'static str {
+        match self {
+            Provider::OneDrive => "OneDrive",
+            Provider::Dropbox => "Dropbox",
+            Provider::GoogleDrive => "Google Drive",
+            Provider::ICloud => "iCloud Drive",
+        }
+    }
+
+    fn instructions(&self) -> &'static str {
+        match self {
+            Provider::OneDrive => {
+                "Right-click a folder and choose Free up space (Windows) or Remove Download (macOS). Turning on Files On-Demand in OneDrive settings makes new files online-only."
+            }
+            Provider::Dropbox => {
+                "Right-click a folder and choose Make online-only. In Dropbox preferences, Sync > Online-only makes it the default for new files."
+            }
+            Provider::GoogleDrive => {
+                "In Drive for desktop preferences, switch My Drive to Stream files, or right-click a folder and turn off Available offline."
+            }
+            Provider::ICloud => {
+                "Right-click a folder in Finder and choose Remove Download, or turn on Optimize Mac Storage in iCloud Drive settings."
+            }
+        }
+    }
+
+    /// A command that turns a folder online-only, where the client offers one.
+    fn free_up_command(&self, path: &Path) -> Option<Vec<String>> {
+        match self {
+            // Unpinned files are what "Free up space" in Explorer leaves behind.
+            Provider::OneDrive if cfg!(windows) => Some(vec![
+                "attrib".to_string(),
+                "+U".to_string(),
+                "-P".to_string(),
+                path.join("*").to_string_lossy().into_owned(),
+                "/S".to_string(),
+                "/D".to_string(),
+            ]),
+            Provider::ICloud if cfg!(target_os = "macos") => {
+                Some(vec!["brctl".to_string(), "evict".to_string(), path.to_string_lossy().into_owned()])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Children of `dir` whose names start with `prefix`, e.g. the per-account folders under
+/// ~/Library/CloudStorage.
+fn children_starting_with(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Dropbox writes where each account syncs to into info.json.
+fn dropbox_paths() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = dirs::home_dir().map(|home| home.join(".dropbox/info.json")).into_iter().collect();
+    files.extend(dirs::data_dir().map(|dir| dir.join("Dropbox").join("info.json")));
+    files.extend(dirs::data_local_dir().map(|dir| dir.join("Dropbox").join("info.json")));
+    let mut found = Vec::new();
+    for file in files {
+        let Ok(info) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let Ok(info) = serde_json::from_str::<serde_json::Value>(&info) else {
+            continue;
+        };
+        for account in info.as_object().into_iter().flat_map(|accounts| accounts.values()) {
+            found.extend(account["path"].as_str().map(PathBuf::from));
+        }
+    }
+    found
+}
+
+/// Local folders the cloud drive clients sync into.
+pub fn sync_folders() -> Vec<(Provider, PathBuf)> {
+    let mut folders = Vec::new();
+    let home = dirs::home_dir();
+    for variable in ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"] {
+        folders.extend(std::env::var_os(variable).map(|path| (Provider::OneDrive, PathBuf::from(path))));
+    }
+    folders.extend(dropbox_paths().into_iter().map(|path| (Provider::Dropbox, path)));
+    if let Some(home) = &home {
+        let cloud_storage = home.join("Library/CloudStorage");
+        for (provider, prefix) in
+            [(Provider::OneDrive, "OneDrive"), (Provider::Dropbox, "Dropbox"), (Provider::GoogleDrive, "GoogleDrive")]
+        {
+            folders.extend(children_starting_with(&cloud_storage, prefix).into_iter().map(|path| (provider, path)));
+        }
+        folders.extend(children_starting_with(home, "OneDrive").into_iter().map(|path| (Provider::OneDrive, path)));
+        folders.push((Provider::Dropbox, home.join("Dropbox")));
+        folders.push((Provider::GoogleDrive, home.join("Google Drive")));
+        folders.push((Provider::GoogleDrive, home.join("My Drive")));
+        folders.push((Provider::ICloud, home.join("Library/Mobile Documents/com~apple~CloudDocs")));
+        folders.push((Provider::ICloud, home.join("iCloudDrive")));
+    }
+    let mut seen = HashSet::new();
+    folders.retain(|(_, path)| path.is_dir() && seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())));
+    folders
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Usage {
+    pub total: u64,
+    /// Bytes downloaded to this device.
+    pub local: u64,
+    /// Local bytes the user asked to always keep on this device.
+    pub pinned: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.total += other.total;
+        self.local += other.local;
+        self.pinned += other.pinned;
+    }
+
+    /// What marking everything online-only would give back.
+    pub fn reclaimable(&self) -> u64 {
+        self.local.saturating_sub(self.pinned)
+    }
+}
+
+pub struct SyncFolder {
+    pub provider: Provider,
+    pub path: PathBuf,
+    pub usage: Usage,
+    /// Top-level entries, most reclaimable first.
+    pub children: Vec<(PathBuf, Usage)>,
+}
+
+/// Reads only metadata, which doesn't make the clients download anything.
+fn measure(provider: Provider, root: PathBuf) -> SyncFolder {
+    let mut children: HashMap<PathBuf, Usage> = HashMap::new();
+    let mut usage = Usage::default();
+    for entry in WalkDir::new(&root).follow_links(false).into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let mut file = Usage { total: metadata.len(), ..Usage::default() };
+        if !scan::is_online_only(&metadata) {
+            file.local = metadata.len();
+            if scan::is_pinned(&metadata) {
+                file.pinned = metadata.len();
+            }
+        }
+        usage.add(file);
+        let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+        if let Some(first) = relative.components().next() {
+            children.entry(root.join(first)).or_default().add(file);
+        }
+    }
+    let mut children: Vec<(PathBuf, Usage)> = children.into_iter().collect();
+    children.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.reclaimable()));
+    SyncFolder { provider, path: root, usage, children }
+}
+
+pub fn analyze() -> Vec<SyncFolder> {
+    sync_folders().into_iter().map(|(provider, path)| measure(provider, path)).collect()
+}
+
+fn run_command(command: Vec<String>) -> Result<String, String> {
+    let (program, args) = command.split_first().ok_or("Empty command")?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(command.join(" "))
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[derive(Default)]
+pub struct CloudSyncState {
+    pub open: bool,
+    job: Option<Job<Vec<SyncFolder>>>,
+    folders: Option<Vec<SyncFolder>>,
+    run_job: Option<Job<Result<String, String>>>,
+    message: Option<Result<String, String>>,
+}
+
+impl CloudSyncState {
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) {
+        if self.folders.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(analyze));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(folders) => {
+                    self.folders = Some(folders);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.run_job {
+            match job.poll() {
+                Some(result) => {
+                    self.message = Some(result);
+                    self.run_job = None;
+                    self.job = Some(Job::spawn(analyze));
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut run = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Cloud Sync Folders"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(660.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr(
+                            "Files a cloud drive keeps downloaded take local space; online-only files don't until they are opened.",
+                        ))
+                        .color(Color32::GRAY),
+                    );
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked() {
+                        self.job = Some(Job::spawn(analyze));
+                    }
+                });
+                if self.job.is_some() || self.run_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.message {
+                    Some(Ok(command)) => {
+                        ui.label(RichText::new(trf("Ran {}", &[command])).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+                let Some(folders) = &self.folders else {
+                    return;
+                };
+                if folders.is_empty() {
+                    ui.label(tr("No OneDrive, Dropbox, Google Drive or iCloud Drive folders found."));
+                    return;
+                }
+                let reclaimable: u64 = folders.iter().map(|folder| folder.usage.reclaimable()).sum();
+                let text = trf("Up to {} could be freed by making files online-only", &[&i18n::format_bytes(reclaimable)]);
+                ui.label(RichText::new(text).strong());
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    for folder in folders {
+                        let usage = folder.usage;
+                        egui::CollapsingHeader::new(trf(
+                            "{} - {}: {} local of {}",
+                            &[
+                                &folder.provider.label(),
+                                &paths::display_path(&folder.path),
+                                &i18n::format_bytes(usage.local),
+                                &i18n::format_bytes(usage.total),
+                            ],
+                        ))
+                        .id_source(("sync_folder", &folder.path))
+                        .show(ui, |ui| {
+                            ui.label(trf(
+                                "Online-only: {}. Kept on this device: {}.",
+                                &[&i18n::format_bytes(usage.total - usage.local), &i18n::format_bytes(usage.pinned)],
+                            ));
+                            ui.label(RichText::new(tr(folder.provider.instructions())).color(Color32::GRAY));
+                            egui::Grid::new(("sync_children", &folder.path)).striped(true).show(ui, |ui| {
+                                ui.label(tr("Folder"));
+                                ui.label(tr("Local"));
+                                ui.label(tr("Reclaimable"));
+                                ui.label("");
+                                ui.end_row();
+                                for (path, usage) in folder.children.iter().take(MAX_FOLDERS) {
+                                    if usage.reclaimable() == 0 {
+                                        break;
+                                    }
+                                    ui.label(paths::display_path(path.strip_prefix(&folder.path).unwrap_or(path)));
+                                    ui.label(i18n::format_bytes(usage.local));
+                                    ui.label(i18n::format_bytes(usage.reclaimable()));
+                                    match folder.provider.free_up_command(path) {
+                                        Some(command) => {
+                                            let button = ui
+                                                .add_enabled(!read_only, egui::Button::new(tr("Make online-only")))
+                                                .on_hover_text(command.join(" "))
+                                                .on_disabled_hover_text(tr("Disabled in read-only mode"));
+                                            if button.clicked() {
+                                                run = Some(command);
+                                            }
+                                        }
+                                        None => {
+                                            ui.label("");
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+        self.open = open;
+
+        if let Some(command) = run {
+            self.message = None;
+            self.run_job = Some(Job::spawn(move || run_command(command)));
+        }
+    }
+}
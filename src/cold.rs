@@ -0,0 +1,159 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    archive::ArchiveState,
+    heatmap,
+    i18n::{self, tr, trf},
+    index::{Node, ScanIndex},
+    paths,
+};
+
+const YEAR: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+/// Years without a read or write after which data counts as cold.
+pub const THRESHOLDS: [u32; 3] = [1, 2, 5];
+const THRESHOLD_LABELS: [&str; 3] =
+    ["Untouched for a year: {} ({}%)", "Untouched for 2 years: {} ({}%)", "Untouched for 5 years: {} ({}%)"];
+const MAX_CHILDREN: usize = 50;
+
+pub struct ColdReport {
+    /// Cold bytes under each node of the index, per threshold.
+    cold: Vec<[u64; 3]>,
+}
+
+impl ColdReport {
+    pub fn total(&self, threshold: usize) -> u64 {
+        self.cold.first().map_or(0, |root| root[threshold])
+    }
+}
+
+fn cold_flags(node: &Node) -> [bool; 3] {
+    let age = heatmap::age(node.accessed);
+    THRESHOLDS.map(|years| age.is_some_and(|age| age >= YEAR * years))
+}
+
+/// Adds up the bytes not used for each threshold, bottom-up through the index. Files that
+/// low-memory mode didn't list follow their folder's most recent use.
+pub fn analyze(index: &ScanIndex) -> ColdReport {
+    let mut cold = vec![[0; 3]; index.nodes.len()];
+    for (id, node) in index.nodes.iter().enumerate().rev() {
+        let flags = cold_flags(node);
+        let own = if node.is_dir { node.small_size } else { node.size };
+        for (threshold, is_cold) in flags.iter().enumerate() {
+            if *is_cold {
+                cold[id][threshold] += own;
+            }
+        }
+        if let Some(parent) = node.parent {
+            let below = cold[id];
+            for (threshold, bytes) in below.iter().enumerate() {
+                cold[parent][threshold] += bytes;
+            }
+        }
+    }
+    ColdReport { cold }
+}
+
+#[derive(Default)]
+pub struct ColdState {
+    pub open: bool,
+    report: Option<ColdReport>,
+    threshold: usize,
+}
+
+impl ColdState {
+    /// Drops the report after the index changes.
+    pub fn invalidate(&mut self) {
+        self.report = None;
+    }
+
+    /// Draws the window and returns a folder to jump to when one is clicked; folders picked
+    /// for archiving are added to `archive`.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>, archive: &mut ArchiveState) -> Option<PathBuf> {
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Cold Data"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr(
+                        "Data nobody has read or changed in years is a candidate for an external or archive drive. Where the system doesn't keep access times, the modification time is used.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                if self.report.is_none() && ui.button(tr("Analyze")).clicked() {
+                    self.report = Some(analyze(index));
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+                let scanned = index.nodes.first().map_or(0, |root| root.size).max(1);
+                ui.horizontal(|ui| {
+                    for (threshold, label) in THRESHOLD_LABELS.iter().enumerate() {
+                        let total = report.total(threshold);
+                        let text = trf(label, &[&i18n::format_bytes(total), &(total * 100 / scanned)]);
+                        ui.selectable_value(&mut self.threshold, threshold, text);
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(440.0).show(ui, |ui| {
+                    render_dir(ui, index, report, 0, self.threshold, &mut navigate, archive);
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
+
+/// Lists the subfolders of `id` that hold cold data, largest share first; each expands into
+/// its own subfolders.
+fn render_dir(
+    ui: &mut egui::Ui,
+    index: &ScanIndex,
+    report: &ColdReport,
+    id: usize,
+    threshold: usize,
+    navigate: &mut Option<PathBuf>,
+    archive: &mut ArchiveState,
+) {
+    let mut children: Vec<usize> = index.nodes[id]
+        .children
+        .iter()
+        .copied()
+        .filter(|&child| index.nodes[child].is_dir && report.cold[child][threshold] > 0)
+        .collect();
+    children.sort_by_key(|&child| std::cmp::Reverse(report.cold[child][threshold]));
+    if children.is_empty() {
+        ui.label(RichText::new(tr("No cold folders below here.")).color(Color32::GRAY));
+        return;
+    }
+    for &child in children.iter().take(MAX_CHILDREN) {
+        let node = &index.nodes[child];
+        let title = trf(
+            "{} - {} cold of {}",
+            &[
+                &paths::display_name(&node.name),
+                &i18n::format_bytes(report.cold[child][threshold]),
+                &i18n::format_bytes(node.size),
+            ],
+        );
+        egui::CollapsingHeader::new(title).id_source(("cold_dir", &node.path)).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.link(tr("Open folder")).clicked() {
+                    *navigate = Some(node.path.clone());
+                }
+                if ui.link(tr("Archive...")).clicked() {
+                    archive.add(node.path.clone(), node.size);
+                }
+            });
+            render_dir(ui, index, report, child, threshold, navigate, archive);
+        });
+    }
+}
@@ -0,0 +1,239 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{config, i18n::tr};
+
+const COLUMNS_FILE: &str = "columns.json";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Column {
+    Size,
+    OwnSize,
+    Percent,
+    Items,
+    Modified,
+    Accessed,
+    Owner,
+    Extension,
+    Logical,
+    Compression,
+}
+
+impl Column {
+    pub const ALL: [Column; 10] = [
+        Column::Size,
+        Column::OwnSize,
+        Column::Percent,
+        Column::Items,
+        Column::Modified,
+        Column::Accessed,
+        Column::Owner,
+        Column::Extension,
+        Column::Logical,
+        Column::Compression,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Column::Size => "Size",
+            Column::OwnSize => "Own size",
+            Column::Percent => "Percent",
+            Column::Items => "Items",
+            Column::Modified => "Modified",
+            Column::Accessed => "Accessed",
+            Column::Owner => "Owner",
+            Column::Extension => "Extension",
+            Column::Logical => "Before compression",
+            Column::Compression => "Compression",
+        }
+    }
+
+    fn width(&self) -> f32 {
+        match self {
+            Column::Size | Column::OwnSize | Column::Logical => 80.0,
+            Column::Percent => 50.0,
+            Column::Items => 70.0,
+            Column::Modified | Column::Accessed => 150.0,
+            Column::Owner => 90.0,
+            Column::Extension | Column::Compression => 60.0,
+        }
+    }
+
+    fn numeric(&self) -> bool {
+        matches!(
+            self,
+            Column::Size | Column::OwnSize | Column::Percent | Column::Items | Column::Logical | Column::Compression
+        )
+    }
+}
+
+/// Lays out one fixed-width cell so the rows line up under the header; numbers are
+/// right-aligned.
+pub fn cell<R>(ui: &mut egui::Ui, column: Column, add: impl FnOnce(&mut egui::Ui) -> R) -> egui::InnerResponse<R> {
+    let layout = if column.numeric() {
+        egui::Layout::right_to_left(egui::Align::Center)
+    } else {
+        egui::Layout::left_to_right(egui::Align::Center)
+    };
+    let size = egui::vec2(column.width(), ui.spacing().interact_size.y);
+    ui.allocate_ui_with_layout(size, layout, |ui| {
+        ui.set_min_size(size);
+        add(ui)
+    })
+}
+
+/// Which columns the file list shows, in order, for each scanned root.
+#[derive(Serialize, Deserialize)]
+pub struct ColumnLayouts {
+    default: Vec<Column>,
+    roots: BTreeMap<PathBuf, Vec<Column>>,
+    #[serde(skip)]
+    dragging: Option<Column>,
+}
+
+impl Default for ColumnLayouts {
+    fn default() -> Self {
+        Self { default: vec![Column::Size, Column::OwnSize], roots: BTreeMap::new(), dragging: None }
+    }
+}
+
+impl ColumnLayouts {
+    pub fn load() -> Self {
+        config::load(COLUMNS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(COLUMNS_FILE, self)
+    }
+
+    pub fn for_root(&self, root: Option<&Path>) -> &[Column] {
+        root.and_then(|root| self.roots.get(root)).unwrap_or(&self.default)
+    }
+
+    /// Changing the columns inside a root gives it its own layout.
+    fn for_root_mut(&mut self, root: Option<&Path>) -> &mut Vec<Column> {
+        match root {
+            Some(root) => self.roots.entry(root.to_path_buf()).or_insert_with(|| self.default.clone()),
+            None => &mut self.default,
+        }
+    }
+
+    fn move_column(&mut self, root: Option<&Path>, column: Column, target: Column) -> bool {
+        let columns = self.for_root_mut(root);
+        let (Some(from), Some(to)) =
+            (columns.iter().position(|&c| c == column), columns.iter().position(|&c| c == target))
+        else {
+            return false;
+        };
+        if from == to {
+            return false;
+        }
+        let column = columns.remove(from);
+        columns.insert(to, column);
+        true
+    }
+
+    /// The contents of the "Columns" menu; returns true when the layout changed.
+    pub fn chooser(&mut self, ui: &mut egui::Ui, root: Option<&Path>) -> bool {
+        let mut changed = false;
+        let current = self.for_root(root).to_vec();
+        let hidden = Column::ALL.into_iter().filter(|column| !current.contains(column));
+        let mut moved = None;
+        for (position, column) in current.iter().copied().chain(hidden).enumerate() {
+            ui.horizontal(|ui| {
+                let mut shown = current.contains(&column);
+                if ui.checkbox(&mut shown, tr(column.label())).changed() {
+                    let columns = self.for_root_mut(root);
+                    if shown {
+                        columns.push(column);
+                    } else {
+                        columns.retain(|&c| c != column);
+                    }
+                    changed = true;
+                }
+                if position < current.len() {
+                    if ui.add_enabled(position > 0, egui::Button::new("⏶").small()).clicked() {
+                        moved = Some((column, current[position - 1]));
+                    }
+                    let last = position + 1 == current.len();
+                    if ui.add_enabled(!last, egui::Button::new("⏷").small()).clicked() {
+                        moved = Some((column, current[position + 1]));
+                    }
+                }
+            });
+        }
+        if let Some((column, target)) = moved {
+            changed |= self.move_column(root, column, target);
+        }
+        if let Some(root) = root {
+            ui.separator();
+            let customized = self.roots.contains_key(root);
+            if ui.add_enabled(customized, egui::Button::new(tr("Use for All Folders"))).clicked() {
+                self.default = self.for_root(Some(root)).to_vec();
+                self.roots.clear();
+                changed = true;
+            }
+            if ui.add_enabled(customized, egui::Button::new(tr("Reset to Default"))).clicked() {
+                self.roots.remove(root);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Column titles above the list; they can be dragged onto each other to reorder them, and
+    /// the size columns sort the list when clicked. Returns whether the layout changed and the
+    /// size column clicked, if any; `sorted` is the one the list is sorted by.
+    pub fn header(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: Option<&Path>,
+        columns: &[Column],
+        sorted: Option<Column>,
+    ) -> (bool, Option<Column>) {
+        let mut cells = Vec::with_capacity(columns.len());
+        let mut clicked = None;
+        for &column in columns {
+            let color = if self.dragging == Some(column) || sorted == Some(column) {
+                Color32::WHITE
+            } else {
+                Color32::GRAY
+            };
+            let mut title = RichText::new(tr(column.label())).small().color(color);
+            if sorted == Some(column) {
+                title = title.strong();
+            }
+            let sortable = matches!(column, Column::Size | Column::OwnSize);
+            let sense = if sortable { egui::Sense::click_and_drag() } else { egui::Sense::drag() };
+            let cell = cell(ui, column, |ui| ui.add(egui::Label::new(title).sense(sense)));
+            if cell.inner.drag_started() {
+                self.dragging = Some(column);
+            }
+            if sortable && cell.inner.clicked() {
+                clicked = Some(column);
+            }
+            let hint = if sortable { tr("Click to sort, drag to reorder") } else { tr("Drag to reorder") };
+            cell.inner.on_hover_cursor(egui::CursorIcon::Grab).on_hover_text(hint);
+            cells.push((column, cell.response.rect));
+        }
+
+        let Some(column) = self.dragging else {
+            return (false, clicked);
+        };
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        if !ui.input(|i| i.pointer.any_released()) {
+            return (false, clicked);
+        }
+        self.dragging = None;
+        let pointer = ui.input(|i| i.pointer.interact_pos());
+        let target = pointer.and_then(|pointer| cells.iter().find(|(_, rect)| rect.x_range().contains(pointer.x)));
+        match target {
+            Some(&(target, _)) => (self.move_column(root, column, target), clicked),
+            None => (false, clicked),
+        }
+    }
+}
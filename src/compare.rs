@@ -0,0 +1,233 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    dialogs,
+    i18n::{self, tr, trf},
+    job::Job,
+    scan::{self, ScanOptions},
+};
+
+pub enum Difference {
+    OnlyA(u64),
+    OnlyB(u64),
+    SizeMismatch { a: u64, b: u64 },
+}
+
+pub struct CompareEntry {
+    pub relative: PathBuf,
+    pub is_dir: bool,
+    pub difference: Difference,
+}
+
+#[derive(Default)]
+pub struct Comparison {
+    pub a_total: u64,
+    pub b_total: u64,
+    pub entries: Vec<CompareEntry>,
+}
+
+struct Side {
+    /// The file's length; folders are measured only when one side lacks them.
+    size: u64,
+    is_dir: bool,
+}
+
+impl Side {
+    fn size(&self, path: &Path) -> u64 {
+        if self.is_dir {
+            scan::measure_dir(path, &ScanOptions::default()).size
+        } else {
+            self.size
+        }
+    }
+}
+
+fn list(dir: &Path) -> BTreeMap<PathBuf, Side> {
+    let mut sides = BTreeMap::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.filter_map(Result::ok) {
+            if let Ok(metadata) = entry.metadata() {
+                let size = if metadata.is_file() { metadata.len() } else { 0 };
+                sides.insert(
+                    PathBuf::from(entry.file_name()),
+                    Side { size, is_dir: metadata.is_dir() },
+                );
+            }
+        }
+    }
+    sides
+}
+
+pub fn compare(a: &Path, b: &Path) -> Comparison {
+    let mut comparison = Comparison::default();
+    (comparison.a_total, comparison.b_total) = compare_dir(a, b, Path::new(""), &mut comparison);
+    comparison
+}
+
+/// Compares every entry below `relative` on both sides and returns the two folders' sizes.
+/// Folders present on both sides are always entered, since equal totals can hide missing,
+/// renamed or empty files; for files, equal sizes are taken to mean equal files.
+fn compare_dir(a: &Path, b: &Path, relative: &Path, comparison: &mut Comparison) -> (u64, u64) {
+    let left = list(&a.join(relative));
+    let mut right = list(&b.join(relative));
+    let (mut total_a, mut total_b) = (0, 0);
+
+    for (name, side_a) in left {
+        let path = relative.join(&name);
+        match right.remove(&name) {
+            Some(side_b) if side_a.is_dir && side_b.is_dir => {
+                let (size_a, size_b) = compare_dir(a, b, &path, comparison);
+                total_a += size_a;
+                total_b += size_b;
+            }
+            None => {
+                let size = side_a.size(&a.join(&path));
+                total_a += size;
+                comparison.entries.push(CompareEntry {
+                    relative: path,
+                    is_dir: side_a.is_dir,
+                    difference: Difference::OnlyA(size),
+                });
+            }
+            Some(side_b) => {
+                let (size_a, size_b) = (side_a.size(&a.join(&path)), side_b.size(&b.join(&path)));
+                total_a += size_a;
+                total_b += size_b;
+                if size_a != size_b || side_a.is_dir != side_b.is_dir {
+                    comparison.entries.push(CompareEntry {
+                        relative: path,
+                        is_dir: side_a.is_dir,
+                        difference: Difference::SizeMismatch { a: size_a, b: size_b },
+                    });
+                }
+            }
+        }
+    }
+
+    for (name, side_b) in right {
+        let path = relative.join(name);
+        let size = side_b.size(&b.join(&path));
+        total_b += size;
+        comparison.entries.push(CompareEntry {
+            relative: path,
+            is_dir: side_b.is_dir,
+            difference: Difference::OnlyB(size),
+        });
+    }
+    (total_a, total_b)
+}
+
+#[derive(Default)]
+pub struct CompareState {
+    pub open: bool,
+    path_a: String,
+    path_b: String,
+    job: Option<Job<Comparison>>,
+    result: Option<Comparison>,
+}
+
+impl CompareState {
+    fn start(&mut self) {
+        let a = PathBuf::from(&self.path_a);
+        let b = PathBuf::from(&self.path_b);
+        self.result = None;
+        self.job = Some(Job::spawn(move || compare(&a, &b)));
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.job {
+            if let Some(result) = job.poll() {
+                self.result = Some(result);
+                self.job = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Compare Directories"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                for (label, path) in [("A:", &mut self.path_a), ("B:", &mut self.path_b)] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.text_edit_singleline(path);
+                        if ui.button(tr("Browse")).clicked() {
+                            if let Some(picked) = dialogs::pick_folder() {
+                                *path = picked.display().to_string();
+                            }
+                        }
+                    });
+                }
+
+                let ready = !self.path_a.is_empty() && !self.path_b.is_empty();
+                if ui.add_enabled(ready && self.job.is_none(), egui::Button::new(tr("Compare"))).clicked() {
+                    self.start();
+                }
+
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+
+                if let Some(result) = &self.result {
+                    render_comparison(ui, result);
+                }
+            });
+        self.open = open;
+    }
+}
+
+fn render_comparison(ui: &mut egui::Ui, result: &Comparison) {
+    ui.separator();
+    ui.label(trf(
+        "A total: {}   B total: {}",
+        &[&i18n::format_bytes(result.a_total), &i18n::format_bytes(result.b_total)],
+    ));
+    if result.entries.is_empty() {
+        ui.label(RichText::new(tr("Directories match")).color(Color32::GREEN));
+        return;
+    }
+
+    let section = |difference: &Difference| match difference {
+        Difference::OnlyA(_) => 0,
+        Difference::OnlyB(_) => 1,
+        Difference::SizeMismatch { .. } => 2,
+    };
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        for (index, title) in ["Only in A", "Only in B", "Size mismatch"].into_iter().enumerate() {
+            let entries: Vec<_> = result
+                .entries
+                .iter()
+                .filter(|e| section(&e.difference) == index)
+                .collect();
+            egui::CollapsingHeader::new(format!("{} ({})", tr(title), entries.len()))
+                .id_source(title)
+                .default_open(!entries.is_empty())
+                .show(ui, |ui| {
+                    for entry in entries {
+                        let icon = if entry.is_dir { "📁" } else { "📄" };
+                        let size = match entry.difference {
+                            Difference::OnlyA(size) | Difference::OnlyB(size) => {
+                                i18n::format_bytes(size)
+                            }
+                            Difference::SizeMismatch { a, b } => format!(
+                                "{} vs {}",
+                                i18n::format_bytes(a),
+                                i18n::format_bytes(b)
+                            ),
+                        };
+                        ui.label(format!("{} {} - {}", icon, entry.relative.display(), size));
+                    }
+                });
+        }
+    });
+}
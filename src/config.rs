@@ -0,0 +1,121 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::i18n::Locale;
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("disk-analyzer"))
+}
+
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    data_dir()
+        .and_then(|dir| fs::read_to_string(dir.join(name)).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<(), String> {
+    let dir = data_dir().ok_or("No data directory available")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Error creating {}: {}", dir.display(), e))?;
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Error serializing {}: {}", name, e))?;
+
+    let path = dir.join(name);
+    let tmp = path.with_extension("tmp");
+    write_private(&tmp, &contents).map_err(|e| format!("Error writing {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// Writes a file only its owner can read, since some settings, like the alert mail
+/// password, are secrets.
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+
+    // The mode only applies to new files, so a leftover from an earlier attempt goes first.
+    let _ = fs::remove_file(path);
+    fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Preferences {
+    pub locale: Locale,
+    pub decimal_units: bool,
+    pub show_exact_bytes: bool,
+    pub ui_scale: f32,
+    pub text_scale: f32,
+    pub show_diagnostics: bool,
+    pub read_only: bool,
+    /// Moving to the trash can be undone, so its confirmation is optional.
+    pub confirm_trash: bool,
+    /// Permanently deleting a folder at least this large asks for its name to be typed.
+    pub typed_confirmation: bool,
+    pub typed_confirmation_size: u64,
+    /// Scan throttling; zero means no limit.
+    pub max_workers: usize,
+    pub max_operations_per_second: u64,
+    pub low_priority_io: bool,
+    /// Scans that take at least this many seconds raise a desktop notification when they
+    /// finish while the window is in the background.
+    pub notify_long_scans: bool,
+    pub long_scan_secs: u64,
+    /// Classifies files at least this large by their first bytes instead of their extension.
+    pub sniff_content: bool,
+    pub sniff_min_size: u64,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            locale: Locale::default(),
+            decimal_units: false,
+            show_exact_bytes: false,
+            ui_scale: 1.0,
+            text_scale: 1.0,
+            show_diagnostics: false,
+            read_only: false,
+            confirm_trash: true,
+            typed_confirmation: true,
+            typed_confirmation_size: 10 * 1024 * 1024 * 1024,
+            max_workers: 0,
+            max_operations_per_second: 0,
+            low_priority_io: false,
+            notify_long_scans: true,
+            long_scan_secs: 30,
+            sniff_content: false,
+            sniff_min_size: 1024 * 1024,
+        }
+    }
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        load(PREFERENCES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        save(PREFERENCES_FILE, self)
+    }
+
+    pub fn needs_typed_confirmation(&self, is_dir: bool, size: u64) -> bool {
+        self.typed_confirmation && is_dir && size >= self.typed_confirmation_size
+    }
+
+    pub fn apply(&self) {
+        crate::i18n::set_locale(self.locale);
+        crate::i18n::set_decimal_units(self.decimal_units);
+        crate::throttle::configure(self.max_workers, self.max_operations_per_second, self.low_priority_io);
+        crate::category::configure_sniffing(self.sniff_content, self.sniff_min_size);
+    }
+}
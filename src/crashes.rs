@@ -0,0 +1,261 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    category, heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, scan, windows_cleanup,
+};
+
+const DUMP_EXTENSIONS: [&str; 5] = ["dmp", "mdmp", "hdmp", "crash", "ips"];
+
+pub struct CrashFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// The crash folder it was found in, or none for a stray dump found in the scan.
+    pub source: Option<&'static str>,
+}
+
+/// Folders where the system and crash reporters keep dumps, by platform.
+fn crash_dirs() -> Vec<(&'static str, PathBuf)> {
+    let mut dirs = Vec::new();
+    if cfg!(windows) {
+        let windows = windows_cleanup::windows_dir();
+        let program_data = std::env::var_os("ProgramData").map(PathBuf::from);
+        dirs.extend(dirs::data_local_dir().map(|dir| ("Application crash dumps", dir.join("CrashDumps"))));
+        dirs.push(("Windows minidumps", windows.join("Minidump")));
+        dirs.push(("Windows memory dump", windows.join("MEMORY.DMP")));
+        dirs.extend(
+            dirs::data_local_dir().map(|dir| ("Windows Error Reporting", dir.join(r"Microsoft\Windows\WER\ReportArchive"))),
+        );
+        for folder in ["ReportArchive", "ReportQueue"] {
+            dirs.extend(
+                program_data
+                    .as_ref()
+                    .map(|dir| ("Windows Error Reporting", dir.join(r"Microsoft\Windows\WER").join(folder))),
+            );
+        }
+    } else if cfg!(target_os = "macos") {
+        dirs.extend(dirs::home_dir().map(|home| ("Diagnostic reports", home.join("Library/Logs/DiagnosticReports"))));
+        dirs.push(("Diagnostic reports", PathBuf::from("/Library/Logs/DiagnosticReports")));
+        dirs.push(("Core dumps", PathBuf::from("/cores")));
+    } else {
+        dirs.push(("systemd-coredump", PathBuf::from("/var/lib/systemd/coredump")));
+        dirs.push(("Apport crash reports", PathBuf::from("/var/crash")));
+        dirs.push(("Apport core dumps", PathBuf::from("/var/lib/apport/coredump")));
+    }
+    dirs
+}
+
+/// Core files are named `core` or `core.<pid>`.
+fn is_dump(path: &Path, name: &str) -> bool {
+    let is_core = name == "core" || name.strip_prefix("core.").is_some_and(|pid| pid.chars().all(|c| c.is_ascii_digit()));
+    is_core || DUMP_EXTENSIONS.contains(&category::extension(path).as_str())
+}
+
+/// Lists what the crash folders hold, then adds dumps from the scan that lie elsewhere.
+pub fn find_crashes(stray: Vec<(PathBuf, u64, Option<SystemTime>)>) -> Vec<CrashFile> {
+    let mut crashes = Vec::new();
+    let mut seen = HashSet::new();
+    for (source, dir) in crash_dirs() {
+        let Ok(metadata) = fs::metadata(&dir) else {
+            continue;
+        };
+        let entries = if metadata.is_dir() {
+            fs::read_dir(&dir).map(|entries| entries.filter_map(Result::ok).map(|entry| entry.path()).collect()).unwrap_or_default()
+        } else {
+            vec![dir]
+        };
+        for path in entries {
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let size = if metadata.is_dir() {
+                scan::measure_dir(&path, &Default::default()).size
+            } else {
+                scan::file_size(&path, &metadata)
+            };
+            seen.insert(path.clone());
+            crashes.push(CrashFile { path, size, modified: metadata.modified().ok(), source: Some(source) });
+        }
+    }
+    for (path, size, modified) in stray {
+        if !seen.contains(&path) && !crashes.iter().any(|crash| path.starts_with(&crash.path)) {
+            crashes.push(CrashFile { path, size, modified, source: None });
+        }
+    }
+    crashes.sort_by_key(|crash| std::cmp::Reverse(crash.size));
+    crashes
+}
+
+fn age_text(time: Option<SystemTime>) -> String {
+    match heatmap::age(time) {
+        Some(age) => trf("{} days ago", &[&i18n::format_count(age.as_secs() / (24 * 60 * 60))]),
+        None => "-".to_string(),
+    }
+}
+
+#[derive(Default)]
+pub struct CrashesState {
+    pub open: bool,
+    job: Option<Job<Vec<CrashFile>>>,
+    crashes: Option<Vec<CrashFile>>,
+    selected: HashSet<PathBuf>,
+    confirm: bool,
+}
+
+impl CrashesState {
+    /// Draws the window and returns the dumps the user confirmed for deletion, with their
+    /// sizes.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>, read_only: bool) -> Vec<(PathBuf, u64)> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(crashes) => {
+                    self.crashes = Some(crashes);
+                    self.selected.clear();
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut clean = Vec::new();
+        let mut open = self.open;
+        egui::Window::new(tr("Crash Dumps"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(tr(
+                        "Core dumps, minidumps and crash reports. They are only useful while a crash is being investigated.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Find Crash Dumps"))).clicked() {
+                        let stray: Vec<(PathBuf, u64, Option<SystemTime>)> = index
+                            .map(|index| {
+                                index
+                                    .nodes
+                                    .iter()
+                                    .filter(|node| !node.is_dir && is_dump(&node.path, &node.name.to_string_lossy()))
+                                    .map(|node| (node.path.clone(), node.size, node.modified))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        self.job = Some(Job::spawn(move || find_crashes(stray)));
+                    }
+                    if self.job.is_some() {
+                        ui.spinner();
+                    }
+                });
+                let Some(crashes) = &self.crashes else {
+                    return;
+                };
+                if crashes.is_empty() {
+                    ui.label(tr("No crash dumps found."));
+                    return;
+                }
+                let total: u64 = crashes.iter().map(|crash| crash.size).sum();
+                ui.label(trf(
+                    "{} crash files, {} in total",
+                    &[&i18n::format_count(crashes.len() as u64), &i18n::format_bytes(total)],
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Select All")).clicked() {
+                        self.selected = crashes.iter().map(|crash| crash.path.clone()).collect();
+                    }
+                    if ui.button(tr("Select None")).clicked() {
+                        self.selected.clear();
+                    }
+                    let selected_size: u64 =
+                        crashes.iter().filter(|crash| self.selected.contains(&crash.path)).map(|crash| crash.size).sum();
+                    let button = egui::Button::new(trf(
+                        "Delete {} Selected ({})",
+                        &[&i18n::format_count(self.selected.len() as u64), &i18n::format_bytes(selected_size)],
+                    ));
+                    if ui
+                        .add_enabled(!read_only && !self.selected.is_empty(), button)
+                        .on_disabled_hover_text(if read_only {
+                            tr("Disabled in read-only mode")
+                        } else {
+                            tr("Select the files to delete first")
+                        })
+                        .clicked()
+                    {
+                        self.confirm = true;
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("crash_dumps").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label(tr("File"));
+                        ui.label(tr("Size"));
+                        ui.label(tr("Modified"));
+                        ui.label(tr("Source"));
+                        ui.end_row();
+                        for crash in crashes {
+                            let mut selected = self.selected.contains(&crash.path);
+                            if ui.checkbox(&mut selected, "").changed() {
+                                if selected {
+                                    self.selected.insert(crash.path.clone());
+                                } else {
+                                    self.selected.remove(&crash.path);
+                                }
+                            }
+                            ui.label(paths::display_path(&crash.path));
+                            ui.label(i18n::format_bytes(crash.size));
+                            ui.label(age_text(crash.modified));
+                            ui.label(crash.source.map_or(tr("Found in scan"), tr));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+
+        if self.confirm {
+            let mut choice = None;
+            egui::Window::new(tr("Confirm Deletion"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf(
+                        "Permanently delete {} crash files?",
+                        &[&i18n::format_count(self.selected.len() as u64)],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Yes")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("No")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            if choice == Some(true) {
+                if let Some(crashes) = &mut self.crashes {
+                    let (chosen, kept): (Vec<CrashFile>, Vec<CrashFile>) =
+                        crashes.drain(..).partition(|crash| self.selected.contains(&crash.path));
+                    *crashes = kept;
+                    clean = chosen.into_iter().map(|crash| (crash.path, crash.size)).collect();
+                }
+                self.selected.clear();
+            }
+            if choice.is_some() {
+                self.confirm = false;
+            }
+        }
+        clean
+    }
+}
@@ -0,0 +1,347 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    caches, config, dialogs,
+    i18n::{self, tr, trf},
+    job::Job,
+    mounts, paths, scan, volume,
+};
+
+const DASHBOARD_FILE: &str = "dashboard.json";
+const DAY: u64 = 24 * 60 * 60;
+/// Growth is measured against the newest sample at least this old.
+const GROWTH_WINDOW: u64 = 7 * DAY;
+/// One sample per root and day is kept, so this is about a year of history.
+const MAX_SAMPLES: usize = 365;
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(120.0, 18.0);
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Sample {
+    at: u64,
+    used: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct DashboardStore {
+    /// Empty means the local drives.
+    roots: Vec<PathBuf>,
+    history: BTreeMap<PathBuf, Vec<Sample>>,
+}
+
+impl DashboardStore {
+    fn load() -> Self {
+        config::load(DASHBOARD_FILE)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        config::save(DASHBOARD_FILE, self)
+    }
+
+    fn roots(&self) -> Vec<PathBuf> {
+        if self.roots.is_empty() {
+            default_roots()
+        } else {
+            self.roots.clone()
+        }
+    }
+
+    /// Keeps the latest measurement of each day.
+    fn record(&mut self, path: &Path, at: u64, used: u64) {
+        let samples = self.history.entry(path.to_path_buf()).or_default();
+        match samples.last_mut() {
+            Some(last) if last.at / DAY == at / DAY => *last = Sample { at, used },
+            _ => samples.push(Sample { at, used }),
+        }
+        if samples.len() > MAX_SAMPLES {
+            samples.drain(..samples.len() - MAX_SAMPLES);
+        }
+    }
+
+    /// Change in use over the last week, or since the oldest sample when there's less history.
+    fn growth(&self, path: &Path, now: u64) -> Option<i64> {
+        let samples = self.history.get(path).filter(|samples| samples.len() >= 2)?;
+        let latest = samples.last()?;
+        let baseline = samples
+            .iter()
+            .rev()
+            .find(|sample| sample.at + GROWTH_WINDOW <= now)
+            .unwrap_or(&samples[0]);
+        Some(latest.used as i64 - baseline.used as i64)
+    }
+}
+
+/// Mounted disks, one per device, or the home folder where mounts can't be listed.
+fn default_roots() -> Vec<PathBuf> {
+    let mut roots = mounts::disks();
+    if roots.is_empty() {
+        roots.extend(dirs::home_dir());
+    }
+    roots
+}
+
+struct RootStatus {
+    path: PathBuf,
+    used: u64,
+    /// Total and available space of the volume holding the root.
+    capacity: Option<(u64, u64)>,
+    /// Roots on the same volume count its capacity once.
+    volume: PathBuf,
+}
+
+struct Overview {
+    roots: Vec<RootStatus>,
+    caches: Vec<caches::CacheUsage>,
+    measured_at: u64,
+}
+
+fn measure(roots: Vec<PathBuf>) -> Overview {
+    let roots = roots
+        .into_iter()
+        .map(|path| {
+            let capacity = volume::info(&path).map(|info| (info.total, info.available));
+            let mount = mounts::mount_of(&path).map(|mount| mount.mount_point);
+            // A whole drive is measured from its free space instead of walking it.
+            let used = match capacity {
+                Some((total, available)) if mount.as_deref() == Some(path.as_path()) => total.saturating_sub(available),
+                _ => scan::measure_dir(&path, &Default::default()).size,
+            };
+            RootStatus { volume: mount.unwrap_or_else(|| path.clone()), path, used, capacity }
+        })
+        .collect();
+    let caches = caches::scan_caches().into_iter().filter(|usage| usage.size > 0).collect();
+    let measured_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs());
+    Overview { roots, caches, measured_at }
+}
+
+fn growth_text(growth: i64) -> RichText {
+    let size = i18n::format_bytes(growth.unsigned_abs());
+    if growth > 0 {
+        RichText::new(format!("+{}", size)).color(Color32::from_rgb(230, 120, 80))
+    } else if growth < 0 {
+        RichText::new(format!("-{}", size)).color(Color32::GREEN)
+    } else {
+        RichText::new(size).color(Color32::GRAY)
+    }
+}
+
+fn sparkline(ui: &mut egui::Ui, samples: &[Sample]) {
+    let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+    if samples.len() < 2 {
+        return;
+    }
+    let low = samples.iter().map(|sample| sample.used).min().unwrap_or_default();
+    let high = samples.iter().map(|sample| sample.used).max().unwrap_or_default().max(low + 1);
+    let points = samples
+        .iter()
+        .enumerate()
+        .map(|(position, sample)| {
+            let x = rect.left() + rect.width() * position as f32 / (samples.len() - 1) as f32;
+            let y = rect.bottom() - rect.height() * (sample.used - low) as f32 / (high - low) as f32;
+            egui::pos2(x, y)
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(1.5, Color32::LIGHT_BLUE)));
+}
+
+pub enum DashboardAction {
+    Open(PathBuf),
+    ShowCaches,
+}
+
+#[derive(Default)]
+pub struct DashboardState {
+    pub open: bool,
+    store: Option<DashboardStore>,
+    job: Option<Job<Overview>>,
+    overview: Option<Overview>,
+    new_root: String,
+    message: Option<String>,
+}
+
+impl DashboardState {
+    fn refresh(&mut self) {
+        let roots = self.store.get_or_insert_with(DashboardStore::load).roots();
+        self.job = Some(Job::spawn(move || measure(roots)));
+    }
+
+    fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        let store = self.store.get_or_insert_with(DashboardStore::load);
+        store.roots = roots;
+        self.message = store.save().err();
+        self.refresh();
+    }
+
+    /// A home screen across every monitored drive or folder; returns what the user picked.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<DashboardAction> {
+        if self.overview.is_none() && self.job.is_none() {
+            self.refresh();
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(overview) => {
+                    let store = self.store.get_or_insert_with(DashboardStore::load);
+                    for root in &overview.roots {
+                        store.record(&root.path, overview.measured_at, root.used);
+                    }
+                    self.message = store.save().err();
+                    self.overview = Some(overview);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut action = None;
+        let mut removed = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Dashboard"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Add root:"));
+                    ui.text_edit_singleline(&mut self.new_root);
+                    if ui.button(tr("Browse")).clicked() {
+                        if let Some(path) = dialogs::pick_folder() {
+                            self.new_root = path.display().to_string();
+                        }
+                    }
+                    let path = PathBuf::from(self.new_root.trim());
+                    if ui.add_enabled(path.is_dir(), egui::Button::new(tr("Add"))).clicked() {
+                        let mut roots = self.store.get_or_insert_with(DashboardStore::load).roots();
+                        if !roots.contains(&path) {
+                            roots.push(path);
+                        }
+                        self.new_root.clear();
+                        self.set_roots(roots);
+                    }
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked() {
+                        self.refresh();
+                    }
+                    if self.job.is_some() {
+                        ui.spinner();
+                    }
+                });
+                if let Some(message) = &self.message {
+                    ui.label(RichText::new(message).color(Color32::RED));
+                }
+                let (Some(overview), Some(store)) = (&self.overview, &self.store) else {
+                    return;
+                };
+                ui.separator();
+
+                let mut volumes = HashSet::new();
+                let (total, available) = overview
+                    .roots
+                    .iter()
+                    .filter(|root| volumes.insert(&root.volume))
+                    .filter_map(|root| root.capacity)
+                    .fold((0, 0), |(total, available), capacity| (total + capacity.0, available + capacity.1));
+                if total > 0 {
+                    let used = total.saturating_sub(available);
+                    ui.label(RichText::new(tr("Combined capacity")).strong());
+                    ui.add(egui::ProgressBar::new(used as f32 / total as f32).text(trf(
+                        "{} used of {} on {} volumes, {} free",
+                        &[
+                            &i18n::format_bytes(used),
+                            &i18n::format_bytes(total),
+                            &i18n::format_count(volumes.len() as u64),
+                            &i18n::format_bytes(available),
+                        ],
+                    )));
+                }
+
+                egui::Grid::new("dashboard_roots").striped(true).show(ui, |ui| {
+                    ui.label(tr("Root"));
+                    ui.label(tr("Used"));
+                    ui.label(tr("Volume"));
+                    ui.label(tr("Last 7 days"));
+                    ui.label(tr("Trend"));
+                    ui.end_row();
+                    for root in &overview.roots {
+                        if ui.link(paths::display_path(&root.path)).clicked() {
+                            action = Some(DashboardAction::Open(root.path.clone()));
+                        }
+                        ui.label(i18n::format_bytes(root.used));
+                        match root.capacity {
+                            Some((total, available)) => {
+                                let fraction = total.saturating_sub(available) as f32 / total.max(1) as f32;
+                                ui.add(
+                                    egui::ProgressBar::new(fraction)
+                                        .desired_width(120.0)
+                                        .text(format!("{:.0}%", fraction * 100.0)),
+                                );
+                            }
+                            None => {
+                                ui.label("-");
+                            }
+                        }
+                        match store.growth(&root.path, overview.measured_at) {
+                            Some(growth) => ui.label(growth_text(growth)),
+                            None => ui.label(RichText::new(tr("No history yet")).color(Color32::GRAY)),
+                        };
+                        sparkline(ui, store.history.get(&root.path).map_or(&[], Vec::as_slice));
+                        if ui.small_button("✖").on_hover_text(tr("Remove from the dashboard")).clicked() {
+                            removed = Some(root.path.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                let mut growing: Vec<(&Path, i64)> = overview
+                    .roots
+                    .iter()
+                    .filter_map(|root| Some((root.path.as_path(), store.growth(&root.path, overview.measured_at)?)))
+                    .filter(|(_, growth)| *growth > 0)
+                    .collect();
+                growing.sort_by_key(|(_, growth)| std::cmp::Reverse(*growth));
+                if !growing.is_empty() {
+                    ui.separator();
+                    ui.label(RichText::new(tr("Largest recent growth")).strong());
+                    for (path, growth) in growing.into_iter().take(3) {
+                        ui.horizontal(|ui| {
+                            ui.label(growth_text(growth));
+                            if ui.link(paths::display_path(path)).clicked() {
+                                action = Some(DashboardAction::Open(path.to_path_buf()));
+                            }
+                        });
+                    }
+                }
+
+                ui.separator();
+                ui.label(RichText::new(tr("Cleanup suggestions")).strong());
+                if overview.caches.is_empty() {
+                    ui.label(RichText::new(tr("Nothing to clean up right now.")).color(Color32::GRAY));
+                } else {
+                    let reclaimable: u64 = overview.caches.iter().map(|usage| usage.size).sum();
+                    for usage in &overview.caches {
+                        ui.label(trf(
+                            "{} cache: {}",
+                            &[&usage.cache.manager, &i18n::format_bytes(usage.size)],
+                        ));
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(trf("{} can be reclaimed", &[&i18n::format_bytes(reclaimable)]));
+                        if ui.button(tr("Package Caches")).clicked() {
+                            action = Some(DashboardAction::ShowCaches);
+                        }
+                    });
+                }
+            });
+        self.open = open;
+        if let Some(path) = removed {
+            // Removing the last root goes back to the local drives.
+            let mut roots = self.store.get_or_insert_with(DashboardStore::load).roots();
+            roots.retain(|root| root != &path);
+            self.set_roots(roots);
+        }
+        action
+    }
+}
@@ -0,0 +1,424 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use walkdir::WalkDir;
+
+use crate::{
+    error::Error,
+    i18n::{self, tr, trf},
+    job::Job,
+    operations::Progress,
+    paths,
+};
+
+#[cfg(windows)]
+use crate::openfiles;
+
+/// An entry a deletion couldn't remove.
+#[derive(Clone)]
+pub struct Failure {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub error: String,
+    /// Another program has the entry open.
+    pub in_use: bool,
+}
+
+impl Failure {
+    fn new(path: PathBuf, is_dir: bool, error: &io::Error) -> Self {
+        Self { path, is_dir, error: error.to_string(), in_use: in_use(error) }
+    }
+}
+
+/// Sharing and lock violations.
+#[cfg(windows)]
+fn in_use(error: &io::Error) -> bool {
+    matches!(error.raw_os_error(), Some(32 | 33))
+}
+
+#[cfg(unix)]
+fn in_use(error: &io::Error) -> bool {
+    matches!(error.raw_os_error(), Some(libc::EBUSY | libc::ETXTBSY))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn in_use(_error: &io::Error) -> bool {
+    false
+}
+
+/// Removes a file or a whole tree one entry at a time, carrying on past entries that fail so
+/// as much as possible goes. Only cancelling stops it early.
+pub fn delete_tree(path: &Path, progress: &Progress) -> Result<Vec<Failure>, Error> {
+    let root = paths::extended(path);
+    let original = |walked: &Path| match walked.strip_prefix(&root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => path.join(relative),
+        _ => path.to_path_buf(),
+    };
+    let mut failures: Vec<Failure> = Vec::new();
+    // A symlinked root is removed as a link, not followed into its target.
+    for entry in WalkDir::new(&root).follow_links(false).follow_root_links(false).contents_first(true) {
+        progress.checkpoint()?;
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let failed = e.path().map_or_else(|| path.to_path_buf(), original);
+                let in_use = e.io_error().is_some_and(in_use);
+                failures.push(Failure { path: failed, is_dir: true, error: e.to_string(), in_use });
+                continue;
+            }
+        };
+        let failed = original(entry.path());
+        if entry.file_type().is_dir() {
+            // A folder that still holds failed entries is reported through them.
+            if let Err(e) = fs::remove_dir(entry.path()) {
+                if !failures.iter().any(|failure| failure.path.starts_with(&failed)) {
+                    failures.push(Failure::new(failed, true, &e));
+                }
+            }
+        } else {
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+            match fs::remove_file(entry.path()) {
+                Ok(()) => progress.advance(size),
+                Err(e) => failures.push(Failure::new(failed, false, &e)),
+            }
+        }
+    }
+    Ok(failures)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Resolution {
+    Retry,
+    RetryElevated,
+    Force,
+}
+
+/// Tries a failed entry again; folders left empty above it, up to `root`, go with it.
+fn resolve(failure: &Failure, root: &Path, resolution: Resolution) -> Result<(), String> {
+    match resolution {
+        Resolution::Retry => remove(failure),
+        Resolution::RetryElevated => remove_elevated(failure),
+        Resolution::Force => force(failure, root),
+    }?;
+    for parent in failure.path.ancestors().skip(1) {
+        if !parent.starts_with(root) || fs::remove_dir(paths::extended(parent)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn remove(failure: &Failure) -> Result<(), String> {
+    let path = paths::extended(&failure.path);
+    let result = if failure.is_dir { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+    result.map_err(|e| e.to_string())
+}
+
+/// Clears read-only flags on the entry, everything below it and its folder when that folder
+/// is still inside `root`, closes the programs holding it where the system allows, then
+/// deletes again.
+fn force(failure: &Failure, root: &Path) -> Result<(), String> {
+    if let Some(parent) = failure.path.parent().filter(|parent| parent.starts_with(root)) {
+        make_writable(parent);
+    }
+    let walk = WalkDir::new(paths::extended(&failure.path)).follow_links(false).follow_root_links(false);
+    for entry in walk.into_iter().filter_map(Result::ok) {
+        make_writable(entry.path());
+    }
+    #[cfg(windows)]
+    if failure.in_use {
+        openfiles::close_holders(&failure.path)?;
+    }
+    remove(failure)
+}
+
+#[cfg(unix)]
+fn make_writable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if !metadata.file_type().is_symlink() {
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(metadata.permissions().mode() | 0o700));
+    }
+}
+
+/// Outside Unix this only clears the read-only attribute.
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn make_writable(path: &Path) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    let mut permissions = metadata.permissions();
+    if permissions.readonly() {
+        permissions.set_readonly(false);
+        let _ = fs::set_permissions(path, permissions);
+    }
+}
+
+/// The elevation helpers don't report whether the deletion itself worked, so success is
+/// judged by the entry being gone afterwards.
+fn remove_elevated(failure: &Failure) -> Result<(), String> {
+    let output = elevated_command(failure)
+        .output()
+        .map_err(|e| format!("Error asking for administrator rights: {}", e))?;
+    if fs::symlink_metadata(paths::extended(&failure.path)).is_err() {
+        return Ok(());
+    }
+    let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if message.is_empty() {
+        Err("Still present after deleting as administrator".to_string())
+    } else {
+        Err(message)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn elevated_command(failure: &Failure) -> Command {
+    let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command.args([
+        "-e",
+        &format!(
+            "do shell script \"rm -rf -- \" & quoted form of \"{}\" with administrator privileges",
+            quote(&failure.path.to_string_lossy())
+        ),
+    ]);
+    command
+}
+
+/// `Start-Process -Verb RunAs` shows the UAC prompt; the elevated shell's exit code is lost.
+#[cfg(windows)]
+fn elevated_command(failure: &Failure) -> Command {
+    let quote = |text: &str| text.replace('\'', "''");
+    let target = failure.path.to_string_lossy();
+    let arguments = if failure.is_dir {
+        format!("/c rd /s /q \"{}\"", target)
+    } else {
+        format!("/c del /f /q /a \"{}\"", target)
+    };
+    let script = format!(
+        "Start-Process cmd -Verb RunAs -Wait -WindowStyle Hidden -ArgumentList '{}'",
+        quote(&arguments)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn elevated_command(failure: &Failure) -> Command {
+    let mut command = Command::new("pkexec");
+    command.args(["rm", "-rf", "--"]).arg(&failure.path);
+    command
+}
+
+/// A deletion that stopped short, waiting for the user to decide about what's left.
+struct Incomplete {
+    path: PathBuf,
+    is_dir: bool,
+    size: u64,
+    failures: Vec<Failure>,
+    skipped: usize,
+}
+
+/// A deletion whose failures have all been retried or skipped.
+pub struct Finished {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    /// The item is gone; otherwise skipped entries keep it in place.
+    pub removed: bool,
+    pub skipped: usize,
+}
+
+pub enum Outcome {
+    ShowProcesses(PathBuf),
+    Finished(Finished),
+}
+
+type Attempts = Vec<(PathBuf, Result<(), String>)>;
+
+#[derive(Default)]
+pub struct DeletionFailuresState {
+    pub open: bool,
+    pending: Vec<Incomplete>,
+    job: Option<Job<Attempts>>,
+}
+
+impl DeletionFailuresState {
+    pub fn add(&mut self, path: PathBuf, is_dir: bool, size: u64, failures: Vec<Failure>) {
+        self.pending.push(Incomplete { path, is_dir, size, failures, skipped: 0 });
+        self.open = true;
+    }
+
+    fn start(&mut self, positions: Vec<usize>, resolution: Resolution) {
+        let Some(incomplete) = self.pending.first() else {
+            return;
+        };
+        let root = incomplete.path.clone();
+        let failures: Vec<Failure> = positions.iter().map(|&position| incomplete.failures[position].clone()).collect();
+        self.job = Some(Job::spawn(move || {
+            failures
+                .iter()
+                .map(|failure| (failure.path.clone(), resolve(failure, &root, resolution)))
+                .collect()
+        }));
+    }
+
+    fn apply(&mut self, attempts: Attempts) {
+        let Some(incomplete) = self.pending.first_mut() else {
+            return;
+        };
+        for (path, result) in attempts {
+            match result {
+                Ok(()) => incomplete.failures.retain(|failure| !failure.path.starts_with(&path)),
+                Err(error) => {
+                    if let Some(failure) = incomplete.failures.iter_mut().find(|failure| failure.path == path) {
+                        failure.error = error;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hands back the current deletion once nothing is left to decide.
+    fn take_finished(&mut self) -> Option<Finished> {
+        if !self.pending.first().is_some_and(|incomplete| incomplete.failures.is_empty()) {
+            return None;
+        }
+        let incomplete = self.pending.remove(0);
+        self.open = !self.pending.is_empty();
+        Some(Finished {
+            removed: fs::symlink_metadata(paths::extended(&incomplete.path)).is_err(),
+            path: incomplete.path,
+            is_dir: incomplete.is_dir,
+            size: incomplete.size,
+            skipped: incomplete.skipped,
+        })
+    }
+
+    fn skip(&mut self, positions: &[usize]) {
+        if let Some(incomplete) = self.pending.first_mut() {
+            incomplete.skipped += positions.len();
+            let mut position = 0;
+            incomplete.failures.retain(|_| {
+                position += 1;
+                !positions.contains(&(position - 1))
+            });
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Outcome> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(attempts) => {
+                    self.job = None;
+                    self.apply(attempts);
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(finished) = self.take_finished() {
+            return Some(Outcome::Finished(finished));
+        }
+        let incomplete = self.pending.first()?;
+
+        let mut open = self.open;
+        let mut skip = Vec::new();
+        let mut request = None;
+        let mut outcome = None;
+        let busy = self.job.is_some();
+        egui::Window::new(tr("Deletion Problems"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(trf("{} could not be deleted completely", &[&paths::display_path(&incomplete.path)]))
+                        .strong(),
+                );
+                ui.label(trf(
+                    "{} items are left. Skipped items stay where they are.",
+                    &[&i18n::format_count(incomplete.failures.len() as u64)],
+                ));
+                if self.pending.len() > 1 {
+                    ui.label(
+                        RichText::new(trf(
+                            "{} more deletions are waiting",
+                            &[&i18n::format_count(self.pending.len() as u64 - 1)],
+                        ))
+                        .color(Color32::GRAY),
+                    );
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("deletion_failures").striped(true).num_columns(3).show(ui, |ui| {
+                        for (position, failure) in incomplete.failures.iter().enumerate() {
+                            let relative = failure.path.strip_prefix(&incomplete.path).unwrap_or(&failure.path);
+                            let name = if relative.as_os_str().is_empty() { &failure.path } else { relative };
+                            ui.label(paths::display_path(name));
+                            ui.label(RichText::new(&failure.error).color(Color32::RED));
+                            ui.add_enabled_ui(!busy, |ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.button(tr("Skip")).clicked() {
+                                        skip.push(position);
+                                    }
+                                    if ui.button(tr("Retry")).clicked() {
+                                        request = Some((vec![position], Resolution::Retry));
+                                    }
+                                    if ui.button(tr("Retry as Administrator")).clicked() {
+                                        request = Some((vec![position], Resolution::RetryElevated));
+                                    }
+                                    if ui
+                                        .button(tr("Force"))
+                                        .on_hover_text(tr(
+                                            "Clears read-only flags and, on Windows, closes the programs using the files",
+                                        ))
+                                        .clicked()
+                                    {
+                                        request = Some((vec![position], Resolution::Force));
+                                    }
+                                    if failure.in_use && ui.button(tr("Show Processes")).clicked() {
+                                        outcome = Some(Outcome::ShowProcesses(failure.path.clone()));
+                                    }
+                                });
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let all: Vec<usize> = (0..incomplete.failures.len()).collect();
+                    if ui.add_enabled(!busy, egui::Button::new(tr("Retry All"))).clicked() {
+                        request = Some((all.clone(), Resolution::Retry));
+                    }
+                    if ui.add_enabled(!busy, egui::Button::new(tr("Skip All"))).clicked() {
+                        skip = all;
+                    }
+                    if busy {
+                        ui.spinner();
+                    }
+                });
+            });
+
+        // Closing the window leaves whatever is left in place.
+        if !open && !busy {
+            skip = (0..incomplete.failures.len()).collect();
+        }
+        if !skip.is_empty() {
+            self.skip(&skip);
+        } else if let Some((positions, resolution)) = request {
+            self.start(positions, resolution);
+        }
+        outcome
+    }
+}
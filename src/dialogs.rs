@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+/// Native file dialogs. The browser build has no blocking dialogs, so there they always
+/// come back cancelled and the web UI asks for folders itself.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_folder() -> Option<PathBuf> {
+    rfd::FileDialog::new().pick_folder()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_file() -> Option<PathBuf> {
+    rfd::FileDialog::new().pick_file()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(file_name: &str) -> Option<PathBuf> {
+    rfd::FileDialog::new().set_file_name(file_name).save_file()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn pick_folder() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(_file_name: &str) -> Option<PathBuf> {
+    None
+}
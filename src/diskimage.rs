@@ -0,0 +1,395 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    category,
+    i18n::{self, tr, trf},
+    job::Job,
+    paths,
+};
+
+const SECTOR: u64 = 2048;
+/// ISO 9660 directories can't nest deeper than this; it also stops loops in corrupt images.
+const MAX_DEPTH: usize = 64;
+/// Larger directory extents only show up in corrupt images.
+const MAX_DIRECTORY_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImageKind {
+    Iso,
+    Vhd,
+    Vhdx,
+    Qcow2,
+    Raw,
+}
+
+impl ImageKind {
+    pub fn of(path: &Path) -> Option<Self> {
+        match category::extension(path).as_str() {
+            "iso" => Some(ImageKind::Iso),
+            "vhd" => Some(ImageKind::Vhd),
+            "vhdx" => Some(ImageKind::Vhdx),
+            "qcow2" | "qcow" => Some(ImageKind::Qcow2),
+            "img" | "raw" => Some(ImageKind::Raw),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImageKind::Iso => "ISO 9660",
+            ImageKind::Vhd => "VHD",
+            ImageKind::Vhdx => "VHDX",
+            ImageKind::Qcow2 => "QCOW2",
+            ImageKind::Raw => tr("Raw disk image"),
+        }
+    }
+}
+
+pub fn is_disk_image(path: &Path) -> bool {
+    ImageKind::of(path).is_some()
+}
+
+#[derive(Clone)]
+pub struct ImageEntry {
+    /// Slash-separated path inside the image.
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+pub struct ImageListing {
+    pub kind: ImageKind,
+    /// Size of the virtual disk, when the container format records it.
+    pub virtual_size: Option<u64>,
+    pub entries: Vec<ImageEntry>,
+    pub reader: &'static str,
+}
+
+fn read_at(file: &mut File, offset: u64, buffer: &mut [u8]) -> std::io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buffer)
+}
+
+fn le32(bytes: &[u8]) -> u64 {
+    u64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn be64(bytes: &[u8]) -> u64 {
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&bytes[..8]);
+    u64::from_be_bytes(value)
+}
+
+/// Virtual disk size from the QCOW2 header or the VHD footer; other formats don't keep it
+/// anywhere cheap to read.
+fn virtual_size(file: &mut File, kind: ImageKind) -> Option<u64> {
+    let mut buffer = [0u8; 512];
+    match kind {
+        ImageKind::Qcow2 => {
+            read_at(file, 0, &mut buffer[..32]).ok()?;
+            (&buffer[..4] == b"QFI\xfb").then(|| be64(&buffer[24..]))
+        }
+        ImageKind::Vhd => {
+            let len = file.metadata().ok()?.len();
+            read_at(file, len.checked_sub(512)?, &mut buffer).ok()?;
+            (&buffer[..8] == b"conectix").then(|| be64(&buffer[48..]))
+        }
+        _ => None,
+    }
+}
+
+struct IsoVolume {
+    file: File,
+    joliet: bool,
+}
+
+impl IsoVolume {
+    /// Picks the Joliet descriptor when present, since it keeps long and mixed-case names.
+    fn open(mut file: File) -> Result<(Self, Vec<u8>), String> {
+        let mut primary = None;
+        let mut joliet = None;
+        for sector in 16..64 {
+            let mut descriptor = vec![0u8; SECTOR as usize];
+            read_at(&mut file, sector * SECTOR, &mut descriptor)
+                .map_err(|e| format!("Error reading volume descriptor: {}", e))?;
+            if &descriptor[1..6] != b"CD001" {
+                break;
+            }
+            match descriptor[0] {
+                1 => primary = Some(descriptor[156..190].to_vec()),
+                2 if matches!(&descriptor[88..91], b"%/@" | b"%/C" | b"%/E") => {
+                    joliet = Some(descriptor[156..190].to_vec())
+                }
+                255 => break,
+                _ => {}
+            }
+        }
+        match (joliet, primary) {
+            (Some(root), _) => Ok((IsoVolume { file, joliet: true }, root)),
+            (None, Some(root)) => Ok((IsoVolume { file, joliet: false }, root)),
+            (None, None) => Err("No ISO 9660 volume descriptor found".to_string()),
+        }
+    }
+
+    fn name(&self, raw: &[u8]) -> String {
+        if self.joliet {
+            let units: Vec<u16> = raw.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            let name = String::from_utf8_lossy(raw);
+            let name = name.split(';').next().unwrap_or_default();
+            name.strip_suffix('.').unwrap_or(name).to_string()
+        }
+    }
+
+    fn walk(
+        &mut self,
+        extent: u64,
+        length: u64,
+        prefix: &str,
+        depth: usize,
+        visited: &mut HashSet<u64>,
+        entries: &mut Vec<ImageEntry>,
+    ) -> Result<(), String> {
+        if depth > MAX_DEPTH || length > MAX_DIRECTORY_BYTES || !visited.insert(extent) {
+            return Ok(());
+        }
+        let mut data = vec![0u8; length as usize];
+        read_at(&mut self.file, extent * SECTOR, &mut data)
+            .map_err(|e| format!("Error reading directory {}: {}", prefix, e))?;
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let record_len = data[offset] as usize;
+            if record_len == 0 {
+                // Records never straddle sectors; the rest of this one is padding.
+                offset = (offset / SECTOR as usize + 1) * SECTOR as usize;
+                continue;
+            }
+            let record = &data[offset..(offset + record_len).min(data.len())];
+            offset += record_len;
+            if record.len() < 34 {
+                break;
+            }
+            let name_len = record[32] as usize;
+            let raw_name = &record[33..(33 + name_len).min(record.len())];
+            if raw_name == [0] || raw_name == [1] {
+                continue;
+            }
+            let path = format!("{}{}", prefix, self.name(raw_name));
+            let child_extent = le32(&record[2..6]);
+            let size = le32(&record[10..14]);
+            let is_dir = record[25] & 0x02 != 0;
+            entries.push(ImageEntry { path: path.clone(), size: if is_dir { 0 } else { size }, is_dir });
+            if is_dir {
+                self.walk(child_extent, size, &format!("{}/", path), depth + 1, visited, entries)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn list_iso(file: File) -> Result<Vec<ImageEntry>, String> {
+    let (mut volume, root) = IsoVolume::open(file)?;
+    let mut entries = Vec::new();
+    volume.walk(le32(&root[2..6]), le32(&root[10..14]), "", 0, &mut HashSet::new(), &mut entries)?;
+    Ok(entries)
+}
+
+/// 7-Zip reads the filesystems inside VHD, VHDX, QCOW2 and raw images (NTFS, FAT, ext and
+/// more), which is far beyond what we parse ourselves.
+fn list_with_7z(path: &Path) -> Result<Vec<ImageEntry>, String> {
+    let output = ["7z", "7zz", "7za"]
+        .iter()
+        .find_map(|program| Command::new(program).arg("l").arg("-slt").arg(path).output().ok())
+        .ok_or("Browsing this image needs 7-Zip (7z) on the PATH")?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let listing = text.split_once("----------").map_or("", |(_, rest)| rest);
+    let mut entries = Vec::new();
+    for block in listing.split("\n\n") {
+        let mut entry = ImageEntry { path: String::new(), size: 0, is_dir: false };
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            match key {
+                "Path" => entry.path = value.replace('\\', "/"),
+                "Size" => entry.size = value.parse().unwrap_or(0),
+                "Folder" => entry.is_dir = value == "+",
+                "Attributes" => entry.is_dir |= value.starts_with('D'),
+                _ => {}
+            }
+        }
+        if !entry.path.is_empty() {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Reads the image without modifying it: ISO 9660 natively, everything else through 7-Zip.
+pub fn inspect(path: &Path) -> Result<ImageListing, String> {
+    let kind = ImageKind::of(path).ok_or("Not a disk image")?;
+    let mut file = File::open(paths::extended(path)).map_err(|e| format!("Error opening image: {}", e))?;
+    let virtual_size = virtual_size(&mut file, kind);
+    let (entries, reader) = match kind {
+        ImageKind::Iso => (list_iso(file)?, "ISO 9660"),
+        ImageKind::Raw => match list_iso(file) {
+            Ok(entries) => (entries, "ISO 9660"),
+            Err(_) => (list_with_7z(path)?, "7-Zip"),
+        },
+        _ => (list_with_7z(path)?, "7-Zip"),
+    };
+    Ok(ImageListing { kind, virtual_size, entries, reader })
+}
+
+struct Row {
+    name: String,
+    size: u64,
+    is_dir: bool,
+}
+
+/// Children of `folder` (empty for the top level), with folder sizes summed from their contents.
+fn rows(entries: &[ImageEntry], folder: &str) -> Vec<Row> {
+    let prefix = if folder.is_empty() { String::new() } else { format!("{}/", folder) };
+    let mut rows: HashMap<&str, Row> = HashMap::new();
+    for entry in entries {
+        let Some(rest) = entry.path.strip_prefix(&prefix) else {
+            continue;
+        };
+        let (name, nested) = match rest.split_once('/') {
+            Some((name, _)) => (name, true),
+            None => (rest, false),
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let row = rows.entry(name).or_insert_with(|| Row { name: name.to_string(), size: 0, is_dir: false });
+        row.size += entry.size;
+        row.is_dir |= nested || entry.is_dir;
+    }
+    let mut rows: Vec<Row> = rows.into_values().collect();
+    rows.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    rows
+}
+
+#[derive(Default)]
+pub struct DiskImageState {
+    pub open: bool,
+    path: Option<PathBuf>,
+    job: Option<Job<Result<ImageListing, String>>>,
+    listing: Option<Result<ImageListing, String>>,
+    folder: String,
+    rows: Option<Vec<Row>>,
+}
+
+impl DiskImageState {
+    pub fn inspect(&mut self, path: PathBuf) {
+        let worker_path = path.clone();
+        self.job = Some(Job::spawn(move || inspect(&worker_path)));
+        self.path = Some(path);
+        self.listing = None;
+        self.folder.clear();
+        self.rows = None;
+        self.open = true;
+    }
+
+    fn enter(&mut self, folder: String) {
+        self.folder = folder;
+        self.rows = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(listing) => {
+                    self.listing = Some(listing);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        let mut enter = None;
+        egui::Window::new(tr("Disk Image"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                if let Some(path) = &self.path {
+                    ui.label(RichText::new(paths::display_path(path)).strong());
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let listing = match &self.listing {
+                    Some(Ok(listing)) => listing,
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                        return;
+                    }
+                    None => return,
+                };
+
+                let contents: u64 = listing.entries.iter().map(|entry| entry.size).sum();
+                ui.label(trf("Format: {} (read with {})", &[&listing.kind.label(), &listing.reader]));
+                if let Some(size) = listing.virtual_size {
+                    ui.label(trf("Virtual disk size: {}", &[&i18n::format_bytes(size)]));
+                }
+                ui.label(trf(
+                    "{} entries, {} of files",
+                    &[&i18n::format_count(listing.entries.len() as u64), &i18n::format_bytes(contents)],
+                ));
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.folder.is_empty(), egui::Button::new("⬆️")).clicked() {
+                        enter = Some(self.folder.rsplit_once('/').map_or(String::new(), |(parent, _)| parent.to_string()));
+                    }
+                    ui.label(format!("/{}", self.folder));
+                });
+                let rows = self.rows.get_or_insert_with(|| rows(&listing.entries, &self.folder));
+                if rows.is_empty() {
+                    ui.label(tr("This folder is empty."));
+                }
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for row in rows.iter() {
+                        let text = format!(
+                            "{} {} - {}",
+                            if row.is_dir { "📁" } else { "📄" },
+                            row.name,
+                            i18n::format_bytes(row.size)
+                        );
+                        if row.is_dir {
+                            if ui.button(text).clicked() {
+                                enter = Some(if self.folder.is_empty() {
+                                    row.name.clone()
+                                } else {
+                                    format!("{}/{}", self.folder, row.name)
+                                });
+                            }
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                });
+            });
+        if let Some(folder) = enter {
+            self.enter(folder);
+        }
+        self.open = open;
+    }
+}
@@ -0,0 +1,306 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{path::Path, process::Command};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    job::Job,
+    scan,
+};
+
+const ENGINES: [&str; 2] = ["docker", "podman"];
+const DOCKER_ROOT: &str = "/var/lib/docker";
+
+#[derive(Clone)]
+pub struct StorageRow {
+    pub kind: String,
+    pub count: String,
+    pub active: String,
+    pub size: u64,
+    pub reclaimable: u64,
+}
+
+pub struct StorageReport {
+    pub engine: Option<&'static str>,
+    pub rows: Vec<StorageRow>,
+    pub error: Option<String>,
+}
+
+/// Parses Docker's human-readable sizes ("1.2GB", "512kB", "0B (0%)"), which use decimal units.
+pub fn parse_docker_size(value: &str) -> u64 {
+    let value = value.split_whitespace().next().unwrap_or("");
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().unwrap_or(0.0);
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => 1.0,
+    };
+    (number * multiplier) as u64
+}
+
+fn field(value: &serde_json::Value, name: &str) -> String {
+    match &value[name] {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn query_engine(engine: &'static str) -> Result<Vec<StorageRow>, String> {
+    let output = Command::new(engine)
+        .args(["system", "df", "--format", "{{json .}}"])
+        .output()
+        .map_err(|e| format!("Error running {}: {}", engine, e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .map(|row| StorageRow {
+            kind: field(&row, "Type"),
+            count: field(&row, "TotalCount"),
+            active: field(&row, "Active"),
+            size: parse_docker_size(&field(&row, "Size")),
+            reclaimable: parse_docker_size(&field(&row, "Reclaimable")),
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// Falls back to measuring the engine's data directory when no CLI is reachable.
+fn inspect_data_root() -> Vec<StorageRow> {
+    let root = Path::new(DOCKER_ROOT);
+    let subdirs = [
+        ("Images", "overlay2"),
+        ("Containers", "containers"),
+        ("Local Volumes", "volumes"),
+        ("Build Cache", "buildkit"),
+    ];
+    subdirs
+        .iter()
+        .filter_map(|(kind, dir)| {
+            let path = root.join(dir);
+            path.is_dir().then(|| StorageRow {
+                kind: kind.to_string(),
+                count: String::new(),
+                active: String::new(),
+                size: scan::measure_dir(&path, &Default::default()).size,
+                reclaimable: 0,
+            })
+        })
+        .collect()
+}
+
+pub fn report() -> StorageReport {
+    let mut errors = Vec::new();
+    for engine in ENGINES {
+        match query_engine(engine) {
+            Ok(rows) => return StorageReport { engine: Some(engine), rows, error: None },
+            Err(e) => errors.push(e),
+        }
+    }
+    StorageReport {
+        engine: None,
+        rows: inspect_data_root(),
+        error: Some(errors.join("\n")),
+    }
+}
+
+pub fn prune_args(kind: &str) -> Option<&'static [&'static str]> {
+    Some(match kind {
+        "Images" => &["image", "prune", "--all", "--force"],
+        "Containers" => &["container", "prune", "--force"],
+        "Local Volumes" => &["volume", "prune", "--force"],
+        "Build Cache" => &["builder", "prune", "--force"],
+        _ => return None,
+    })
+}
+
+pub fn prune(engine: &str, kind: &str) -> Result<String, String> {
+    let args = prune_args(kind).ok_or(format!("Nothing to prune for {}", kind))?;
+    let output = Command::new(engine)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", engine, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout.lines().last().unwrap_or_default().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[derive(Default)]
+pub struct DockerState {
+    pub open: bool,
+    job: Option<Job<StorageReport>>,
+    report: Option<StorageReport>,
+    prune_job: Option<Job<audit::Audited<String>>>,
+    confirm_prune: Option<String>,
+    message: Option<Result<String, String>>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl DockerState {
+    fn refresh(&mut self) {
+        self.job = Some(Job::spawn(report));
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) {
+        if self.report.is_none() && self.job.is_none() {
+            self.refresh();
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.prune_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = Some(result);
+                    self.prune_job = None;
+                    self.refresh();
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Container Storage"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked() {
+                    self.refresh();
+                }
+                if self.job.is_some() || self.prune_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.message {
+                    Some(Ok(message)) => {
+                        ui.label(RichText::new(message).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+                match report.engine {
+                    Some(engine) => {
+                        ui.label(trf("Reported by {}", &[&engine]));
+                    }
+                    None => {
+                        ui.label(
+                            RichText::new(trf("No container engine answered; measured {} instead.", &[&DOCKER_ROOT]))
+                                .color(Color32::YELLOW),
+                        );
+                        if let Some(error) = &report.error {
+                            ui.label(RichText::new(error).small().color(Color32::GRAY));
+                        }
+                    }
+                }
+
+                let mut prune = None;
+                egui::Grid::new("docker_storage").striped(true).show(ui, |ui| {
+                    ui.label(tr("Type"));
+                    ui.label(tr("Total"));
+                    ui.label(tr("Active"));
+                    ui.label(tr("Size"));
+                    ui.label(tr("Reclaimable"));
+                    ui.end_row();
+                    for row in &report.rows {
+                        ui.label(tr_kind(&row.kind));
+                        ui.label(&row.count);
+                        ui.label(&row.active);
+                        ui.label(i18n::format_bytes(row.size));
+                        ui.label(i18n::format_bytes(row.reclaimable));
+                        let can_prune = !read_only
+                            && report.engine.is_some()
+                            && row.reclaimable > 0
+                            && prune_args(&row.kind).is_some();
+                        let button = ui.add_enabled(can_prune, egui::Button::new(tr("Prune")));
+                        let button = if read_only {
+                            button.on_disabled_hover_text(tr("Disabled in read-only mode"))
+                        } else {
+                            button
+                        };
+                        if button.clicked() {
+                            prune = Some(row.kind.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+                if prune.is_some() {
+                    self.confirm_prune = prune;
+                }
+            });
+        self.open = open;
+
+        self.render_prune_confirmation(ctx);
+    }
+
+    fn render_prune_confirmation(&mut self, ctx: &egui::Context) {
+        let (Some(kind), Some(engine)) = (&self.confirm_prune, self.report.as_ref().and_then(|r| r.engine)) else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(tr("Confirm Prune"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let args = prune_args(kind).unwrap_or_default().join(" ");
+                ui.label(trf("This runs `{} {}` and cannot be undone.", &[&engine, &args]));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Yes")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("No")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => {
+                let kind = kind.clone();
+                self.prune_job = Some(Job::spawn(move || {
+                    let result = prune(engine, &kind);
+                    let target = format!("{} {}", engine, kind);
+                    let audited = audit::record(audit::PRUNE, &target, None, result.as_ref().err());
+                    (result, audited)
+                }));
+                self.confirm_prune = None;
+            }
+            Some(false) => self.confirm_prune = None,
+            None => {}
+        }
+    }
+}
+
+fn tr_kind(kind: &str) -> &str {
+    match kind {
+        "Images" => tr("Images"),
+        "Containers" => tr("Containers"),
+        "Local Volumes" => tr("Local Volumes"),
+        "Build Cache" => tr("Build Cache"),
+        other => other,
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    index::{Node, ScanIndex},
+    ncdu,
+    scan::ScanOptions,
+};
+
+const FORMAT: &str = "disk-analyzer-scan";
+const VERSION: u32 = 1;
+pub const DEFAULT_FILE_NAME: &str = "scan.jsonl";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Header {
+    format: String,
+    version: u32,
+    pub root: PathBuf,
+    #[serde(default)]
+    pub host: String,
+    pub scanned_at: u64,
+    #[serde(default)]
+    pub show_hidden: bool,
+    #[serde(default)]
+    pub options: ScanOptions,
+    nodes: usize,
+}
+
+impl Header {
+    /// A header for an index that didn't come from a scan of this program, e.g. an ncdu
+    /// export or an object storage listing.
+    pub fn new(root: PathBuf, host: String, scanned_at: u64, nodes: usize) -> Self {
+        Header {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            root,
+            host,
+            scanned_at,
+            show_hidden: true,
+            options: ScanOptions::default(),
+            nodes,
+        }
+    }
+}
+
+/// One file or folder; `parent` is the position of its parent's line after the header.
+#[derive(Serialize, Deserialize)]
+struct Line {
+    name: String,
+    parent: Option<usize>,
+    dir: bool,
+    size: u64,
+    entries: u64,
+    modified: Option<u64>,
+    #[serde(default)]
+    accessed: Option<u64>,
+    #[serde(default)]
+    unreachable: bool,
+    #[serde(default)]
+    online_only: bool,
+    #[serde(default)]
+    small_files: u64,
+    #[serde(default)]
+    small_size: u64,
+    /// Left out by older versions, which counted files at their length.
+    #[serde(default)]
+    logical: Option<u64>,
+}
+
+#[cfg(unix)]
+pub fn host_name() -> String {
+    let mut buffer = [0u8; 256];
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return String::new();
+    }
+    let length = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..length]).into_owned()
+}
+
+#[cfg(not(unix))]
+pub fn host_name() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+pub fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs())
+}
+
+/// Writes the whole index as a scan file; the format is described in the README.
+pub fn export(index: &ScanIndex, options: &ScanOptions, show_hidden: bool, path: &Path) -> Result<(), String> {
+    let root = index.nodes.first().ok_or("Nothing has been scanned yet")?;
+    let error = |e: std::io::Error| format!("Error writing {}: {}", path.display(), e);
+    let mut writer = BufWriter::new(File::create(path).map_err(error)?);
+    let header = Header {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        root: root.path.clone(),
+        host: host_name(),
+        scanned_at: seconds(SystemTime::now()),
+        show_hidden,
+        options: options.clone(),
+        nodes: index.nodes.len(),
+    };
+    write_line(&mut writer, &header).map_err(error)?;
+    for node in &index.nodes {
+        let line = Line {
+            name: node.name.to_string_lossy().into_owned(),
+            parent: node.parent,
+            dir: node.is_dir,
+            size: node.size,
+            entries: node.entries,
+            modified: node.modified.map(seconds),
+            accessed: node.accessed.map(seconds),
+            unreachable: node.unreachable,
+            online_only: node.online_only,
+            small_files: node.small_files,
+            small_size: node.small_size,
+            logical: Some(node.logical),
+        };
+        write_line(&mut writer, &line).map_err(error)?;
+    }
+    writer.flush().map_err(error)
+}
+
+fn write_line(writer: &mut impl Write, value: &impl Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
+/// Reads a scan file, or an ncdu export, back into an index whose paths point at the
+/// machine that made it.
+pub fn import(path: &Path) -> Result<(Header, ScanIndex), String> {
+    let file = File::open(path).map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    // ncdu exports are one JSON array; scan files start with the header object.
+    let starts_with_array = reader
+        .fill_buf()
+        .is_ok_and(|buffer| buffer.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'['));
+    if starts_with_array {
+        let (root, scanned_at, index) = ncdu::import(path)?;
+        let header = Header::new(root, String::new(), scanned_at, index.nodes.len());
+        return Ok((header, index));
+    }
+    let mut lines = reader.lines();
+    let mut next = |number: usize| -> Result<String, String> {
+        match lines.next() {
+            Some(Ok(line)) => Ok(line),
+            Some(Err(e)) => Err(format!("Error reading {}: {}", path.display(), e)),
+            None => Err(format!("Error reading {}: the file ends at line {}", path.display(), number)),
+        }
+    };
+    let invalid = |number: usize, e: serde_json::Error| format!("Invalid scan file at line {}: {}", number, e);
+
+    let header: Header = serde_json::from_str(&next(1)?).map_err(|e| invalid(1, e))?;
+    if header.format != FORMAT {
+        return Err(format!("{} is not a disk-analyzer scan file", path.display()));
+    }
+    if header.version > VERSION {
+        return Err(format!(
+            "{} was written by a newer version (format {}); update to open it",
+            path.display(),
+            header.version
+        ));
+    }
+
+    // The count comes from the file, so it only sizes the first allocation up to a point.
+    let mut nodes: Vec<Node> = Vec::with_capacity(header.nodes.min(1 << 20));
+    for id in 0..header.nodes {
+        let number = id + 2;
+        let line: Line = serde_json::from_str(&next(number)?).map_err(|e| invalid(number, e))?;
+        let node_path = match line.parent {
+            None if id == 0 => header.root.clone(),
+            Some(parent) if parent < id => nodes[parent].path.join(&line.name),
+            _ => return Err(format!("Invalid scan file at line {}: the parent must come first", number)),
+        };
+        if let Some(parent) = line.parent {
+            nodes[parent].children.push(id);
+        }
+        nodes.push(Node {
+            path: node_path,
+            name: line.name.into(),
+            size: line.size,
+            logical: line.logical.unwrap_or(line.size),
+            entries: line.entries,
+            is_dir: line.dir,
+            modified: line.modified.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+            accessed: line.accessed.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+            unreachable: line.unreachable,
+            online_only: line.online_only,
+            parent: line.parent,
+            children: Vec::new(),
+            small_files: line.small_files,
+            small_size: line.small_size,
+        });
+    }
+    if nodes.is_empty() {
+        return Err(format!("{} contains no entries", path.display()));
+    }
+    Ok((header, ScanIndex::from_nodes(nodes)))
+}
@@ -0,0 +1,121 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+};
+use thiserror::Error;
+
+use crate::{i18n::tr, paths};
+
+/// What can go wrong while scanning folders or changing files on disk.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading {}: {source}", paths::display_path(.path))]
+    Read { path: PathBuf, source: io::Error },
+    #[error("Error reading directory: {0}")]
+    Walk(#[from] walkdir::Error),
+    #[error("Error writing {}: {source}", paths::display_path(.path))]
+    Write { path: PathBuf, source: io::Error },
+    #[error("Error copying {}: {source}", paths::display_path(.path))]
+    Copy { path: PathBuf, source: io::Error },
+    #[error("Error moving {}: {source}", paths::display_path(.path))]
+    Move { path: PathBuf, source: io::Error },
+    #[error("Error deleting {}: {source}", paths::display_path(.path))]
+    Delete { path: PathBuf, source: io::Error },
+    #[error("Error moving {} to the trash: {source}", paths::display_path(.path))]
+    Trash { path: PathBuf, source: io::Error },
+    #[error("{} already exists", paths::display_path(.0))]
+    Exists(PathBuf),
+    /// A folder moved or copied into itself or one of its subfolders.
+    #[error("Cannot put {} inside itself", paths::display_path(.0))]
+    IntoItself(PathBuf),
+    #[error("Nothing to {0}")]
+    NoName(&'static str),
+    #[error("{count} items could not be deleted")]
+    Incomplete { count: usize },
+    /// Copies that read back differently from their originals.
+    #[error("{}", mismatch_message(.0))]
+    Verification(Vec<PathBuf>),
+    #[error("Error running {program}: {source}")]
+    Spawn { program: &'static str, source: io::Error },
+    #[error("{program} failed with {status}")]
+    Exit { program: &'static str, status: ExitStatus },
+    #[error("{}", tr("Disabled in read-only mode"))]
+    ReadOnly,
+    #[error("Cancelled")]
+    Cancelled,
+    /// Errors from code that still reports plain messages.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl Error {
+    /// The file or folder the error is about, when there is one.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Read { path, .. }
+            | Error::Write { path, .. }
+            | Error::Copy { path, .. }
+            | Error::Move { path, .. }
+            | Error::Delete { path, .. }
+            | Error::Trash { path, .. }
+            | Error::Exists(path)
+            | Error::IntoItself(path) => Some(path),
+            Error::Verification(copies) => copies.first().map(PathBuf::as_path),
+            Error::Walk(error) => error.path(),
+            _ => None,
+        }
+    }
+
+    /// What the system reported, beyond the message: the full path, the kind of I/O error and
+    /// its code.
+    pub fn details(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(path) = self.path() {
+            lines.push(format!("Path: {}", path.display()));
+        }
+        let io_error = match self {
+            Error::Read { source, .. }
+            | Error::Write { source, .. }
+            | Error::Copy { source, .. }
+            | Error::Move { source, .. }
+            | Error::Delete { source, .. }
+            | Error::Trash { source, .. }
+            | Error::Spawn { source, .. } => Some(source),
+            Error::Walk(error) => error.io_error(),
+            _ => None,
+        };
+        if let Some(error) = io_error {
+            lines.push(format!("Kind: {:?}", error.kind()));
+            if let Some(code) = error.raw_os_error() {
+                lines.push(format!("OS error: {}", code));
+            }
+        }
+        if let Error::Exit { status, .. } = self {
+            lines.push(format!("Exit status: {}", status));
+        }
+        if let Error::Verification(copies) = self {
+            lines.extend(copies.iter().skip(1).map(|copy| format!("Path: {}", copy.display())));
+        }
+        lines.join("\n")
+    }
+}
+
+fn mismatch_message(copies: &[PathBuf]) -> String {
+    match copies {
+        [copy] => format!("Verification failed for {}", paths::display_path(copy)),
+        _ => format!("Verification failed for {} copied files", copies.len()),
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DirectoryFilter {
+    /// Compare a directory by everything below it.
+    #[default]
+    Cumulative,
+    /// Compare a directory by the files directly inside it.
+    Own,
+    /// Never hide directories.
+    Never,
+}
+
+impl DirectoryFilter {
+    pub const ALL: [DirectoryFilter; 3] = [DirectoryFilter::Cumulative, DirectoryFilter::Own, DirectoryFilter::Never];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DirectoryFilter::Cumulative => "Folders by total size",
+            DirectoryFilter::Own => "Folders by own size",
+            DirectoryFilter::Never => "Always show folders",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SizeFilter {
+    pub min_size: u64,
+    pub show_all: bool,
+    pub directories: DirectoryFilter,
+}
+
+impl SizeFilter {
+    /// Files are hidden below `min_size`; directories follow `directories`. `own_size` is only
+    /// evaluated when a directory is filtered by its own size.
+    pub fn hides(&self, is_dir: bool, size: u64, own_size: impl FnOnce() -> u64) -> bool {
+        if self.show_all {
+            return false;
+        }
+        if !is_dir {
+            return size < self.min_size;
+        }
+        match self.directories {
+            DirectoryFilter::Cumulative => size < self.min_size,
+            DirectoryFilter::Own => own_size() < self.min_size,
+            DirectoryFilter::Never => false,
+        }
+    }
+}
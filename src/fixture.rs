@@ -0,0 +1,267 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::paths;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    Fixed,
+    Uniform,
+    LogUniform,
+}
+
+impl SizeDistribution {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "fixed" => Ok(SizeDistribution::Fixed),
+            "uniform" => Ok(SizeDistribution::Uniform),
+            "log" => Ok(SizeDistribution::LogUniform),
+            other => Err(format!("Unknown size distribution: {}", other)),
+        }
+    }
+}
+
+pub struct FixtureSpec {
+    pub output: PathBuf,
+    pub depth: u32,
+    pub fan_out: u32,
+    pub files_per_dir: u32,
+    pub distribution: SizeDistribution,
+    pub min_size: u64,
+    pub max_size: u64,
+    pub seed: u64,
+    pub dense: bool,
+    pub exotic: bool,
+}
+
+impl Default for FixtureSpec {
+    fn default() -> Self {
+        Self {
+            output: PathBuf::from("fixture"),
+            depth: 3,
+            fan_out: 4,
+            files_per_dir: 16,
+            distribution: SizeDistribution::LogUniform,
+            min_size: 1,
+            max_size: 1024 * 1024 * 64,
+            seed: 1,
+            dense: false,
+            exotic: false,
+        }
+    }
+}
+
+impl FixtureSpec {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut spec = FixtureSpec::default();
+        let mut output = None;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| {
+                iter.next()
+                    .cloned()
+                    .ok_or_else(|| format!("Missing value for {}", name))
+            };
+            match arg.as_str() {
+                "--depth" => spec.depth = parse_number(&value(arg)?)?,
+                "--fan-out" => spec.fan_out = parse_number(&value(arg)?)?,
+                "--files" => spec.files_per_dir = parse_number(&value(arg)?)?,
+                "--sizes" => spec.distribution = SizeDistribution::parse(&value(arg)?)?,
+                "--min-size" => spec.min_size = parse_number(&value(arg)?)?,
+                "--max-size" => spec.max_size = parse_number(&value(arg)?)?,
+                "--seed" => spec.seed = parse_number(&value(arg)?)?,
+                "--dense" => spec.dense = true,
+                "--exotic" => spec.exotic = true,
+                other if other.starts_with("--") => {
+                    return Err(format!("Unknown option: {}", other));
+                }
+                other => output = Some(PathBuf::from(other)),
+            }
+        }
+
+        spec.output = output.ok_or("Missing output directory")?;
+        if spec.min_size > spec.max_size {
+            return Err("--min-size must not exceed --max-size".to_string());
+        }
+        Ok(spec)
+    }
+}
+
+pub struct FixtureSummary {
+    pub dirs: u64,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        // xorshift64*, good enough for reproducible fixture shapes
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+pub fn generate(spec: &FixtureSpec) -> Result<FixtureSummary, String> {
+    let mut rng = Rng(spec.seed.max(1));
+    let mut summary = FixtureSummary { dirs: 0, files: 0, bytes: 0 };
+    fs::create_dir_all(&spec.output)
+        .map_err(|e| format!("Error creating {}: {}", spec.output.display(), e))?;
+    generate_dir(spec, &spec.output, spec.depth, &mut rng, &mut summary)?;
+    if spec.exotic {
+        generate_exotic(&spec.output.join("exotic"), &mut summary)?;
+    }
+    Ok(summary)
+}
+
+/// Names that aren't valid UTF-8 on this platform: a stray byte on Unix, an unpaired
+/// surrogate on Windows.
+#[cfg(unix)]
+fn undecodable_name() -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(b"invalid_\xFF\xFEname.bin".to_vec())
+}
+
+#[cfg(windows)]
+fn undecodable_name() -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+    let mut wide: Vec<u16> = "invalid_".encode_utf16().collect();
+    wide.push(0xD800);
+    wide.extend("name.bin".encode_utf16());
+    OsString::from_wide(&wide)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn undecodable_name() -> OsString {
+    OsString::from("invalid_name.bin")
+}
+
+/// Files that tend to break path handling: undecodable and unusual names, plus a folder
+/// chain nested well past Windows' 260-character MAX_PATH.
+fn generate_exotic(dir: &Path, summary: &mut FixtureSummary) -> Result<(), String> {
+    let dir = paths::extended(dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Error creating {}: {}", dir.display(), e))?;
+    summary.dirs += 1;
+
+    let names = [
+        undecodable_name(),
+        OsString::from("emoji_\u{1F4BE}_caf\u{E9}.bin"),
+        OsString::from("combining_e\u{301}.bin"),
+        OsString::from(" leading and trailing spaces .bin"),
+        OsString::from(format!("long_{}.bin", "x".repeat(200))),
+    ];
+    for name in names {
+        let path = dir.join(&name);
+        write_file(&path, 1024, false)
+            .map_err(|e| format!("Error writing {}: {}", paths::display_path(&path), e))?;
+        summary.files += 1;
+        summary.bytes += 1024;
+    }
+
+    let mut deep = dir.join("deep");
+    for level in 0..12 {
+        deep.push(format!("level_{:02}_{}", level, "d".repeat(40)));
+    }
+    fs::create_dir_all(&deep).map_err(|e| format!("Error creating {}: {}", deep.display(), e))?;
+    summary.dirs += 13;
+    write_file(&deep.join("deep.bin"), 1024, false)
+        .map_err(|e| format!("Error writing in {}: {}", deep.display(), e))?;
+    summary.files += 1;
+    summary.bytes += 1024;
+    Ok(())
+}
+
+fn generate_dir(
+    spec: &FixtureSpec,
+    dir: &Path,
+    depth: u32,
+    rng: &mut Rng,
+    summary: &mut FixtureSummary,
+) -> Result<(), String> {
+    summary.dirs += 1;
+
+    for i in 0..spec.files_per_dir {
+        let size = sample_size(spec, rng);
+        let path = dir.join(format!("file_{:04}.bin", i));
+        write_file(&path, size, spec.dense)
+            .map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+        summary.files += 1;
+        summary.bytes += size;
+    }
+
+    if depth == 0 {
+        return Ok(());
+    }
+
+    for i in 0..spec.fan_out {
+        let child = dir.join(format!("dir_{:03}", i));
+        // Left over from an earlier run into the same output is fine; its files are rewritten.
+        fs::create_dir_all(&child)
+            .map_err(|e| format!("Error creating {}: {}", child.display(), e))?;
+        generate_dir(spec, &child, depth - 1, rng, summary)?;
+    }
+
+    Ok(())
+}
+
+fn sample_size(spec: &FixtureSpec, rng: &mut Rng) -> u64 {
+    let (min, max) = (spec.min_size, spec.max_size);
+    match spec.distribution {
+        SizeDistribution::Fixed => max,
+        SizeDistribution::Uniform => min + (rng.next_f64() * (max - min) as f64) as u64,
+        SizeDistribution::LogUniform => {
+            let low = (min.max(1) as f64).ln();
+            let high = (max.max(1) as f64).ln();
+            (low + rng.next_f64() * (high - low)).exp() as u64
+        }
+    }
+}
+
+fn write_file(path: &Path, size: u64, dense: bool) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    if !dense {
+        return file.set_len(size);
+    }
+
+    let chunk = vec![0xA5u8; 64 * 1024];
+    let mut remaining = size;
+    while remaining > 0 {
+        let len = remaining.min(chunk.len() as u64) as usize;
+        file.write_all(&chunk[..len])?;
+        remaining -= len as u64;
+    }
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number: {}", value))
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let spec = FixtureSpec::from_args(args)?;
+    let summary = generate(&spec)?;
+    println!(
+        "Generated {} directories, {} files ({} bytes) in {}",
+        summary.dirs,
+        summary.files,
+        summary.bytes,
+        spec.output.display()
+    );
+    Ok(())
+}
+
+pub const USAGE: &str = "Usage: disk-analyzer gen-fixture <output> [--depth N] [--fan-out N] \
+[--files N] [--sizes fixed|uniform|log] [--min-size BYTES] [--max-size BYTES] [--seed N] [--dense] [--exotic]";
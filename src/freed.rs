@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{config, volume};
+
+const STATS_FILE: &str = "stats.json";
+/// How often the free space of the scanned volume is read again.
+const FREE_SPACE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct LifetimeStats {
+    pub bytes_freed: u64,
+    pub items_removed: u64,
+}
+
+pub struct RemovedItem {
+    pub path: PathBuf,
+    pub size: u64,
+    pub is_dir: bool,
+    pub removed_at: SystemTime,
+}
+
+/// Free space on the volume of a scanned folder, as first seen this session and as of now.
+struct FreeSpace {
+    root: PathBuf,
+    at_start: u64,
+    available: u64,
+    checked_at: Instant,
+}
+
+pub struct FreedTracker {
+    pub session: Vec<RemovedItem>,
+    pub lifetime: LifetimeStats,
+    free_space: Vec<FreeSpace>,
+}
+
+impl FreedTracker {
+    pub fn load() -> Self {
+        Self {
+            session: Vec::new(),
+            lifetime: config::load(STATS_FILE),
+            free_space: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, path: PathBuf, size: u64, is_dir: bool) {
+        self.session.push(RemovedItem {
+            path,
+            size,
+            is_dir,
+            removed_at: SystemTime::now(),
+        });
+        self.lifetime.bytes_freed += size;
+        self.lifetime.items_removed += 1;
+        let _ = config::save(STATS_FILE, &self.lifetime);
+    }
+
+    pub fn session_bytes(&self) -> u64 {
+        self.session.iter().map(|item| item.size).sum()
+    }
+
+    /// How much free space the volume holding `root` gained since `root` was first scanned
+    /// this session; negative when it lost some. Unlike `session_bytes` this counts changes
+    /// made by anything, not just this app.
+    pub fn free_space_delta(&mut self, root: &Path) -> Option<i64> {
+        let position = match self.free_space.iter().position(|free| free.root == root) {
+            Some(position) => position,
+            None => {
+                let available = volume::info(root)?.available;
+                self.free_space.push(FreeSpace {
+                    root: root.to_path_buf(),
+                    at_start: available,
+                    available,
+                    checked_at: Instant::now(),
+                });
+                self.free_space.len() - 1
+            }
+        };
+        let free = &mut self.free_space[position];
+        if free.checked_at.elapsed() >= FREE_SPACE_INTERVAL {
+            free.checked_at = Instant::now();
+            if let Some(info) = volume::info(root) {
+                free.available = info.available;
+            }
+        }
+        Some(free.available as i64 - free.at_start as i64)
+    }
+}
@@ -0,0 +1,343 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    heatmap,
+    i18n::{self, tr, trf},
+    job::Job,
+    mounts, paths, scan, storage, volume,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Launcher {
+    Steam,
+    Epic,
+}
+
+impl Launcher {
+    fn label(self) -> &'static str {
+        match self {
+            Launcher::Steam => "Steam",
+            Launcher::Epic => "Epic Games",
+        }
+    }
+
+    fn move_guidance(self) -> &'static str {
+        match self {
+            Launcher::Steam => {
+                "In Steam, open Settings > Storage, select the game and choose Move to put it in a library on another drive."
+            }
+            Launcher::Epic => {
+                "Epic can't move games: move the folder to the other drive, then install the game there from the launcher and it verifies the files instead of downloading them."
+            }
+        }
+    }
+}
+
+pub struct Game {
+    pub launcher: Launcher,
+    pub id: String,
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub last_played: Option<SystemTime>,
+    /// Mount point of the drive the game is installed on.
+    pub drive: Option<PathBuf>,
+}
+
+impl Game {
+    fn uninstall_url(&self) -> Option<String> {
+        match self.launcher {
+            Launcher::Steam => Some(format!("steam://uninstall/{}", self.id)),
+            Launcher::Epic => None,
+        }
+    }
+}
+
+pub struct GameLibrary {
+    pub games: Vec<Game>,
+    /// Drives with their free space, as places to move games to.
+    pub drives: Vec<(PathBuf, u64)>,
+}
+
+/// Reads the quoted key/value pairs of a Valve KeyValues file such as `libraryfolders.vdf` or
+/// an `appmanifest_*.acf`, flattening nested sections.
+fn vdf_pairs(text: &str) -> Vec<(String, String)> {
+    let mut tokens: Vec<Option<String>> = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut token = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => token.extend(chars.next()),
+                        c => token.push(c),
+                    }
+                }
+                tokens.push(Some(token));
+            }
+            '{' | '}' => tokens.push(None),
+            _ => {}
+        }
+    }
+    let mut pairs = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if let (Some(Some(key)), Some(Some(value))) = (tokens.get(index), tokens.get(index + 1)) {
+            pairs.push((key.clone(), value.clone()));
+            index += 2;
+        } else {
+            index += 1;
+        }
+    }
+    pairs
+}
+
+fn vdf_value<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)).map(|(_, value)| value.as_str())
+}
+
+fn steam_roots() -> Vec<PathBuf> {
+    let candidates = if cfg!(windows) {
+        // Steam can be installed on any drive, and libraries set up on other drives hold
+        // their own libraryfolders.vdf, so every drive gets a look.
+        let mut candidates = vec![std::env::var_os("ProgramFiles(x86)").map(|dir| PathBuf::from(dir).join("Steam"))];
+        for drive in mounts::disks() {
+            for folder in ["Steam", "SteamLibrary", "Program Files (x86)/Steam", "Program Files/Steam"] {
+                candidates.push(Some(drive.join(folder)));
+            }
+        }
+        candidates
+    } else if cfg!(target_os = "macos") {
+        vec![dirs::config_dir().map(|dir| dir.join("Steam"))]
+    } else {
+        vec![
+            dirs::data_dir().map(|dir| dir.join("Steam")),
+            dirs::home_dir().map(|dir| dir.join(".steam/steam")),
+            dirs::home_dir().map(|dir| dir.join(".var/app/com.valvesoftware.Steam/data/Steam")),
+        ]
+    };
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in candidates.into_iter().flatten() {
+        // ~/.steam/steam is usually a link to the data directory.
+        let root = fs::canonicalize(&root).unwrap_or(root);
+        if root.join("steamapps").is_dir() && !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+fn steam_games() -> Vec<Game> {
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for root in steam_roots() {
+        let listed = fs::read_to_string(root.join("steamapps/libraryfolders.vdf")).unwrap_or_default();
+        let paths = vdf_pairs(&listed).into_iter().filter(|(key, _)| key == "path").map(|(_, path)| PathBuf::from(path));
+        for library in std::iter::once(root.clone()).chain(paths) {
+            let library = fs::canonicalize(&library).unwrap_or(library);
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+    }
+
+    let mut games = Vec::new();
+    for library in libraries {
+        let steamapps = library.join("steamapps");
+        let Ok(entries) = fs::read_dir(&steamapps) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !(name.starts_with("appmanifest_") && name.ends_with(".acf")) {
+                continue;
+            }
+            let Ok(manifest) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let pairs = vdf_pairs(&manifest);
+            let (Some(id), Some(install_dir)) = (vdf_value(&pairs, "appid"), vdf_value(&pairs, "installdir")) else {
+                continue;
+            };
+            let path = steamapps.join("common").join(install_dir);
+            let size = vdf_value(&pairs, "SizeOnDisk").and_then(|size| size.parse().ok()).unwrap_or_default();
+            let last_played = vdf_value(&pairs, "LastPlayed")
+                .and_then(|time| time.parse().ok())
+                .filter(|&seconds| seconds > 0)
+                .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds));
+            games.push(Game {
+                launcher: Launcher::Steam,
+                id: id.to_string(),
+                name: vdf_value(&pairs, "name").unwrap_or(install_dir).to_string(),
+                path,
+                size,
+                last_played,
+                drive: None,
+            });
+        }
+    }
+    games
+}
+
+fn epic_games() -> Vec<Game> {
+    let manifests = if cfg!(windows) {
+        std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join(r"Epic\EpicGamesLauncher\Data\Manifests"))
+    } else if cfg!(target_os = "macos") {
+        dirs::config_dir().map(|dir| dir.join("Epic/EpicGamesLauncher/Data/Manifests"))
+    } else {
+        None
+    };
+    let Some(Ok(entries)) = manifests.map(fs::read_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "item"))
+        .filter_map(|entry| {
+            let manifest: serde_json::Value = serde_json::from_slice(&fs::read(entry.path()).ok()?).ok()?;
+            let path = PathBuf::from(manifest["InstallLocation"].as_str()?);
+            Some(Game {
+                launcher: Launcher::Epic,
+                id: manifest["AppName"].as_str().unwrap_or_default().to_string(),
+                name: manifest["DisplayName"].as_str().unwrap_or_default().to_string(),
+                size: manifest["InstallSize"].as_u64().unwrap_or_default(),
+                // The launcher doesn't record play time in its manifests.
+                last_played: None,
+                path,
+                drive: None,
+            })
+        })
+        .collect()
+}
+
+pub fn find_games() -> GameLibrary {
+    let mut games: Vec<Game> = steam_games().into_iter().chain(epic_games()).filter(|game| game.path.is_dir()).collect();
+    for game in &mut games {
+        if game.size == 0 {
+            game.size = scan::measure_dir(&game.path, &Default::default()).size;
+        }
+        game.drive = mounts::mount_of(&game.path).map(|mount| mount.mount_point);
+    }
+    games.sort_by_key(|game| std::cmp::Reverse(game.size));
+    let drives = storage::drives()
+        .into_iter()
+        .filter_map(|drive| volume::info(&drive).map(|info| (drive, info.available)))
+        .collect();
+    GameLibrary { games, drives }
+}
+
+fn last_played_text(time: Option<SystemTime>) -> String {
+    match heatmap::age(time) {
+        Some(age) => trf("{} days ago", &[&i18n::format_count(age.as_secs() / (24 * 60 * 60))]),
+        None => "-".to_string(),
+    }
+}
+
+#[derive(Default)]
+pub struct GamesState {
+    pub open: bool,
+    job: Option<Job<GameLibrary>>,
+    library: Option<GameLibrary>,
+}
+
+impl GamesState {
+    /// Draws the window and returns a game folder to jump to when one is clicked.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<PathBuf> {
+        if self.library.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(find_games));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(library) => {
+                    self.library = Some(library);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Game Libraries"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(tr("Games installed by Steam and the Epic Games launcher.")).color(Color32::GRAY));
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked() {
+                        self.job = Some(Job::spawn(find_games));
+                    }
+                });
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let Some(library) = &self.library else {
+                    return;
+                };
+                if library.games.is_empty() {
+                    ui.label(tr("No installed games found."));
+                    return;
+                }
+                let total: u64 = library.games.iter().map(|game| game.size).sum();
+                ui.label(trf(
+                    "{} games, {} in total",
+                    &[&i18n::format_count(library.games.len() as u64), &i18n::format_bytes(total)],
+                ));
+                egui::ScrollArea::vertical().max_height(460.0).show(ui, |ui| {
+                    for game in &library.games {
+                        let title = format!(
+                            "{} - {} - {}",
+                            game.name,
+                            i18n::format_bytes(game.size),
+                            trf("last played {}", &[&last_played_text(game.last_played)])
+                        );
+                        egui::CollapsingHeader::new(title).id_source(("game", &game.path)).show(ui, |ui| {
+                            ui.label(RichText::new(game.launcher.label()).small().color(Color32::GRAY));
+                            ui.horizontal(|ui| {
+                                if ui.link(paths::display_path(&game.path)).on_hover_text(tr("Open folder")).clicked() {
+                                    navigate = Some(game.path.clone());
+                                }
+                                match game.uninstall_url() {
+                                    Some(url) => {
+                                        ui.hyperlink_to(tr("Uninstall..."), url);
+                                    }
+                                    None => {
+                                        ui.label(
+                                            RichText::new(tr("Uninstall it from the launcher's library."))
+                                                .color(Color32::GRAY),
+                                        );
+                                    }
+                                }
+                            });
+                            let targets: Vec<&(PathBuf, u64)> = library
+                                .drives
+                                .iter()
+                                .filter(|(drive, available)| Some(drive) != game.drive.as_ref() && *available > game.size)
+                                .collect();
+                            if targets.is_empty() {
+                                ui.label(RichText::new(tr("No other drive has room for this game.")).color(Color32::GRAY));
+                                return;
+                            }
+                            ui.label(tr(game.launcher.move_guidance()));
+                            for (drive, available) in targets {
+                                ui.label(trf(
+                                    "{} - {} free",
+                                    &[&paths::display_path(drive), &i18n::format_bytes(*available)],
+                                ));
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
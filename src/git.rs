@@ -0,0 +1,290 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, scan, throttle,
+};
+
+const TOP_BLOBS: usize = 10;
+/// History at least this large, or larger than the checked-out files, is worth compacting.
+const LARGE_HISTORY: u64 = 100 * 1024 * 1024;
+/// A single blob this large is better kept in Git LFS or removed from history.
+const LARGE_BLOB: u64 = 10 * 1024 * 1024;
+const ARTIFACT_SHARE: f64 = 0.5;
+
+pub struct RepoReport {
+    pub path: PathBuf,
+    pub total: u64,
+    pub git_size: u64,
+    pub untracked: u64,
+    /// Ignored files, which are mostly build output and dependencies.
+    pub ignored: u64,
+    /// Largest objects anywhere in the history, by their packed size.
+    pub blobs: Vec<(String, u64)>,
+    pub error: Option<String>,
+}
+
+impl RepoReport {
+    fn suggestions(&self) -> Vec<(&'static str, String)> {
+        let mut suggestions = Vec::new();
+        let tracked = self.total.saturating_sub(self.git_size + self.untracked + self.ignored);
+        if self.git_size >= LARGE_HISTORY || self.git_size > tracked {
+            suggestions.push((
+                "History is larger than it needs to be; repacking usually shrinks it",
+                "git gc --aggressive --prune=now".to_string(),
+            ));
+        }
+        if self.blobs.iter().any(|(_, size)| *size >= LARGE_BLOB) {
+            suggestions.push((
+                "Large files in history stay there after deletion; move them to Git LFS or rewrite history",
+                "git filter-repo --strip-blobs-bigger-than 10M".to_string(),
+            ));
+        }
+        if self.ignored as f64 >= self.total as f64 * ARTIFACT_SHARE && self.ignored > 0 {
+            suggestions.push((
+                "Most of this folder is ignored build output; list what would be removed",
+                "git clean -ndX".to_string(),
+            ));
+        }
+        suggestions
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running git: {}", e))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(format!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Total size of the files and folders `git ls-files` lists with the given filters.
+fn listed_size(repo: &Path, filters: &[&str]) -> Result<u64, String> {
+    let args: Vec<&str> = ["ls-files", "-z", "--others", "--directory", "--exclude-standard"]
+        .into_iter()
+        .chain(filters.iter().copied())
+        .collect();
+    let listed = git(repo, &args)?;
+    Ok(listed
+        .split(|&byte| byte == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let path = repo.join(String::from_utf8_lossy(entry).trim_end_matches('/'));
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.is_dir() => scan::measure_dir(&path, &Default::default()).size,
+                Ok(metadata) => scan::file_size(&path, &metadata),
+                Err(_) => 0,
+            }
+        })
+        .sum())
+}
+
+/// The biggest blobs in the object store, named by the path they were committed under.
+/// Blobs no ref reaches any more keep their object id as the name.
+fn largest_blobs(repo: &Path) -> Result<Vec<(String, u64)>, String> {
+    let objects = git(
+        repo,
+        &["cat-file", "--batch-all-objects", "--batch-check=%(objecttype) %(objectname) %(objectsize:disk)"],
+    )?;
+    let mut blobs: Vec<(String, u64)> = String::from_utf8_lossy(&objects)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            if fields.next()? != "blob" {
+                return None;
+            }
+            let id = fields.next()?.to_string();
+            Some((id, fields.next()?.parse().ok()?))
+        })
+        .collect();
+    blobs.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    blobs.truncate(TOP_BLOBS);
+
+    let reachable = git(repo, &["rev-list", "--objects", "--all"])?;
+    let mut names: HashMap<&str, &str> = HashMap::new();
+    let reachable = String::from_utf8_lossy(&reachable);
+    for line in reachable.lines() {
+        if let Some((id, path)) = line.split_once(' ') {
+            names.entry(id).or_insert(path);
+        }
+    }
+    for (id, _) in &mut blobs {
+        if let Some(path) = names.get(id.as_str()) {
+            *id = path.to_string();
+        }
+    }
+    Ok(blobs)
+}
+
+fn inspect(path: PathBuf, total: u64, git_size: u64) -> RepoReport {
+    let mut report =
+        RepoReport { path, total, git_size, untracked: 0, ignored: 0, blobs: Vec::new(), error: None };
+    let result = (|| {
+        report.untracked = listed_size(&report.path, &[])?;
+        report.ignored = listed_size(&report.path, &["--ignored"])?;
+        report.blobs = largest_blobs(&report.path)?;
+        Ok::<(), String>(())
+    })();
+    report.error = result.err();
+    report
+}
+
+/// Finds the repositories among the scanned folders. Hidden folders may not be in the index,
+/// so `.git` is looked up on disk when it isn't.
+pub fn find_repos(candidates: Vec<(PathBuf, u64, Option<u64>)>) -> Vec<RepoReport> {
+    let mut reports: Vec<RepoReport> = candidates
+        .into_iter()
+        .filter(|(path, _, git_size)| {
+            throttle::pace();
+            git_size.is_some() || path.join(".git").is_dir()
+        })
+        .map(|(path, total, git_size)| match git_size {
+            Some(git_size) => inspect(path, total, git_size),
+            None => {
+                // The index total leaves out `.git` when hidden files aren't scanned.
+                let git_size = scan::measure_dir(&path.join(".git"), &Default::default()).size;
+                inspect(path, total + git_size, git_size)
+            }
+        })
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.git_size));
+    reports
+}
+
+#[derive(Default)]
+pub struct GitReposState {
+    pub open: bool,
+    job: Option<Job<Vec<RepoReport>>>,
+    reports: Option<Vec<RepoReport>>,
+}
+
+impl GitReposState {
+    /// Draws the window and returns a repository to jump to when one is clicked.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>) -> Option<PathBuf> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(reports) => {
+                    self.reports = Some(reports);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Git Repositories"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(660.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr(
+                        "Splits each repository into history, untracked files and ignored build output. Needs git.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Find Repositories"))).clicked() {
+                        let candidates: Vec<(PathBuf, u64, Option<u64>)> = index
+                            .nodes
+                            .iter()
+                            .filter(|node| node.is_dir && node.name != ".git")
+                            .map(|node| {
+                                let git = node
+                                    .children
+                                    .iter()
+                                    .map(|&child| &index.nodes[child])
+                                    .find(|child| child.is_dir && child.name == ".git");
+                                (node.path.clone(), node.size, git.map(|git| git.size))
+                            })
+                            .collect();
+                        self.job = Some(Job::spawn(move || find_repos(candidates)));
+                    }
+                    if self.job.is_some() {
+                        ui.spinner();
+                    }
+                });
+                let Some(reports) = &self.reports else {
+                    return;
+                };
+                if reports.is_empty() {
+                    ui.label(tr("No git repositories found."));
+                    return;
+                }
+                let history: u64 = reports.iter().map(|report| report.git_size).sum();
+                ui.label(trf(
+                    "{} repositories, {} of history",
+                    &[&i18n::format_count(reports.len() as u64), &i18n::format_bytes(history)],
+                ));
+                egui::ScrollArea::vertical().max_height(460.0).show(ui, |ui| {
+                    for report in reports {
+                        let title = format!(
+                            "{} - {}",
+                            paths::display_path(&report.path),
+                            trf("{} of history", &[&i18n::format_bytes(report.git_size)])
+                        );
+                        egui::CollapsingHeader::new(title).id_source(("git_repo", &report.path)).show(ui, |ui| {
+                            if ui.link(tr("Open folder")).clicked() {
+                                navigate = Some(report.path.clone());
+                            }
+                            egui::Grid::new(("git_sizes", &report.path)).show(ui, |ui| {
+                                ui.label(tr("Total"));
+                                ui.label(i18n::format_bytes(report.total));
+                                ui.end_row();
+                                ui.label(tr(".git folder"));
+                                ui.label(i18n::format_bytes(report.git_size));
+                                ui.end_row();
+                                ui.label(tr("Untracked"));
+                                ui.label(i18n::format_bytes(report.untracked));
+                                ui.end_row();
+                                ui.label(tr("Ignored build output"));
+                                ui.label(i18n::format_bytes(report.ignored));
+                                ui.end_row();
+                            });
+                            if let Some(error) = &report.error {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                            if !report.blobs.is_empty() {
+                                ui.label(RichText::new(tr("Largest objects in history")).strong());
+                                for (path, size) in &report.blobs {
+                                    ui.label(format!("{}  {}", i18n::format_bytes(*size), path));
+                                }
+                            }
+                            for (reason, command) in report.suggestions() {
+                                ui.horizontal(|ui| {
+                                    ui.label(tr(reason));
+                                    ui.label(RichText::new(&command).monospace());
+                                    if ui.button(tr("Copy")).clicked() {
+                                        let text = format!("cd \"{}\" && {}", report.path.display(), command);
+                                        ui.output_mut(|output| output.copied_text = text);
+                                    }
+                                });
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
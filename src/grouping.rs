@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::{ffi::OsStr, path::Path, time::SystemTime};
+
+use crate::{
+    category::{self, Category},
+    heatmap,
+    i18n::tr,
+};
+
+const DAY: u64 = 60 * 60 * 24;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum GroupBy {
+    #[default]
+    None,
+    Extension,
+    Category,
+    FirstLetter,
+    Age,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 5] = [
+        GroupBy::None,
+        GroupBy::Extension,
+        GroupBy::Category,
+        GroupBy::FirstLetter,
+        GroupBy::Age,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::None => "None",
+            GroupBy::Extension => "Extension",
+            GroupBy::Category => "Category",
+            GroupBy::FirstLetter => "First letter",
+            GroupBy::Age => "Age",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AgeBucket {
+    Today,
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+    Older,
+    Unknown,
+}
+
+impl AgeBucket {
+    pub fn of(modified: Option<SystemTime>) -> Self {
+        let Some(age) = heatmap::age(modified) else {
+            return AgeBucket::Unknown;
+        };
+        match age.as_secs() / DAY {
+            0 => AgeBucket::Today,
+            1..=6 => AgeBucket::ThisWeek,
+            7..=30 => AgeBucket::ThisMonth,
+            31..=365 => AgeBucket::ThisYear,
+            _ => AgeBucket::Older,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgeBucket::Today => "Today",
+            AgeBucket::ThisWeek => "This week",
+            AgeBucket::ThisMonth => "This month",
+            AgeBucket::ThisYear => "This year",
+            AgeBucket::Older => "Older than a year",
+            AgeBucket::Unknown => "Unknown age",
+        }
+    }
+}
+
+/// Folders sort ahead of every other group.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GroupKey {
+    Folders,
+    Extension(String),
+    Category(Category),
+    Letter(char),
+    Age(AgeBucket),
+}
+
+impl GroupKey {
+    pub fn label(&self) -> String {
+        match self {
+            GroupKey::Folders => tr("Folders").to_string(),
+            GroupKey::Extension(extension) if extension.is_empty() => tr("No extension").to_string(),
+            GroupKey::Extension(extension) => format!(".{}", extension),
+            GroupKey::Category(category) => tr(category.label()).to_string(),
+            GroupKey::Letter(letter) => letter.to_string(),
+            GroupKey::Age(bucket) => tr(bucket.label()).to_string(),
+        }
+    }
+}
+
+pub fn key(
+    group_by: GroupBy,
+    path: &Path,
+    name: &OsStr,
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+) -> Option<GroupKey> {
+    Some(match group_by {
+        GroupBy::None => return None,
+        GroupBy::Extension | GroupBy::Category if is_dir => GroupKey::Folders,
+        GroupBy::Extension => GroupKey::Extension(category::extension(path)),
+        GroupBy::Category => GroupKey::Category(category::classify_file(path, size)),
+        GroupBy::FirstLetter => {
+            let first = name.to_string_lossy().trim_start_matches('.').chars().next().unwrap_or('#');
+            GroupKey::Letter(if first.is_alphabetic() { first.to_uppercase().next().unwrap_or(first) } else { '#' })
+        }
+        GroupBy::Age => GroupKey::Age(AgeBucket::of(modified)),
+    })
+}
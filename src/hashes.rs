@@ -0,0 +1,176 @@
+use eframe::egui::{self, Color32, RichText};
+use sha2::Digest;
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    a11y,
+    i18n::{self, tr},
+    job::Job,
+    paths,
+};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    Md5,
+    Sha256,
+    Blake3,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Md5, Algorithm::Sha256, Algorithm::Blake3];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Blake3 => "BLAKE3",
+        }
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Feeds the file through `update` chunk by chunk, counting bytes read into `read`.
+fn read_chunks(path: &Path, read: &AtomicU64, mut update: impl FnMut(&[u8])) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("Error reading {}: {}", paths::display_path(path), e);
+    let mut file = File::open(paths::extended(path)).map_err(error)?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let count = file.read(&mut buffer).map_err(error)?;
+        if count == 0 {
+            return Ok(());
+        }
+        update(&buffer[..count]);
+        read.fetch_add(count as u64, Ordering::Relaxed);
+    }
+}
+
+fn digest<D: Digest>(path: &Path, read: &AtomicU64) -> Result<String, String> {
+    let mut hasher = D::new();
+    read_chunks(path, read, |chunk| hasher.update(chunk))?;
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Hashes a file, returning the digest as lowercase hex.
+pub fn hash_file(path: &Path, algorithm: Algorithm, read: &AtomicU64) -> Result<String, String> {
+    match algorithm {
+        Algorithm::Md5 => digest::<md5::Md5>(path, read),
+        Algorithm::Sha256 => digest::<sha2::Sha256>(path, read),
+        Algorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            read_chunks(path, read, |chunk| {
+                hasher.update(chunk);
+            })?;
+            Ok(hex(hasher.finalize().as_bytes()))
+        }
+    }
+}
+
+struct Running {
+    algorithm: Algorithm,
+    read: Arc<AtomicU64>,
+    job: Job<Result<String, String>>,
+}
+
+/// Checksums of the file shown in the details panel, computed on demand in the background.
+pub struct Checksums {
+    pub path: PathBuf,
+    size: u64,
+    results: Vec<(Algorithm, Result<String, String>)>,
+    running: Option<Running>,
+    expected: String,
+}
+
+impl Checksums {
+    pub fn new(path: PathBuf, size: u64) -> Self {
+        Self {
+            path,
+            size,
+            results: Vec::new(),
+            running: None,
+            expected: String::new(),
+        }
+    }
+
+    fn start(&mut self, algorithm: Algorithm) {
+        let read = Arc::new(AtomicU64::new(0));
+        let path = self.path.clone();
+        let worker_read = Arc::clone(&read);
+        let job = Job::spawn(move || hash_file(&path, algorithm, &worker_read));
+        self.results.retain(|(done, _)| *done != algorithm);
+        self.running = Some(Running { algorithm, read, job });
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if let Some(result) = self.running.as_ref().and_then(|running| running.job.poll()) {
+            if let Some(running) = self.running.take() {
+                self.results.push((running.algorithm, result));
+            }
+        }
+
+        ui.label(RichText::new(paths::display_name(self.path.file_name().unwrap_or_default())).strong());
+        ui.label(i18n::format_bytes(self.size));
+        ui.horizontal(|ui| {
+            for algorithm in Algorithm::ALL {
+                if ui.add_enabled(self.running.is_none(), egui::Button::new(algorithm.label())).clicked() {
+                    self.start(algorithm);
+                }
+            }
+        });
+
+        if let Some(running) = &self.running {
+            let read = running.read.load(Ordering::Relaxed);
+            let fraction = if self.size == 0 { 1.0 } else { (read as f64 / self.size as f64).min(1.0) as f32 };
+            ui.add(egui::ProgressBar::new(fraction).text(format!(
+                "{} {} / {}",
+                running.algorithm.label(),
+                i18n::format_bytes(read),
+                i18n::format_bytes(self.size)
+            )));
+            ui.ctx().request_repaint();
+        }
+
+        if self.results.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr("Compare with:"));
+            ui.text_edit_singleline(&mut self.expected);
+        });
+        let expected = self.expected.trim().to_lowercase();
+        for (algorithm, result) in &self.results {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(algorithm.label()).strong());
+                match result {
+                    Ok(hash) => {
+                        ui.label(RichText::new(hash).monospace());
+                        if a11y::icon_button(ui, "📋", tr("Copy")).clicked() {
+                            ui.output_mut(|output| output.copied_text = hash.clone());
+                        }
+                        if *hash == expected {
+                            ui.label(RichText::new(tr("Match")).color(Color32::GREEN));
+                        }
+                    }
+                    Err(error) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                }
+            });
+        }
+        if !expected.is_empty() && !self.results.iter().any(|(_, result)| result.as_ref() == Ok(&expected)) {
+            ui.label(RichText::new(tr("No computed checksum matches")).color(Color32::YELLOW));
+        }
+    }
+}
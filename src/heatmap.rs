@@ -0,0 +1,41 @@
+use eframe::egui::{self, Color32, RichText};
+use std::time::{Duration, SystemTime};
+
+use crate::i18n::tr;
+
+const DAY: u64 = 60 * 60 * 24;
+const OLDEST_DAYS: f32 = 5.0 * 365.0;
+
+pub fn age(modified: Option<SystemTime>) -> Option<Duration> {
+    modified.and_then(|time| SystemTime::now().duration_since(time).ok())
+}
+
+pub fn age_color(modified: Option<SystemTime>) -> Color32 {
+    let Some(age) = age(modified) else {
+        return Color32::GRAY;
+    };
+    let days = age.as_secs() as f32 / DAY as f32;
+    let t = ((1.0 + days).ln() / (1.0 + OLDEST_DAYS).ln()).clamp(0.0, 1.0);
+    gradient(t)
+}
+
+fn gradient(t: f32) -> Color32 {
+    let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    let (green, yellow, red) = ((80u8, 200u8, 80u8), (230u8, 210u8, 60u8), (230u8, 70u8, 60u8));
+    let (from, to, t) = if t < 0.5 {
+        (green, yellow, t * 2.0)
+    } else {
+        (yellow, red, (t - 0.5) * 2.0)
+    };
+    Color32::from_rgb(lerp(from.0, to.0, t), lerp(from.1, to.1, t), lerp(from.2, to.2, t))
+}
+
+pub fn render_legend(ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label(tr("Last modified:"));
+        for (label, days) in [("today", 0u64), ("1 month", 30), ("1 year", 365), ("5+ years", 5 * 365)] {
+            let time = SystemTime::now() - Duration::from_secs(days * DAY);
+            ui.label(RichText::new(format!("■ {}", tr(label))).color(age_color(Some(time))));
+        }
+    });
+}
@@ -0,0 +1,116 @@
+use eframe::egui::{self, Color32, RichText};
+
+use crate::{
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+};
+
+/// Upper bounds of each bucket in decimal bytes; the last bucket is open-ended.
+const BOUNDS: [u64; 7] = [
+    1_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+];
+const LABELS: [&str; 8] = [
+    "< 1 KB",
+    "1 KB – 100 KB",
+    "100 KB – 1 MB",
+    "1 MB – 10 MB",
+    "10 MB – 100 MB",
+    "100 MB – 1 GB",
+    "1 GB – 10 GB",
+    "> 10 GB",
+];
+
+#[derive(Clone, Copy, Default)]
+pub struct Bucket {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+#[derive(Default)]
+pub struct Histogram {
+    pub buckets: [Bucket; 8],
+}
+
+impl Histogram {
+    pub fn of(index: &ScanIndex) -> Self {
+        let mut histogram = Histogram::default();
+        for node in index.nodes.iter().filter(|node| !node.is_dir) {
+            let bucket = BOUNDS.iter().position(|&bound| node.size < bound).unwrap_or(BOUNDS.len());
+            histogram.buckets[bucket].count += 1;
+            histogram.buckets[bucket].bytes += node.size;
+        }
+        histogram
+    }
+
+    pub fn total(&self) -> Bucket {
+        self.buckets.iter().fold(Bucket::default(), |total, bucket| Bucket {
+            count: total.count + bucket.count,
+            bytes: total.bytes + bucket.bytes,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct HistogramState {
+    pub open: bool,
+    histogram: Option<Histogram>,
+}
+
+impl HistogramState {
+    /// Drops the cached figures after the index changes.
+    pub fn invalidate(&mut self) {
+        self.histogram = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>) {
+        let mut open = self.open;
+        egui::Window::new(tr("Size Histogram"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                let histogram = self.histogram.get_or_insert_with(|| Histogram::of(index));
+                let total = histogram.total();
+                ui.label(trf(
+                    "{} files, {}",
+                    &[&i18n::format_count(total.count), &i18n::format_bytes(total.bytes)],
+                ));
+
+                let mut cumulative = 0;
+                egui::Grid::new("size_histogram").striped(true).show(ui, |ui| {
+                    ui.label(tr("Size"));
+                    ui.label(tr("Files"));
+                    ui.label(tr("Bytes"));
+                    ui.label(tr("Cumulative"));
+                    ui.label(tr("Share of bytes"));
+                    ui.end_row();
+                    for (label, bucket) in LABELS.iter().zip(&histogram.buckets) {
+                        cumulative += bucket.bytes;
+                        let share = if total.bytes > 0 { bucket.bytes as f32 / total.bytes as f32 } else { 0.0 };
+                        ui.label(*label);
+                        ui.label(i18n::format_count(bucket.count));
+                        ui.label(i18n::format_bytes(bucket.bytes));
+                        ui.label(RichText::new(i18n::format_bytes(cumulative)).color(Color32::GRAY));
+                        ui.add(
+                            egui::ProgressBar::new(share)
+                                .desired_width(160.0)
+                                .text(format!("{:.1}%", share * 100.0)),
+                        );
+                        ui.end_row();
+                    }
+                });
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,57 @@
+const MAX_HISTORY: usize = 100;
+
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    coalescing: bool,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            coalescing: false,
+        }
+    }
+}
+
+impl<T: PartialEq> History<T> {
+    /// Records `before` if it differs from `after`. Consecutive coalescable
+    /// changes (e.g. typing into the search box) collapse into one step.
+    pub fn observe(&mut self, before: T, after: &T, coalescable: bool) {
+        if before == *after {
+            return;
+        }
+        if !(coalescable && self.coalescing) {
+            self.undo.push(before);
+            if self.undo.len() > MAX_HISTORY {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.coalescing = coalescable;
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.coalescing = false;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.coalescing = false;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
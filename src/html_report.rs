@@ -0,0 +1,262 @@
+use serde::Serialize;
+use std::{
+    collections::{BinaryHeap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    paths, tree,
+};
+
+/// Keeps reports of huge scans small enough to open in a browser; the largest folders are
+/// expanded first and the rest appear collapsed with their totals.
+const MAX_NODES: usize = 50_000;
+
+#[derive(Serialize)]
+struct ReportNode {
+    name: String,
+    size: u64,
+    /// The size already formatted the way the app shows it.
+    label: String,
+    dir: bool,
+    /// Entries below a folder whose contents were left out of the report.
+    hidden: u64,
+    children: Vec<ReportNode>,
+}
+
+/// Picks which folders get their children listed, largest first, until `MAX_NODES` is reached.
+fn expanded_folders(index: &ScanIndex) -> HashSet<usize> {
+    let mut expanded = HashSet::new();
+    let mut candidates = BinaryHeap::from([(index.nodes[0].size, 0)]);
+    let mut included = 1;
+    while let Some((_, id)) = candidates.pop() {
+        let children = &index.nodes[id].children;
+        if included + children.len() > MAX_NODES {
+            break;
+        }
+        included += children.len();
+        expanded.insert(id);
+        candidates.extend(
+            children
+                .iter()
+                .filter(|&&child| index.nodes[child].is_dir)
+                .map(|&child| (index.nodes[child].size, child)),
+        );
+    }
+    expanded
+}
+
+fn report_node(index: &ScanIndex, id: usize, expanded: &HashSet<usize>) -> ReportNode {
+    let node = &index.nodes[id];
+    let mut report = ReportNode {
+        name: match id {
+            0 => paths::display_path(&node.path).into_owned(),
+            _ => paths::display_name(&node.name).into_owned(),
+        },
+        size: node.size,
+        label: i18n::format_bytes(node.size),
+        dir: node.is_dir,
+        hidden: 0,
+        children: Vec::new(),
+    };
+    if !expanded.contains(&id) {
+        report.hidden = if node.is_dir { node.entries } else { 0 };
+        return report;
+    }
+    report.children = node.children.iter().map(|&child| report_node(index, child, expanded)).collect();
+    if node.small_files > 0 {
+        report.children.push(ReportNode {
+            name: tree::small_files_label(node.small_files, node.small_size),
+            size: node.small_size,
+            label: i18n::format_bytes(node.small_size),
+            dir: false,
+            hidden: 0,
+            children: Vec::new(),
+        });
+    }
+    report
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// A single self-contained page with a collapsible tree and a treemap of the scan, readable
+/// without the app.
+pub fn render(index: &ScanIndex) -> Result<String, String> {
+    if index.nodes.is_empty() {
+        return Err("Nothing has been scanned yet".to_string());
+    }
+    let root = report_node(index, 0, &expanded_folders(index));
+    // `</` would end the script element early.
+    let data = serde_json::to_string(&root)
+        .map_err(|e| format!("Error writing report: {}", e))?
+        .replace("</", "<\\/");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs());
+    let title = escape_html(&trf("Disk usage of {}", &[&root.name]));
+    let subtitle = escape_html(&trf(
+        "{} in {} items, generated {}",
+        &[&root.label, &i18n::format_count(index.nodes[0].entries), &audit::format_timestamp(now)],
+    ));
+    let hidden_label = serde_json::to_string(tr("{} items not included")).unwrap_or_default();
+
+    Ok(TEMPLATE
+        .replace("{{title}}", &title)
+        .replace("{{subtitle}}", &subtitle)
+        .replace("{{hidden_label}}", &hidden_label)
+        .replace("{{data}}", &data))
+}
+
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{title}}</title>
+<style>
+body { font-family: system-ui, sans-serif; margin: 0; background: #1b1b1b; color: #ddd; }
+header { padding: 12px 16px; border-bottom: 1px solid #333; }
+h1 { font-size: 18px; margin: 0 0 4px; }
+header p { margin: 0; color: #999; font-size: 13px; }
+main { display: flex; height: calc(100vh - 64px); }
+#tree { width: 40%; overflow: auto; padding: 8px; font-size: 13px; border-right: 1px solid #333; }
+#tree ul { list-style: none; margin: 0; padding-left: 16px; }
+#tree > ul { padding-left: 0; }
+#tree li > div { display: flex; gap: 8px; padding: 1px 4px; cursor: pointer; white-space: nowrap; }
+#tree li > div:hover, #tree li > div.selected { background: #2e3b4e; }
+.toggle { width: 12px; color: #888; }
+.size { margin-left: auto; color: #aaa; }
+.hidden { color: #777; font-style: italic; padding-left: 20px; }
+#map { position: relative; flex: 1; margin: 8px; }
+#map div { position: absolute; box-sizing: border-box; border: 1px solid #1b1b1b; overflow: hidden;
+  font-size: 11px; padding: 2px; color: #111; cursor: pointer; }
+</style>
+</head>
+<body>
+<header><h1>{{title}}</h1><p>{{subtitle}}</p></header>
+<main><div id="tree"></div><div id="map"></div></main>
+<script type="application/json" id="scan">{{data}}</script>
+<script>
+const root = JSON.parse(document.getElementById("scan").textContent);
+const hiddenLabel = {{hidden_label}};
+const tree = document.getElementById("tree");
+const map = document.getElementById("map");
+let selectedRow = null;
+let shown = root;
+
+function link(node, parent) {
+  node.parent = parent;
+  (node.children || []).forEach(child => link(child, node));
+}
+link(root, null);
+
+function row(node) {
+  const item = document.createElement("li");
+  const line = document.createElement("div");
+  const toggle = document.createElement("span");
+  toggle.className = "toggle";
+  toggle.textContent = node.children.length || node.hidden ? "▸" : "";
+  const name = document.createElement("span");
+  name.textContent = (node.dir ? "📁 " : "📄 ") + node.name;
+  const size = document.createElement("span");
+  size.className = "size";
+  size.textContent = node.label;
+  line.append(toggle, name, size);
+  item.append(line);
+  node.row = line;
+  line.onclick = () => { expand(node, !node.open); select(node); };
+  return item;
+}
+
+function expand(node, open) {
+  const item = node.row.parentElement;
+  if ((!node.children.length && !node.hidden) || node.open === open) return;
+  node.open = open;
+  node.row.firstChild.textContent = open ? "▾" : "▸";
+  if (!open) { item.querySelector("ul").remove(); return; }
+  const list = document.createElement("ul");
+  node.children.forEach(child => list.append(row(child)));
+  if (node.hidden) {
+    const more = document.createElement("li");
+    more.className = "hidden";
+    more.textContent = hiddenLabel.replace("{}", node.hidden.toLocaleString());
+    list.append(more);
+  }
+  item.append(list);
+}
+
+function select(node) {
+  if (selectedRow) selectedRow.classList.remove("selected");
+  selectedRow = node.row;
+  selectedRow.classList.add("selected");
+  draw(node.children.length ? node : node.parent || node);
+}
+
+function reveal(node) {
+  const path = [];
+  for (let current = node; current; current = current.parent) path.unshift(current);
+  path.forEach(step => { if (step !== node) expand(step, true); });
+  select(node);
+  node.row.scrollIntoView({ block: "nearest" });
+}
+
+// Squarified treemap: lay children out in rows whose aspect ratio stays closest to square.
+function layout(items, x, y, w, h, out) {
+  const total = items.reduce((sum, item) => sum + item.area, 0);
+  if (!items.length || total <= 0) return;
+  const vertical = w < h;
+  const side = vertical ? w : h;
+  let rowItems = [], rowArea = 0, best = Infinity;
+  for (const item of items) {
+    const area = rowArea + item.area;
+    const thickness = area / side;
+    const worst = Math.max(...rowItems.concat(item).map(i => Math.max(thickness / (i.area / thickness), (i.area / thickness) / thickness)));
+    if (worst > best) break;
+    rowItems.push(item); rowArea = area; best = worst;
+  }
+  const thickness = rowArea / side;
+  let offset = 0;
+  for (const item of rowItems) {
+    const length = item.area / thickness;
+    out.push(vertical ? [item.node, x + offset, y, length, thickness] : [item.node, x, y + offset, thickness, length]);
+    offset += length;
+  }
+  const rest = items.slice(rowItems.length);
+  if (vertical) layout(rest, x, y + thickness, w, h - thickness, out);
+  else layout(rest, x + thickness, y, w - thickness, h, out);
+}
+
+function draw(folder) {
+  shown = folder;
+  map.replaceChildren();
+  const w = map.clientWidth, h = map.clientHeight;
+  const children = folder.children.filter(child => child.size > 0);
+  const total = children.reduce((sum, child) => sum + child.size, 0);
+  if (!total) return;
+  const items = children.map(child => ({ node: child, area: child.size / total * w * h }));
+  const rects = [];
+  layout(items, 0, 0, w, h, rects);
+  rects.forEach(([node, x, y, width, height], i) => {
+    const cell = document.createElement("div");
+    Object.assign(cell.style, { left: x + "px", top: y + "px", width: width + "px", height: height + "px",
+      background: node.dir ? `hsl(${(i * 47) % 360}, 55%, 65%)` : "#999" });
+    cell.title = node.name + " - " + node.label;
+    if (width > 40 && height > 14) cell.textContent = node.name;
+    cell.onclick = () => reveal(node);
+    map.append(cell);
+  });
+}
+
+const list = document.createElement("ul");
+list.append(row(root));
+tree.append(list);
+expand(root, true);
+select(root);
+window.onresize = () => draw(shown);
+</script>
+</body>
+</html>
+"##;
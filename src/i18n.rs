@@ -0,0 +1,905 @@
+use humansize::{format_size, BINARY, DECIMAL};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    English,
+    PortugueseBrazil,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::PortugueseBrazil];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::PortugueseBrazil => "Português (Brasil)",
+        }
+    }
+
+    pub fn detect() -> Self {
+        let lang = std::env::var("LC_ALL")
+            .or_else(|_| std::env::var("LANG"))
+            .unwrap_or_default()
+            .to_lowercase();
+        if lang.starts_with("pt") {
+            Locale::PortugueseBrazil
+        } else {
+            Locale::English
+        }
+    }
+
+    fn decimal_separator(&self) -> char {
+        match self {
+            Locale::English => '.',
+            Locale::PortugueseBrazil => ',',
+        }
+    }
+
+    fn group_separator(&self) -> char {
+        match self {
+            Locale::English => ',',
+            Locale::PortugueseBrazil => '.',
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::detect()
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+static DECIMAL_UNITS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn set_decimal_units(decimal: bool) {
+    DECIMAL_UNITS.store(decimal, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::PortugueseBrazil,
+        _ => Locale::English,
+    }
+}
+
+/// Translates an English UI string; strings without a translation fall back to English.
+pub fn tr(text: &'static str) -> &'static str {
+    match locale() {
+        Locale::English => text,
+        Locale::PortugueseBrazil => pt_br(text).unwrap_or(text),
+    }
+}
+
+/// Translates `text` and substitutes each `{}` with the next argument.
+pub fn trf(text: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut result = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(text).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        result.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                result.push_str(&arg.to_string());
+            }
+        }
+    }
+    result
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if DECIMAL_UNITS.load(Ordering::Relaxed) {
+        localize_decimal(format_size(bytes, DECIMAL))
+    } else {
+        localize_decimal(format_size(bytes, BINARY))
+    }
+}
+
+pub fn format_exact_bytes(bytes: u64) -> String {
+    trf("{} bytes", &[&format_count(bytes)])
+}
+
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let separator = locale().group_separator();
+    let mut result = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(separator);
+        }
+        result.push(digit);
+    }
+    result
+}
+
+fn localize_decimal(text: String) -> String {
+    let separator = locale().decimal_separator();
+    if separator == '.' {
+        text
+    } else {
+        text.replace('.', &separator.to_string())
+    }
+}
+
+fn pt_br(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Select Directory" => "Selecionar Pasta",
+        "Compare..." => "Comparar...",
+        "Settings" => "Configurações",
+        "Profile: {}" => "Perfil: {}",
+        "Auto Refresh" => "Atualizar Automaticamente",
+        "Sort by Size" => "Ordenar por Tamanho",
+        "Show Hidden" => "Mostrar Ocultos",
+        "Age Heatmap" => "Mapa de Idade",
+        "Respect .gitignore" => "Respeitar .gitignore",
+        "Skip entries excluded by .gitignore and .ignore files" => {
+            "Ignorar entradas excluídas por arquivos .gitignore e .ignore"
+        }
+        "Show Ignored Size" => "Mostrar Tamanho Ignorado",
+        "Total Size: {}" => "Tamanho Total: {}",
+        "Search:" => "Buscar:",
+        "Show All Files" => "Mostrar Todos os Arquivos",
+        "Apply Filter" => "Aplicar Filtro",
+        "Tag" => "Etiqueta",
+        "Any" => "Qualquer",
+        "Scanning..." => "Analisando...",
+        "Undo (Ctrl+Z)" => "Desfazer (Ctrl+Z)",
+        "Redo (Ctrl+Shift+Z)" => "Refazer (Ctrl+Shift+Z)",
+        "Drop a folder to analyze it" => "Solte uma pasta para analisá-la",
+        "Confirm Deletion" => "Confirmar Exclusão",
+        "Are you sure you want to delete {}?" => "Tem certeza de que deseja excluir {}?",
+        "Secure delete (overwrite before removing)" => {
+            "Exclusão segura (sobrescrever antes de remover)"
+        }
+        crate::wipe::SSD_WARNING => {
+            "Sobrescrever não garante a destruição dos dados em SSDs, pendrives, sistemas de \
+arquivos copy-on-write (btrfs, ZFS, APFS) ou arquivos com snapshots ou backups. Use \
+criptografia de disco completo nesses casos."
+        }
+        "Yes" => "Sim",
+        "No" => "Não",
+        "Close" => "Fechar",
+        "Cancel" => "Cancelar",
+        "Save" => "Salvar",
+        "Install" => "Instalar",
+        "Remove" => "Remover",
+        "Secure Delete" => "Exclusão Segura",
+        "Overwriting {}" => "Sobrescrevendo {}",
+        "Directory Statistics:" => "Estatísticas da Pasta:",
+        "Total items: {}" => "Total de itens: {}",
+        "Total size: {}" => "Tamanho total: {}",
+        "Files: {}" => "Arquivos: {}",
+        "Directories: {}" => "Pastas: {}",
+        "Changed during scan: {}" => "Alterados durante a análise: {}",
+        "Changed while scanning; size may be out of date" => {
+            "Alterado durante a análise; o tamanho pode estar desatualizado"
+        }
+        "Context menu" => "Menu de contexto",
+        "Adds \"{}\" to the file manager's folder menu." => {
+            "Adiciona \"{}\" ao menu de pastas do gerenciador de arquivos."
+        }
+        "Installed {} entries" => "{} entradas instaladas",
+        "Removed {} entries" => "{} entradas removidas",
+        "Scan profiles" => "Perfis de análise",
+        "Save current settings" => "Salvar configurações atuais",
+        "Profile saved" => "Perfil salvo",
+        "Profile removed" => "Perfil removido",
+        "Language" => "Idioma",
+        "UI scale" => "Escala da interface",
+        "Text size" => "Tamanho do texto",
+        "Up one level" => "Subir um nível",
+        "Delete" => "Excluir",
+        "Delete profile" => "Excluir perfil",
+        "Details" => "Detalhes",
+        "Refresh" => "Atualizar",
+        "Units" => "Unidades",
+        "Binary (KiB, MiB, GiB)" => "Binário (KiB, MiB, GiB)",
+        "Decimal (kB, MB, GB)" => "Decimal (kB, MB, GB)",
+        "Show exact byte counts" => "Mostrar contagem exata de bytes",
+        "{} bytes" => "{} bytes",
+        "You've freed {}" => "Você liberou {}",
+        "Nothing freed yet this session" => "Nada liberado nesta sessão ainda",
+        "({} all time)" => "({} no total)",
+        "Summary" => "Resumo",
+        "Session Summary" => "Resumo da Sessão",
+        "Removed {} items, freeing {}" => "{} itens removidos, liberando {}",
+        "All time: {} items, {}" => "No total: {} itens, {}",
+        "{} min ago" => "há {} min",
+        "Tags & Notes" => "Etiquetas e Notas",
+        "Tags & notes" => "Etiquetas e notas",
+        "Add Tag" => "Adicionar Etiqueta",
+        "Note:" => "Nota:",
+        "Folders" => "Pastas",
+        "{} ignored entries - {}" => "{} entradas ignoradas - {}",
+        "Last modified:" => "Última modificação:",
+        "today" => "hoje",
+        "1 month" => "1 mês",
+        "1 year" => "1 ano",
+        "5+ years" => "5+ anos",
+        "Compare Directories" => "Comparar Pastas",
+        "Browse" => "Procurar",
+        "Compare" => "Comparar",
+        "A total: {}   B total: {}" => "Total A: {}   Total B: {}",
+        "Directories match" => "As pastas são iguais",
+        "Only in A" => "Somente em A",
+        "Only in B" => "Somente em B",
+        "Size mismatch" => "Tamanhos diferentes",
+        "Reports" => "Relatórios",
+        "Run Report" => "Gerar Relatório",
+        "Select a directory first." => "Selecione uma pasta primeiro.",
+        "Hidden Usage" => "Uso Oculto",
+        "Only deleted files under the scanned root" => "Somente arquivos excluídos dentro da pasta analisada",
+        "Broken symlinks ({})" => "Links simbólicos quebrados ({})",
+        "Deleted but still open ({}, {})" => "Excluídos mas ainda abertos ({}, {})",
+        "Space is released when the process closes the file or exits." => {
+            "O espaço é liberado quando o processo fecha o arquivo ou termina."
+        }
+        "Usage by User" => "Uso por Usuário",
+        "Export CSV" => "Exportar CSV",
+        "User" => "Usuário",
+        "Home" => "Pasta pessoal",
+        "Home size" => "Tamanho da pasta pessoal",
+        "Owned" => "Pertencente",
+        "Files" => "Arquivos",
+        "Resume Last Session" => "Retomar Última Sessão",
+        "Resume" => "Retomar",
+        "Start Fresh" => "Começar do Zero",
+        "Tree View" => "Visão em Árvore",
+        "Indexing..." => "Indexando...",
+        "Expand" => "Expandir",
+        "Collapse" => "Recolher",
+        "Entire tree" => "Árvore inteira",
+        "{} matches in {}" => "{} resultados em {}",
+        "Go to parent folder" => "Ir para a pasta pai",
+        "Showing the {} largest matches" => "Mostrando os {} maiores resultados",
+        "Minimum size" => "Tamanho mínimo",
+        "Folders by total size" => "Pastas pelo tamanho total",
+        "Folders by own size" => "Pastas pelo tamanho próprio",
+        "Always show folders" => "Sempre mostrar pastas",
+        "Name" => "Nome",
+        "Own size" => "Tamanho próprio",
+        "Total size" => "Tamanho total",
+        "own {}" => "próprio {}",
+        "Show scan diagnostics" => "Mostrar diagnóstico da análise",
+        "{} entries/s" => "{} entradas/s",
+        "{}/s measured" => "{}/s medidos",
+        "{} of {} threads busy" => "{} de {} threads ocupadas",
+        "Cache hits: {}% ({} / {})" => "Acertos de cache: {}% ({} / {})",
+        "Cache hits: -" => "Acertos de cache: -",
+        "{} entries total" => "{} entradas no total",
+        "Network Share Mode" => "Modo de Compartilhamento de Rede",
+        "Lists many folders in parallel with timeouts; suited to SMB and NFS mounts" => {
+            "Lista muitas pastas em paralelo com limite de tempo; indicado para montagens SMB e NFS"
+        }
+        "Unreachable or timed out; size is incomplete" => {
+            "Inacessível ou tempo esgotado; o tamanho está incompleto"
+        }
+        "Container Storage" => "Armazenamento de Contêineres",
+        "Reported by {}" => "Informado por {}",
+        "No container engine answered; measured {} instead." => {
+            "Nenhum mecanismo de contêineres respondeu; {} foi medido no lugar."
+        }
+        "Type" => "Tipo",
+        "Total" => "Total",
+        "Active" => "Ativos",
+        "Size" => "Tamanho",
+        "Reclaimable" => "Recuperável",
+        "Prune" => "Limpar",
+        "Confirm Prune" => "Confirmar Limpeza",
+        "This runs `{} {}` and cannot be undone." => "Isto executa `{} {}` e não pode ser desfeito.",
+        "Containers" => "Contêineres",
+        "Local Volumes" => "Volumes Locais",
+        "Build Cache" => "Cache de Build",
+        "Package Caches" => "Caches de Pacotes",
+        "Ran {}" => "Executado: {}",
+        "No known package caches found." => "Nenhum cache de pacotes conhecido encontrado.",
+        "Total: {}" => "Total: {}",
+        "Copy" => "Copiar",
+        "Run" => "Executar",
+        "Needs administrator rights; copy the command instead" => {
+            "Requer privilégios de administrador; copie o comando"
+        }
+        "Snapshots" => "Snapshots",
+        crate::snapshots::SNAPSHOT_WARNING => {
+            "Este sistema de arquivos compartilha dados entre snapshots. Excluir arquivos pode não \
+liberar espaço até que os snapshots que ainda os referenciam sejam removidos."
+        }
+        "This folder is not on a btrfs or ZFS filesystem." => "Esta pasta não está em um sistema de arquivos btrfs ou ZFS.",
+        "Filesystem: {}" => "Sistema de arquivos: {}",
+        "Sizes unavailable: {}" => "Tamanhos indisponíveis: {}",
+        "Referenced" => "Referenciado",
+        "Exclusive" => "Exclusivo",
+        "Volume:" => "Volume:",
+        "Capacity: {}" => "Capacidade: {}",
+        "Available: {}" => "Disponível: {}",
+        "Cloned files: {} ({} shared with other copies)" => {
+            "Arquivos clonados: {} ({} compartilhados com outras cópias)"
+        }
+        "Local snapshots: {}" => "Snapshots locais: {}",
+        crate::volume::PURGEABLE_NOTE => {
+            "O Finder conta o espaço removível (snapshots locais do Time Machine, arquivos do iCloud \
+otimizados e caches do sistema) como disponível, por isso mostra mais espaço livre que o próprio \
+volume. Arquivos clonados compartilham seus dados, então excluir um libera apenas a parte própria."
+        }
+        "online-only" => "somente online",
+        "Stored in the cloud; takes no space on this device until downloaded" => {
+            "Armazenado na nuvem; não ocupa espaço neste dispositivo até ser baixado"
+        }
+        "Directory Budgets" => "Orçamentos de Pastas",
+        "Folder:" => "Pasta:",
+        "Current Folder" => "Pasta Atual",
+        "Budget:" => "Orçamento:",
+        "Add" => "Adicionar",
+        "Notify when a folder goes over budget" => "Notificar quando uma pasta passar do orçamento",
+        "Checks every {} minutes while the app is open" => "Verifica a cada {} minutos enquanto o app está aberto",
+        "Check Now" => "Verificar Agora",
+        "No budgets set." => "Nenhum orçamento definido.",
+        "Folder" => "Pasta",
+        "Budget" => "Orçamento",
+        "Used" => "Usado",
+        "Directory over budget" => "Pasta acima do orçamento",
+        "{} is {} over its {} budget" => "{} está {} acima do orçamento de {}",
+        "Panels" => "Painéis",
+        "Reset Layout" => "Restaurar Layout",
+        "Tree" => "Árvore",
+        "Errors" => "Erros",
+        "No errors." => "Nenhum erro.",
+        "Clear" => "Limpar",
+        "Go to Path" => "Ir para Caminho",
+        "Go to path (Ctrl+P)" => "Ir para caminho (Ctrl+P)",
+        "Type part of a path" => "Digite parte de um caminho",
+        "No matches" => "Nenhum resultado",
+        "Group by" => "Agrupar por",
+        "None" => "Nenhum",
+        "Extension" => "Extensão",
+        "Category" => "Categoria",
+        "First letter" => "Primeira letra",
+        "Age" => "Idade",
+        "Today" => "Hoje",
+        "This week" => "Esta semana",
+        "This month" => "Este mês",
+        "This year" => "Este ano",
+        "Older than a year" => "Mais de um ano",
+        "Unknown age" => "Idade desconhecida",
+        "No extension" => "Sem extensão",
+        "{} items" => "{} itens",
+        "Size Histogram" => "Histograma de Tamanhos",
+        "{} files, {}" => "{} arquivos, {}",
+        "Bytes" => "Bytes",
+        "Cumulative" => "Acumulado",
+        "Share of bytes" => "Parcela dos bytes",
+        "Inode Usage" => "Uso de Inodes",
+        "Rank folders by how many entries they contain instead of by size" => {
+            "Ordena as pastas pela quantidade de entradas em vez do tamanho"
+        }
+        "Entries: {}" => "Entradas: {}",
+        "Inodes: {} of {} used ({}%)" => "Inodes: {} de {} usados ({}%)",
+        "Read-only mode" => "Modo somente leitura",
+        "Read-only" => "Somente leitura",
+        "Disables deleting, wiping, pruning and cleaning" => "Desativa exclusão, sobrescrita, limpeza e prune",
+        "Turned on by --read-only" => "Ativado por --read-only",
+        "Disabled in read-only mode" => "Desativado no modo somente leitura",
+        "This permanently deletes {}. Type the folder name to confirm:" => {
+            "Isto exclui {} permanentemente. Digite o nome da pasta para confirmar:"
+        }
+        "Type the folder name to delete folders over" => "Digitar o nome da pasta para excluir pastas acima de",
+        "History" => "Histórico",
+        "Secure delete" => "Exclusão segura",
+        "Clean" => "Limpeza",
+        "When" => "Quando",
+        "Action" => "Ação",
+        "Target" => "Alvo",
+        "Result" => "Resultado",
+        "OK" => "OK",
+        "Nothing has been deleted or cleaned yet." => "Nada foi excluído ou limpo ainda.",
+        "Freed over all recorded actions: {}" => "Liberado em todas as ações registradas: {}",
+        "Operations" => "Operações",
+        "Move" => "Mover",
+        "Compress" => "Compactar",
+        "Run tasks:" => "Executar tarefas:",
+        "One at a time" => "Uma de cada vez",
+        "In parallel" => "Em paralelo",
+        "Pause all" => "Pausar todas",
+        "Resume all" => "Retomar todas",
+        "Clear finished" => "Limpar concluídas",
+        "No operations queued." => "Nenhuma operação na fila.",
+        "Cancelled" => "Cancelada",
+        "Done" => "Concluída",
+        "Queued" => "Na fila",
+        "{} (paused)" => "{} (pausada)",
+        "{} written" => "{} gravados",
+        "Pause" => "Pausar",
+        "Delete in background" => "Excluir em segundo plano",
+        "Move to..." => "Mover para...",
+        "Compare with:" => "Comparar com:",
+        "Match" => "Confere",
+        "No computed checksum matches" => "Nenhum checksum calculado confere",
+        "Similar Media" => "Mídia semelhante",
+        "Finds re-encoded and resized copies of images and videos. Video frames need ffmpeg." => "Encontra cópias recodificadas e redimensionadas de imagens e vídeos. Quadros de vídeo exigem o ffmpeg.",
+        "Tolerance:" => "Tolerância:",
+        " bits" => " bits",
+        "Find Similar" => "Procurar semelhantes",
+        "{} of {} files" => "{} de {} arquivos",
+        "{} groups of similar files; keeping only the largest of each frees {}" => "{} grupos de arquivos semelhantes; manter só o maior de cada libera {}",
+        "Compared {} files" => "{} arquivos comparados",
+        "{} files could not be decoded" => "{} arquivos não puderam ser decodificados",
+        "Group {} - {} files, {} reclaimable" => "Grupo {} - {} arquivos, {} recuperáveis",
+        "Show in folder" => "Mostrar na pasta",
+        "largest" => "maior",
+        "Raw disk image" => "Imagem de disco bruta",
+        "Disk Image" => "Imagem de disco",
+        "Format: {} (read with {})" => "Formato: {} (lido com {})",
+        "Virtual disk size: {}" => "Tamanho do disco virtual: {}",
+        "{} entries, {} of files" => "{} entradas, {} em arquivos",
+        "This folder is empty." => "Esta pasta está vazia.",
+        "Browse image contents" => "Explorar conteúdo da imagem",
+        "Windows Cleanup" => "Limpeza do Windows",
+        "Nothing to clean up." => "Nada para limpar.",
+        "Needs administrator rights; run the command from an elevated prompt" => "Requer direitos de administrador; execute o comando em um prompt elevado",
+        "Temporary files" => "Arquivos temporários",
+        "Recycle Bin" => "Lixeira",
+        "Windows Update cache" => "Cache do Windows Update",
+        "Delivery Optimization files" => "Arquivos de Otimização de Entrega",
+        "Previous Windows installation" => "Instalação anterior do Windows",
+        "Hibernation file" => "Arquivo de hibernação",
+        "Move to Trash" => "Mover para a lixeira",
+        "Delete permanently" => "Excluir permanentemente",
+        "Ask before moving to the trash" => "Perguntar antes de mover para a lixeira",
+        "Move {} to the trash?" => "Mover {} para a lixeira?",
+        "It keeps using disk space until the trash is emptied." => "Continua ocupando espaço em disco até a lixeira ser esvaziada.",
+        "Trash:" => "Lixeira:",
+        "{} on {} ({} items)" => "{} em {} ({} itens)",
+        "The trash is empty." => "A lixeira está vazia.",
+        "Files in the trash still use disk space until it is emptied." => "Arquivos na lixeira ainda ocupam espaço até ela ser esvaziada.",
+        "Empty Trash" => "Esvaziar lixeira",
+        "Permanently delete {} items ({}) from the trash?" => "Excluir permanentemente {} itens ({}) da lixeira?",
+        "This browser can't open folders; try a Chromium-based browser such as Chrome or Edge" => "Este navegador não consegue abrir pastas; tente um navegador baseado no Chromium, como Chrome ou Edge",
+        "Choose a folder to see what takes up its space. Nothing leaves your browser." => "Escolha uma pasta para ver o que ocupa espaço nela. Nada sai do seu navegador.",
+        "Choose Folder..." => "Escolher Pasta...",
+        "Reading folder..." => "Lendo pasta...",
+        "Async I/O" => "E/S Assíncrona",
+        "Keeps many file system calls in flight at once; suited to SFTP and cloud mounts" => "Mantém muitas chamadas ao sistema de arquivos em andamento ao mesmo tempo; indicado para SFTP e montagens na nuvem",
+        "Continued from a checkpoint saved {}" => "Continuado de um ponto de controle salvo em {}",
+        "Scan throttling" => "Limitação da varredura",
+        "Max worker threads" => "Máximo de threads de trabalho",
+        "Max file operations per second" => "Máximo de operações de arquivo por segundo",
+        "0 means no limit" => "0 significa sem limite",
+        "Low-priority background scans" => "Varreduras em segundo plano com baixa prioridade",
+        "Scans use idle I/O and the lowest CPU priority so other programs stay responsive" => "As varreduras usam E/S ociosa e a menor prioridade de CPU para que outros programas continuem responsivos",
+        "Low-Memory Mode" => "Modo de Pouca Memória",
+        "Keeps only large files in the scan index and counts smaller ones per folder" => "Mantém apenas arquivos grandes no índice e conta os menores por pasta",
+        "Files below this size are only counted" => "Arquivos abaixo deste tamanho são apenas contados",
+        "{} small files - {}" => "{} arquivos pequenos - {}",
+        "Open Files" => "Arquivos Abertos",
+        "Show processes using this" => "Mostrar processos usando isto",
+        "No process has files open here." => "Nenhum processo tem arquivos abertos aqui.",
+        "{} processes have files open here" => "{} processos têm arquivos abertos aqui",
+        "{} processes could not be inspected; run as administrator to include them" => "{} processos não puderam ser inspecionados; execute como administrador para incluí-los",
+        "{} (PID {}) - {} files" => "{} (PID {}) - {} arquivos",
+        "The system doesn't report which files" => "O sistema não informa quais arquivos",
+        "writing" => "gravando",
+        "Deletion Problems" => "Problemas na exclusão",
+        "{} could not be deleted completely" => "{} não pôde ser excluído por completo",
+        "{} items are left. Skipped items stay where they are." => "Restam {} itens. Itens ignorados permanecem onde estão.",
+        "{} more deletions are waiting" => "Mais {} exclusões aguardando",
+        "Skip" => "Ignorar",
+        "Retry" => "Tentar novamente",
+        "Retry as Administrator" => "Tentar como administrador",
+        "Force" => "Forçar",
+        "Clears read-only flags and, on Windows, closes the programs using the files" => "Remove o atributo somente leitura e, no Windows, fecha os programas que usam os arquivos",
+        "Show Processes" => "Mostrar processos",
+        "Retry All" => "Tentar todos novamente",
+        "Skip All" => "Ignorar todos",
+        "Bookmarks" => "Favoritos",
+        "Bookmark" => "Favoritar",
+        "Remove bookmark" => "Remover favorito",
+        "Open a folder to bookmark places in it." => "Abra uma pasta para favoritar locais dentro dela.",
+        "★ Remove Bookmark Here" => "★ Remover favorito daqui",
+        "☆ Bookmark This Folder" => "☆ Favoritar esta pasta",
+        "No bookmarks yet. Star folders to come back to them." => "Nenhum favorito ainda. Marque pastas com estrela para voltar a elas.",
+        "This folder no longer exists" => "Esta pasta não existe mais",
+        "Add a note" => "Adicionar uma nota",
+        "Export HTML Report..." => "Exportar relatório HTML...",
+        "Available once the tree has been scanned" => "Disponível depois que a árvore for escaneada",
+        "Disk usage of {}" => "Uso de disco de {}",
+        "{} in {} items, generated {}" => "{} em {} itens, gerado em {}",
+        "{} items not included" => "{} itens não incluídos",
+        "Export Scan..." => "Exportar varredura...",
+        "Saves the whole scan to a file that can be opened on another machine" => "Salva a varredura inteira em um arquivo que pode ser aberto em outra máquina",
+        "Open Scan File..." => "Abrir arquivo de varredura...",
+        "Scan file from {}, saved {}" => "Arquivo de varredura de {}, salvo em {}",
+        "Export for ncdu..." => "Exportar para o ncdu...",
+        "({} smaller entries)" => "({} entradas menores)",
+        "Scan finished" => "Análise concluída",
+        "Test alert sent" => "Alerta de teste enviado",
+        "Webhook URL:" => "URL do webhook:",
+        "SMTP server:" => "Servidor SMTP:",
+        "SMTP user:" => "Usuário SMTP:",
+        "SMTP password:" => "Senha SMTP:",
+        "From:" => "De:",
+        "To:" => "Para:",
+        "Also report every scheduled check" => "Relatar também cada verificação agendada",
+        "Sends the size of each budgeted folder after every check, not only breaches" => "Envia o tamanho de cada pasta com orçamento após cada verificação, não só quando excede",
+        "Messages ({path}, {host}, {size}, {entries}, {budget}, {over} and {top} are filled in):" => "Mensagens ({path}, {host}, {size}, {entries}, {budget}, {over} e {top} são preenchidos):",
+        "Send Test Alert" => "Enviar Alerta de Teste",
+        "Webhook and Email Alerts" => "Alertas por Webhook e E-mail",
+        "{}: {} in {} items, scanned in {} s" => "{}: {} em {} itens, analisado em {} s",
+        "Notify in the background after scans longer than" => "Notificar em segundo plano após análises com mais de",
+        "Dashboard" => "Painel",
+        "Add root:" => "Adicionar raiz:",
+        "Combined capacity" => "Capacidade combinada",
+        "{} used of {} on {} volumes, {} free" => "{} usados de {} em {} volumes, {} livres",
+        "Root" => "Raiz",
+        "Volume" => "Volume",
+        "Last 7 days" => "Últimos 7 dias",
+        "Trend" => "Tendência",
+        "No history yet" => "Sem histórico ainda",
+        "Remove from the dashboard" => "Remover do painel",
+        "Largest recent growth" => "Maior crescimento recente",
+        "Cleanup suggestions" => "Sugestões de limpeza",
+        "Nothing to clean up right now." => "Nada para limpar agora.",
+        "{} cache: {}" => "Cache do {}: {}",
+        "{} can be reclaimed" => "{} podem ser recuperados",
+        "Columns" => "Colunas",
+        "Percent" => "Percentual",
+        "Items" => "Itens",
+        "Modified" => "Modificado",
+        "Accessed" => "Acessado",
+        "Owner" => "Dono",
+        "Use for All Folders" => "Usar em Todas as Pastas",
+        "Reset to Default" => "Restaurar Padrão",
+        "Drag to reorder" => "Arraste para reordenar",
+        "Expand in place" => "Expandir aqui",
+        "video files" => "arquivos de vídeo",
+        "audio files" => "arquivos de áudio",
+        "images" => "imagens",
+        "documents" => "documentos",
+        "archives" => "arquivos compactados",
+        "source code" => "código-fonte",
+        "programs and libraries" => "programas e bibliotecas",
+        "{} of this folder is {} ({})" => "{} desta pasta são {} ({})",
+        "The largest item, {}, takes {} on its own ({})" => "O maior item, {}, ocupa sozinho {} ({})",
+        "A {} folder takes {}" => "Uma pasta {} ocupa {}",
+        "{} folders account for {} across {} projects" => "Pastas {} somam {} em {} projetos",
+        "Files untouched for over a year total {} ({} files, {} of this folder)" => "Arquivos sem alteração há mais de um ano somam {} ({} arquivos, {} desta pasta)",
+        "{} folders couldn't be read, so the real total may be higher" => "{} pastas não puderam ser lidas, então o total real pode ser maior",
+        "No single kind of file or folder stands out here." => "Nenhum tipo de arquivo ou pasta se destaca aqui.",
+        "What uses the space here" => "O que ocupa o espaço aqui",
+        "System" => "Sistema",
+        "Apps" => "Aplicativos",
+        "Pictures" => "Imagens",
+        "Music" => "Música",
+        "Temporary" => "Temporários",
+        "{}: {} ({})" => "{}: {} ({})",
+        "Free: {}" => "Livre: {}",
+        "Free" => "Livre",
+        "Storage Categories" => "Categorias de Armazenamento",
+        "Sorts each drive the way the system storage settings do, by known locations first and file type otherwise. Analyzing reads the whole drive." => "Classifica cada unidade como as configurações de armazenamento do sistema: primeiro por locais conhecidos e depois pelo tipo de arquivo. A análise lê a unidade inteira.",
+        "{} used of {}" => "{} usados de {}",
+        "Analyze" => "Analisar",
+        "Detect file types from contents for files over" => "Detectar o tipo pelo conteúdo em arquivos acima de",
+        "Reads the first bytes of large files so misnamed and extensionless files land in the right category" => "Lê os primeiros bytes de arquivos grandes para que arquivos sem extensão ou com nome enganoso caiam na categoria certa",
+        "Duration" => "Duração",
+        "Resolution" => "Resolução",
+        "Video codec" => "Codec de vídeo",
+        "Audio codec" => "Codec de áudio",
+        "Bitrate" => "Taxa de bits",
+        "Size per minute" => "Tamanho por minuto",
+        "Low-Value Media" => "Mídia de Baixo Valor",
+        "Ranks videos by size per minute of playback, so bloated re-encodes stand out. Needs ffprobe." => "Classifica vídeos pelo tamanho por minuto de reprodução, destacando recodificações inchadas. Requer ffprobe.",
+        "Rank Videos" => "Classificar Vídeos",
+        "No videos found." => "Nenhum vídeo encontrado.",
+        "{} files could not be read" => "{} arquivos não puderam ser lidos",
+        "File" => "Arquivo",
+        "Photo Library" => "Biblioteca de Fotos",
+        "Finds RAW files shot together with a JPEG, burst sequences and screenshots." => "Encontra arquivos RAW gravados junto com um JPEG, sequências de disparos e capturas de tela.",
+        "RAW+JPEG pairs: {}" => "Pares RAW+JPEG: {}",
+        "Removing the JPEG copies frees {}; removing the RAW files frees {}" => "Remover as cópias JPEG libera {}; remover os arquivos RAW libera {}",
+        "Burst sequences: {}, {} reclaimable" => "Sequências de disparos: {}, {} recuperáveis",
+        "{} shots, keeping the largest frees {}" => "{} fotos; manter só a maior libera {}",
+        "Screenshots: {}, {}" => "Capturas de tela: {}, {}",
+        "Git Repositories" => "Repositórios Git",
+        "Splits each repository into history, untracked files and ignored build output. Needs git." => "Divide cada repositório em histórico, arquivos não rastreados e saída de build ignorada. Requer git.",
+        "Find Repositories" => "Encontrar Repositórios",
+        "No git repositories found." => "Nenhum repositório git encontrado.",
+        "{} repositories, {} of history" => "{} repositórios, {} de histórico",
+        "{} of history" => "{} de histórico",
+        "Open folder" => "Abrir pasta",
+        ".git folder" => "Pasta .git",
+        "Untracked" => "Não rastreados",
+        "Ignored build output" => "Saída de build ignorada",
+        "Largest objects in history" => "Maiores objetos no histórico",
+        "History is larger than it needs to be; repacking usually shrinks it" => "O histórico está maior do que precisa; reempacotar costuma reduzi-lo",
+        "Large files in history stay there after deletion; move them to Git LFS or rewrite history" => "Arquivos grandes continuam no histórico mesmo depois de apagados; mova-os para o Git LFS ou reescreva o histórico",
+        "Most of this folder is ignored build output; list what would be removed" => "A maior parte desta pasta é saída de build ignorada; liste o que seria removido",
+        "Build Artifacts" => "Artefatos de Build",
+        "Finds target, node_modules, build, .venv and dist folders of projects. They can be rebuilt, so cleaning those of dormant projects is safe." => "Encontra pastas target, node_modules, build, .venv e dist de projetos. Elas podem ser recriadas, então limpar as de projetos parados é seguro.",
+        "Find Build Artifacts" => "Encontrar Artefatos de Build",
+        "Projects untouched for" => "Projetos sem alterações há",
+        " months" => " meses",
+        "No build artifacts found." => "Nenhum artefato de build encontrado.",
+        "{} folders, {} in total; {} in projects untouched for {} months" => "{} pastas, {} no total; {} em projetos sem alterações há {} meses",
+        "Select Dormant" => "Selecionar Parados",
+        "Select None" => "Limpar Seleção",
+        "Clean {} Selected ({})" => "Limpar {} Selecionadas ({})",
+        "Select the folders to clean first" => "Selecione antes as pastas a limpar",
+        "Last build" => "Último build",
+        "Project last changed" => "Última alteração do projeto",
+        "Confirm Cleaning" => "Confirmar Limpeza",
+        "Permanently delete {} build folders? Projects rebuild them when needed." => "Excluir permanentemente {} pastas de build? Os projetos as recriam quando necessário.",
+        "{} days ago" => "há {} dias",
+        "Browser Data" => "Dados dos Navegadores",
+        "No browser profiles found." => "Nenhum perfil de navegador encontrado.",
+        "Cache" => "Cache",
+        "Clear Cache" => "Limpar Cache",
+        "Service workers" => "Service workers",
+        "Site storage" => "Armazenamento de sites",
+        "Everything else" => "Todo o resto",
+        "Largest sites" => "Maiores sites",
+        "Confirm Clearing" => "Confirmar Limpeza",
+        "Clear {} of cached data from {} ({})? Close the browser first; logins and site data are kept." => "Limpar {} de dados em cache do {} ({})? Feche o navegador antes; logins e dados dos sites são mantidos.",
+        "Mail Storage" => "Armazenamento de E-mail",
+        "Mailbox sizes of Thunderbird, Outlook and Mail accounts." => "Tamanho das caixas de correio das contas do Thunderbird, Outlook e Mail.",
+        "No mail stores found." => "Nenhum armazenamento de e-mail encontrado.",
+        "This store keeps all folders in one file." => "Este armazenamento guarda todas as pastas em um único arquivo.",
+        "Messages" => "Mensagens",
+        "Attachments" => "Anexos",
+        "Mostly attachments" => "Principalmente anexos",
+        "Game Libraries" => "Bibliotecas de Jogos",
+        "Games installed by Steam and the Epic Games launcher." => "Jogos instalados pela Steam e pelo launcher da Epic Games.",
+        "No installed games found." => "Nenhum jogo instalado encontrado.",
+        "{} games, {} in total" => "{} jogos, {} no total",
+        "last played {}" => "jogado pela última vez {}",
+        "Uninstall..." => "Desinstalar...",
+        "Uninstall it from the launcher's library." => "Desinstale-o pela biblioteca do launcher.",
+        "No other drive has room for this game." => "Nenhuma outra unidade tem espaço para este jogo.",
+        "In Steam, open Settings > Storage, select the game and choose Move to put it in a library on another drive." => "Na Steam, abra Configurações > Armazenamento, selecione o jogo e escolha Mover para colocá-lo em uma biblioteca em outra unidade.",
+        "Epic can't move games: move the folder to the other drive, then install the game there from the launcher and it verifies the files instead of downloading them." => "A Epic não move jogos: mova a pasta para a outra unidade e instale o jogo lá pelo launcher, que verifica os arquivos em vez de baixá-los.",
+        "{} - {} free" => "{} - {} livres",
+        "Flatpak, Snap and AppImage" => "Flatpak, Snap e AppImage",
+        "Unused Flatpak runtimes, old Snap revisions and forgotten AppImages." => "Runtimes Flatpak sem uso, revisões antigas do Snap e AppImages esquecidos.",
+        "Flatpak: {}, {} possibly unused" => "Flatpak: {}, {} possivelmente sem uso",
+        "Flatpak isn't installed." => "O Flatpak não está instalado.",
+        "Flatpak knows best which runtimes nothing needs any more:" => "O Flatpak sabe melhor quais runtimes não são mais necessários:",
+        "Runtime" => "Runtime",
+        "App" => "Aplicativo",
+        "No installed app uses this runtime" => "Nenhum aplicativo instalado usa este runtime",
+        "Snap: {} old revisions, {}" => "Snap: {} revisões antigas, {}",
+        "Snap isn't installed." => "O Snap não está instalado.",
+        "Snap keeps earlier revisions for rollback; keeping only two of each:" => "O Snap guarda revisões anteriores para reversão; para manter apenas duas de cada:",
+        "AppImage: {} without a launcher, {}" => "AppImage: {} sem atalho, {}",
+        "Scan a folder to look for AppImages in it." => "Analise uma pasta para procurar AppImages nela.",
+        "last used {}" => "usado pela última vez {}",
+        "Launcher for a deleted AppImage" => "Atalho de um AppImage excluído",
+        "Time Machine local snapshots" => "Snapshots locais do Time Machine",
+        "Volume Shadow Copies" => "Cópias de Sombra de Volume",
+        "Thin as much as possible" => "Reduzir o máximo possível",
+        "Delete all" => "Excluir todos",
+        "Delete the oldest" => "Excluir a mais antiga",
+        "Limit to 5% of the volume" => "Limitar a 5% do volume",
+        "Open Time Machine Settings" => "Abrir Ajustes do Time Machine",
+        "Open System Protection" => "Abrir Proteção do Sistema",
+        "No volume has snapshots." => "Nenhum volume tem snapshots.",
+        "They explain free space that is lower than the files add up to." => "Eles explicam um espaço livre menor do que a soma dos arquivos.",
+        "{}: {} snapshots using {}" => "{}: {} snapshots usando {}",
+        "{}: {} snapshots" => "{}: {} snapshots",
+        "Limited to {}" => "Limitado a {}",
+        "The system doesn't report how much space each snapshot holds." => "O sistema não informa quanto espaço cada snapshot ocupa.",
+        "Log Files" => "Arquivos de Log",
+        "Finds runaway logs and rotated logs that were never compressed." => "Encontra logs descontrolados e logs rotacionados que nunca foram comprimidos.",
+        "Logs over" => "Logs acima de",
+        "Find Logs" => "Encontrar Logs",
+        "No oversized or uncompressed logs found." => "Nenhum log grande demais ou sem compressão encontrado.",
+        "{} logs, {} in total; compressing them would save about {}" => "{} logs, {} no total; comprimi-los economizaria cerca de {}",
+        "Compress {} Logs Older Than" => "Comprimir {} Logs Mais Antigos Que",
+        "Saves about {}" => "Economiza cerca de {}",
+        "No logs are that old" => "Nenhum log é tão antigo",
+        " days" => " dias",
+        "Compressed" => "Comprimido",
+        "Still being written to" => "Ainda está sendo escrito",
+        "Rotated log that was never compressed" => "Log rotacionado que nunca foi comprimido",
+        "Copy Path" => "Copiar Caminho",
+        "Log folders over the limit" => "Pastas de log acima do limite",
+        "Confirm Compression" => "Confirmar Compressão",
+        "Replace {} logs with gzip-compressed copies? They can still be read with zcat or zless." => "Substituir {} logs por cópias comprimidas com gzip? Eles ainda podem ser lidos com zcat ou zless.",
+        "Crash Dumps" => "Despejos de Falha",
+        "Core dumps, minidumps and crash reports. They are only useful while a crash is being investigated." => "Core dumps, minidumps e relatórios de falha. Só são úteis enquanto uma falha está sendo investigada.",
+        "Find Crash Dumps" => "Encontrar Despejos de Falha",
+        "No crash dumps found." => "Nenhum despejo de falha encontrado.",
+        "{} crash files, {} in total" => "{} arquivos de falha, {} no total",
+        "Select All" => "Selecionar Tudo",
+        "Delete {} Selected ({})" => "Excluir {} Selecionados ({})",
+        "Select the files to delete first" => "Selecione primeiro os arquivos a excluir",
+        "Source" => "Origem",
+        "Found in scan" => "Encontrado na análise",
+        "Permanently delete {} crash files?" => "Excluir permanentemente {} arquivos de falha?",
+        "Application crash dumps" => "Despejos de falha de aplicativos",
+        "Windows minidumps" => "Minidumps do Windows",
+        "Windows memory dump" => "Despejo de memória do Windows",
+        "Windows Error Reporting" => "Relatório de Erros do Windows",
+        "Diagnostic reports" => "Relatórios de diagnóstico",
+        "Core dumps" => "Core dumps",
+        "systemd-coredump" => "systemd-coredump",
+        "Apport crash reports" => "Relatórios de falha do Apport",
+        "Apport core dumps" => "Core dumps do Apport",
+        "Cold Data" => "Dados Frios",
+        "Data nobody has read or changed in years is a candidate for an external or archive drive. Where the system doesn't keep access times, the modification time is used." => "Dados que ninguém lê ou altera há anos são candidatos a um disco externo ou de arquivo. Onde o sistema não registra o horário de acesso, usa-se o de modificação.",
+        "Untouched for a year: {} ({}%)" => "Sem uso há um ano: {} ({}%)",
+        "Untouched for 2 years: {} ({}%)" => "Sem uso há 2 anos: {} ({}%)",
+        "Untouched for 5 years: {} ({}%)" => "Sem uso há 5 anos: {} ({}%)",
+        "No cold folders below here." => "Nenhuma pasta fria aqui dentro.",
+        "{} - {} cold of {}" => "{} - {} frios de {}",
+        "Archive" => "Arquivar",
+        "Archive..." => "Arquivar...",
+        "Copies folders to an external drive or network share, checks every file against a SHA-256 hash and writes a manifest beside the copy." => "Copia pastas para um disco externo ou compartilhamento de rede, confere cada arquivo com um hash SHA-256 e grava um manifesto ao lado da cópia.",
+        "Choose folders to archive from the Cold Data report or an item's menu." => "Escolha pastas para arquivar no relatório de Dados Frios ou no menu de um item.",
+        "Destination:" => "Destino:",
+        "Browse..." => "Procurar...",
+        "Only {} free at the destination" => "Apenas {} livres no destino",
+        "{} free at the destination" => "{} livres no destino",
+        "The destination folder doesn't exist" => "A pasta de destino não existe",
+        "Delete originals after verifying" => "Excluir originais após verificar",
+        "Archive {} Folders" => "Arquivar {} Pastas",
+        "Confirm Archive" => "Confirmar Arquivamento",
+        "Archive {} folders to {} and delete the originals once each copy is verified?" => "Arquivar {} pastas em {} e excluir os originais depois que cada cópia for verificada?",
+        "Cloud Storage" => "Armazenamento em Nuvem",
+        "Enter a bucket name" => "Informe o nome do bucket",
+        "Enter the storage account and container as account/container" => "Informe a conta de armazenamento e o contêiner como conta/contêiner",
+        "This build has no cloud storage support. Rebuild with the cloud-s3, cloud-gcs or cloud-azure feature." => "Esta versão não tem suporte a armazenamento em nuvem. Compile com o recurso cloud-s3, cloud-gcs ou cloud-azure.",
+        "Lists a bucket with the provider's command-line tool (aws, gcloud or az) and opens it like a scan: every prefix is a folder." => "Lista um bucket com a ferramenta de linha de comando do provedor (aws, gcloud ou az) e o abre como uma varredura: cada prefixo é uma pasta.",
+        "List Bucket" => "Listar Bucket",
+        "By storage class: {}" => "Por classe de armazenamento: {}",
+        "Prefixes in {}" => "Prefixos em {}",
+        "Cloud Sync Folders" => "Pastas Sincronizadas na Nuvem",
+        "Right-click a folder and choose Free up space (Windows) or Remove Download (macOS). Turning on Files On-Demand in OneDrive settings makes new files online-only." => "Clique com o botão direito numa pasta e escolha Liberar espaço (Windows) ou Remover Download (macOS). Ativar Arquivos Sob Demanda nas configurações do OneDrive deixa os novos arquivos apenas online.",
+        "Right-click a folder and choose Make online-only. In Dropbox preferences, Sync > Online-only makes it the default for new files." => "Clique com o botão direito numa pasta e escolha Tornar somente online. Nas preferências do Dropbox, Sincronização > Somente online torna isso o padrão para novos arquivos.",
+        "In Drive for desktop preferences, switch My Drive to Stream files, or right-click a folder and turn off Available offline." => "Nas preferências do Drive para computador, mude Meu Drive para Fazer streaming de arquivos, ou clique com o botão direito numa pasta e desative Disponível off-line.",
+        "Right-click a folder in Finder and choose Remove Download, or turn on Optimize Mac Storage in iCloud Drive settings." => "Clique com o botão direito numa pasta no Finder e escolha Remover Download, ou ative Otimizar Armazenamento do Mac nas configurações do iCloud Drive.",
+        "Files a cloud drive keeps downloaded take local space; online-only files don't until they are opened." => "Arquivos que um drive na nuvem mantém baixados ocupam espaço local; arquivos apenas online não ocupam até serem abertos.",
+        "No OneDrive, Dropbox, Google Drive or iCloud Drive folders found." => "Nenhuma pasta do OneDrive, Dropbox, Google Drive ou iCloud Drive encontrada.",
+        "Up to {} could be freed by making files online-only" => "Até {} podem ser liberados deixando arquivos apenas online",
+        "{} - {}: {} local of {}" => "{} - {}: {} locais de {}",
+        "Online-only: {}. Kept on this device: {}." => "Apenas online: {}. Mantido neste dispositivo: {}.",
+        "Local" => "Local",
+        "Make online-only" => "Tornar apenas online",
+        "Plugins" => "Plugins",
+        "From the {} plugin" => "Do plugin {}",
+        "Open a folder first" => "Abra uma pasta primeiro",
+        "Plugins are libraries that add reports and context menu actions. Only enable plugins you trust: they run with the same rights as this program." => "Plugins são bibliotecas que adicionam relatórios e ações ao menu de contexto. Ative apenas plugins confiáveis: eles rodam com as mesmas permissões deste programa.",
+        "Plugin folder: {}" => "Pasta de plugins: {}",
+        "Running plugin action..." => "Executando ação do plugin...",
+        "No plugins found. Copy .{} files into the plugin folder." => "Nenhum plugin encontrado. Copie arquivos .{} para a pasta de plugins.",
+        "Nothing found." => "Nada encontrado.",
+        "Console" => "Console",
+        "Run Script" => "Executar Script",
+        "Stop" => "Parar",
+        "Stopped" => "Interrompido",
+        "Clear Output" => "Limpar Saída",
+        "Scripts use Rhai. entries(), files() and dirs() list the scan; each entry has path, name, ext, parent, size, is_dir, modified_days and accessed_days. delete(entry), compress(entry) and move_to(entry, folder) ask for operations, which are listed for confirmation before they're queued." => "Scripts usam Rhai. entries(), files() e dirs() listam a varredura; cada entrada tem path, name, ext, parent, size, is_dir, modified_days e accessed_days. delete(entry), compress(entry) e move_to(entry, pasta) pedem operações, que são listadas para confirmação antes de entrar na fila.",
+        "Asked for {} operations; confirm them to queue them" => "{} operações pedidas; confirme-as para enfileirá-las",
+        "Query:" => "Consulta:",
+        "Export CSV..." => "Exportar CSV...",
+        "Fields: size, name, ext, path, type, entries, modified, accessed. Operators: = != > >= < <= and ~ (contains), joined with and, or, not. Dates are YYYY-MM-DD or a number of days ago like 90d." => "Campos: size, name, ext, path, type, entries, modified, accessed. Operadores: = != > >= < <= e ~ (contém), combinados com and, or, not. Datas são AAAA-MM-DD ou um número de dias atrás como 90d.",
+        "Save as Report..." => "Salvar como Relatório...",
+        "Saved Reports" => "Relatórios Salvos",
+        "Saved Reports ({} changed)" => "Relatórios Salvos ({} alterados)",
+        "Manually" => "Manualmente",
+        "Every hour" => "A cada hora",
+        "Every day" => "Todo dia",
+        "Every week" => "Toda semana",
+        "Name:" => "Nome:",
+        "Old large files" => "Arquivos grandes antigos",
+        "Regenerate:" => "Regenerar:",
+        "Save Report" => "Salvar Relatório",
+        "A report with this name already exists" => "Já existe um relatório com este nome",
+        "New Report" => "Novo Relatório",
+        "No saved reports." => "Nenhum relatório salvo.",
+        "Never" => "Nunca",
+        "{}. Last run: {}" => "{}. Última execução: {}",
+        "Run Now" => "Executar Agora",
+        "Changed since the previous run: {} new, {} gone" => "Alterado desde a execução anterior: {} novos, {} removidos",
+        "Mark as Seen" => "Marcar como Visto",
+        "{} results, {}" => "{} resultados, {}",
+        "Showing the first {}" => "Mostrando os primeiros {}",
+        "Security Report" => "Relatório de Segurança",
+        "World-writable directories" => "Diretórios graváveis por todos",
+        "Setuid files" => "Arquivos setuid",
+        "Setgid files" => "Arquivos setgid",
+        "Files without an owner" => "Arquivos sem dono",
+        "Any account can add files here. Sizes include everything inside; the sticky bit at least stops users from deleting each other's files." => "Qualquer conta pode adicionar arquivos aqui. Os tamanhos incluem todo o conteúdo; o sticky bit ao menos impede que usuários apaguem arquivos uns dos outros.",
+        "These run with the rights of their owner, whoever starts them." => "Estes executam com os direitos do dono, não importa quem os inicie.",
+        "These run with the rights of their group, whoever starts them." => "Estes executam com os direitos do grupo, não importa quem os inicie.",
+        "Owned by user IDs with no local account, usually left behind by deleted users. A new account given that ID would own them." => "Pertencem a IDs de usuário sem conta local, geralmente deixados por usuários removidos. Uma nova conta com esse ID passaria a ser dona deles.",
+        "Permission bits and user IDs are only available on Unix-like systems." => "Bits de permissão e IDs de usuário só estão disponíveis em sistemas do tipo Unix.",
+        "Finding" => "Achado",
+        "Count" => "Quantidade",
+        "(sticky)" => "(sticky)",
+        "Before compression" => "Antes da compressão",
+        "Compression" => "Compressão",
+        "Before compression: {} ({} smaller on disk)" => "Antes da compressão: {} ({} menor no disco)",
+        "Deleting compressed files frees their size on disk, not their size before compression." => "Apagar arquivos comprimidos libera o tamanho no disco, não o tamanho antes da compressão.",
+        "Select" => "Selecionar",
+        "{} selected" => "{} selecionados",
+        "{} files" => "{} arquivos",
+        "Logical size: {}" => "Tamanho lógico: {}",
+        "On disk: {}" => "No disco: {}",
+        "Estimated reclaim: {}" => "Recuperação estimada: {}",
+        "What deleting the selection would free: its size on disk, less hard-linked files that keep other names and data shared with clones." => "O que apagar a seleção liberaria: o tamanho no disco, menos arquivos com hard links que mantêm outros nomes e dados compartilhados com clones.",
+        "Queue Deletion" => "Enfileirar Exclusão",
+        "Clear Selection" => "Limpar Seleção",
+        "{} stays in use by hard links outside the selection" => "{} continua em uso por hard links fora da seleção",
+        "{} is shared with cloned or deduplicated copies" => "{} é compartilhado com cópias clonadas ou deduplicadas",
+        "{} snapshots on this volume may keep the space in use until they are removed" => "{} snapshots neste volume podem manter o espaço em uso até serem removidos",
+        "Dismiss" => "Dispensar",
+        "Move {} into {}?" => "Mover {} para {}?",
+        "Overwrite" => "Sobrescrever",
+        "Rename" => "Renomear",
+        "Name Conflict" => "Conflito de nomes",
+        "{} already exists in {}." => "{} já existe em {}.",
+        "Overwriting replaces the existing item, with everything in it if it's a folder." => "Sobrescrever substitui o item existente, com tudo o que houver dentro se for uma pasta.",
+        "Apply to all {} conflicts" => "Aplicar a todos os {} conflitos",
+        "Copy with verification..." => "Copiar com verificação...",
+        "Importing..." => "Importando...",
+        "{} running, {} queued" => "{} em execução, {} na fila",
+        "Free space unchanged" => "Espaço livre inalterado",
+        "Free space +{} this session" => "Espaço livre +{} nesta sessão",
+        "Free space -{} this session" => "Espaço livre -{} nesta sessão",
+        "Change in free space on this volume, including changes made by other programs" => "Variação do espaço livre neste volume, incluindo mudanças feitas por outros programas",
+        "Show all errors" => "Mostrar todos os erros",
+        "Icicle" => "Pingente",
+        "⬆ Zoom out" => "⬆ Afastar",
+        "Whole tree" => "Árvore inteira",
+        "Show in file list" => "Mostrar na lista de arquivos",
+        "Click a folder to zoom in, and the top band or right-click to zoom out" => "Clique em uma pasta para aproximar, e na faixa superior ou com o botão direito para afastar",
+        "Nothing to show in an empty folder" => "Nada para mostrar em uma pasta vazia",
+        "{}, {} of {}" => "{}, {} de {}",
+        "Treemap" => "Mapa em árvore",
+        "{} smaller entries" => "{} itens menores",
+        "in {}" => "em {}",
+        "and {} more" => "e mais {}",
+        "Confirm Script Operations" => "Confirmar operações do script",
+        "The script asks for {} operations on {}:" => "O script pede {} operações sobre {}:",
+        "{} items will be permanently deleted." => "{} itens serão excluídos permanentemente.",
+        "Queue All" => "Enfileirar todas",
+        "Are you sure you want to delete {} items ({})?" => "Tem certeza de que deseja excluir {} itens ({})?",
+        "This permanently deletes {}. Type the number of items to confirm:" => "Isto exclui permanentemente {}. Digite o número de itens para confirmar:",
+        "Run Command" => "Executar comando",
+        "Run this command?" => "Executar este comando?",
+        "It removes about {}, which can't be undone." => "Ele remove cerca de {}, o que não pode ser desfeito.",
+        "Removed snapshots are gone for good; nothing can be restored from them afterwards." => "Snapshots removidos somem de vez; nada poderá ser restaurado a partir deles depois.",
+        "Error reading {}: {}" => "Erro ao ler {}: {}",
+        "Removed {} items from {} ({} in use were skipped)" => "{} itens removidos de {} ({} em uso foram ignorados)",
+        "Empty command" => "Comando vazio",
+        "Error running {}: {}" => "Erro ao executar {}: {}",
+        "{} failed with {}" => "{} falhou com {}",
+        "No folder to clean" => "Nenhuma pasta para limpar",
+        "Emptied the Recycle Bin" => "Lixeira esvaziada",
+        "Clean Up" => "Limpar",
+        "Clean up {}?" => "Limpar {}?",
+        "This permanently removes about {}." => "Isto remove permanentemente cerca de {}.",
+        "Purgeable: {}" => "Limpável: {}",
+        "Run Plugin Action" => "Executar ação de plugin",
+        "Run {} from the {} plugin on:" => "Executar {} do plugin {} em:",
+        "This action may change or delete files." => "Esta ação pode alterar ou apagar arquivos.",
+        "Restore" => "Restaurar",
+        "Put back ({})" => "Devolver ({})",
+        "Compress in place" => "Comprimir no lugar",
+        "Turns on btrfs compression for it and recompresses what it holds" => "Ativa a compressão do btrfs para o item e recomprime o que ele contém",
+        "Click to sort, drag to reorder" => "Clique para ordenar, arraste para reordenar",
+        "Video" => "Vídeo",
+        "Audio" => "Áudio",
+        "Images" => "Imagens",
+        "Documents" => "Documentos",
+        "Archives" => "Arquivos compactados",
+        "Code" => "Código",
+        "Executables" => "Executáveis",
+        "Other" => "Outros",
+        _ => return None,
+    })
+}
@@ -0,0 +1,238 @@
+use eframe::egui::{self, ecolor::Hsva, Color32, FontId, Rect, Sense, Stroke};
+use std::path::PathBuf;
+
+use crate::{
+    category, icons,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    paths, summary,
+};
+
+const ROW_HEIGHT: f32 = 22.0;
+/// Narrower slices are left out, along with their smaller siblings.
+const MIN_WIDTH: f32 = 1.0;
+/// Slices narrower than this get no name.
+const MIN_LABEL_WIDTH: f32 = 36.0;
+const MINIMAP_HEIGHT: f32 = 40.0;
+const MINIMAP_DEPTH: usize = 5;
+
+/// One rectangle of the chart and the node it stands for.
+struct Tile {
+    id: usize,
+    rect: Rect,
+    depth: usize,
+}
+
+/// Sizes as horizontal bands, one per level, with each entry as wide as its share of the
+/// folder the view is zoomed into. A strip underneath shows where that folder sits in the
+/// whole tree.
+#[derive(Default)]
+pub struct Icicle {
+    /// Kept as a path so the zoom survives rescans; the root when unset or gone.
+    focus: Option<PathBuf>,
+}
+
+impl Icicle {
+    /// Returns a folder to show in the file list.
+    pub fn show(&mut self, ui: &mut egui::Ui, index: &ScanIndex) -> Option<PathBuf> {
+        let focus = self.focus.as_deref().and_then(|path| index.find_dir(path)).unwrap_or(0);
+        let mut open = None;
+        ui.horizontal(|ui| {
+            let parent = index.nodes[focus].parent;
+            if ui.add_enabled(parent.is_some(), egui::Button::new(tr("⬆ Zoom out"))).clicked() {
+                self.zoom(index, parent);
+            }
+            if ui.add_enabled(focus != 0, egui::Button::new(tr("Whole tree"))).clicked() {
+                self.focus = None;
+            }
+            if ui.button(tr("Show in file list")).clicked() {
+                open = Some(index.nodes[focus].path.clone());
+            }
+            ui.label(format!(
+                "{} ({})",
+                index.nodes[focus].path.display(),
+                i18n::format_bytes(index.nodes[focus].size)
+            ));
+        });
+        ui.label(
+            egui::RichText::new(tr("Click a folder to zoom in, and the top band or right-click to zoom out"))
+                .small()
+                .color(Color32::GRAY),
+        );
+        if index.nodes[focus].size == 0 {
+            ui.label(egui::RichText::new(tr("Nothing to show in an empty folder")).color(Color32::GRAY));
+            return open;
+        }
+
+        let available = ui.available_rect_before_wrap();
+        let (mut chart, mut minimap) = (available, available);
+        chart.max.y -= MINIMAP_HEIGHT + 4.0;
+        minimap.min.y = minimap.max.y - MINIMAP_HEIGHT;
+        ui.allocate_rect(available, Sense::hover());
+
+        let response = ui.interact(chart, ui.id().with("icicle_chart"), Sense::click());
+        let tiles = layout(index, focus, chart, ROW_HEIGHT);
+        let painter = ui.painter_at(chart);
+        let hovered = response.hover_pos().and_then(|pos| tiles.iter().find(|tile| tile.rect.contains(pos)));
+        for tile in &tiles {
+            let node = &index.nodes[tile.id];
+            painter.rect(tile.rect.shrink(0.5), 1.0, fill(index, tile.id, tile.depth), Stroke::NONE);
+            if tile.rect.width() >= MIN_LABEL_WIDTH {
+                painter.with_clip_rect(tile.rect.shrink(2.0)).text(
+                    tile.rect.left_center() + egui::vec2(4.0, 0.0),
+                    egui::Align2::LEFT_CENTER,
+                    paths::display_name(&node.name),
+                    FontId::proportional(12.0),
+                    Color32::BLACK,
+                );
+            }
+        }
+        if let Some(tile) = hovered {
+            painter.rect_stroke(tile.rect.shrink(0.5), 1.0, Stroke::new(1.5, Color32::WHITE));
+        }
+
+        if response.secondary_clicked() {
+            self.zoom(index, index.nodes[focus].parent);
+        } else if response.clicked() {
+            if let Some(tile) = hovered {
+                if tile.id == focus {
+                    self.zoom(index, index.nodes[focus].parent);
+                } else if index.nodes[tile.id].is_dir {
+                    self.zoom(index, Some(tile.id));
+                }
+            }
+        }
+        if let Some(tile) = hovered {
+            let (id, total) = (tile.id, index.nodes[focus].size);
+            response.on_hover_ui_at_pointer(|ui| {
+                let node = &index.nodes[id];
+                ui.label(egui::RichText::new(paths::display_name(&node.name)).strong());
+                ui.label(trf(
+                    "{}, {} of {}",
+                    &[
+                        &i18n::format_bytes(node.size),
+                        &percent(node.size, total),
+                        &paths::display_name(&index.nodes[focus].name),
+                    ],
+                ));
+                if node.is_dir {
+                    ui.separator();
+                    summary::show_breakdown(ui, index, id);
+                }
+            });
+        }
+
+        self.show_minimap(ui, index, focus, minimap);
+        open
+    }
+
+    /// The top levels of the whole tree, with the zoomed folder's span outlined. Clicking
+    /// zooms to the folder under the pointer.
+    fn show_minimap(&mut self, ui: &mut egui::Ui, index: &ScanIndex, focus: usize, rect: Rect) {
+        let response = ui.interact(rect, ui.id().with("icicle_minimap"), Sense::click());
+        let tiles = layout(index, 0, rect, MINIMAP_HEIGHT / MINIMAP_DEPTH as f32);
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        for tile in &tiles {
+            painter.rect_filled(tile.rect.shrink(0.25), 0.0, fill(index, tile.id, tile.depth).linear_multiply(0.6));
+        }
+        let (start, width) = span(index, focus);
+        let outline = Rect::from_x_y_ranges(
+            rect.left() + start * rect.width()..=rect.left() + (start + width) * rect.width(),
+            rect.y_range(),
+        );
+        painter.rect(
+            outline.expand2(egui::vec2(1.0, 0.0)),
+            1.0,
+            Color32::WHITE.gamma_multiply(0.15),
+            Stroke::new(1.5, Color32::WHITE),
+        );
+        if response.clicked() {
+            let picked = response
+                .interact_pointer_pos()
+                .and_then(|pos| tiles.iter().find(|tile| tile.rect.contains(pos)))
+                .map(|tile| match index.nodes[tile.id].is_dir {
+                    true => tile.id,
+                    false => index.nodes[tile.id].parent.unwrap_or(0),
+                });
+            if picked.is_some() {
+                self.zoom(index, picked);
+            }
+        }
+    }
+
+    fn zoom(&mut self, index: &ScanIndex, id: Option<usize>) {
+        self.focus = id.filter(|&id| id != 0).map(|id| index.nodes[id].path.clone());
+    }
+}
+
+/// Lays out `root` across the top of `area` and its descendants in the rows below, as deep
+/// as the area allows.
+fn layout(index: &ScanIndex, root: usize, area: Rect, row_height: f32) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    let mut pending = vec![(root, area.left(), area.right(), 0)];
+    while let Some((id, left, right, depth)) = pending.pop() {
+        let top = area.top() + depth as f32 * row_height;
+        if top + row_height > area.bottom() {
+            continue;
+        }
+        let rect = Rect::from_min_max(egui::pos2(left, top), egui::pos2(right, top + row_height));
+        tiles.push(Tile { id, rect, depth });
+        let size = index.nodes[id].size;
+        if size == 0 {
+            continue;
+        }
+        let scale = (right - left) / size as f32;
+        let mut x = left;
+        // Children are sorted largest first, so the first one too narrow ends the row.
+        for &child in &index.nodes[id].children {
+            let width = index.nodes[child].size as f32 * scale;
+            if width < MIN_WIDTH {
+                break;
+            }
+            pending.push((child, x, x + width, depth + 1));
+            x += width;
+        }
+    }
+    tiles
+}
+
+/// Where `id` starts and how wide it is, as fractions of the whole tree's width.
+fn span(index: &ScanIndex, id: usize) -> (f32, f32) {
+    let (mut start, mut width) = (0.0, 1.0);
+    let mut child = id;
+    while let Some(parent) = index.nodes[child].parent {
+        let total = index.nodes[parent].size.max(1) as f64;
+        let before: u64 = index.nodes[parent]
+            .children
+            .iter()
+            .take_while(|&&sibling| sibling != child)
+            .map(|&sibling| index.nodes[sibling].size)
+            .sum();
+        start = (before as f64 + start * index.nodes[child].size as f64) / total;
+        width *= index.nodes[child].size as f64 / total;
+        child = parent;
+    }
+    (start as f32, width as f32)
+}
+
+/// Folders in warm flame colours that vary by name so neighbours stand apart, files in the
+/// colour of their category.
+fn fill(index: &ScanIndex, id: usize, depth: usize) -> Color32 {
+    let node = &index.nodes[id];
+    if node.unreachable || node.online_only {
+        return Color32::from_gray(120);
+    }
+    if !node.is_dir {
+        return icons::color(category::classify(&node.path));
+    }
+    let hash =
+        node.name.as_encoded_bytes().iter().fold(0u32, |hash, &byte| hash.wrapping_mul(31).wrapping_add(byte as u32));
+    let hue = 0.02 + (hash % 100) as f32 / 100.0 * 0.12;
+    let value = 0.95 - (depth % 4) as f32 * 0.05;
+    Hsva::new(hue, 0.6, value, 1.0).into()
+}
+
+fn percent(part: u64, total: u64) -> String {
+    format!("{:.1}%", part as f64 * 100.0 / total.max(1) as f64)
+}
@@ -0,0 +1,301 @@
+use eframe::egui::{self, Color32, Pos2, Rect, Stroke};
+use std::{collections::HashMap, path::Path};
+
+use crate::category::{self, Category};
+
+/// Icons are square, this many points on a side.
+pub const SIZE: f32 = 16.0;
+
+/// Per-type icons for the file list: the system's own where it can be read (Windows shell,
+/// freedesktop icon themes), otherwise a painted set mapped by extension.
+#[derive(Default)]
+pub struct Icons {
+    /// System icons by lowercase extension, with "/" for folders; None when the system has
+    /// none for it.
+    textures: HashMap<String, Option<egui::TextureHandle>>,
+}
+
+impl Icons {
+    pub fn show(&mut self, ui: &mut egui::Ui, path: &Path, is_dir: bool) -> egui::Response {
+        let key = if is_dir { "/".to_string() } else { category::extension(path) };
+        let texture = self
+            .textures
+            .entry(key)
+            .or_insert_with_key(|key| {
+                let image = platform::load(key, is_dir)?;
+                Some(ui.ctx().load_texture(format!("icon {}", key), image, egui::TextureOptions::LINEAR))
+            })
+            .clone();
+        match texture {
+            Some(texture) => ui.add(egui::Image::from_texture((texture.id(), egui::vec2(SIZE, SIZE)))),
+            None => paint(ui, path, is_dir),
+        }
+    }
+}
+
+pub fn color(category: Category) -> Color32 {
+    match category {
+        Category::Video => Color32::from_rgb(220, 90, 90),
+        Category::Audio => Color32::from_rgb(180, 110, 220),
+        Category::Image => Color32::from_rgb(80, 190, 120),
+        Category::Document => Color32::from_rgb(90, 140, 230),
+        Category::Archive => Color32::from_rgb(240, 140, 60),
+        Category::Code => Color32::from_rgb(90, 200, 210),
+        Category::Executable => Color32::from_rgb(240, 190, 70),
+        Category::Other => Color32::GRAY,
+    }
+}
+
+/// A folder, or a page with a folded corner and a band in the colour of the file's category.
+fn paint(ui: &mut egui::Ui, path: &Path, is_dir: bool) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(SIZE, SIZE), egui::Sense::hover());
+    if !ui.is_rect_visible(rect) {
+        return response;
+    }
+    let painter = ui.painter();
+    let rect = rect.shrink(1.5);
+    if is_dir {
+        let folder = Color32::from_rgb(230, 180, 80);
+        let tab = Rect::from_min_size(rect.min, egui::vec2(rect.width() * 0.45, rect.height() * 0.3));
+        painter.rect_filled(tab, 1.0, folder);
+        let body = Rect::from_min_max(Pos2::new(rect.min.x, rect.min.y + rect.height() * 0.2), rect.max);
+        painter.rect_filled(body, 1.5, folder);
+        return response;
+    }
+
+    let page = rect.shrink2(egui::vec2(rect.width() * 0.12, 0.0));
+    let fold = page.width() * 0.35;
+    let outline = vec![
+        page.left_top(),
+        Pos2::new(page.right() - fold, page.top()),
+        Pos2::new(page.right(), page.top() + fold),
+        page.right_bottom(),
+        page.left_bottom(),
+    ];
+    painter.add(egui::Shape::convex_polygon(outline, Color32::from_gray(225), Stroke::NONE));
+    painter.add(egui::Shape::convex_polygon(
+        vec![
+            Pos2::new(page.right() - fold, page.top()),
+            Pos2::new(page.right() - fold, page.top() + fold),
+            Pos2::new(page.right(), page.top() + fold),
+        ],
+        Color32::from_gray(160),
+        Stroke::NONE,
+    ));
+    let band = Rect::from_min_max(Pos2::new(page.left(), page.bottom() - page.height() * 0.4), page.right_bottom());
+    painter.rect_filled(band, 0.0, color(category::classify(path)));
+    response
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use eframe::egui::ColorImage;
+    use std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::OnceLock,
+    };
+
+    /// Icon sizes to try, smallest first; the texture is scaled to `super::SIZE` either way.
+    const SIZES: [&str; 5] = ["16", "22", "24", "32", "48"];
+
+    /// What the shared MIME database and the current icon theme say, read once.
+    struct Theme {
+        /// MIME type by lowercase extension, from `mime/globs2`.
+        types: HashMap<String, String>,
+        /// Generic icon names by MIME type, from `mime/generic-icons`.
+        generic: HashMap<String, String>,
+        /// Theme folders to look in, the current theme first and `hicolor` last.
+        folders: Vec<PathBuf>,
+    }
+
+    static THEME: OnceLock<Theme> = OnceLock::new();
+
+    /// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, with the defaults the spec gives.
+    fn data_dirs() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+        let system = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        dirs.extend(system.split(':').map(PathBuf::from));
+        dirs
+    }
+
+    /// The theme GTK is set to use, when it says.
+    fn gtk_theme() -> Option<String> {
+        let settings = fs::read_to_string(dirs::config_dir()?.join("gtk-3.0/settings.ini")).ok()?;
+        settings.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "gtk-icon-theme-name").then(|| value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    fn read_theme() -> Theme {
+        let data_dirs = data_dirs();
+        let mut types = HashMap::new();
+        let mut generic = HashMap::new();
+        for dir in &data_dirs {
+            // Lines are `weight:type:glob`, heaviest first; only plain `*.ext` globs map to an
+            // extension, and the first type given for it wins.
+            for line in fs::read_to_string(dir.join("mime/globs2")).unwrap_or_default().lines() {
+                let mut fields = line.splitn(4, ':').skip(1);
+                let (Some(mime), Some(glob)) = (fields.next(), fields.next()) else {
+                    continue;
+                };
+                let extension = glob.strip_prefix("*.").filter(|extension| !extension.contains(['*', '?', '[']));
+                if let Some(extension) = extension {
+                    types.entry(extension.to_lowercase()).or_insert_with(|| mime.to_string());
+                }
+            }
+            for line in fs::read_to_string(dir.join("mime/generic-icons")).unwrap_or_default().lines() {
+                if let Some((mime, icon)) = line.split_once(':') {
+                    generic.entry(mime.to_string()).or_insert_with(|| icon.to_string());
+                }
+            }
+        }
+
+        let mut themes: Vec<String> = gtk_theme().into_iter().collect();
+        for fallback in ["Adwaita", "hicolor"] {
+            if !themes.iter().any(|theme| theme == fallback) {
+                themes.push(fallback.to_string());
+            }
+        }
+        let mut icon_dirs: Vec<PathBuf> = dirs::home_dir().map(|home| home.join(".icons")).into_iter().collect();
+        icon_dirs.extend(data_dirs.iter().map(|dir| dir.join("icons")));
+        let folders = themes
+            .iter()
+            .flat_map(|theme| icon_dirs.iter().map(move |dir| dir.join(theme)))
+            .filter(|folder| folder.is_dir())
+            .collect();
+        Theme { types, generic, folders }
+    }
+
+    /// Finds a PNG for the icon in any of the theme folders. Themes lay their folders out as
+    /// either `16x16/mimetypes` or `mimetypes/16`.
+    fn find(theme: &Theme, context: &str, name: &str) -> Option<PathBuf> {
+        let file = format!("{}.png", name);
+        theme.folders.iter().find_map(|folder| {
+            SIZES.iter().find_map(|size| {
+                [folder.join(format!("{0}x{0}", size)).join(context), folder.join(context).join(size)]
+                    .into_iter()
+                    .map(|dir| dir.join(&file))
+                    .find(|path| path.is_file())
+            })
+        })
+    }
+
+    fn decode(path: &Path) -> Option<ColorImage> {
+        let image = image::load_from_memory(&fs::read(path).ok()?).ok()?.to_rgba8();
+        let (width, height) = image.dimensions();
+        Some(ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &image.into_raw()))
+    }
+
+    pub fn load(extension: &str, is_dir: bool) -> Option<ColorImage> {
+        let theme = THEME.get_or_init(read_theme);
+        if is_dir {
+            return decode(&find(theme, "places", "folder")?);
+        }
+        let mime = theme.types.get(extension)?;
+        let media = mime.split('/').next().unwrap_or_default();
+        let names = [
+            Some(mime.replace('/', "-")),
+            theme.generic.get(mime).cloned(),
+            Some(format!("{}-x-generic", media)),
+        ];
+        names.into_iter().flatten().find_map(|name| find(theme, "mimetypes", &name)).and_then(|path| decode(&path))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use eframe::egui::ColorImage;
+    use std::{ffi::OsStr, mem, os::windows::ffi::OsStrExt, ptr};
+    use winapi::{
+        shared::windef::HICON,
+        um::{
+            shellapi::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_SMALLICON, SHGFI_USEFILEATTRIBUTES},
+            wingdi::{DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS},
+            winnt::{FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL},
+            winuser::{DestroyIcon, GetDC, GetIconInfo, ReleaseDC, ICONINFO},
+        },
+    };
+
+    pub fn load(extension: &str, is_dir: bool) -> Option<ColorImage> {
+        // With SHGFI_USEFILEATTRIBUTES the file doesn't have to exist; only its type is looked up.
+        let name = if is_dir { "folder".to_string() } else { format!("file.{}", extension) };
+        let wide: Vec<u16> = OsStr::new(&name).encode_wide().chain(Some(0)).collect();
+        let attributes = if is_dir { FILE_ATTRIBUTE_DIRECTORY } else { FILE_ATTRIBUTE_NORMAL };
+        let mut info: SHFILEINFOW = unsafe { mem::zeroed() };
+        let flags = SHGFI_ICON | SHGFI_SMALLICON | SHGFI_USEFILEATTRIBUTES;
+        let found = unsafe {
+            SHGetFileInfoW(wide.as_ptr(), attributes, &mut info, mem::size_of::<SHFILEINFOW>() as u32, flags)
+        };
+        if found == 0 || info.hIcon.is_null() {
+            return None;
+        }
+        let image = unsafe { pixels(info.hIcon) };
+        unsafe { DestroyIcon(info.hIcon) };
+        image
+    }
+
+    /// Reads the colour bitmap of the icon as RGBA.
+    unsafe fn pixels(icon: HICON) -> Option<ColorImage> {
+        let mut icon_info: ICONINFO = mem::zeroed();
+        if GetIconInfo(icon, &mut icon_info) == 0 {
+            return None;
+        }
+        let mut image = None;
+        let mut bitmap: BITMAP = mem::zeroed();
+        let size = mem::size_of::<BITMAP>() as i32;
+        if !icon_info.hbmColor.is_null() && GetObjectW(icon_info.hbmColor as _, size, &mut bitmap as *mut _ as _) != 0 {
+            let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+            let mut header: BITMAPINFO = mem::zeroed();
+            header.bmiHeader.biSize = mem::size_of::<BITMAPINFOHEADER>() as u32;
+            header.bmiHeader.biWidth = width;
+            // Negative heights ask for rows top to bottom.
+            header.bmiHeader.biHeight = -height;
+            header.bmiHeader.biPlanes = 1;
+            header.bmiHeader.biBitCount = 32;
+            header.bmiHeader.biCompression = BI_RGB;
+            let mut bgra = vec![0u8; width as usize * height as usize * 4];
+            let dc = GetDC(ptr::null_mut());
+            let lines = GetDIBits(
+                dc,
+                icon_info.hbmColor,
+                0,
+                height as u32,
+                bgra.as_mut_ptr() as _,
+                &mut header,
+                DIB_RGB_COLORS,
+            );
+            ReleaseDC(ptr::null_mut(), dc);
+            if lines == height {
+                // Icons without an alpha channel leave it at zero and rely on their mask.
+                let opaque = bgra.chunks_exact(4).all(|pixel| pixel[3] == 0);
+                for pixel in bgra.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                    if opaque {
+                        pixel[3] = 255;
+                    }
+                }
+                image = Some(ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &bgra));
+            }
+        }
+        DeleteObject(icon_info.hbmColor as _);
+        DeleteObject(icon_info.hbmMask as _);
+        image
+    }
+}
+
+/// macOS only hands out icons through AppKit, and the browser build has no system to ask;
+/// both use the painted set.
+#[cfg(not(any(all(unix, not(target_os = "macos")), windows)))]
+mod platform {
+    use eframe::egui::ColorImage;
+
+    pub fn load(_extension: &str, _is_dir: bool) -> Option<ColorImage> {
+        None
+    }
+}
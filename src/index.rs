@@ -0,0 +1,601 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{OsStr, OsString},
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Instant, SystemTime},
+};
+
+use crate::{
+    category,
+    checkpoint::{self, Checkpoint, SavedNode},
+    network,
+    paths,
+    scan::{self, ScanOptions},
+    telemetry, throttle,
+};
+
+pub struct Node {
+    pub path: PathBuf,
+    pub name: OsString,
+    pub size: u64,
+    /// Bytes before transparent compression; see `scan::logical_size`.
+    pub logical: u64,
+    /// Entries below this node, counting files, folders and links alike.
+    pub entries: u64,
+    pub is_dir: bool,
+    pub modified: Option<SystemTime>,
+    /// Last read or write; see `scan::last_used`.
+    pub accessed: Option<SystemTime>,
+    pub unreachable: bool,
+    pub online_only: bool,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// Files directly inside this folder that low-memory mode counted instead of indexing.
+    pub small_files: u64,
+    pub small_size: u64,
+}
+
+/// The whole scanned hierarchy under a root, stored as a flat arena where the root is node 0
+/// and every node comes after its parent.
+pub struct ScanIndex {
+    pub nodes: Vec<Node>,
+    ids: HashMap<PathBuf, usize>,
+    /// When the checkpoint this scan continued from was written.
+    pub resumed_from: Option<SystemTime>,
+}
+
+/// A way of walking a root into a `ScanIndex`. Each scan picks one from its options with
+/// `scanner`.
+pub trait Scanner {
+    fn scan(&self, root: &Path, options: &ScanOptions, show_hidden: bool) -> ScanIndex;
+
+    /// Whether folder listings should come from the index instead of reading the folder
+    /// again, for scanners meant for filesystems where every call is slow.
+    fn lists_from_index(&self) -> bool {
+        false
+    }
+}
+
+/// The regular walk of the tree, optionally writing checkpoints to resume from.
+pub struct WalkScanner {
+    pub checkpoints: bool,
+}
+
+impl Scanner for WalkScanner {
+    fn scan(&self, root: &Path, options: &ScanOptions, show_hidden: bool) -> ScanIndex {
+        let index = ScanIndex::walk(root, options, show_hidden, self.checkpoints);
+        if self.checkpoints {
+            checkpoint::discard();
+        }
+        index
+    }
+}
+
+/// Lists many folders at once with a timeout each, for high-latency shares.
+pub struct NetworkScanner;
+
+impl Scanner for NetworkScanner {
+    fn scan(&self, root: &Path, options: &ScanOptions, show_hidden: bool) -> ScanIndex {
+        ScanIndex::build_network(root, options, show_hidden)
+    }
+
+    fn lists_from_index(&self) -> bool {
+        true
+    }
+}
+
+/// Walks with tokio's asynchronous file I/O, for SFTP and cloud mounts.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct AsyncScanner;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Scanner for AsyncScanner {
+    fn scan(&self, root: &Path, options: &ScanOptions, show_hidden: bool) -> ScanIndex {
+        ScanIndex::build_async(root, options, show_hidden)
+    }
+
+    fn lists_from_index(&self) -> bool {
+        true
+    }
+}
+
+/// The scanner the options ask for. `resumable` only matters to the regular walk, the one
+/// scanner that checkpoints.
+pub fn scanner(options: &ScanOptions, resumable: bool) -> Box<dyn Scanner> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if options.async_io {
+        return Box::new(AsyncScanner);
+    }
+    if options.network_mode {
+        return Box::new(NetworkScanner);
+    }
+    Box::new(WalkScanner { checkpoints: resumable })
+}
+
+impl ScanIndex {
+    pub fn build(root: &Path, options: &ScanOptions, show_hidden: bool) -> Self {
+        scanner(options, false).scan(root, options, show_hidden)
+    }
+
+    /// Like `build`, but writes a checkpoint every `checkpoint::INTERVAL` and continues from the
+    /// one an interrupted scan of the same root left behind.
+    pub fn build_resumable(root: &Path, options: &ScanOptions, show_hidden: bool) -> Self {
+        scanner(options, true).scan(root, options, show_hidden)
+    }
+
+    fn walk(root: &Path, options: &ScanOptions, show_hidden: bool, checkpoints: bool) -> Self {
+        let mut index = ScanIndex { nodes: Vec::new(), ids: HashMap::new(), resumed_from: None };
+        let saved = checkpoints.then(|| Checkpoint::load(root, options, show_hidden)).flatten();
+        index.resumed_from = saved.as_ref().map(|checkpoint| checkpoint.saved_at);
+        let mut restored = saved.map(Checkpoint::by_parent).unwrap_or_default();
+        let skip: HashSet<PathBuf> =
+            restored.values().flatten().filter_map(|subtree| subtree.first()).map(|top| top.path.clone()).collect();
+        let mut walker = scan::tree_walker(root, options, skip);
+        walker.hidden(!show_hidden);
+
+        // The walk is depth-first, so the folders still being walked are always one chain
+        // from the root down; every other folder seen so far is complete.
+        let mut open_dirs: Vec<usize> = Vec::new();
+        let mut last_checkpoint = Instant::now();
+        for entry in walker.build().filter_map(Result::ok) {
+            if checkpoints && last_checkpoint.elapsed() >= checkpoint::INTERVAL {
+                let _ = index.checkpoint(root, options, show_hidden, &open_dirs).save();
+                last_checkpoint = Instant::now();
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let parent = match entry.depth() {
+                0 => None,
+                _ => match entry.path().parent().and_then(|parent| index.ids.get(parent)) {
+                    Some(&parent) => Some(parent),
+                    None => continue,
+                },
+            };
+            while open_dirs.last().is_some_and(|&open| Some(open) != parent) {
+                open_dirs.pop();
+            }
+            if index.summarize(entry.path(), &metadata, parent, options) {
+                continue;
+            }
+            let id = index.push(entry.path().to_path_buf(), entry.file_name(), &metadata, parent);
+            if metadata.is_dir() {
+                open_dirs.push(id);
+                for subtree in restored.remove(entry.path()).unwrap_or_default() {
+                    index.graft(subtree, id);
+                }
+            }
+        }
+
+        index.finish();
+        index
+    }
+
+    fn checkpoint(&self, root: &Path, options: &ScanOptions, show_hidden: bool, open_dirs: &[usize]) -> Checkpoint {
+        let open: HashSet<usize> = open_dirs.iter().copied().collect();
+        let subtrees = open_dirs
+            .iter()
+            .flat_map(|&dir| &self.nodes[dir].children)
+            .filter(|&&child| self.nodes[child].is_dir && !open.contains(&child))
+            .map(|&child| self.saved_subtree(child))
+            .collect();
+        Checkpoint {
+            root: root.to_path_buf(),
+            options: options.clone(),
+            show_hidden,
+            saved_at: SystemTime::now(),
+            subtrees,
+        }
+    }
+
+    fn saved_subtree(&self, top: usize) -> Vec<SavedNode> {
+        let mut saved = Vec::new();
+        let mut positions = HashMap::new();
+        let mut pending = vec![top];
+        while let Some(id) = pending.pop() {
+            let node = &self.nodes[id];
+            let parent = if id == top { None } else { node.parent.and_then(|parent| positions.get(&parent).copied()) };
+            positions.insert(id, saved.len());
+            saved.push(SavedNode {
+                path: node.path.clone(),
+                name: node.name.clone(),
+                size: node.size,
+                logical: node.logical,
+                is_dir: node.is_dir,
+                modified: node.modified,
+                accessed: node.accessed,
+                unreachable: node.unreachable,
+                online_only: node.online_only,
+                small_files: node.small_files,
+                small_size: node.small_size,
+                parent,
+            });
+            pending.extend(&node.children);
+        }
+        saved
+    }
+
+    /// Puts a subtree from a checkpoint back under `parent`.
+    fn graft(&mut self, subtree: Vec<SavedNode>, parent: usize) {
+        let first = self.nodes.len();
+        for saved in subtree {
+            let id = self.nodes.len();
+            let parent = saved.parent.map_or(parent, |position| first + position);
+            if saved.is_dir {
+                self.ids.insert(saved.path.clone(), id);
+            }
+            self.nodes.push(Node {
+                path: saved.path,
+                name: saved.name,
+                size: saved.size,
+                logical: saved.logical,
+                entries: 0,
+                is_dir: saved.is_dir,
+                modified: saved.modified,
+                accessed: saved.accessed,
+                unreachable: saved.unreachable,
+                online_only: saved.online_only,
+                parent: Some(parent),
+                children: Vec::new(),
+                small_files: saved.small_files,
+                small_size: saved.small_size,
+            });
+            self.nodes[parent].children.push(id);
+        }
+    }
+
+    /// Walks a high-latency filesystem with many directories in flight at once. Each directory
+    /// is listed in a single batch with a timeout; ones that fail are kept as unreachable nodes.
+    fn build_network(root: &Path, options: &ScanOptions, show_hidden: bool) -> Self {
+        let mut index = ScanIndex { nodes: Vec::new(), ids: HashMap::new(), resumed_from: None };
+        let Ok(metadata) = fs::metadata(root) else {
+            return index;
+        };
+        index.push(root.to_path_buf(), root.file_name().unwrap_or(root.as_os_str()), &metadata, None);
+        let root_device = options.root_device(root);
+
+        let (job_sender, job_receiver) = mpsc::channel::<(usize, PathBuf)>();
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        for _ in 0..throttle::workers(network::WORKERS) {
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+            let options = options.clone();
+            thread::spawn(move || loop {
+                throttle::enter_background();
+                let job = job_receiver.lock().map(|receiver| receiver.recv());
+                let Ok(Ok((id, dir))) = job else {
+                    break;
+                };
+                let listing = network::list_dir(&dir, network::ENTRY_TIMEOUT)
+                    .map(|listing| network::drop_ignored(&dir, listing, &options));
+                if result_sender.send((id, listing)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_sender);
+
+        let mut pending = 0;
+        if job_sender.send((0, root.to_path_buf())).is_ok() {
+            pending += 1;
+        }
+        while pending > 0 {
+            let Ok((parent, listing)) = result_receiver.recv() else {
+                break;
+            };
+            pending -= 1;
+            let Some(listing) = listing else {
+                index.nodes[parent].unreachable = true;
+                continue;
+            };
+            for entry in listing {
+                if (!show_hidden && paths::is_dotfile(&entry.name))
+                    || options.excludes(&entry.name)
+                    || scan::crosses_device(&entry.metadata, root_device)
+                {
+                    continue;
+                }
+                if index.summarize(&entry.path, &entry.metadata, Some(parent), options) {
+                    continue;
+                }
+                let id = index.push(entry.path.clone(), &entry.name, &entry.metadata, Some(parent));
+                if entry.metadata.is_dir() && job_sender.send((id, entry.path)).is_ok() {
+                    pending += 1;
+                }
+            }
+        }
+
+        index.finish();
+        index
+    }
+
+    /// Walks with tokio's async file system calls, keeping `network::ASYNC_IN_FLIGHT` directory
+    /// reads going at once. Falls back to the threaded network walk if no runtime can be started.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_async(root: &Path, options: &ScanOptions, show_hidden: bool) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(throttle::workers(telemetry::available_threads()))
+            .on_thread_start(throttle::enter_background)
+            .enable_time()
+            .build();
+        match runtime {
+            Ok(runtime) => runtime.block_on(Self::walk_async(root, options, show_hidden)),
+            Err(_) => Self::build_network(root, options, show_hidden),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn walk_async(root: &Path, options: &ScanOptions, show_hidden: bool) -> Self {
+        let mut index = ScanIndex { nodes: Vec::new(), ids: HashMap::new(), resumed_from: None };
+        let Ok(metadata) = tokio::fs::metadata(root).await else {
+            return index;
+        };
+        index.push(root.to_path_buf(), root.file_name().unwrap_or(root.as_os_str()), &metadata, None);
+        let root_device = options.root_device(root);
+
+        let permits = Arc::new(tokio::sync::Semaphore::new(throttle::workers(network::ASYNC_IN_FLIGHT)));
+        let mut listings = tokio::task::JoinSet::new();
+        listings.spawn(network::list_dir_async(0, root.to_path_buf(), Arc::clone(&permits)));
+        while let Some(result) = listings.join_next().await {
+            let Ok((parent, listing)) = result else {
+                continue;
+            };
+            let Some(listing) = listing else {
+                index.nodes[parent].unreachable = true;
+                continue;
+            };
+            let dir = index.nodes[parent].path.clone();
+            for entry in network::drop_ignored(&dir, listing, options) {
+                if (!show_hidden && paths::is_dotfile(&entry.name))
+                    || options.excludes(&entry.name)
+                    || scan::crosses_device(&entry.metadata, root_device)
+                {
+                    continue;
+                }
+                if index.summarize(&entry.path, &entry.metadata, Some(parent), options) {
+                    continue;
+                }
+                let id = index.push(entry.path.clone(), &entry.name, &entry.metadata, Some(parent));
+                if entry.metadata.is_dir() {
+                    listings.spawn(network::list_dir_async(id, entry.path, Arc::clone(&permits)));
+                }
+            }
+        }
+
+        index.finish();
+        index
+    }
+
+    fn push(&mut self, path: PathBuf, name: &OsStr, metadata: &Metadata, parent: Option<usize>) -> usize {
+        throttle::pace();
+        let size = if metadata.is_file() { scan::file_size(&path, metadata) } else { 0 };
+        telemetry::record_entry(size);
+        if metadata.is_file() {
+            category::sniff_file(&path, size);
+        }
+        let id = self.nodes.len();
+        if metadata.is_dir() {
+            self.ids.insert(path.clone(), id);
+        }
+        self.nodes.push(Node {
+            path,
+            name: name.to_os_string(),
+            size,
+            logical: if metadata.is_file() { scan::logical_size(metadata) } else { 0 },
+            entries: 0,
+            is_dir: metadata.is_dir(),
+            modified: metadata.modified().ok(),
+            // Listing a folder updates its own access time, so only its contents count.
+            accessed: if metadata.is_dir() { metadata.modified().ok() } else { scan::last_used(metadata) },
+            unreachable: false,
+            online_only: scan::is_online_only(metadata),
+            parent,
+            children: Vec::new(),
+            small_files: 0,
+            small_size: 0,
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        id
+    }
+
+    /// In low-memory mode, counts a small file into its folder instead of keeping a node for
+    /// it. Returns whether the file was taken care of that way.
+    fn summarize(&mut self, path: &Path, metadata: &Metadata, parent: Option<usize>, options: &ScanOptions) -> bool {
+        let Some(parent) = parent else {
+            return false;
+        };
+        if !metadata.is_file() || metadata.len() >= options.summarize_below {
+            return false;
+        }
+        throttle::pace();
+        let size = scan::file_size(path, metadata);
+        telemetry::record_entry(size);
+        let folder = &mut self.nodes[parent];
+        folder.small_files += 1;
+        folder.small_size += size;
+        // Folders have no bytes of their own, so this only ever holds what's summarized here
+        // until `finish` adds the rest.
+        folder.logical += scan::logical_size(metadata);
+        let modified = metadata.modified().ok();
+        if modified > folder.modified {
+            folder.modified = modified;
+        }
+        let accessed = scan::last_used(metadata);
+        if accessed > folder.accessed {
+            folder.accessed = accessed;
+        }
+        true
+    }
+
+    fn finish(&mut self) {
+        for node in &mut self.nodes {
+            node.size += node.small_size;
+            node.entries += node.small_files;
+        }
+        for id in (1..self.nodes.len()).rev() {
+            let (size, entries, modified) = (self.nodes[id].size, self.nodes[id].entries, self.nodes[id].modified);
+            let (accessed, logical) = (self.nodes[id].accessed, self.nodes[id].logical);
+            if let Some(parent) = self.nodes[id].parent {
+                let parent = &mut self.nodes[parent];
+                parent.size += size;
+                parent.logical += logical;
+                parent.entries += entries + 1;
+                if modified > parent.modified {
+                    parent.modified = modified;
+                }
+                if accessed > parent.accessed {
+                    parent.accessed = accessed;
+                }
+            }
+        }
+
+        let sizes: Vec<u64> = self.nodes.iter().map(|node| node.size).collect();
+        for node in &mut self.nodes {
+            node.children.sort_by_key(|&child| std::cmp::Reverse(sizes[child]));
+        }
+    }
+
+    /// Wraps nodes that are already totalled and in arena order, e.g. ones read from a scan file.
+    pub fn from_nodes(nodes: Vec<Node>) -> Self {
+        let ids = nodes.iter().enumerate().filter(|(_, node)| node.is_dir).map(|(id, node)| (node.path.clone(), id)).collect();
+        ScanIndex { nodes, ids, resumed_from: None }
+    }
+
+    /// Like `from_nodes` for nodes carrying only their own sizes; totals are worked out here.
+    pub fn from_unfinished(nodes: Vec<Node>) -> Self {
+        let mut index = Self::from_nodes(nodes);
+        index.finish();
+        index
+    }
+
+    pub fn find_dir(&self, path: &Path) -> Option<usize> {
+        self.ids.get(path).copied()
+    }
+
+    pub fn own_size(&self, id: usize) -> u64 {
+        let files: u64 = self.nodes[id]
+            .children
+            .iter()
+            .map(|&child| &self.nodes[child])
+            .filter(|node| !node.is_dir)
+            .map(|node| node.size)
+            .sum();
+        files + self.nodes[id].small_size
+    }
+
+    /// Finds every entry below the root whose name contains `query`, largest first.
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        let mut results: Vec<usize> = (1..self.nodes.len())
+            .filter(|&id| self.nodes[id].name.to_string_lossy().to_lowercase().contains(&query))
+            .collect();
+        results.sort_by_key(|&id| std::cmp::Reverse(self.nodes[id].size));
+        results
+    }
+
+    /// Drops a deleted entry and subtracts its size from every ancestor.
+    pub fn remove(&mut self, path: &Path) {
+        let Some(parent) = path.parent().and_then(|parent| self.find_dir(parent)) else {
+            return;
+        };
+        let nodes = &self.nodes;
+        let Some(position) = nodes[parent].children.iter().position(|&child| nodes[child].path == path) else {
+            return;
+        };
+        let id = self.nodes[parent].children.remove(position);
+        let (size, logical) = (self.nodes[id].size, self.nodes[id].logical);
+        let mut ancestor = Some(parent);
+        while let Some(id) = ancestor {
+            self.nodes[id].size = self.nodes[id].size.saturating_sub(size);
+            self.nodes[id].logical = self.nodes[id].logical.saturating_sub(logical);
+            ancestor = self.nodes[id].parent;
+        }
+        self.ids.retain(|dir, _| !dir.starts_with(path));
+    }
+
+    /// Follows an entry that was moved to `target`: it's dropped where it was and added again
+    /// below the folder it went to, with both sets of ancestors updated. When the new folder
+    /// isn't part of this scan it's only dropped.
+    pub fn relocate(&mut self, path: &Path, target: &Path) {
+        let Some(id) = self.find_entry(path) else {
+            return;
+        };
+        self.remove(path);
+        self.attach_copy(id, path, target);
+    }
+
+    /// Adds a copy of the entry at `path` that was made at `target`.
+    pub fn duplicate(&mut self, path: &Path, target: &Path) {
+        if let Some(id) = self.find_entry(path) {
+            self.attach_copy(id, path, target);
+        }
+    }
+
+    fn find_entry(&self, path: &Path) -> Option<usize> {
+        let parent = self.find_dir(path.parent()?)?;
+        self.nodes[parent].children.iter().copied().find(|&child| self.nodes[child].path == path)
+    }
+
+    /// Copies the subtree of `id` below the folder holding `target`, when it's part of this scan.
+    fn attach_copy(&mut self, id: usize, path: &Path, target: &Path) {
+        let Some(parent) = target.parent().and_then(|parent| self.find_dir(parent)) else {
+            return;
+        };
+        let subtree = self.subtree(id);
+
+        // The copies go at the end, so every node still comes after its parent.
+        let mut moved: HashMap<usize, usize> = HashMap::new();
+        for old in subtree {
+            let node = &self.nodes[old];
+            let new_path = target.join(node.path.strip_prefix(path).unwrap_or(&node.path));
+            let name = match target.file_name() {
+                Some(name) if old == id => name.to_os_string(),
+                _ => node.name.clone(),
+            };
+            let new_parent = node.parent.and_then(|parent| moved.get(&parent).copied()).unwrap_or(parent);
+            let copy = Node {
+                path: new_path,
+                name,
+                parent: Some(new_parent),
+                children: Vec::new(),
+                ..*node
+            };
+            let new_id = self.nodes.len();
+            if copy.is_dir {
+                self.ids.insert(copy.path.clone(), new_id);
+            }
+            self.nodes.push(copy);
+            self.nodes[new_parent].children.push(new_id);
+            moved.insert(old, new_id);
+        }
+
+        let (size, logical) = (self.nodes[moved[&id]].size, self.nodes[moved[&id]].logical);
+        let mut ancestor = Some(parent);
+        while let Some(id) = ancestor {
+            self.nodes[id].size += size;
+            self.nodes[id].logical += logical;
+            ancestor = self.nodes[id].parent;
+        }
+        let nodes = &self.nodes;
+        let mut children = nodes[parent].children.clone();
+        children.sort_by_key(|&child| std::cmp::Reverse(nodes[child].size));
+        self.nodes[parent].children = children;
+    }
+
+    /// `id` and everything below it, each node before its children.
+    fn subtree(&self, id: usize) -> Vec<usize> {
+        let mut order = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            stack.extend(self.nodes[id].children.iter().rev());
+        }
+        order
+    }
+}
@@ -0,0 +1,244 @@
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::{fs, path::Path};
+
+pub const MENU_LABEL: &str = "Analyze with Disk Analyzer";
+
+fn current_exe() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| format!("Error locating executable: {}", e))
+}
+
+#[cfg(unix)]
+fn write_file(path: &Path, contents: &str, executable: bool) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creating {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+    if executable {
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Error setting permissions on {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Single-quotes the path for `sh`, so spaces, `$`, backticks and quotes in it stay literal.
+#[cfg(unix)]
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn remove_path(path: &Path) -> Result<bool, String> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(format!("Error removing {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    const KEYS: [(&str, &str); 2] = [
+        (r"HKCU\Software\Classes\Directory\shell\DiskAnalyzer", "%1"),
+        (r"HKCU\Software\Classes\Directory\Background\shell\DiskAnalyzer", "%V"),
+    ];
+
+    fn reg(args: &[&str]) -> Result<(), String> {
+        let status = Command::new("reg")
+            .args(args)
+            .status()
+            .map_err(|e| format!("Error running reg.exe: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("reg.exe failed with {}", status))
+        }
+    }
+
+    pub fn install() -> Result<Vec<PathBuf>, String> {
+        let exe = current_exe()?;
+        for (key, placeholder) in KEYS {
+            let command = format!("\"{}\" \"{}\"", exe.display(), placeholder);
+            let exe_icon = exe.display().to_string();
+            reg(&["add", key, "/ve", "/d", MENU_LABEL, "/f"])?;
+            reg(&["add", key, "/v", "Icon", "/d", &exe_icon, "/f"])?;
+            reg(&["add", &format!(r"{}\command", key), "/ve", "/d", &command, "/f"])?;
+        }
+        Ok(KEYS.iter().map(|(key, _)| PathBuf::from(key)).collect())
+    }
+
+    pub fn uninstall() -> Result<Vec<PathBuf>, String> {
+        let mut removed = Vec::new();
+        for (key, _) in KEYS {
+            if reg(&["delete", key, "/f"]).is_ok() {
+                removed.push(PathBuf::from(key));
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    fn workflow_dir() -> Result<PathBuf, String> {
+        dirs::home_dir()
+            .map(|home| home.join("Library/Services").join(format!("{}.workflow", MENU_LABEL)))
+            .ok_or_else(|| "No home directory".to_string())
+    }
+
+    const INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>NSServices</key>
+	<array>
+		<dict>
+			<key>NSMenuItem</key>
+			<dict><key>default</key><string>{label}</string></dict>
+			<key>NSMessage</key>
+			<string>runWorkflowAsService</string>
+			<key>NSRequiredContext</key>
+			<dict><key>NSApplicationIdentifier</key><string>com.apple.finder</string></dict>
+			<key>NSSendFileTypes</key>
+			<array><string>public.folder</string></array>
+		</dict>
+	</array>
+</dict>
+</plist>
+"#;
+
+    const DOCUMENT_WFLOW: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>actions</key>
+	<array>
+		<dict>
+			<key>action</key>
+			<dict>
+				<key>ActionBundlePath</key>
+				<string>/System/Library/Automator/Run Shell Script.action</string>
+				<key>ActionParameters</key>
+				<dict>
+					<key>COMMAND_STRING</key>
+					<string>for f in "$@"; do {exe} "$f" &amp; done</string>
+					<key>inputMethod</key>
+					<integer>1</integer>
+					<key>shell</key>
+					<string>/bin/sh</string>
+				</dict>
+				<key>BundleIdentifier</key>
+				<string>com.apple.RunShellScript</string>
+			</dict>
+		</dict>
+	</array>
+	<key>workflowMetaData</key>
+	<dict>
+		<key>serviceInputTypeIdentifier</key>
+		<string>com.apple.Automator.fileSystemObject.folder</string>
+		<key>workflowTypeIdentifier</key>
+		<string>com.apple.Automator.servicesMenu</string>
+	</dict>
+</dict>
+</plist>
+"#;
+
+    pub fn install() -> Result<Vec<PathBuf>, String> {
+        let exe = current_exe()?;
+        let dir = workflow_dir()?;
+        let contents = dir.join("Contents");
+        write_file(&contents.join("Info.plist"), &INFO_PLIST.replace("{label}", MENU_LABEL), false)?;
+        let quoted = shell_quote(&exe).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let document = DOCUMENT_WFLOW.replace("{exe}", &quoted);
+        write_file(&contents.join("document.wflow"), &document, false)?;
+        Ok(vec![dir])
+    }
+
+    pub fn uninstall() -> Result<Vec<PathBuf>, String> {
+        let dir = workflow_dir()?;
+        Ok(if remove_path(&dir)? { vec![dir] } else { Vec::new() })
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    /// Quotes the path for a desktop entry's `Exec` key: the characters special inside its
+    /// double quotes get a backslash, and backslashes are doubled again for the string value.
+    fn exec_quote(path: &Path) -> String {
+        let mut quoted = String::new();
+        for c in path.to_string_lossy().chars() {
+            match c {
+                '\\' => quoted.push_str(r"\\\\"),
+                '"' | '`' | '$' => {
+                    quoted.push_str(r"\\");
+                    quoted.push(c);
+                }
+                '%' => quoted.push_str("%%"),
+                c => quoted.push(c),
+            }
+        }
+        format!("\"{}\"", quoted)
+    }
+
+    fn targets() -> Result<(PathBuf, PathBuf), String> {
+        let data = dirs::data_dir().ok_or("No data directory")?;
+        Ok((
+            data.join("nautilus/scripts").join(MENU_LABEL),
+            data.join("kio/servicemenus/disk-analyzer.desktop"),
+        ))
+    }
+
+    pub fn install() -> Result<Vec<PathBuf>, String> {
+        let exe = current_exe()?;
+        let (nautilus, dolphin) = targets()?;
+
+        let script = format!("#!/bin/sh\nexec {} \"${{1:-$PWD}}\"\n", shell_quote(&exe));
+        write_file(&nautilus, &script, true)?;
+
+        let service_menu = format!(
+            "[Desktop Entry]\n\
+             Type=Service\n\
+             MimeType=inode/directory;\n\
+             Actions=analyze\n\
+             X-KDE-ServiceTypes=KonqPopupMenu/Plugin\n\
+             \n\
+             [Desktop Action analyze]\n\
+             Name={}\n\
+             Icon=drive-harddisk\n\
+             Exec={} %f\n",
+            MENU_LABEL,
+            exec_quote(&exe)
+        );
+        write_file(&dolphin, &service_menu, true)?;
+
+        Ok(vec![nautilus, dolphin])
+    }
+
+    pub fn uninstall() -> Result<Vec<PathBuf>, String> {
+        let (nautilus, dolphin) = targets()?;
+        let mut removed = Vec::new();
+        for path in [nautilus, dolphin] {
+            if remove_path(&path)? {
+                removed.push(path);
+            }
+        }
+        Ok(removed)
+    }
+}
+
+pub use platform::{install, uninstall};
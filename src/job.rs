@@ -0,0 +1,34 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::{telemetry, throttle};
+
+pub struct Job<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn(work: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        send_when_done(sender, work);
+        Self { receiver }
+    }
+
+    pub fn poll(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Runs `work` in the background like `Job::spawn`, but sends the result down a channel that
+/// other workers may share.
+pub fn send_when_done<T: Send + 'static>(sender: Sender<T>, work: impl FnOnce() -> T + Send + 'static) {
+    telemetry::job_started();
+    thread::spawn(move || {
+        throttle::enter_background();
+        let result = work();
+        telemetry::job_finished();
+        let _ = sender.send(result);
+    });
+}
@@ -0,0 +1,98 @@
+use egui_dock::{DockState, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const LAYOUT_FILE: &str = "layout.json";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Panel {
+    Files,
+    Tree,
+    Details,
+    Errors,
+    Reports,
+    History,
+    Operations,
+    Bookmarks,
+    Summary,
+    Console,
+    Icicle,
+    Treemap,
+}
+
+impl Panel {
+    pub const ALL: [Panel; 12] = [
+        Panel::Files,
+        Panel::Tree,
+        Panel::Details,
+        Panel::Errors,
+        Panel::Reports,
+        Panel::History,
+        Panel::Operations,
+        Panel::Bookmarks,
+        Panel::Summary,
+        Panel::Console,
+        Panel::Icicle,
+        Panel::Treemap,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Panel::Files => "Files",
+            Panel::Tree => "Tree",
+            Panel::Details => "Details",
+            Panel::Errors => "Errors",
+            Panel::Reports => "Reports",
+            Panel::History => "History",
+            Panel::Operations => "Operations",
+            Panel::Bookmarks => "Bookmarks",
+            Panel::Summary => "Summary",
+            Panel::Console => "Console",
+            Panel::Icicle => "Icicle",
+            Panel::Treemap => "Treemap",
+        }
+    }
+}
+
+/// The dockable panel arrangement, saved between runs.
+#[derive(Serialize, Deserialize)]
+pub struct Layout {
+    pub dock: DockState<Panel>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        let mut dock = DockState::new(vec![Panel::Files]);
+        let surface = dock.main_surface_mut();
+        let [files, _] = surface.split_left(NodeIndex::root(), 0.75, vec![Panel::Tree, Panel::Bookmarks]);
+        let [files, _] = surface.split_right(files, 0.7, vec![Panel::Details, Panel::Summary, Panel::Icicle, Panel::Treemap]);
+        surface.split_below(files, 0.75, vec![Panel::Errors, Panel::Reports, Panel::History, Panel::Operations, Panel::Console]);
+        Self { dock }
+    }
+}
+
+impl Layout {
+    pub fn load() -> Self {
+        config::load(LAYOUT_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(LAYOUT_FILE, self)
+    }
+
+    pub fn is_open(&self, panel: Panel) -> bool {
+        self.dock.find_tab(&panel).is_some()
+    }
+
+    /// Closing removes the tab; reopening puts it back beside the focused one.
+    pub fn set_open(&mut self, panel: Panel, open: bool) {
+        match self.dock.find_tab(&panel) {
+            Some(location) if !open => {
+                self.dock.remove_tab(location);
+            }
+            None if open => self.dock.push_to_focused_leaf(panel),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use crate::{
+    audit, category, heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, scan,
+};
+
+const SNAP_DIR: &str = "/var/lib/snapd/snaps";
+const SNAP_MOUNTS: &str = "/snap";
+const SNAP_RETAIN: &str = "sudo snap set system refresh.retain=2";
+const DESKTOP_DIRS: [&str; 2] = ["/usr/share/applications", "/usr/local/share/applications"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Installation {
+    System,
+    User,
+}
+
+impl Installation {
+    fn flag(self) -> &'static str {
+        match self {
+            Installation::System => "--system",
+            Installation::User => "--user",
+        }
+    }
+
+    fn needs_root(self) -> bool {
+        self == Installation::System
+    }
+}
+
+pub struct FlatpakRef {
+    pub installation: Installation,
+    pub runtime: bool,
+    /// `name/arch/branch`.
+    pub id: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// No installed app is built on this runtime or on the runtime it extends.
+    pub unused: bool,
+}
+
+impl FlatpakRef {
+    fn uninstall(&self) -> Vec<String> {
+        let kind = if self.runtime { "runtime" } else { "app" };
+        ["flatpak", "uninstall", self.installation.flag(), "-y"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .chain(std::iter::once(format!("{}/{}", kind, self.id)))
+            .collect()
+    }
+}
+
+pub struct SnapRevision {
+    pub name: String,
+    pub revision: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub current: bool,
+}
+
+pub struct AppImage {
+    pub path: PathBuf,
+    pub size: u64,
+    pub last_used: Option<SystemTime>,
+    /// Whether a desktop launcher points at it.
+    pub integrated: bool,
+}
+
+#[derive(Default)]
+pub struct AppsReport {
+    pub flatpaks: Vec<FlatpakRef>,
+    pub snaps: Vec<SnapRevision>,
+    pub appimages: Vec<AppImage>,
+    /// Launchers whose AppImage no longer exists.
+    pub stale_launchers: Vec<PathBuf>,
+}
+
+fn entries(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    entries.filter_map(Result::ok).map(|entry| entry.path()).collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+fn installations() -> Vec<(Installation, PathBuf)> {
+    let mut installations = vec![(Installation::System, PathBuf::from("/var/lib/flatpak"))];
+    installations.extend(dirs::data_dir().map(|dir| (Installation::User, dir.join("flatpak"))));
+    installations
+}
+
+/// Lists the `name/arch/branch` folders under an installation's `app` or `runtime` folder.
+fn refs(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut refs = Vec::new();
+    for name in entries(dir) {
+        for arch in entries(&name) {
+            for branch in entries(&arch) {
+                if branch.join("active").exists() {
+                    let id = format!("{}/{}/{}", file_name(&name), file_name(&arch), file_name(&branch));
+                    refs.push((id, branch));
+                }
+            }
+        }
+    }
+    refs
+}
+
+/// The runtime an app declares in its metadata, as `name/arch/branch`.
+fn app_runtime(app: &Path) -> Option<String> {
+    let metadata = fs::read_to_string(app.join("active/metadata")).ok()?;
+    metadata.lines().find_map(|line| line.strip_prefix("runtime=")).map(|runtime| runtime.trim().to_string())
+}
+
+/// A runtime is in use when an app runs on it, or when it's an extension (GL drivers,
+/// codecs, translations) of a runtime whose name it starts with. SDKs are only needed to build
+/// apps, so they count as unused. Themes and extensions named on their own are picked up by
+/// apps without being declared, so they're never counted as unused.
+fn runtime_used(id: &str, used: &HashSet<String>) -> bool {
+    let mut parts = id.splitn(3, '/');
+    let (name, branch) = (parts.next().unwrap_or_default(), parts.nth(1).unwrap_or_default());
+    let lower = name.to_lowercase();
+    if lower.contains(".extension") || lower.contains("theme") {
+        return true;
+    }
+    used.iter().any(|runtime| {
+        let mut parts = runtime.splitn(3, '/');
+        let (base, base_branch) = (parts.next().unwrap_or_default(), parts.nth(1).unwrap_or_default());
+        if name == base {
+            branch == base_branch
+        } else {
+            name.starts_with(&format!("{}.", base))
+        }
+    })
+}
+
+fn flatpaks() -> Vec<FlatpakRef> {
+    let mut flatpaks = Vec::new();
+    let mut used = HashSet::new();
+    let mut runtimes = Vec::new();
+    for (installation, dir) in installations() {
+        for (id, path) in refs(&dir.join("app")) {
+            used.extend(app_runtime(&path));
+            let size = scan::measure_dir(&path, &Default::default()).size;
+            flatpaks.push(FlatpakRef { installation, runtime: false, id, path, size, unused: false });
+        }
+        runtimes.extend(refs(&dir.join("runtime")).into_iter().map(|(id, path)| (installation, id, path)));
+    }
+    for (installation, id, path) in runtimes {
+        let size = scan::measure_dir(&path, &Default::default()).size;
+        let unused = !runtime_used(&id, &used);
+        flatpaks.push(FlatpakRef { installation, runtime: true, id, path, size, unused });
+    }
+    flatpaks.sort_by_key(|flatpak| std::cmp::Reverse(flatpak.size));
+    flatpaks
+}
+
+/// Snap keeps each revision as `<name>_<revision>.snap`; the one `/snap/<name>/current`
+/// points at is in use and the rest are kept for rollback.
+fn snaps() -> Vec<SnapRevision> {
+    let mut snaps: Vec<SnapRevision> = entries(Path::new(SNAP_DIR))
+        .into_iter()
+        .filter_map(|path| {
+            let stem = path.file_name()?.to_str()?.strip_suffix(".snap")?.to_string();
+            let (name, revision) = stem.rsplit_once('_')?;
+            let current = fs::read_link(Path::new(SNAP_MOUNTS).join(name).join("current"))
+                .is_ok_and(|target| file_name(&target) == revision);
+            let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+            Some(SnapRevision { name: name.to_string(), revision: revision.to_string(), path, size, current })
+        })
+        .collect();
+    snaps.sort_by_key(|snap| std::cmp::Reverse(snap.size));
+    snaps
+}
+
+/// AppImages the desktop launchers point at, and the launchers whose AppImage is gone.
+fn launchers() -> (HashSet<PathBuf>, Vec<PathBuf>) {
+    let mut targets = HashSet::new();
+    let mut stale = Vec::new();
+    let dirs = DESKTOP_DIRS.iter().map(PathBuf::from).chain(dirs::data_dir().map(|dir| dir.join("applications")));
+    for desktop in dirs.flat_map(|dir| entries(&dir)) {
+        let Ok(contents) = fs::read_to_string(&desktop) else {
+            continue;
+        };
+        let Some(exec) = contents.lines().find_map(|line| line.strip_prefix("Exec=")) else {
+            continue;
+        };
+        let program = exec.trim().trim_start_matches('"');
+        let program = program.split(['"', ' ']).next().unwrap_or_default();
+        if !program.to_lowercase().ends_with(".appimage") {
+            continue;
+        }
+        let program = PathBuf::from(program);
+        if program.exists() {
+            targets.insert(program);
+        } else {
+            stale.push(desktop);
+        }
+    }
+    (targets, stale)
+}
+
+pub fn analyze(candidates: Vec<(PathBuf, u64)>) -> AppsReport {
+    let (integrated, stale_launchers) = launchers();
+    let mut appimages: Vec<AppImage> = candidates
+        .into_iter()
+        .map(|(path, size)| AppImage {
+            last_used: fs::metadata(&path).and_then(|metadata| metadata.accessed()).ok(),
+            integrated: integrated.contains(&path),
+            path,
+            size,
+        })
+        .collect();
+    appimages.sort_by_key(|appimage| std::cmp::Reverse(appimage.size));
+    AppsReport { flatpaks: flatpaks(), snaps: snaps(), appimages, stale_launchers }
+}
+
+fn run_command(command: Vec<String>) -> Result<String, String> {
+    let (program, args) = command.split_first().ok_or("Empty command")?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(command.join(" "))
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn last_used_text(time: Option<SystemTime>) -> String {
+    match heatmap::age(time) {
+        Some(age) => trf("{} days ago", &[&i18n::format_count(age.as_secs() / (24 * 60 * 60))]),
+        None => "-".to_string(),
+    }
+}
+
+/// A row with a cleanup command that can be copied, or run when it needs no root.
+fn command_row(
+    ui: &mut egui::Ui,
+    command: &[String],
+    size: u64,
+    needs_root: bool,
+    read_only: bool,
+    run: &mut Option<(Vec<String>, u64)>,
+) {
+    let line = if needs_root { format!("sudo {}", command.join(" ")) } else { command.join(" ") };
+    ui.label(RichText::new(&line).monospace());
+    if ui.button(tr("Copy")).clicked() {
+        ui.output_mut(|output| output.copied_text = line.clone());
+    }
+    let button = ui
+        .add_enabled(!read_only && !needs_root, egui::Button::new(tr("Run")))
+        .on_disabled_hover_text(if read_only {
+            tr("Disabled in read-only mode")
+        } else {
+            tr("Needs administrator rights; copy the command instead")
+        });
+    if button.clicked() {
+        *run = Some((command.to_vec(), size));
+    }
+}
+
+#[derive(Default)]
+pub struct LinuxAppsState {
+    pub open: bool,
+    job: Option<Job<AppsReport>>,
+    report: Option<AppsReport>,
+    run_job: Option<Job<audit::Audited<String>>>,
+    message: Option<Result<String, String>>,
+    /// A command waiting for the user to confirm it, and the space it frees.
+    confirm: Option<(Vec<String>, u64)>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl LinuxAppsState {
+    fn spawn(&mut self, index: Option<&ScanIndex>) {
+        let candidates: Vec<(PathBuf, u64)> = index
+            .map(|index| {
+                index
+                    .nodes
+                    .iter()
+                    .filter(|node| !node.is_dir && category::extension(&node.path) == "appimage")
+                    .map(|node| (node.path.clone(), node.size))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.job = Some(Job::spawn(move || analyze(candidates)));
+    }
+
+    /// Draws the window and returns the entries the user chose to delete, whether each is a
+    /// folder, and their sizes; they still need confirming.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        index: Option<&ScanIndex>,
+        read_only: bool,
+    ) -> Vec<(PathBuf, bool, u64)> {
+        if self.report.is_none() && self.job.is_none() {
+            self.spawn(index);
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.run_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = Some(result);
+                    self.run_job = None;
+                    self.spawn(index);
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut delete = Vec::new();
+        let mut run = None;
+        let mut refresh = false;
+        let mut open = self.open;
+        egui::Window::new(tr("Flatpak, Snap and AppImage"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr("Unused Flatpak runtimes, old Snap revisions and forgotten AppImages."))
+                            .color(Color32::GRAY),
+                    );
+                    refresh = ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked();
+                });
+                if self.job.is_some() || self.run_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.message {
+                    Some(Ok(command)) => {
+                        ui.label(RichText::new(trf("Ran {}", &[command])).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    let flatpak_size: u64 = report.flatpaks.iter().map(|flatpak| flatpak.size).sum();
+                    let unused: Vec<&FlatpakRef> = report.flatpaks.iter().filter(|flatpak| flatpak.unused).collect();
+                    let unused_size: u64 = unused.iter().map(|flatpak| flatpak.size).sum();
+                    egui::CollapsingHeader::new(trf(
+                        "Flatpak: {}, {} possibly unused",
+                        &[&i18n::format_bytes(flatpak_size), &i18n::format_bytes(unused_size)],
+                    ))
+                    .id_source("flatpak_refs")
+                    .show(ui, |ui| {
+                        if report.flatpaks.is_empty() {
+                            ui.label(tr("Flatpak isn't installed."));
+                            return;
+                        }
+                        ui.label(tr("Flatpak knows best which runtimes nothing needs any more:"));
+                        egui::Grid::new("flatpak_unused").show(ui, |ui| {
+                            for installation in [Installation::User, Installation::System] {
+                                if report.flatpaks.iter().any(|flatpak| flatpak.installation == installation) {
+                                    let command: Vec<String> = ["flatpak", "uninstall", installation.flag(), "--unused", "-y"]
+                                        .iter()
+                                        .map(|arg| arg.to_string())
+                                        .collect();
+                                    let size = unused
+                                        .iter()
+                                        .filter(|flatpak| flatpak.installation == installation)
+                                        .map(|flatpak| flatpak.size)
+                                        .sum();
+                                    command_row(ui, &command, size, installation.needs_root(), read_only, &mut run);
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                        egui::Grid::new("flatpak_refs").striped(true).show(ui, |ui| {
+                            for flatpak in &report.flatpaks {
+                                let kind = if flatpak.runtime { tr("Runtime") } else { tr("App") };
+                                let text = format!("{} {}", kind, flatpak.id);
+                                if flatpak.unused {
+                                    ui.label(RichText::new(text).color(Color32::YELLOW))
+                                        .on_hover_text(tr("No installed app uses this runtime"));
+                                } else {
+                                    ui.label(text);
+                                }
+                                ui.label(i18n::format_bytes(flatpak.size)).on_hover_text(paths::display_path(&flatpak.path));
+                                if flatpak.unused {
+                                    let needs_root = flatpak.installation.needs_root();
+                                    command_row(ui, &flatpak.uninstall(), flatpak.size, needs_root, read_only, &mut run);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                    let old: Vec<&SnapRevision> = report.snaps.iter().filter(|snap| !snap.current).collect();
+                    let old_size: u64 = old.iter().map(|snap| snap.size).sum();
+                    egui::CollapsingHeader::new(trf(
+                        "Snap: {} old revisions, {}",
+                        &[&i18n::format_count(old.len() as u64), &i18n::format_bytes(old_size)],
+                    ))
+                    .id_source("snap_revisions")
+                    .show(ui, |ui| {
+                        if report.snaps.is_empty() {
+                            ui.label(tr("Snap isn't installed."));
+                            return;
+                        }
+                        ui.label(tr("Snap keeps earlier revisions for rollback; keeping only two of each:"));
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(SNAP_RETAIN).monospace());
+                            if ui.button(tr("Copy")).clicked() {
+                                ui.output_mut(|output| output.copied_text = SNAP_RETAIN.to_string());
+                            }
+                        });
+                        egui::Grid::new("snap_revisions").striped(true).show(ui, |ui| {
+                            for snap in old {
+                                ui.label(format!("{} ({})", snap.name, snap.revision));
+                                ui.label(i18n::format_bytes(snap.size)).on_hover_text(paths::display_path(&snap.path));
+                                let command: Vec<String> =
+                                    ["snap", "remove", &snap.name, &format!("--revision={}", snap.revision)]
+                                        .iter()
+                                        .map(|arg| arg.to_string())
+                                        .collect();
+                                command_row(ui, &command, snap.size, true, read_only, &mut run);
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                    let orphaned: Vec<&AppImage> = report.appimages.iter().filter(|appimage| !appimage.integrated).collect();
+                    let orphaned_size: u64 = orphaned.iter().map(|appimage| appimage.size).sum();
+                    egui::CollapsingHeader::new(trf(
+                        "AppImage: {} without a launcher, {}",
+                        &[&i18n::format_count(orphaned.len() as u64), &i18n::format_bytes(orphaned_size)],
+                    ))
+                    .id_source("appimages")
+                    .show(ui, |ui| {
+                        if index.is_none() {
+                            ui.label(tr("Scan a folder to look for AppImages in it."));
+                        }
+                        egui::Grid::new("appimages").striped(true).show(ui, |ui| {
+                            for appimage in orphaned {
+                                ui.label(paths::display_path(&appimage.path));
+                                ui.label(i18n::format_bytes(appimage.size));
+                                ui.label(trf("last used {}", &[&last_used_text(appimage.last_used)]));
+                                let button = ui
+                                    .add_enabled(!read_only, egui::Button::new(tr("Delete")))
+                                    .on_disabled_hover_text(tr("Disabled in read-only mode"));
+                                if button.clicked() {
+                                    delete.push((appimage.path.clone(), is_dir(&appimage.path), appimage.size));
+                                }
+                                ui.end_row();
+                            }
+                            for launcher in &report.stale_launchers {
+                                ui.label(paths::display_path(launcher));
+                                ui.label(tr("Launcher for a deleted AppImage"));
+                                ui.label("");
+                                let button = ui
+                                    .add_enabled(!read_only, egui::Button::new(tr("Delete")))
+                                    .on_disabled_hover_text(tr("Disabled in read-only mode"));
+                                if button.clicked() {
+                                    let size = fs::metadata(launcher).map_or(0, |metadata| metadata.len());
+                                    delete.push((launcher.clone(), is_dir(launcher), size));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            });
+        self.open = open;
+
+        if refresh {
+            self.message = None;
+            self.spawn(index);
+        }
+        if run.is_some() {
+            self.confirm = run;
+        }
+        if let Some((command, size)) = self.confirm_command(ctx) {
+            self.message = None;
+            self.run_job = Some(Job::spawn(move || {
+                let line = command.join(" ");
+                let result = run_command(command);
+                let audited = audit::record(audit::CLEAN, &line, Some(size), result.as_ref().err());
+                (result, audited)
+            }));
+        }
+        if let Some(report) = &mut self.report {
+            let deleted: HashSet<&PathBuf> = delete.iter().map(|(path, _, _)| path).collect();
+            report.appimages.retain(|appimage| !deleted.contains(&appimage.path));
+            report.stale_launchers.retain(|launcher| !deleted.contains(launcher));
+        }
+        delete
+    }
+
+    /// Shows the pending command until the user runs or drops it; returns it once confirmed.
+    fn confirm_command(&mut self, ctx: &egui::Context) -> Option<(Vec<String>, u64)> {
+        let (command, size) = self.confirm.as_ref()?;
+        let mut choice = None;
+        egui::Window::new(tr("Run Command"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr("Run this command?"));
+                ui.label(RichText::new(command.join(" ")).monospace());
+                ui.label(
+                    RichText::new(trf("It removes about {}, which can't be undone.", &[&i18n::format_bytes(*size)]))
+                        .color(Color32::YELLOW),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Run")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => self.confirm.take(),
+            Some(false) => {
+                self.confirm = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+fn is_dir(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir())
+}
@@ -0,0 +1,48 @@
+use eframe::egui::{self, Color32, InnerResponse, Rect, Shape};
+
+/// Every row is at least this tall, so rows with and without buttons line up.
+pub const HEIGHT: f32 = 22.0;
+
+/// One row of a list, laid out left to right across the full width. Odd rows are striped,
+/// and the hovered and selected rows are highlighted behind their contents. Lists using it
+/// should set `item_spacing.y` to zero so the backgrounds meet.
+pub struct ListRow {
+    index: usize,
+    selected: bool,
+}
+
+impl ListRow {
+    /// `index` is the row's position among the rows shown, which decides its stripe.
+    pub fn new(index: usize) -> Self {
+        Self { index, selected: false }
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// The returned response covers the whole row.
+    pub fn show<R>(self, ui: &mut egui::Ui, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> InnerResponse<R> {
+        // Painted once the row's height is known, but underneath what the row draws.
+        let background = ui.painter().add(Shape::Noop);
+        let row = ui.horizontal(|ui| {
+            ui.set_min_height(HEIGHT);
+            add_contents(ui)
+        });
+        let rect = Rect::from_x_y_ranges(ui.max_rect().x_range(), row.response.rect.y_range());
+        let visuals = ui.visuals();
+        let fill = if self.selected {
+            visuals.selection.bg_fill.linear_multiply(0.4)
+        } else if ui.rect_contains_pointer(rect) {
+            visuals.widgets.hovered.weak_bg_fill
+        } else if self.index % 2 == 1 {
+            visuals.faint_bg_color
+        } else {
+            Color32::TRANSPARENT
+        };
+        ui.painter().set(background, Shape::rect_filled(rect, 2.0, fill));
+        let response = row.response.with_new_rect(rect);
+        InnerResponse::new(row.inner, response)
+    }
+}
@@ -0,0 +1,404 @@
+use eframe::egui::{self, Color32, RichText};
+use flate2::{write::GzEncoder, Compression};
+use rayon::prelude::*;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    audit, category, heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, throttle,
+};
+
+const MIB: u64 = 1024 * 1024;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_THRESHOLD_MIB: u64 = 100;
+const DEFAULT_DAYS: u32 = 7;
+/// A log written to within this long is still being appended to.
+const GROWING: Duration = DAY;
+/// How much of each log is compressed to estimate the savings.
+const SAMPLE: u64 = 4 * MIB;
+const COMPRESSED_EXTENSIONS: [&str; 7] = ["gz", "xz", "bz2", "zst", "zip", "lz4", "7z"];
+const LOG_EXTENSIONS: [&str; 4] = ["log", "out", "err", "txt"];
+
+pub struct LogFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// An older generation left behind by rotation, like `app.log.1` or `syslog-20240501`.
+    pub rotated: bool,
+    /// Size after gzip, estimated from a sample of the file.
+    pub compressed: Option<u64>,
+}
+
+impl LogFile {
+    pub fn growing(&self) -> bool {
+        heatmap::age(self.modified).is_some_and(|age| age < GROWING)
+    }
+
+    pub fn savings(&self) -> u64 {
+        self.compressed.map_or(0, |compressed| self.size.saturating_sub(compressed))
+    }
+
+    fn older_than(&self, days: u32) -> bool {
+        heatmap::age(self.modified).is_some_and(|age| age >= DAY * days)
+    }
+}
+
+/// A log found in the scan: path, size, modification time and whether it's rotated.
+type Candidate = (PathBuf, u64, Option<SystemTime>, bool);
+/// Logs that were compressed with the bytes each saved, and the errors of the rest.
+type Compressed = (Vec<(PathBuf, u64)>, Vec<String>);
+
+pub struct LogDir {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+#[derive(Default)]
+pub struct LogReport {
+    /// Logs over the size threshold and uncompressed rotated logs, largest first.
+    pub files: Vec<LogFile>,
+    /// Log folders over the size threshold.
+    pub dirs: Vec<LogDir>,
+}
+
+fn is_log_dir(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name == "log" || name == "logs"
+}
+
+fn in_log_dir(path: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .filter_map(Path::file_name)
+        .any(|name| is_log_dir(&name.to_string_lossy()))
+}
+
+/// Rotation appends a generation number or a date: `app.log.1`, `app.log.2024-05-01`,
+/// `syslog-20240501`.
+fn is_rotated(name: &str) -> bool {
+    let is_stamp = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit() || c == '-');
+    let dotted = name.rsplit_once('.').is_some_and(|(stem, suffix)| !stem.is_empty() && is_stamp(suffix));
+    let dashed = name
+        .rsplit_once('-')
+        .is_some_and(|(_, suffix)| suffix.len() == 8 && suffix.chars().all(|c| c.is_ascii_digit()));
+    dotted || dashed
+}
+
+/// Returns whether the file is a log, and whether it's a rotated one.
+fn classify(path: &Path, name: &str) -> Option<bool> {
+    let extension = category::extension(path);
+    if COMPRESSED_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let lower = name.to_lowercase();
+    let rotated = is_rotated(&lower);
+    let named_log = lower.ends_with(".log") || lower.contains(".log.");
+    let in_logs = in_log_dir(path) && (rotated || LOG_EXTENSIONS.contains(&extension.as_str()));
+    (named_log || in_logs).then_some(rotated)
+}
+
+/// Collects the logs worth a look from the scan: those over the threshold, and rotated ones
+/// that were never compressed.
+pub fn find_logs(index: &ScanIndex, threshold: u64) -> (Vec<Candidate>, Vec<LogDir>) {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    for node in &index.nodes {
+        if node.is_dir {
+            if is_log_dir(&node.name.to_string_lossy()) && node.size >= threshold {
+                dirs.push(LogDir { path: node.path.clone(), size: node.size });
+            }
+            continue;
+        }
+        if let Some(rotated) = classify(&node.path, &node.name.to_string_lossy()) {
+            if rotated || node.size >= threshold {
+                files.push((node.path.clone(), node.size, node.modified, rotated));
+            }
+        }
+    }
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.size));
+    (files, dirs)
+}
+
+/// Gzips the start of the file and scales the ratio up to its whole size.
+fn estimate(path: &Path, size: u64) -> Option<u64> {
+    let mut sample = Vec::new();
+    File::open(path).ok()?.take(SAMPLE).read_to_end(&mut sample).ok()?;
+    if sample.is_empty() {
+        return Some(size);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&sample).ok()?;
+    let compressed = encoder.finish().ok()?.len();
+    Some((size as f64 * compressed as f64 / sample.len() as f64) as u64)
+}
+
+pub fn analyze(files: Vec<Candidate>, dirs: Vec<LogDir>) -> LogReport {
+    let mut files: Vec<LogFile> = throttle::run_limited(|| {
+        files
+            .into_par_iter()
+            .map(|(path, size, modified, rotated)| {
+                throttle::pace();
+                let compressed = estimate(&path, size);
+                LogFile { path, size, modified, rotated, compressed }
+            })
+            .collect()
+    });
+    files.sort_by_key(|file| std::cmp::Reverse(file.size));
+    LogReport { files, dirs }
+}
+
+/// Replaces the log with `<name>.gz`, keeping its modification time, and returns the bytes
+/// saved.
+fn gzip(path: &Path, size: u64) -> Result<u64, String> {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".gz");
+    let target = PathBuf::from(name);
+    if target.exists() {
+        return Err(format!("{} already exists", paths::display_path(&target)));
+    }
+    let result = (|| {
+        let mut input = File::open(path)?;
+        let modified = input.metadata()?.modified()?;
+        let mut encoder = GzEncoder::new(BufWriter::new(File::create(&target)?), Compression::default());
+        io::copy(&mut input, &mut encoder)?;
+        let output = encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+        output.set_modified(modified)?;
+        Ok::<u64, io::Error>(output.metadata()?.len())
+    })();
+    let compressed = match result {
+        Ok(compressed) => compressed,
+        Err(e) => {
+            let _ = fs::remove_file(&target);
+            return Err(format!("Error compressing {}: {}", paths::display_path(path), e));
+        }
+    };
+    fs::remove_file(path).map_err(|e| format!("Error removing {}: {}", paths::display_path(path), e))?;
+    Ok(size.saturating_sub(compressed))
+}
+
+fn compress_all(files: Vec<(PathBuf, u64)>) -> Compressed {
+    let mut compressed = Vec::new();
+    let mut errors = Vec::new();
+    for (path, size) in files {
+        throttle::pace();
+        let result = gzip(&path, size);
+        if let Err(error) = audit::record_path(audit::COMPRESS, &path, size, result.as_ref().err()) {
+            errors.push(error);
+        }
+        match result {
+            Ok(saved) => compressed.push((path, saved)),
+            Err(error) => errors.push(error),
+        }
+    }
+    (compressed, errors)
+}
+
+fn age_text(time: Option<SystemTime>) -> String {
+    match heatmap::age(time) {
+        Some(age) => trf("{} days ago", &[&i18n::format_count(age.as_secs() / DAY.as_secs())]),
+        None => "-".to_string(),
+    }
+}
+
+pub struct LogsState {
+    pub open: bool,
+    threshold_mib: u64,
+    days: u32,
+    job: Option<Job<LogReport>>,
+    report: Option<LogReport>,
+    compress_job: Option<Job<Compressed>>,
+    errors: Vec<String>,
+    confirm: bool,
+}
+
+impl Default for LogsState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            threshold_mib: DEFAULT_THRESHOLD_MIB,
+            days: DEFAULT_DAYS,
+            job: None,
+            report: None,
+            compress_job: None,
+            errors: Vec::new(),
+            confirm: false,
+        }
+    }
+}
+
+impl LogsState {
+    fn stale(&self) -> Vec<&LogFile> {
+        self.report.iter().flat_map(|report| &report.files).filter(|file| file.older_than(self.days)).collect()
+    }
+
+    /// Draws the window and returns the logs that were compressed, with the bytes each saved.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>, read_only: bool) -> Vec<(PathBuf, u64)> {
+        let mut compressed = Vec::new();
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.compress_job {
+            match job.poll() {
+                Some((done, errors)) => {
+                    if let Some(report) = &mut self.report {
+                        report.files.retain(|file| !done.iter().any(|(path, _)| *path == file.path));
+                    }
+                    compressed = done;
+                    self.errors = errors;
+                    self.compress_job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Log Files"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr("Finds runaway logs and rotated logs that were never compressed."))
+                        .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    ui.label(tr("Logs over"));
+                    ui.add(egui::DragValue::new(&mut self.threshold_mib).clamp_range(1..=100_000).suffix(" MiB"));
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Find Logs"))).clicked() {
+                        let (files, dirs) = find_logs(index, self.threshold_mib * MIB);
+                        self.job = Some(Job::spawn(move || analyze(files, dirs)));
+                        self.errors.clear();
+                    }
+                });
+                if self.job.is_some() || self.compress_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                for error in &self.errors {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+                if report.files.is_empty() && report.dirs.is_empty() {
+                    ui.label(tr("No oversized or uncompressed logs found."));
+                    return;
+                }
+
+                let savings: u64 = report.files.iter().map(LogFile::savings).sum();
+                ui.label(trf(
+                    "{} logs, {} in total; compressing them would save about {}",
+                    &[
+                        &i18n::format_count(report.files.len() as u64),
+                        &i18n::format_bytes(report.files.iter().map(|file| file.size).sum::<u64>()),
+                        &i18n::format_bytes(savings),
+                    ],
+                ));
+                ui.horizontal(|ui| {
+                    let stale: Vec<&LogFile> = report.files.iter().filter(|file| file.older_than(self.days)).collect();
+                    let (count, stale_savings) = (stale.len(), stale.iter().map(|file| file.savings()).sum::<u64>());
+                    let button =
+                        egui::Button::new(trf("Compress {} Logs Older Than", &[&i18n::format_count(count as u64)]));
+                    let clicked = ui
+                        .add_enabled(!read_only && count > 0, button)
+                        .on_hover_text(trf("Saves about {}", &[&i18n::format_bytes(stale_savings)]))
+                        .on_disabled_hover_text(if read_only {
+                            tr("Disabled in read-only mode")
+                        } else {
+                            tr("No logs are that old")
+                        })
+                        .clicked();
+                    ui.add(egui::DragValue::new(&mut self.days).clamp_range(1..=3650).suffix(tr(" days")));
+                    if clicked {
+                        self.confirm = true;
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(440.0).show(ui, |ui| {
+                    egui::Grid::new("log_files").striped(true).show(ui, |ui| {
+                        ui.label(tr("File"));
+                        ui.label(tr("Size"));
+                        ui.label(tr("Compressed"));
+                        ui.label(tr("Modified"));
+                        ui.end_row();
+                        for file in &report.files {
+                            let text = RichText::new(paths::display_path(&file.path));
+                            let label = if file.growing() {
+                                ui.label(text.color(Color32::YELLOW)).on_hover_text(tr("Still being written to"))
+                            } else if file.rotated {
+                                ui.label(text).on_hover_text(tr("Rotated log that was never compressed"))
+                            } else {
+                                ui.label(text)
+                            };
+                            label.context_menu(|ui| {
+                                if ui.button(tr("Copy Path")).clicked() {
+                                    ui.output_mut(|output| output.copied_text = file.path.display().to_string());
+                                    ui.close_menu();
+                                }
+                            });
+                            ui.label(i18n::format_bytes(file.size));
+                            ui.label(file.compressed.map_or("-".to_string(), |size| format!("~{}", i18n::format_bytes(size))));
+                            ui.label(age_text(file.modified));
+                            ui.end_row();
+                        }
+                    });
+                    if !report.dirs.is_empty() {
+                        ui.separator();
+                        ui.label(RichText::new(tr("Log folders over the limit")).strong());
+                        for dir in &report.dirs {
+                            ui.label(format!("{}  {}", i18n::format_bytes(dir.size), paths::display_path(&dir.path)));
+                        }
+                    }
+                });
+            });
+        self.open = open;
+
+        if self.confirm {
+            let stale: Vec<(PathBuf, u64)> = self.stale().iter().map(|file| (file.path.clone(), file.size)).collect();
+            let mut choice = None;
+            egui::Window::new(tr("Confirm Compression"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(trf(
+                        "Replace {} logs with gzip-compressed copies? They can still be read with zcat or zless.",
+                        &[&i18n::format_count(stale.len() as u64)],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Yes")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button(tr("No")).clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            if choice == Some(true) {
+                self.compress_job = Some(Job::spawn(move || compress_all(stale)));
+            }
+            if choice.is_some() {
+                self.confirm = false;
+            }
+        }
+        compressed
+    }
+}
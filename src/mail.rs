@@ -0,0 +1,338 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    i18n::{self, tr, trf},
+    job::Job,
+    paths, scan, throttle,
+};
+
+/// Folders whose messages average at least this much are mostly attachments.
+const HEAVY_MESSAGE_SIZE: u64 = 1024 * 1024;
+const HEAVY_ATTACHMENT_SHARE: f64 = 0.5;
+const MAX_FOLDERS: usize = 100;
+const READ_CHUNK: usize = 1024 * 1024;
+
+pub struct MailFolder {
+    pub name: String,
+    pub size: u64,
+    pub messages: Option<u64>,
+    /// Bytes in separately stored attachments, where the client keeps them apart.
+    pub attachments: Option<u64>,
+}
+
+impl MailFolder {
+    pub fn attachment_heavy(&self) -> bool {
+        let by_share = self.attachments.is_some_and(|bytes| bytes as f64 >= self.size as f64 * HEAVY_ATTACHMENT_SHARE);
+        let by_average = self.messages.is_some_and(|count| count > 0 && self.size / count >= HEAVY_MESSAGE_SIZE);
+        by_share || by_average
+    }
+}
+
+pub struct MailAccount {
+    pub client: &'static str,
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Largest first; empty for stores that keep everything in one file.
+    pub folders: Vec<MailFolder>,
+}
+
+fn size(path: &Path) -> u64 {
+    scan::measure_dir(path, &Default::default()).size
+}
+
+fn entries(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    entries.filter_map(Result::ok).map(|entry| entry.path()).collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+/// Counts the `From ` separator lines that start each message in an mbox file.
+fn count_mbox_messages(path: &Path) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    let mut buffer = vec![0; READ_CHUNK];
+    let (mut count, mut at_line_start, mut matched) = (0, true, 0);
+    loop {
+        throttle::pace();
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            return Some(count);
+        }
+        for &byte in &buffer[..read] {
+            if matched > 0 || at_line_start {
+                matched = if byte == b"From "[matched] { matched + 1 } else { 0 };
+                if matched == 5 {
+                    count += 1;
+                    matched = 0;
+                }
+            }
+            at_line_start = byte == b'\n';
+        }
+    }
+}
+
+/// Thunderbird folders are mbox files next to a `.msf` summary, or maildir folders, with
+/// subfolders in a `.sbd` directory.
+fn thunderbird_folders(dir: &Path, prefix: &str, folders: &mut Vec<MailFolder>) {
+    for path in entries(dir) {
+        let name = file_name(&path);
+        if path.is_file() && dir.join(format!("{}.msf", name)).is_file() {
+            let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+            folders.push(MailFolder {
+                name: format!("{}{}", prefix, name),
+                size,
+                messages: count_mbox_messages(&path),
+                attachments: None,
+            });
+        } else if path.join("cur").is_dir() {
+            let messages = ["cur", "new"].iter().map(|sub| entries(&path.join(sub)).len() as u64).sum();
+            folders.push(MailFolder {
+                name: format!("{}{}", prefix, name),
+                size: size(&path),
+                messages: Some(messages),
+                attachments: None,
+            });
+        } else if let Some(parent) = name.strip_suffix(".sbd") {
+            thunderbird_folders(&path, &format!("{}{}/", prefix, parent), folders);
+        }
+    }
+}
+
+fn thunderbird_accounts() -> Vec<MailAccount> {
+    let profiles = if cfg!(windows) {
+        dirs::config_dir().map(|dir| dir.join(r"Thunderbird\Profiles"))
+    } else if cfg!(target_os = "macos") {
+        dirs::config_dir().map(|dir| dir.join("Thunderbird/Profiles"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".thunderbird"))
+    };
+    let mut accounts = Vec::new();
+    for profile in profiles.map(|profiles| entries(&profiles)).unwrap_or_default() {
+        if !profile.join("prefs.js").is_file() {
+            continue;
+        }
+        for server in ["Mail", "ImapMail"].iter().flat_map(|store| entries(&profile.join(store))) {
+            if !server.is_dir() {
+                continue;
+            }
+            let mut folders = Vec::new();
+            thunderbird_folders(&server, "", &mut folders);
+            accounts.push(MailAccount {
+                client: "Thunderbird",
+                name: file_name(&server),
+                size: size(&server),
+                path: server,
+                folders,
+            });
+        }
+    }
+    accounts
+}
+
+/// Sums a Mail.app mailbox, leaving nested mailboxes to be reported on their own.
+fn apple_mailbox(dir: &Path, folder: &mut MailFolder, in_attachments: bool) {
+    for path in entries(dir) {
+        let name = file_name(&path);
+        if path.is_dir() {
+            if !name.ends_with(".mbox") {
+                apple_mailbox(&path, folder, in_attachments || name == "Attachments");
+            }
+        } else if let Ok(metadata) = fs::metadata(&path) {
+            folder.size += metadata.len();
+            if in_attachments {
+                *folder.attachments.get_or_insert(0) += metadata.len();
+            } else if name.ends_with(".emlx") {
+                *folder.messages.get_or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+fn apple_mailboxes(dir: &Path, prefix: &str, folders: &mut Vec<MailFolder>) {
+    for path in entries(dir).into_iter().filter(|path| path.is_dir()) {
+        let name = file_name(&path);
+        let Some(mailbox) = name.strip_suffix(".mbox") else {
+            continue;
+        };
+        let mut folder =
+            MailFolder { name: format!("{}{}", prefix, mailbox), size: 0, messages: None, attachments: Some(0) };
+        apple_mailbox(&path, &mut folder, false);
+        folders.push(folder);
+        apple_mailboxes(&path, &format!("{}{}/", prefix, mailbox), folders);
+    }
+}
+
+fn apple_mail_accounts() -> Vec<MailAccount> {
+    let Some(mail) = dirs::home_dir().map(|home| home.join("Library/Mail")) else {
+        return Vec::new();
+    };
+    let versions = entries(&mail).into_iter().filter(|path| file_name(path).starts_with('V'));
+    versions
+        .flat_map(|version| entries(&version))
+        .filter(|account| account.is_dir() && file_name(account) != "MailData")
+        .map(|account| {
+            let mut folders = Vec::new();
+            apple_mailboxes(&account, "", &mut folders);
+            MailAccount { client: "Mail", name: file_name(&account), size: size(&account), path: account, folders }
+        })
+        .collect()
+}
+
+/// Outlook for Windows keeps each account in one `.ost` or `.pst` file, so only the total is
+/// known; Outlook for Mac has a folder per kind of item.
+fn outlook_accounts() -> Vec<MailAccount> {
+    let mut accounts = Vec::new();
+    let stores = [
+        dirs::data_local_dir().map(|dir| dir.join(r"Microsoft\Outlook")),
+        dirs::document_dir().map(|dir| dir.join("Outlook Files")),
+    ];
+    for file in stores.iter().flatten().flat_map(|dir| entries(dir)) {
+        let extension = file.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+        if extension == "ost" || extension == "pst" {
+            let size = fs::metadata(&file).map_or(0, |metadata| metadata.len());
+            accounts.push(MailAccount { client: "Outlook", name: file_name(&file), path: file, size, folders: Vec::new() });
+        }
+    }
+    let mac_profiles = dirs::home_dir()
+        .map(|home| home.join("Library/Group Containers/UBF8T346G9.Office/Outlook/Outlook 15 Profiles"));
+    for profile in mac_profiles.map(|profiles| entries(&profiles)).unwrap_or_default() {
+        let data = profile.join("Data");
+        if !data.is_dir() {
+            continue;
+        }
+        let attachments = size(&data.join("Message Attachments"));
+        let folders = entries(&data)
+            .into_iter()
+            .filter(|path| path.is_dir())
+            .map(|path| {
+                let name = file_name(&path);
+                MailFolder {
+                    attachments: (name == "Messages").then_some(attachments),
+                    size: size(&path),
+                    messages: None,
+                    name,
+                }
+            })
+            .collect();
+        accounts.push(MailAccount { client: "Outlook", name: file_name(&profile), size: size(&data), path: data, folders });
+    }
+    accounts
+}
+
+pub fn find_accounts() -> Vec<MailAccount> {
+    let mut accounts: Vec<MailAccount> =
+        thunderbird_accounts().into_iter().chain(apple_mail_accounts()).chain(outlook_accounts()).collect();
+    for account in &mut accounts {
+        account.folders.sort_by_key(|folder| std::cmp::Reverse(folder.size));
+    }
+    accounts.sort_by_key(|account| std::cmp::Reverse(account.size));
+    accounts
+}
+
+#[derive(Default)]
+pub struct MailState {
+    pub open: bool,
+    job: Option<Job<Vec<MailAccount>>>,
+    accounts: Option<Vec<MailAccount>>,
+}
+
+impl MailState {
+    /// Draws the window and returns a folder to jump to when an account is clicked.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<PathBuf> {
+        if self.accounts.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(find_accounts));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(accounts) => {
+                    self.accounts = Some(accounts);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Mail Storage"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr("Mailbox sizes of Thunderbird, Outlook and Mail accounts."))
+                            .color(Color32::GRAY),
+                    );
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked() {
+                        self.job = Some(Job::spawn(find_accounts));
+                    }
+                });
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let Some(accounts) = &self.accounts else {
+                    return;
+                };
+                if accounts.is_empty() {
+                    ui.label(tr("No mail stores found."));
+                    return;
+                }
+                let total: u64 = accounts.iter().map(|account| account.size).sum();
+                ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+                egui::ScrollArea::vertical().max_height(460.0).show(ui, |ui| {
+                    for account in accounts {
+                        let title = format!("{} - {} - {}", account.client, account.name, i18n::format_bytes(account.size));
+                        egui::CollapsingHeader::new(title).id_source(("mail_account", &account.path)).show(ui, |ui| {
+                            let location = ui.link(paths::display_path(&account.path)).on_hover_text(tr("Show in folder"));
+                            if location.clicked() {
+                                navigate = if account.path.is_dir() {
+                                    Some(account.path.clone())
+                                } else {
+                                    account.path.parent().map(Path::to_path_buf)
+                                };
+                            }
+                            if account.folders.is_empty() {
+                                ui.label(
+                                    RichText::new(tr("This store keeps all folders in one file.")).color(Color32::GRAY),
+                                );
+                                return;
+                            }
+                            egui::Grid::new(("mail_folders", &account.path)).striped(true).show(ui, |ui| {
+                                ui.label(tr("Folder"));
+                                ui.label(tr("Size"));
+                                ui.label(tr("Messages"));
+                                ui.label(tr("Attachments"));
+                                ui.end_row();
+                                for folder in account.folders.iter().take(MAX_FOLDERS) {
+                                    if folder.attachment_heavy() {
+                                        ui.label(RichText::new(&folder.name).color(Color32::YELLOW))
+                                            .on_hover_text(tr("Mostly attachments"));
+                                    } else {
+                                        ui.label(&folder.name);
+                                    }
+                                    ui.label(i18n::format_bytes(folder.size));
+                                    ui.label(folder.messages.map(i18n::format_count).unwrap_or_default());
+                                    ui.label(folder.attachments.map(i18n::format_bytes).unwrap_or_default());
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
@@ -0,0 +1,282 @@
+use eframe::egui::{self, Color32, RichText};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    category::{self, Category},
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, throttle,
+};
+
+/// Smaller videos are clips and previews where the size per minute says little.
+const MIN_VIDEO_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_ROWS: usize = 200;
+
+pub struct MediaInfo {
+    pub duration: Option<f64>,
+    pub resolution: Option<(u64, u64)>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// Bits per second over the whole file.
+    pub bitrate: Option<u64>,
+}
+
+impl MediaInfo {
+    /// Bytes per minute of playback.
+    pub fn size_per_minute(&self, size: u64) -> Option<u64> {
+        let minutes = self.duration.filter(|duration| *duration > 0.0)? / 60.0;
+        Some((size as f64 / minutes) as u64)
+    }
+}
+
+pub fn is_media(path: &Path, size: u64) -> bool {
+    matches!(category::classify_file(path, size), Category::Video | Category::Audio)
+}
+
+/// ffprobe reports numbers as strings in its JSON output.
+fn number(value: &Value) -> Option<f64> {
+    value.as_str().and_then(|text| text.parse().ok()).or_else(|| value.as_f64())
+}
+
+/// Reads the container and stream headers with ffprobe, which has to be on the PATH.
+pub fn probe(path: &Path) -> Result<MediaInfo, String> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-print_format", "json", "-show_format", "-show_streams"])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Error running ffprobe: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let probed: Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Error reading ffprobe output: {}", e))?;
+    let streams = probed["streams"].as_array().map_or(&[][..], Vec::as_slice);
+    let stream = |kind: &str| streams.iter().find(|stream| stream["codec_type"] == kind);
+    let video = stream("video").filter(|stream| stream["disposition"]["attached_pic"] != 1);
+    let codec = |stream: Option<&Value>| stream.and_then(|stream| stream["codec_name"].as_str()).map(str::to_string);
+    Ok(MediaInfo {
+        duration: number(&probed["format"]["duration"]),
+        resolution: video.and_then(|video| Some((video["width"].as_u64()?, video["height"].as_u64()?))),
+        video_codec: codec(video),
+        audio_codec: codec(stream("audio")),
+        bitrate: number(&probed["format"]["bit_rate"]).map(|bitrate| bitrate as u64),
+    })
+}
+
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn format_bitrate(bitrate: u64) -> String {
+    if bitrate >= 1_000_000 {
+        format!("{:.1} Mb/s", bitrate as f64 / 1_000_000.0)
+    } else {
+        format!("{} kb/s", bitrate / 1000)
+    }
+}
+
+/// Duration, resolution, codecs and bitrate of the media file shown in the details panel.
+pub struct MediaDetails {
+    pub path: PathBuf,
+    size: u64,
+    job: Option<Job<Result<MediaInfo, String>>>,
+    info: Option<Result<MediaInfo, String>>,
+}
+
+impl MediaDetails {
+    pub fn new(path: PathBuf, size: u64) -> Self {
+        let probed = path.clone();
+        Self { path, size, job: Some(Job::spawn(move || probe(&probed))), info: None }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(info) => {
+                    self.info = Some(info);
+                    self.job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+        let info = match &self.info {
+            Some(Ok(info)) => info,
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::GRAY));
+                return;
+            }
+            None => {
+                ui.spinner();
+                return;
+            }
+        };
+        egui::Grid::new("media_details").show(ui, |ui| {
+            let mut row = |label: &'static str, value: Option<String>| {
+                if let Some(value) = value {
+                    ui.label(tr(label));
+                    ui.label(value);
+                    ui.end_row();
+                }
+            };
+            row("Duration", info.duration.map(format_duration));
+            row("Resolution", info.resolution.map(|(width, height)| format!("{} × {}", width, height)));
+            row("Video codec", info.video_codec.clone());
+            row("Audio codec", info.audio_codec.clone());
+            row("Bitrate", info.bitrate.map(format_bitrate));
+            row("Size per minute", info.size_per_minute(self.size).map(i18n::format_bytes));
+        });
+    }
+}
+
+pub struct LowValueVideo {
+    pub path: PathBuf,
+    pub size: u64,
+    pub info: MediaInfo,
+    pub per_minute: u64,
+}
+
+pub struct LowValueReport {
+    /// Most bytes per minute first.
+    pub videos: Vec<LowValueVideo>,
+    /// Videos ffprobe couldn't read.
+    pub skipped: usize,
+}
+
+pub fn rank_videos(files: Vec<(PathBuf, u64)>, done: &AtomicUsize) -> LowValueReport {
+    let total = files.len();
+    let mut videos: Vec<LowValueVideo> = throttle::run_limited(|| {
+        files
+            .into_par_iter()
+            .filter_map(|(path, size)| {
+                let info = probe(&path).ok();
+                done.fetch_add(1, Ordering::Relaxed);
+                let info = info?;
+                let per_minute = info.size_per_minute(size)?;
+                Some(LowValueVideo { path, size, info, per_minute })
+            })
+            .collect()
+    });
+    videos.sort_by_key(|video| std::cmp::Reverse(video.per_minute));
+    LowValueReport { skipped: total - videos.len(), videos }
+}
+
+#[derive(Default)]
+pub struct LowValueState {
+    pub open: bool,
+    done: Arc<AtomicUsize>,
+    total: usize,
+    job: Option<Job<LowValueReport>>,
+    report: Option<LowValueReport>,
+}
+
+impl LowValueState {
+    /// Draws the window and returns a folder to jump to when a file's location is clicked.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>) -> Option<PathBuf> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Low-Value Media"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr(
+                        "Ranks videos by size per minute of playback, so bloated re-encodes stand out. Needs ffprobe.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Rank Videos"))).clicked() {
+                    let files: Vec<(PathBuf, u64)> = index
+                        .nodes
+                        .iter()
+                        .filter(|node| !node.is_dir && !node.online_only && node.size >= MIN_VIDEO_SIZE)
+                        .filter(|node| category::classify_file(&node.path, node.size) == Category::Video)
+                        .map(|node| (node.path.clone(), node.size))
+                        .collect();
+                    self.total = files.len();
+                    self.done = Arc::default();
+                    let done = Arc::clone(&self.done);
+                    self.job = Some(Job::spawn(move || rank_videos(files, &done)));
+                }
+
+                if self.job.is_some() {
+                    let done = self.done.load(Ordering::Relaxed);
+                    let fraction = if self.total == 0 { 1.0 } else { done as f32 / self.total as f32 };
+                    ui.add(egui::ProgressBar::new(fraction).text(trf(
+                        "{} of {} files",
+                        &[&i18n::format_count(done as u64), &i18n::format_count(self.total as u64)],
+                    )));
+                    return;
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+                if report.videos.is_empty() {
+                    ui.label(tr("No videos found."));
+                }
+                if report.skipped > 0 {
+                    ui.label(
+                        RichText::new(trf("{} files could not be read", &[&i18n::format_count(report.skipped as u64)]))
+                            .color(Color32::GRAY),
+                    );
+                }
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("low_value_media").striped(true).show(ui, |ui| {
+                        ui.label(tr("File"));
+                        ui.label(tr("Size"));
+                        ui.label(tr("Duration"));
+                        ui.label(tr("Resolution"));
+                        ui.label(tr("Size per minute"));
+                        ui.end_row();
+                        for video in report.videos.iter().take(MAX_ROWS) {
+                            let name = paths::display_name(video.path.file_name().unwrap_or_default());
+                            let link = ui.link(name).on_hover_text(paths::display_path(&video.path));
+                            if link.clicked() {
+                                navigate = video.path.parent().map(Path::to_path_buf);
+                            }
+                            ui.label(i18n::format_bytes(video.size));
+                            ui.label(video.info.duration.map(format_duration).unwrap_or_default());
+                            ui.label(
+                                video.info.resolution.map(|(width, height)| format!("{} × {}", width, height)).unwrap_or_default(),
+                            );
+                            ui.label(i18n::format_bytes(video.per_minute));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use crate::monitor::{PathStatus, Statuses};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// Drops clients that stall so they can't hold up the next scrape.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Label values escape backslashes, quotes and newlines.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn write_gauge(
+    output: &mut String,
+    statuses: &[(String, PathStatus)],
+    name: &str,
+    help: &str,
+    value: impl Fn(&PathStatus) -> Option<f64>,
+) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} gauge", name);
+    for (path, status) in statuses {
+        if let Some(value) = value(status) {
+            let _ = writeln!(output, "{}{{path=\"{}\"}} {}", name, path, value);
+        }
+    }
+}
+
+/// The latest measurements in the Prometheus text format.
+pub fn render(statuses: &Statuses) -> String {
+    let statuses: Vec<(String, PathStatus)> = match statuses.lock() {
+        Ok(statuses) => statuses
+            .iter()
+            .map(|(path, status)| (escape_label(&path.to_string_lossy()), status.clone()))
+            .collect(),
+        Err(_) => return String::new(),
+    };
+    let mut output = String::new();
+    write_gauge(&mut output, &statuses, "disk_analyzer_path_bytes", "Total size of the folder.", |status| {
+        Some(status.size as f64)
+    });
+    write_gauge(&mut output, &statuses, "disk_analyzer_path_entries", "Files and folders inside the folder.", |status| {
+        Some(status.entries as f64)
+    });
+    write_gauge(&mut output, &statuses, "disk_analyzer_path_budget_bytes", "Directory budget of the folder.", |status| {
+        status.budget.map(|budget| budget as f64)
+    });
+    write_gauge(
+        &mut output,
+        &statuses,
+        "disk_analyzer_scan_duration_seconds",
+        "How long the last scan of the folder took.",
+        |status| Some(status.scan_duration.as_secs_f64()),
+    );
+    write_gauge(
+        &mut output,
+        &statuses,
+        "disk_analyzer_last_scan_timestamp_seconds",
+        "When the folder was last scanned, as a Unix time.",
+        |status| status.scanned_at.duration_since(UNIX_EPOCH).ok().map(|age| age.as_secs() as f64),
+    );
+    output
+}
+
+fn respond(mut stream: TcpStream, statuses: &Statuses) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let (status, content_type, body) = match request_line.split_whitespace().nth(1) {
+        Some("/metrics") => ("200 OK", CONTENT_TYPE, render(statuses)),
+        _ => ("404 Not Found", "text/plain", "Metrics are served at /metrics\n".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Serves `/metrics` on `address` until the process exits. Requests are answered one at a
+/// time, which is plenty for a scraper polling every few seconds.
+pub fn serve(address: &str, statuses: &Statuses) -> Result<(), String> {
+    let listener = TcpListener::bind(address).map_err(|e| format!("Error listening on {}: {}", address, e))?;
+    println!("Serving metrics at http://{}/metrics", address);
+    for stream in listener.incoming().filter_map(Result::ok) {
+        if let Err(e) = respond(stream, statuses) {
+            eprintln!("Error answering a metrics request: {}", e);
+        }
+    }
+    Ok(())
+}
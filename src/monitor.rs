@@ -0,0 +1,108 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    alerts::{self, Alert, AlertKind},
+    metrics, quotas,
+    scan::{self, ScanOptions},
+};
+
+/// The latest measurement of one watched folder.
+#[derive(Clone)]
+pub struct PathStatus {
+    pub size: u64,
+    pub entries: u64,
+    pub budget: Option<u64>,
+    pub scan_duration: Duration,
+    pub scanned_at: SystemTime,
+}
+
+pub type Statuses = Arc<Mutex<BTreeMap<PathBuf, PathStatus>>>;
+
+pub struct MonitorOptions {
+    pub paths: Vec<PathBuf>,
+    pub interval: Duration,
+    pub scan: ScanOptions,
+    pub metrics_address: String,
+}
+
+/// The folders given on the command line plus every folder with a budget.
+fn watched(paths: &[PathBuf], store: &quotas::QuotaStore) -> Vec<(PathBuf, Option<u64>)> {
+    let mut watched: BTreeMap<PathBuf, Option<u64>> = paths.iter().map(|path| (path.clone(), None)).collect();
+    for (path, limit) in store.budgets() {
+        watched.insert(path, Some(limit));
+    }
+    watched.into_iter().collect()
+}
+
+/// Measures every watched folder once and sends the configured alerts. A folder already over
+/// budget on the first round isn't reported as a new breach, like in the app.
+fn measure_all(
+    paths: &[PathBuf],
+    options: &ScanOptions,
+    statuses: &Statuses,
+    breached: &mut HashSet<PathBuf>,
+    first_round: bool,
+) {
+    // Reloaded every round so budgets and alert settings changed in the app apply.
+    let store = quotas::QuotaStore::load();
+    for (path, budget) in watched(paths, &store) {
+        let started = Instant::now();
+        let measurement = scan::measure_dir(&path, options);
+        let status = PathStatus {
+            size: measurement.size,
+            entries: measurement.entries,
+            budget,
+            scan_duration: started.elapsed(),
+            scanned_at: SystemTime::now(),
+        };
+        let over = budget.is_some_and(|budget| status.size > budget);
+        let kind = if over && !breached.contains(&path) && !first_round {
+            Some(AlertKind::OverBudget)
+        } else if store.alerts.on_scan_finished {
+            Some(AlertKind::ScanFinished)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            let alert = Alert { kind, path: path.clone(), size: status.size, entries: status.entries, budget };
+            if let Err(e) = alerts::send(&store.alerts, &alert, options) {
+                eprintln!("{}", e);
+            }
+        }
+        if over {
+            breached.insert(path.clone());
+        } else {
+            breached.remove(&path);
+        }
+        if let Ok(mut statuses) = statuses.lock() {
+            statuses.insert(path, status);
+        }
+    }
+}
+
+/// Runs without a window: measures the watched folders every interval, sends alerts and serves
+/// the latest results to Prometheus until the process is stopped.
+pub fn run(options: MonitorOptions) -> Result<(), String> {
+    if watched(&options.paths, &quotas::QuotaStore::load()).is_empty() {
+        return Err("Nothing to monitor: pass a PATH or --watch, or set directory budgets".to_string());
+    }
+    let statuses = Statuses::default();
+    let worker_statuses = Arc::clone(&statuses);
+    let MonitorOptions { paths, interval, scan, metrics_address } = options;
+    thread::spawn(move || {
+        let mut breached = HashSet::new();
+        let mut first_round = true;
+        loop {
+            measure_all(&paths, &scan, &worker_statuses, &mut breached, first_round);
+            first_round = false;
+            thread::sleep(interval);
+        }
+    });
+    metrics::serve(&metrics_address, &statuses)
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+pub struct Mount {
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+#[cfg(target_os = "linux")]
+pub fn all() -> Vec<Mount> {
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().map(|field| field.replace("\\040", " "));
+            let device = fields.next()?;
+            let mount_point = PathBuf::from(fields.next()?);
+            let fs_type = fields.next()?;
+            Some(Mount { device, mount_point, fs_type })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn all() -> Vec<Mount> {
+    use std::ffi::CStr;
+
+    let text = |field: &[libc::c_char]| unsafe { CStr::from_ptr(field.as_ptr()) }.to_string_lossy().into_owned();
+    let mut mounts: *mut libc::statfs = std::ptr::null_mut();
+    let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+    if count <= 0 || mounts.is_null() {
+        return Vec::new();
+    }
+    unsafe { std::slice::from_raw_parts(mounts, count as usize) }
+        .iter()
+        .map(|mount| Mount {
+            device: text(&mount.f_mntfromname),
+            mount_point: PathBuf::from(text(&mount.f_mntonname)),
+            fs_type: text(&mount.f_fstypename),
+        })
+        .collect()
+}
+
+/// Every drive letter. Local drives have their volume name (`\\?\Volume{...}\`) as the
+/// device, the others just the letter.
+#[cfg(windows)]
+pub fn all() -> Vec<Mount> {
+    use std::{ffi::OsString, os::windows::ffi::OsStringExt, ptr};
+    use winapi::um::{
+        fileapi::{GetDriveTypeW, GetLogicalDriveStringsW, GetVolumeInformationW, GetVolumeNameForVolumeMountPointW},
+        winbase::{DRIVE_FIXED, DRIVE_REMOVABLE},
+    };
+
+    let text = |buffer: &[u16]| {
+        let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        OsString::from_wide(&buffer[..end]).to_string_lossy().into_owned()
+    };
+    let mut roots = [0u16; 512];
+    let length = unsafe { GetLogicalDriveStringsW(roots.len() as u32, roots.as_mut_ptr()) } as usize;
+    roots[..length.min(roots.len())]
+        .split(|&c| c == 0)
+        .filter(|root| !root.is_empty())
+        .map(|root| {
+            let root: Vec<u16> = root.iter().copied().chain(Some(0)).collect();
+            let mut fs_type = [0u16; 64];
+            let mut volume = [0u16; 64];
+            unsafe {
+                GetVolumeInformationW(
+                    root.as_ptr(),
+                    ptr::null_mut(),
+                    0,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    fs_type.as_mut_ptr(),
+                    fs_type.len() as u32,
+                );
+            }
+            let local = matches!(unsafe { GetDriveTypeW(root.as_ptr()) }, DRIVE_FIXED | DRIVE_REMOVABLE);
+            let named = local
+                && unsafe { GetVolumeNameForVolumeMountPointW(root.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) }
+                    != 0;
+            let mount_point = text(&root);
+            Mount {
+                device: if named { text(&volume) } else { mount_point.clone() },
+                mount_point: PathBuf::from(mount_point),
+                fs_type: text(&fs_type),
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn all() -> Vec<Mount> {
+    Vec::new()
+}
+
+/// Whether the mount is a disk worth showing rather than a pseudo, network or loop mount.
+/// macOS mounts its system volumes under /System/Volumes next to the one users see as /.
+fn is_disk(mount: &Mount) -> bool {
+    if cfg!(windows) {
+        return mount.device.starts_with(r"\\?\Volume");
+    }
+    mount.device.starts_with("/dev/")
+        && !mount.device.starts_with("/dev/loop")
+        && !(cfg!(target_os = "macos") && mount.mount_point.starts_with("/System/Volumes"))
+}
+
+/// Mount points of disks, one per device.
+pub fn disks() -> Vec<PathBuf> {
+    let mut devices = HashSet::new();
+    all()
+        .into_iter()
+        .filter(is_disk)
+        .filter(|mount| devices.insert(mount.device.clone()))
+        .map(|mount| mount.mount_point)
+        .collect()
+}
+
+/// Finds the mount that contains `path` (the longest matching mount point).
+pub fn mount_of(path: &Path) -> Option<Mount> {
+    all()
+        .into_iter()
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.as_os_str().len())
+}
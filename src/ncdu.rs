@@ -0,0 +1,134 @@
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::index::{Node, ScanIndex};
+
+/// The `ncdu -o` export format, version 1.2: `[1, 2, {metadata}, root]`, where a folder is an
+/// array of its own info object followed by its children and a file is just its info object.
+const MAJOR_VERSION: u64 = 1;
+const MINOR_VERSION: u64 = 2;
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |age| age.as_secs())
+}
+
+/// ncdu counts a folder's own entry into its size, so files low-memory mode only counted go
+/// there and the folder totals come out the same.
+fn info(index: &ScanIndex, id: usize) -> Value {
+    let node = &index.nodes[id];
+    let name = if id == 0 { node.path.to_string_lossy() } else { node.name.to_string_lossy() };
+    let (size, logical) = if node.is_dir { (node.small_size, 0) } else { (node.size, node.logical) };
+    let mut info = json!({ "name": name, "asize": logical.max(size), "dsize": size });
+    if let Some(modified) = node.modified {
+        info["mtime"] = json!(seconds(modified));
+    }
+    if node.unreachable {
+        info["read_error"] = json!(true);
+    }
+    info
+}
+
+fn write_entry(writer: &mut impl Write, index: &ScanIndex, id: usize) -> std::io::Result<()> {
+    let node = &index.nodes[id];
+    if !node.is_dir {
+        return serde_json::to_writer(&mut *writer, &info(index, id)).map_err(Into::into);
+    }
+    writer.write_all(b"[")?;
+    serde_json::to_writer(&mut *writer, &info(index, id))?;
+    for &child in &node.children {
+        writer.write_all(b",\n")?;
+        write_entry(writer, index, child)?;
+    }
+    writer.write_all(b"]")
+}
+
+pub fn export(index: &ScanIndex, path: &Path) -> Result<(), String> {
+    if index.nodes.is_empty() {
+        return Err("Nothing has been scanned yet".to_string());
+    }
+    let error = |e: std::io::Error| format!("Error writing {}: {}", path.display(), e);
+    let mut writer = BufWriter::new(File::create(path).map_err(error)?);
+    let metadata = json!({
+        "progname": "disk-analyzer",
+        "progver": env!("CARGO_PKG_VERSION"),
+        "timestamp": seconds(SystemTime::now()),
+    });
+    writeln!(writer, "[{},{},{},", MAJOR_VERSION, MINOR_VERSION, metadata).map_err(error)?;
+    write_entry(&mut writer, index, 0).map_err(error)?;
+    writer.write_all(b"]\n").map_err(error)?;
+    writer.flush().map_err(error)
+}
+
+/// Reads an ncdu export into an index, returning the scanned root and when ncdu ran.
+/// Excluded entries are left out, and a folder's own entry size is ignored like in scans.
+pub fn import(path: &Path) -> Result<(PathBuf, u64, ScanIndex), String> {
+    let file = File::open(path).map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+    let export: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Error reading ncdu export {}: {}", path.display(), e))?;
+    let invalid = || format!("{} is not an ncdu export", path.display());
+    let parts = export.as_array().ok_or_else(invalid)?;
+    if parts.first().and_then(Value::as_u64) != Some(MAJOR_VERSION) || parts.len() < 4 {
+        return Err(invalid());
+    }
+    let timestamp = parts[2]["timestamp"].as_u64().unwrap_or_default();
+
+    let root_info = parts[3].as_array().and_then(|dir| dir.first()).ok_or_else(invalid)?;
+    let root = PathBuf::from(root_info["name"].as_str().ok_or_else(invalid)?);
+    let mut nodes: Vec<Node> = Vec::new();
+    // Folders are added before what's inside them, as the index expects.
+    let mut pending: Vec<(&Value, Option<usize>)> = vec![(&parts[3], None)];
+    while let Some((entry, parent)) = pending.pop() {
+        let (info, children) = match entry {
+            Value::Array(dir) => match dir.split_first() {
+                Some((info, children)) => (info, Some(children)),
+                None => continue,
+            },
+            info => (info, None),
+        };
+        if info.get("excluded").is_some() {
+            continue;
+        }
+        let name = info["name"].as_str().unwrap_or_default().to_string();
+        let id = nodes.len();
+        let node_path = match parent {
+            Some(parent) => nodes[parent].path.join(&name),
+            None => root.clone(),
+        };
+        // ncdu's disk usage includes the slack in the last block, which sizes here leave out.
+        let (asize, dsize) = (info["asize"].as_u64(), info["dsize"].as_u64());
+        let logical = asize.or(dsize).unwrap_or_default();
+        let size = dsize.map_or(logical, |dsize| dsize.min(logical));
+        nodes.push(Node {
+            path: node_path,
+            name: match root.file_name().filter(|_| parent.is_none()) {
+                Some(file) => file.to_os_string(),
+                None => name.into(),
+            },
+            size: if children.is_some() { 0 } else { size },
+            logical: if children.is_some() { 0 } else { logical },
+            entries: 0,
+            is_dir: children.is_some(),
+            modified: info["mtime"].as_u64().map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+            // ncdu doesn't record access times.
+            accessed: None,
+            unreachable: info["read_error"].as_bool().unwrap_or_default(),
+            online_only: false,
+            parent,
+            children: Vec::new(),
+            small_files: 0,
+            small_size: 0,
+        });
+        if let Some(parent) = parent {
+            nodes[parent].children.push(id);
+        }
+        for child in children.into_iter().flatten().rev() {
+            pending.push((child, Some(id)));
+        }
+    }
+    Ok((root, timestamp, ScanIndex::from_unfinished(nodes)))
+}
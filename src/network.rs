@@ -0,0 +1,101 @@
+use std::{
+    ffi::OsString,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    scan::{self, ScanOptions},
+    throttle,
+};
+
+pub const WORKERS: usize = 16;
+pub const ENTRY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Directory reads the async walk keeps going at once; they cost no threads while waiting.
+#[cfg(not(target_arch = "wasm32"))]
+pub const ASYNC_IN_FLIGHT: usize = 64;
+
+#[cfg(target_os = "linux")]
+const NETWORK_FILESYSTEMS: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs", "9p", "afs", "ceph"];
+
+pub struct Listed {
+    pub path: PathBuf,
+    pub name: OsString,
+    pub metadata: Metadata,
+}
+
+/// Detects SMB/NFS style mounts so network mode can be switched on automatically.
+#[cfg(target_os = "linux")]
+pub fn is_network_path(path: &Path) -> bool {
+    crate::mounts::mount_of(path).is_some_and(|mount| NETWORK_FILESYSTEMS.contains(&mount.fs_type.as_str()))
+}
+
+#[cfg(windows)]
+pub fn is_network_path(path: &Path) -> bool {
+    path.to_string_lossy().starts_with(r"\\")
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn is_network_path(_path: &Path) -> bool {
+    false
+}
+
+/// Leaves out what ignore files hide when the scan respects them. Finding the rules reads the
+/// folder once more, so nothing is read when ignores are off.
+pub fn drop_ignored(dir: &Path, listing: Vec<Listed>, options: &ScanOptions) -> Vec<Listed> {
+    if !options.respect_ignores {
+        return listing;
+    }
+    let unignored = scan::unignored_children(dir, options);
+    listing.into_iter().filter(|entry| unignored.contains(&entry.path)).collect()
+}
+
+/// Reads a whole directory, including every entry's metadata, in one batch on a helper thread.
+/// Returns `None` if the directory can't be read or doesn't answer within `timeout`; a hung
+/// helper is left behind rather than blocking the scan.
+pub fn list_dir(dir: &Path, timeout: Duration) -> Option<Vec<Listed>> {
+    let (sender, receiver) = mpsc::channel();
+    let dir = dir.to_path_buf();
+    thread::spawn(move || {
+        let listing = fs::read_dir(&dir).ok().map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    throttle::pace();
+                    let metadata = entry.metadata().ok()?;
+                    Some(Listed { path: entry.path(), name: entry.file_name(), metadata })
+                })
+                .collect::<Vec<_>>()
+        });
+        let _ = sender.send(listing);
+    });
+    receiver.recv_timeout(timeout).ok().flatten()
+}
+
+/// Async counterpart of `list_dir` for the tokio walk: reads `dir` once a permit is free and
+/// hands back `id` so the result can be attached to its node.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn list_dir_async(
+    id: usize,
+    dir: PathBuf,
+    permits: std::sync::Arc<tokio::sync::Semaphore>,
+) -> (usize, Option<Vec<Listed>>) {
+    let Ok(_permit) = permits.acquire_owned().await else {
+        return (id, None);
+    };
+    let listing = tokio::time::timeout(ENTRY_TIMEOUT, async {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        let mut listed = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if let Ok(metadata) = entry.metadata().await {
+                listed.push(Listed { path: entry.path(), name: entry.file_name(), metadata });
+            }
+        }
+        Ok::<_, std::io::Error>(listed)
+    })
+    .await;
+    (id, listing.ok().and_then(Result::ok))
+}
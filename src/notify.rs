@@ -0,0 +1,49 @@
+use std::process::Command;
+
+/// Shows a desktop notification through the platform's own tool.
+pub fn send(title: &str, body: &str) -> Result<(), String> {
+    let mut command = notification_command(title, body);
+    let output = command.output().map_err(|e| format!("Error sending notification: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(title: &str, body: &str) -> Command {
+    let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command.args([
+        "-e",
+        &format!("display notification \"{}\" with title \"{}\"", quote(body), quote(title)),
+    ]);
+    command
+}
+
+#[cfg(windows)]
+fn notification_command(title: &str, body: &str) -> Command {
+    let quote = |text: &str| text.replace('\'', "''");
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $icon = New-Object System.Windows.Forms.NotifyIcon; \
+         $icon.Icon = [System.Drawing.SystemIcons]::Warning; \
+         $icon.Visible = $true; \
+         $icon.ShowBalloonTip(10000, '{}', '{}', 'Warning'); \
+         Start-Sleep -Seconds 10; \
+         $icon.Dispose()",
+        quote(title),
+        quote(body)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn notification_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=Disk Analyzer", title, body]);
+    command
+}
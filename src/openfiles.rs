@@ -0,0 +1,336 @@
+use eframe::egui::{self, Color32, RichText};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    i18n::{self, tr, trf},
+    job::Job,
+    paths,
+};
+
+pub struct OpenFile {
+    pub path: PathBuf,
+    pub writing: bool,
+}
+
+pub struct Holder {
+    pub pid: u32,
+    pub name: String,
+    /// Empty where the platform only reports which processes are involved, not which files.
+    pub files: Vec<OpenFile>,
+}
+
+pub struct OpenFilesReport {
+    pub holders: Vec<Holder>,
+    /// Processes that couldn't be inspected, usually ones owned by other users.
+    pub inaccessible: usize,
+}
+
+/// Reads `/proc/<pid>/fd` directly, the same source lsof uses.
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{fs, path::Path};
+
+    use super::{Holder, OpenFile, OpenFilesReport};
+
+    /// The access mode bits of `flags:` in fdinfo are non-zero for O_WRONLY and O_RDWR.
+    fn opened_for_writing(fdinfo: &str) -> bool {
+        fdinfo
+            .lines()
+            .find_map(|line| line.strip_prefix("flags:"))
+            .and_then(|flags| u32::from_str_radix(flags.trim(), 8).ok())
+            .is_some_and(|flags| flags & 0o3 != 0)
+    }
+
+    pub fn holders(target: &Path) -> Result<OpenFilesReport, String> {
+        let processes = fs::read_dir("/proc").map_err(|e| format!("Error reading /proc: {}", e))?;
+        let mut report = OpenFilesReport { holders: Vec::new(), inaccessible: 0 };
+        for process in processes.filter_map(Result::ok) {
+            let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(descriptors) = fs::read_dir(process.path().join("fd")) else {
+                report.inaccessible += 1;
+                continue;
+            };
+            let mut files = Vec::new();
+            for descriptor in descriptors.filter_map(Result::ok) {
+                let Ok(path) = fs::read_link(descriptor.path()) else {
+                    continue;
+                };
+                if path.starts_with(target) {
+                    let fdinfo = process.path().join("fdinfo").join(descriptor.file_name());
+                    let writing = fs::read_to_string(fdinfo).is_ok_and(|info| opened_for_writing(&info));
+                    files.push(OpenFile { path, writing });
+                }
+            }
+            if !files.is_empty() {
+                let name = fs::read_to_string(process.path().join("comm")).unwrap_or_default();
+                report.holders.push(Holder { pid, name: name.trim().to_string(), files });
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+mod platform {
+    use std::{path::Path, process::Command};
+
+    use super::{Holder, OpenFile, OpenFilesReport};
+
+    /// Parses `lsof -F pcan` output: a `p` line starts each process, `c` names it, and each
+    /// open file is an `a` (access mode) line followed by its `n` (name) line.
+    pub fn holders(target: &Path) -> Result<OpenFilesReport, String> {
+        let output = Command::new("lsof")
+            .args(["-n", "-P", "-F", "pcan"])
+            .output()
+            .map_err(|e| format!("Error running lsof: {}", e))?;
+        let mut report = OpenFilesReport { holders: Vec::new(), inaccessible: 0 };
+        let mut current: Option<Holder> = None;
+        let mut writing = false;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let (field, value) = line.split_at(line.len().min(1));
+            match field {
+                "p" => {
+                    report.holders.extend(current.take().filter(|holder| !holder.files.is_empty()));
+                    current = value.parse().ok().map(|pid| Holder { pid, name: String::new(), files: Vec::new() });
+                }
+                "c" => {
+                    if let Some(holder) = &mut current {
+                        holder.name = value.to_string();
+                    }
+                }
+                "a" => writing = value.contains('w') || value.contains('u'),
+                "n" => {
+                    if let Some(holder) = current.as_mut().filter(|_| Path::new(value).starts_with(target)) {
+                        holder.files.push(OpenFile { path: value.into(), writing });
+                    }
+                }
+                _ => {}
+            }
+        }
+        report.holders.extend(current.filter(|holder| !holder.files.is_empty()));
+        Ok(report)
+    }
+}
+
+/// Asks the Restart Manager which processes use any file below the target. It names the
+/// processes but not the individual files.
+#[cfg(windows)]
+mod platform {
+    use std::{os::windows::ffi::OsStrExt, path::Path, ptr};
+    use walkdir::WalkDir;
+    use winapi::um::restartmanager::{
+        RmEndSession, RmForceShutdown, RmGetList, RmRegisterResources, RmShutdown, RmStartSession, RM_PROCESS_INFO,
+    };
+
+    use super::{Holder, OpenFilesReport};
+    use crate::paths;
+
+    const SESSION_KEY_LENGTH: usize = 33;
+    const ERROR_MORE_DATA: u32 = 234;
+    /// Registering every file of a huge tree makes the query crawl; the first ones are enough
+    /// to catch the usual culprits.
+    const MAX_FILES: usize = 10_000;
+
+    fn wide(path: &Path) -> Vec<u16> {
+        paths::extended(path).as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn register(session: u32, names: &mut [*const u16]) -> Result<(), String> {
+        let registered = unsafe {
+            RmRegisterResources(session, names.len() as u32, names.as_mut_ptr(), 0, ptr::null_mut(), 0, ptr::null_mut())
+        };
+        if registered != 0 {
+            return Err(format!("Error registering files with the Restart Manager ({})", registered));
+        }
+        Ok(())
+    }
+
+    fn query(session: u32) -> Result<Vec<RM_PROCESS_INFO>, String> {
+        let mut infos: Vec<RM_PROCESS_INFO> = Vec::new();
+        loop {
+            let (mut needed, mut count, mut reasons) = (0, infos.len() as u32, 0);
+            let status = unsafe { RmGetList(session, &mut needed, &mut count, infos.as_mut_ptr(), &mut reasons) };
+            match status {
+                0 => {
+                    infos.truncate(count as usize);
+                    return Ok(infos);
+                }
+                ERROR_MORE_DATA => infos = (0..needed).map(|_| unsafe { std::mem::zeroed() }).collect(),
+                status => return Err(format!("Error querying the Restart Manager ({})", status)),
+            }
+        }
+    }
+
+    /// Runs `action` in a Restart Manager session with the files below `target` registered.
+    fn with_session<T>(target: &Path, action: impl FnOnce(u32) -> Result<T, String>) -> Result<T, String> {
+        let files: Vec<Vec<u16>> = WalkDir::new(target)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .take(MAX_FILES)
+            .map(|entry| wide(entry.path()))
+            .collect();
+        let mut names: Vec<*const u16> = files.iter().map(|file| file.as_ptr()).collect();
+
+        let mut session = 0;
+        let mut key = [0u16; SESSION_KEY_LENGTH];
+        if unsafe { RmStartSession(&mut session, 0, key.as_mut_ptr()) } != 0 {
+            return Err("Error starting a Restart Manager session".to_string());
+        }
+        let result = register(session, &mut names).and_then(|()| action(session));
+        unsafe { RmEndSession(session) };
+        result
+    }
+
+    pub fn holders(target: &Path) -> Result<OpenFilesReport, String> {
+        let holders = with_session(target, query)?
+            .iter()
+            .map(|info| {
+                let length = info.strAppName.iter().position(|&c| c == 0).unwrap_or(info.strAppName.len());
+                Holder {
+                    pid: info.Process.dwProcessId,
+                    name: String::from_utf16_lossy(&info.strAppName[..length]),
+                    files: Vec::new(),
+                }
+            })
+            .collect();
+        Ok(OpenFilesReport { holders, inaccessible: 0 })
+    }
+
+    /// Shuts down the applications using the files; unsaved work in them is lost.
+    pub fn close_holders(target: &Path) -> Result<(), String> {
+        with_session(target, |session| match unsafe { RmShutdown(session, RmForceShutdown, None) } {
+            0 => Ok(()),
+            status => Err(format!("Error closing programs using the files ({})", status)),
+        })
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::path::Path;
+
+    use super::OpenFilesReport;
+
+    pub fn holders(_target: &Path) -> Result<OpenFilesReport, String> {
+        Err("Listing open files isn't supported on this platform".to_string())
+    }
+}
+
+/// Open files only block deletion on Windows, so that's the only place they get closed.
+#[cfg(windows)]
+pub use platform::close_holders;
+
+/// Lists processes holding files open at or below `target`, largest number of files first.
+pub fn holders(target: &Path) -> Result<OpenFilesReport, String> {
+    let mut report = platform::holders(target)?;
+    report.holders.sort_by_key(|holder| std::cmp::Reverse(holder.files.len()));
+    Ok(report)
+}
+
+#[derive(Default)]
+pub struct OpenFilesState {
+    pub open: bool,
+    path: Option<PathBuf>,
+    job: Option<Job<Result<OpenFilesReport, String>>>,
+    report: Option<Result<OpenFilesReport, String>>,
+}
+
+impl OpenFilesState {
+    pub fn inspect(&mut self, path: PathBuf) {
+        let worker_path = path.clone();
+        self.job = Some(Job::spawn(move || holders(&worker_path)));
+        self.path = Some(path);
+        self.report = None;
+        self.open = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        let mut refresh = false;
+        egui::Window::new(tr("Open Files"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(path) = &self.path {
+                        ui.label(RichText::new(paths::display_path(path)).strong());
+                    }
+                    refresh = ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Refresh"))).clicked();
+                });
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let report = match &self.report {
+                    Some(Ok(report)) => report,
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                        return;
+                    }
+                    None => return,
+                };
+
+                if report.holders.is_empty() {
+                    ui.label(tr("No process has files open here."));
+                } else {
+                    ui.label(trf("{} processes have files open here", &[&i18n::format_count(report.holders.len() as u64)]));
+                }
+                if report.inaccessible > 0 {
+                    ui.label(
+                        RichText::new(trf(
+                            "{} processes could not be inspected; run as administrator to include them",
+                            &[&i18n::format_count(report.inaccessible as u64)],
+                        ))
+                        .color(Color32::GRAY),
+                    );
+                }
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for holder in &report.holders {
+                        let title = if holder.files.is_empty() {
+                            format!("{} (PID {})", holder.name, holder.pid)
+                        } else {
+                            trf(
+                                "{} (PID {}) - {} files",
+                                &[&holder.name, &holder.pid, &i18n::format_count(holder.files.len() as u64)],
+                            )
+                        };
+                        let writing = holder.files.iter().any(|file| file.writing);
+                        let title = if writing { RichText::new(title).color(Color32::YELLOW) } else { RichText::new(title) };
+                        egui::CollapsingHeader::new(title).id_source(("open_files", holder.pid)).show(ui, |ui| {
+                            if holder.files.is_empty() {
+                                ui.label(RichText::new(tr("The system doesn't report which files")).color(Color32::GRAY));
+                            }
+                            for file in &holder.files {
+                                ui.horizontal(|ui| {
+                                    ui.label(paths::display_path(&file.path));
+                                    if file.writing {
+                                        ui.label(RichText::new(tr("writing")).small().color(Color32::YELLOW));
+                                    }
+                                });
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        if refresh {
+            if let Some(path) = self.path.clone() {
+                self.inspect(path);
+            }
+        }
+    }
+}
@@ -0,0 +1,689 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::{Child, Command},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use crate::{
+    a11y, archive, deletion,
+    error::Error,
+    i18n::{self, tr, trf},
+    job::Job,
+    paths, trash, wipe,
+};
+
+/// How many tasks run at once under the parallel policy.
+const PARALLEL_TASKS: usize = 4;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, PartialEq)]
+pub enum Action {
+    Delete,
+    SecureDelete,
+    /// Moves the item into the given folder.
+    Move(PathBuf),
+    /// Moves the item to the given path, which may differ from its name. With `replace`,
+    /// whatever is there already is replaced once the item has arrived.
+    MoveAs { target: PathBuf, replace: bool },
+    /// Copies the item into the given folder and checks the copy against hashes of the
+    /// original.
+    Copy(PathBuf),
+    /// Like `MoveAs`, for a copy.
+    CopyAs { target: PathBuf, replace: bool },
+    /// Packs the item into a `.tar.gz` beside it, leaving the original in place.
+    Compress,
+    /// Has btrfs compress the item where it is.
+    CompressInPlace,
+    /// Copies the item into the given folder with a verified manifest, then optionally
+    /// deletes the original.
+    Archive { destination: PathBuf, delete_originals: bool },
+    /// Puts an item from the trash back at `target`, where it was deleted from, and drops its
+    /// trash record. `replace` works as for `MoveAs`.
+    Restore { target: PathBuf, replace: bool },
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Delete => tr("Delete"),
+            Action::SecureDelete => tr("Secure delete"),
+            Action::Move(_) | Action::MoveAs { .. } => tr("Move"),
+            Action::Copy(_) | Action::CopyAs { .. } => tr("Copy"),
+            Action::Compress => tr("Compress"),
+            Action::CompressInPlace => tr("Compress in place"),
+            Action::Archive { .. } => tr("Archive"),
+            Action::Restore { .. } => tr("Restore"),
+        }
+    }
+
+    /// Whether progress can be measured against the item's size.
+    fn measurable(&self) -> bool {
+        !matches!(self, Action::Compress | Action::CompressInPlace)
+    }
+
+    /// Where the item at `path` ends up, for actions that can find that path taken.
+    pub fn target(&self, path: &Path) -> Option<PathBuf> {
+        match self {
+            Action::Move(destination) | Action::Copy(destination) => path.file_name().map(|name| destination.join(name)),
+            Action::Restore { target, replace: false } => Some(target.clone()),
+            _ => None,
+        }
+    }
+
+    /// What to run instead when the item's name is already taken at `target`; nothing when
+    /// it's skipped.
+    pub fn resolve(&self, target: &Path, resolution: Resolution) -> Option<Action> {
+        match (self, resolution) {
+            (_, Resolution::Skip) => None,
+            (Action::Move(_), Resolution::Overwrite) => {
+                Some(Action::MoveAs { target: target.to_path_buf(), replace: true })
+            }
+            (Action::Move(_), Resolution::Rename) => {
+                Some(Action::MoveAs { target: unused_target(target), replace: false })
+            }
+            (Action::Copy(_), Resolution::Overwrite) => {
+                Some(Action::CopyAs { target: target.to_path_buf(), replace: true })
+            }
+            (Action::Copy(_), Resolution::Rename) => {
+                Some(Action::CopyAs { target: unused_target(target), replace: false })
+            }
+            (Action::Restore { .. }, Resolution::Overwrite) => {
+                Some(Action::Restore { target: target.to_path_buf(), replace: true })
+            }
+            (Action::Restore { .. }, Resolution::Rename) => {
+                Some(Action::Restore { target: unused_target(target), replace: false })
+            }
+            _ => Some(self.clone()),
+        }
+    }
+}
+
+/// What to do with an item whose name is already taken where it's going.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Overwrite,
+    Skip,
+    /// Give it the first free name of the form "name (2).ext".
+    Rename,
+}
+
+impl Resolution {
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Overwrite => tr("Overwrite"),
+            Resolution::Skip => tr("Skip"),
+            Resolution::Rename => tr("Rename"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Policy {
+    Sequential,
+    Parallel,
+}
+
+impl Policy {
+    fn limit(&self) -> usize {
+        match self {
+            Policy::Sequential => 1,
+            Policy::Parallel => PARALLEL_TASKS,
+        }
+    }
+}
+
+/// Shared between a task and its worker: bytes processed so far plus the pause and cancel
+/// switches the worker checks between units of work.
+#[derive(Default)]
+pub struct Progress {
+    done: AtomicU64,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    /// Entries a deletion had to leave behind.
+    failures: Mutex<Vec<deletion::Failure>>,
+}
+
+impl Progress {
+    pub fn advance(&self, bytes: u64) {
+        self.done.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Waits while the task is paused and fails once it has been cancelled.
+    pub fn checkpoint(&self) -> Result<(), Error> {
+        loop {
+            if self.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+            if !self.is_paused() {
+                return Ok(());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Status {
+    Queued,
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+impl Status {
+    fn finished(&self) -> bool {
+        matches!(self, Status::Done | Status::Failed(_) | Status::Cancelled)
+    }
+}
+
+pub struct Task {
+    pub action: Action,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub status: Status,
+    progress: Arc<Progress>,
+    job: Option<Job<Result<(), Error>>>,
+}
+
+impl Task {
+    fn start(&mut self) {
+        let action = self.action.clone();
+        let path = self.path.clone();
+        let is_dir = self.is_dir;
+        let progress = Arc::clone(&self.progress);
+        self.job = Some(Job::spawn(move || run(&action, &path, is_dir, &progress)));
+        self.status = Status::Running;
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.size == 0 {
+            1.0
+        } else {
+            (self.progress.done() as f64 / self.size as f64).min(1.0) as f32
+        }
+    }
+}
+
+/// A task that just stopped, handed back so the caller can update its views and the audit log.
+pub struct Finished {
+    pub action: Action,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub result: Result<(), Error>,
+    pub failures: Vec<deletion::Failure>,
+}
+
+#[derive(Default)]
+pub struct Summary {
+    pub running: usize,
+    pub queued: usize,
+    pub done: u64,
+    pub total: u64,
+}
+
+pub struct OperationQueue {
+    pub policy: Policy,
+    tasks: Vec<Task>,
+}
+
+impl Default for OperationQueue {
+    fn default() -> Self {
+        Self { policy: Policy::Sequential, tasks: Vec::new() }
+    }
+}
+
+impl OperationQueue {
+    pub fn push(&mut self, action: Action, path: PathBuf, is_dir: bool, size: u64) {
+        self.tasks.push(Task {
+            action,
+            path,
+            is_dir,
+            size,
+            status: Status::Queued,
+            progress: Arc::default(),
+            job: None,
+        });
+    }
+
+    /// Tasks started and waiting, and the bytes the measurable running ones have processed
+    /// out of their total, for the status bar.
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::default();
+        for task in &self.tasks {
+            match task.status {
+                Status::Running => {
+                    summary.running += 1;
+                    if task.action.measurable() {
+                        summary.done += task.progress.done().min(task.size);
+                        summary.total += task.size;
+                    }
+                }
+                Status::Queued => summary.queued += 1,
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.tasks.iter().any(|task| !task.status.finished())
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.tasks.iter().any(|task| !task.status.finished() && task.path == path)
+    }
+
+    /// Collects finished workers and starts queued tasks as the policy allows.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Vec<Finished> {
+        let mut finished = Vec::new();
+        for task in &mut self.tasks {
+            let Some(result) = task.job.as_ref().and_then(|job| job.poll()) else {
+                continue;
+            };
+            task.job = None;
+            task.status = match &result {
+                Ok(()) => Status::Done,
+                Err(Error::Cancelled) => Status::Cancelled,
+                Err(error) => Status::Failed(error.to_string()),
+            };
+            finished.push(Finished {
+                action: task.action.clone(),
+                path: task.path.clone(),
+                is_dir: task.is_dir,
+                size: task.size,
+                result,
+                failures: task.progress.failures.lock().map(|mut failures| std::mem::take(&mut *failures)).unwrap_or_default(),
+            });
+        }
+
+        let running = self.tasks.iter().filter(|task| task.status == Status::Running).count();
+        let available = self.policy.limit().saturating_sub(running);
+        for task in self
+            .tasks
+            .iter_mut()
+            .filter(|task| task.status == Status::Queued && !task.progress.is_paused())
+            .take(available)
+        {
+            task.start();
+        }
+
+        if self.is_busy() {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+        finished
+    }
+
+    fn set_paused(&self, paused: bool) {
+        for task in &self.tasks {
+            task.progress.paused.store(paused, Ordering::Relaxed);
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Run tasks:"));
+            ui.radio_value(&mut self.policy, Policy::Sequential, tr("One at a time"));
+            ui.radio_value(&mut self.policy, Policy::Parallel, tr("In parallel"));
+            ui.separator();
+            if ui.button(tr("Pause all")).clicked() {
+                self.set_paused(true);
+            }
+            if ui.button(tr("Resume all")).clicked() {
+                self.set_paused(false);
+            }
+            if ui.button(tr("Clear finished")).clicked() {
+                self.tasks.retain(|task| !task.status.finished());
+            }
+        });
+        if self.tasks.is_empty() {
+            ui.label(tr("No operations queued."));
+            return;
+        }
+
+        let mut cancelled = Vec::new();
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            egui::Grid::new("operations").striped(true).show(ui, |ui| {
+                for (position, task) in self.tasks.iter().enumerate() {
+                    ui.label(task.action.label());
+                    let target = match &task.action {
+                        Action::Move(destination)
+                        | Action::MoveAs { target: destination, .. }
+                        | Action::Copy(destination)
+                        | Action::CopyAs { target: destination, .. }
+                        | Action::Restore { target: destination, .. }
+                        | Action::Archive { destination, .. } => format!(
+                            "{} → {}",
+                            paths::display_path(&task.path),
+                            paths::display_path(destination)
+                        ),
+                        _ => paths::display_path(&task.path).into_owned(),
+                    };
+                    ui.label(target);
+
+                    let paused = task.progress.is_paused() && !task.status.finished();
+                    match &task.status {
+                        Status::Failed(error) => {
+                            ui.label(RichText::new(error).color(Color32::RED));
+                        }
+                        Status::Cancelled => {
+                            ui.label(RichText::new(tr("Cancelled")).color(Color32::GRAY));
+                        }
+                        Status::Done => {
+                            ui.label(RichText::new(tr("Done")).color(Color32::GREEN));
+                        }
+                        Status::Queued if !paused => {
+                            ui.label(tr("Queued"));
+                        }
+                        _ if task.action.measurable() => {
+                            let text = format!(
+                                "{} / {}",
+                                i18n::format_bytes(task.progress.done()),
+                                i18n::format_bytes(task.size)
+                            );
+                            let text = if paused { trf("{} (paused)", &[&text]) } else { text };
+                            ui.add(egui::ProgressBar::new(task.fraction()).desired_width(200.0).text(text));
+                        }
+                        _ => {
+                            let text = trf("{} written", &[&i18n::format_bytes(task.progress.done())]);
+                            ui.label(if paused { trf("{} (paused)", &[&text]) } else { text });
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        if task.status.finished() {
+                            return;
+                        }
+                        if paused {
+                            if a11y::icon_button(ui, "▶", tr("Resume")).clicked() {
+                                task.progress.paused.store(false, Ordering::Relaxed);
+                            }
+                        } else if a11y::icon_button(ui, "⏸", tr("Pause")).clicked() {
+                            task.progress.paused.store(true, Ordering::Relaxed);
+                        }
+                        if a11y::icon_button(ui, "✖", tr("Cancel")).clicked() {
+                            task.progress.cancelled.store(true, Ordering::Relaxed);
+                            if task.status == Status::Queued {
+                                cancelled.push(position);
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+        for position in cancelled {
+            self.tasks[position].status = Status::Cancelled;
+        }
+    }
+}
+
+fn run(action: &Action, path: &Path, is_dir: bool, progress: &Progress) -> Result<(), Error> {
+    match action {
+        Action::Delete => delete(path, progress),
+        Action::SecureDelete => wipe::wipe_path(path, is_dir, progress),
+        Action::Move(destination) => {
+            let target = destination.join(path.file_name().ok_or(Error::NoName("move"))?);
+            replacing(&target, false, || transfer(path, &target, progress))
+        }
+        Action::MoveAs { target, replace } => replacing(target, *replace, || transfer(path, target, progress)),
+        Action::Copy(destination) => {
+            let target = destination.join(path.file_name().ok_or(Error::NoName("copy"))?);
+            replacing(&target, false, || copy(path, &target, progress))
+        }
+        Action::CopyAs { target, replace } => replacing(target, *replace, || copy(path, target, progress)),
+        Action::Compress => compress(path, progress),
+        Action::CompressInPlace => compress_in_place(path, progress),
+        Action::Archive { destination, delete_originals } => {
+            archive::archive(path, destination, progress)?;
+            if *delete_originals {
+                delete(path, progress)?;
+                archive::record_deletion(path, destination)?;
+            }
+            Ok(())
+        }
+        Action::Restore { target, replace } => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(paths::extended(parent))
+                    .map_err(|source| Error::Write { path: parent.to_path_buf(), source })?;
+            }
+            replacing(target, *replace, || transfer(path, target, progress))?;
+            trash::forget(path)
+        }
+    }
+}
+
+/// Deletes as much as it can; what's left is kept in `progress` for the caller to resolve.
+fn delete(path: &Path, progress: &Progress) -> Result<(), Error> {
+    let failures = deletion::delete_tree(path, progress)?;
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let error = Error::Incomplete { count: failures.len() };
+    if let Ok(mut kept) = progress.failures.lock() {
+        *kept = failures;
+    }
+    Err(error)
+}
+
+/// Runs `put`, which puts an item at `target`. Unless `replace` is set, an item already there
+/// is an error; otherwise it's only set aside until `put` succeeds, so a failure can put it
+/// back.
+fn replacing(target: &Path, replace: bool, put: impl FnOnce() -> Result<(), Error>) -> Result<(), Error> {
+    if !target.exists() {
+        return put();
+    }
+    if !replace {
+        return Err(Error::Exists(target.to_path_buf()));
+    }
+
+    let mut hidden = OsString::from(".");
+    hidden.push(target.file_name().ok_or(Error::NoName("replace"))?);
+    hidden.push(".replaced");
+    let aside = unused_target(&target.with_file_name(hidden));
+    fs::rename(paths::extended(target), paths::extended(&aside))
+        .map_err(|source| Error::Write { path: target.to_path_buf(), source })?;
+    if let Err(e) = put() {
+        let _ = fs::rename(paths::extended(&aside), paths::extended(target));
+        return Err(e);
+    }
+    let failures = deletion::delete_tree(&aside, &Progress::default())?;
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Incomplete { count: failures.len() })
+    }
+}
+
+/// Renames when possible. Across filesystems the item is copied, the copy is checked against
+/// hashes of the original, and only then is the original removed; any other failure to
+/// rename is reported as is.
+fn transfer(path: &Path, target: &Path, progress: &Progress) -> Result<(), Error> {
+    progress.checkpoint()?;
+    if paths::is_within(target, path) {
+        return Err(Error::IntoItself(path.to_path_buf()));
+    }
+    match fs::rename(paths::extended(path), paths::extended(target)) {
+        Ok(()) => {
+            progress.advance(fs::symlink_metadata(target).map_or(0, |metadata| metadata.len()));
+            Ok(())
+        }
+        Err(e) if cross_device(&e) => {
+            copy(path, target, progress)?;
+            delete(path, &Progress::default())
+        }
+        Err(source) => Err(Error::Move { path: path.to_path_buf(), source }),
+    }
+}
+
+#[cfg(unix)]
+fn cross_device(error: &std::io::Error) -> bool {
+    error.raw_os_error() == Some(libc::EXDEV)
+}
+
+/// ERROR_NOT_SAME_DEVICE.
+#[cfg(windows)]
+fn cross_device(error: &std::io::Error) -> bool {
+    error.raw_os_error() == Some(17)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn cross_device(_error: &std::io::Error) -> bool {
+    false
+}
+
+/// Copies the item and checks the copy against hashes of the original. A copy that fails,
+/// including one that reads back differently, is removed again.
+fn copy(path: &Path, target: &Path, progress: &Progress) -> Result<(), Error> {
+    if paths::is_within(target, path) {
+        return Err(Error::IntoItself(path.to_path_buf()));
+    }
+    if let Err(e) = archive::copy_and_verify(path, target, progress) {
+        let _ = if target.is_dir() { fs::remove_dir_all(target) } else { fs::remove_file(target) };
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// `target`, or the first of "name (2).ext", "name (3).ext"... beside it that doesn't exist yet.
+pub fn unused_target(target: &Path) -> PathBuf {
+    let stem = target.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = target.extension().map(|extension| format!(".{}", extension.to_string_lossy()));
+    let mut candidate = target.to_path_buf();
+    let mut number = 2;
+    while candidate.exists() {
+        candidate = target.with_file_name(format!("{} ({}){}", stem, number, extension.as_deref().unwrap_or("")));
+        number += 1;
+    }
+    candidate
+}
+
+#[cfg(unix)]
+pub fn copy_link(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+pub fn copy_link(source: &Path, destination: &Path) -> std::io::Result<()> {
+    fs::copy(source, destination).map(|_| ())
+}
+
+/// Runs `tar` (bundled with Windows 10 and later too), stopping and resuming the process
+/// when the task is paused; the archive grows as it goes, which is what progress shows.
+fn compress(path: &Path, progress: &Progress) -> Result<(), Error> {
+    let name = path.file_name().ok_or(Error::NoName("compress"))?;
+    let parent = path.parent().ok_or(Error::NoName("compress"))?;
+    let mut archive_name = name.to_os_string();
+    archive_name.push(".tar.gz");
+    let archive = parent.join(archive_name);
+    if archive.exists() {
+        return Err(Error::Exists(archive));
+    }
+
+    let child = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .spawn()
+        .map_err(|source| Error::Spawn { program: "tar", source })?;
+    let result = supervise(child, "tar", progress, || {
+        let written = fs::metadata(&archive).map_or(0, |metadata| metadata.len());
+        progress.done.store(written, Ordering::Relaxed);
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&archive);
+    }
+    result
+}
+
+/// Turns on zstd compression for the item and has btrfs rewrite what it already holds, so
+/// the savings apply now rather than only to later writes. Without the btrfs tool, `chattr +c`
+/// still compresses whatever is written from then on.
+fn compress_in_place(path: &Path, progress: &Progress) -> Result<(), Error> {
+    let property = Command::new("btrfs").args(["property", "set"]).arg(path).args(["compression", "zstd"]).spawn();
+    match property {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let child = Command::new("chattr")
+                .args(["-R", "+c"])
+                .arg(path)
+                .spawn()
+                .map_err(|source| Error::Spawn { program: "chattr", source })?;
+            supervise(child, "chattr", progress, || {})
+        }
+        Err(source) => Err(Error::Spawn { program: "btrfs", source }),
+        Ok(child) => {
+            supervise(child, "btrfs", progress, || {})?;
+            let child = Command::new("btrfs")
+                .args(["filesystem", "defragment", "-r", "-czstd"])
+                .arg(path)
+                .spawn()
+                .map_err(|source| Error::Spawn { program: "btrfs", source })?;
+            supervise(child, "btrfs", progress, || {})
+        }
+    }
+}
+
+/// Waits for the tool to exit while following the task's pause and cancel buttons, calling
+/// `poll` in between to report progress.
+fn supervise(mut child: Child, program: &'static str, progress: &Progress, poll: impl Fn()) -> Result<(), Error> {
+    let mut stopped = false;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|source| Error::Spawn { program, source })? {
+            break status;
+        }
+        if progress.is_cancelled() {
+            if stopped {
+                signal(&child, false);
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::Cancelled);
+        }
+        if progress.is_paused() != stopped {
+            stopped = progress.is_paused();
+            signal(&child, stopped);
+        }
+        poll();
+        thread::sleep(POLL_INTERVAL);
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Exit { program, status })
+    }
+}
+
+#[cfg(unix)]
+fn signal(child: &Child, stop: bool) {
+    let signal = if stop { libc::SIGSTOP } else { libc::SIGCONT };
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, signal);
+    }
+}
+
+/// Processes can't be suspended portably here, so pausing only holds back queued tasks.
+#[cfg(not(unix))]
+fn signal(_child: &Child, _stop: bool) {}
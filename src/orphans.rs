@@ -0,0 +1,188 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    i18n::{self, tr, trf},
+    job::Job,
+};
+
+pub struct BrokenLink {
+    pub path: PathBuf,
+    pub target: PathBuf,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct DeletedOpenFile {
+    pub path: String,
+    pub size: u64,
+    pub pid: u32,
+    pub process: String,
+}
+
+#[derive(Default)]
+pub struct OrphanReport {
+    pub broken_links: Vec<BrokenLink>,
+    pub deleted_open: Vec<DeletedOpenFile>,
+}
+
+pub fn find_broken_links(root: &Path) -> Vec<BrokenLink> {
+    WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path_is_symlink() && fs::metadata(entry.path()).is_err())
+        .map(|entry| BrokenLink {
+            target: fs::read_link(entry.path()).unwrap_or_default(),
+            path: entry.into_path(),
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn find_deleted_open_files(root: Option<&Path>) -> Vec<DeletedOpenFile> {
+    use std::{collections::HashSet, os::unix::fs::MetadataExt};
+
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return files;
+    };
+
+    for process in processes.filter_map(Result::ok) {
+        let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let name = fs::read_to_string(process.path().join("comm"))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_default();
+
+        for fd in fds.filter_map(Result::ok) {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(path) = target.strip_suffix(" (deleted)") else {
+                continue;
+            };
+            if root.is_some_and(|root| !Path::new(path).starts_with(root)) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(fd.path()) else {
+                continue;
+            };
+            if !metadata.is_file() || !seen.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            files.push(DeletedOpenFile {
+                path: path.to_string(),
+                size: metadata.len(),
+                pid,
+                process: name.clone(),
+            });
+        }
+    }
+
+    files.sort_by_key(|file| std::cmp::Reverse(file.size));
+    files
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn find_deleted_open_files(_root: Option<&Path>) -> Vec<DeletedOpenFile> {
+    Vec::new()
+}
+
+#[derive(Default)]
+pub struct OrphanState {
+    pub open: bool,
+    only_under_root: bool,
+    job: Option<Job<OrphanReport>>,
+    report: Option<OrphanReport>,
+}
+
+impl OrphanState {
+    pub fn show(&mut self, ctx: &egui::Context, root: Option<&Path>) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Hidden Usage"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                let Some(root) = root else {
+                    ui.label(tr("Select a directory first."));
+                    return;
+                };
+                if cfg!(target_os = "linux") {
+                    ui.checkbox(&mut self.only_under_root, tr("Only deleted files under the scanned root"));
+                }
+                if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Run Report"))).clicked() {
+                    let root = root.to_path_buf();
+                    let only_under_root = self.only_under_root;
+                    self.job = Some(Job::spawn(move || OrphanReport {
+                        broken_links: find_broken_links(&root),
+                        deleted_open: find_deleted_open_files(only_under_root.then_some(root.as_path())),
+                    }));
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if let Some(report) = &self.report {
+                    render_report(ui, report);
+                }
+            });
+        self.open = open;
+    }
+}
+
+fn render_report(ui: &mut egui::Ui, report: &OrphanReport) {
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::CollapsingHeader::new(trf("Broken symlinks ({})", &[&report.broken_links.len()]))
+            .default_open(true)
+            .show(ui, |ui| {
+                for link in &report.broken_links {
+                    ui.label(format!("🔗 {} → {}", link.path.display(), link.target.display()));
+                }
+            });
+
+        if cfg!(target_os = "linux") {
+            let total: u64 = report.deleted_open.iter().map(|f| f.size).sum();
+            egui::CollapsingHeader::new(trf(
+                "Deleted but still open ({}, {})",
+                &[&report.deleted_open.len(), &i18n::format_bytes(total)],
+            ))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.label(
+                    RichText::new(tr("Space is released when the process closes the file or exits."))
+                        .color(Color32::GRAY),
+                );
+                for file in &report.deleted_open {
+                    ui.label(format!(
+                        "{} - {} ({} [{}])",
+                        file.path,
+                        i18n::format_bytes(file.size),
+                        file.process,
+                        file.pid
+                    ));
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,84 @@
+use std::fs::Metadata;
+
+#[cfg(unix)]
+pub fn owner_id(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.uid())
+}
+
+#[cfg(not(unix))]
+pub fn owner_id(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+/// Asks the system for the account's name, so accounts from a directory service are found
+/// as well as local ones.
+#[cfg(unix)]
+fn lookup(uid: u32) -> Option<String> {
+    let mut buffer = vec![0 as libc::c_char; 1024];
+    loop {
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe { libc::getpwuid_r(uid, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+        if status == libc::ERANGE && buffer.len() < 1 << 20 {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+        return Some(name.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(unix)]
+pub fn owner_name(uid: u32) -> Option<String> {
+    use std::{collections::BTreeMap, sync::Mutex};
+
+    // Directory services can be slow to answer, and listings ask for the same few owners.
+    static USERS: Mutex<BTreeMap<u32, Option<String>>> = Mutex::new(BTreeMap::new());
+    if let Some(name) = USERS.lock().ok().and_then(|users| users.get(&uid).cloned()) {
+        return name;
+    }
+    let name = lookup(uid);
+    if let Ok(mut users) = USERS.lock() {
+        users.insert(uid, name.clone());
+    }
+    name
+}
+
+#[cfg(not(unix))]
+pub fn owner_name(_uid: u32) -> Option<String> {
+    None
+}
+
+/// Whether the account exists, locally or in a directory service.
+#[cfg(unix)]
+pub fn known_owner(uid: u32) -> bool {
+    owner_name(uid).is_some()
+}
+
+#[cfg(not(unix))]
+pub fn known_owner(_uid: u32) -> bool {
+    true
+}
+
+/// Permission bits, including setuid, setgid and sticky.
+#[cfg(unix)]
+pub fn mode(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+pub fn mode(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+pub fn display_owner(uid: Option<u32>) -> String {
+    match uid {
+        Some(uid) => owner_name(uid).unwrap_or_else(|| uid.to_string()),
+        None => "?".to_string(),
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Deserializer};
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    path::Path,
+};
+
+/// Renders a file name without losing information: valid text is shown as is and anything
+/// that isn't (stray bytes on Unix, unpaired surrogates on Windows) is escaped, so two
+/// different names never look the same.
+#[cfg(unix)]
+pub fn display_name(name: &OsStr) -> Cow<'_, str> {
+    use std::os::unix::ffi::OsStrExt;
+
+    if let Some(name) = name.to_str() {
+        return Cow::Borrowed(name);
+    }
+    let mut shown = String::new();
+    for chunk in name.as_bytes().utf8_chunks() {
+        shown.push_str(chunk.valid());
+        for byte in chunk.invalid() {
+            shown.push_str(&format!("\\x{:02X}", byte));
+        }
+    }
+    Cow::Owned(shown)
+}
+
+#[cfg(windows)]
+pub fn display_name(name: &OsStr) -> Cow<'_, str> {
+    use std::os::windows::ffi::OsStrExt;
+
+    if let Some(name) = name.to_str() {
+        return Cow::Borrowed(name);
+    }
+    let shown = char::decode_utf16(name.encode_wide())
+        .map(|unit| match unit {
+            Ok(c) => c.to_string(),
+            Err(e) => format!("\\u{{{:04X}}}", e.unpaired_surrogate()),
+        })
+        .collect();
+    Cow::Owned(shown)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn display_name(name: &OsStr) -> Cow<'_, str> {
+    name.to_string_lossy()
+}
+
+/// Whether the name starts with a dot, which hides it on Unix.
+pub fn is_dotfile(name: &OsStr) -> bool {
+    name.as_encoded_bytes().starts_with(b".")
+}
+
+/// Reads a saved file name, which older versions stored as text instead of an `OsString`.
+pub fn deserialize_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OsString, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Name {
+        Text(String),
+        Native(OsString),
+    }
+    Ok(match Name::deserialize(deserializer)? {
+        Name::Text(name) => name.into(),
+        Name::Native(name) => name,
+    })
+}
+
+/// Like `Path::display`, but escaping undecodable parts the same way as `display_name`.
+pub fn display_path(path: &Path) -> Cow<'_, str> {
+    display_name(path.as_os_str())
+}
+
+/// Adds the `\\?\` prefix so Windows APIs accept paths longer than MAX_PATH. Paths that
+/// aren't absolute, are already verbatim or contain `.`/`..` (which verbatim paths don't
+/// resolve) come back unchanged; other platforms have no such limit.
+#[cfg(windows)]
+pub fn extended(path: &Path) -> Cow<'_, Path> {
+    use std::{
+        ffi::OsString,
+        path::{Component, PathBuf, Prefix},
+    };
+
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return Cow::Borrowed(path);
+    };
+    let relative = path
+        .components()
+        .any(|component| matches!(component, Component::CurDir | Component::ParentDir));
+    if relative || !path.has_root() {
+        return Cow::Borrowed(path);
+    }
+    let mut extended = OsString::from(r"\\?\");
+    match prefix.kind() {
+        Prefix::Disk(_) => extended.push(prefix.as_os_str()),
+        Prefix::UNC(server, share) => {
+            extended.push(r"UNC\");
+            extended.push(server);
+            extended.push(r"\");
+            extended.push(share);
+        }
+        _ => return Cow::Borrowed(path),
+    }
+    for component in path.components().skip(1) {
+        if let Component::Normal(name) = component {
+            extended.push(r"\");
+            extended.push(name);
+        }
+    }
+    if path.components().count() == 2 {
+        extended.push(r"\");
+    }
+    Cow::Owned(PathBuf::from(extended))
+}
+
+#[cfg(not(windows))]
+pub fn extended(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+/// Whether `target` is `path` itself or somewhere inside it, also when either is reached
+/// through a link. Moving or copying a folder there would never finish.
+pub fn is_within(target: &Path, path: &Path) -> bool {
+    if target.starts_with(path) {
+        return true;
+    }
+    let canonical = |path: &Path| std::fs::canonicalize(path).ok();
+    match (target.parent().and_then(canonical), canonical(path)) {
+        (Some(parent), Some(path)) => parent.join(target.file_name().unwrap_or_default()).starts_with(path),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index::ScanIndex, scan::ScanOptions};
+    use std::{fs, path::PathBuf};
+
+    /// A fresh folder under the system's temporary directory, removed when dropped.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("disk-analyzer-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(extended(&dir));
+            fs::create_dir_all(&dir).unwrap();
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(extended(&self.0));
+        }
+    }
+
+    #[cfg(unix)]
+    fn undecodable(prefix: &str) -> OsString {
+        use std::os::unix::ffi::OsStringExt;
+        let mut name = prefix.as_bytes().to_vec();
+        name.extend_from_slice(b"\xFF\xFE.bin");
+        OsString::from_vec(name)
+    }
+
+    #[cfg(windows)]
+    fn undecodable(prefix: &str) -> OsString {
+        use std::os::windows::ffi::OsStringExt;
+        let mut name: Vec<u16> = prefix.encode_utf16().collect();
+        name.push(0xD800);
+        name.extend(".bin".encode_utf16());
+        OsString::from_wide(&name)
+    }
+
+    #[test]
+    fn valid_names_are_shown_unchanged() {
+        assert_eq!(display_name(OsStr::new("caf\u{E9} \u{1F4BE}.txt")), "caf\u{E9} \u{1F4BE}.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn undecodable_bytes_are_escaped() {
+        assert_eq!(display_name(&undecodable("a")), "a\\xFF\\xFE.bin");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn unpaired_surrogates_are_escaped() {
+        assert_eq!(display_name(&undecodable("a")), "a\\u{D800}.bin");
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn undecodable_names_dont_look_like_replacement_characters() {
+        assert_ne!(display_name(&undecodable("a")), display_name(OsStr::new("a\u{FFFD}\u{FFFD}.bin")));
+    }
+
+    #[test]
+    fn saved_names_load_from_text_and_native_forms() {
+        #[derive(Deserialize)]
+        struct Saved {
+            #[serde(deserialize_with = "deserialize_name")]
+            name: OsString,
+        }
+        let text: Saved = serde_json::from_str(r#"{"name": "report.pdf"}"#).unwrap();
+        assert_eq!(text.name, "report.pdf");
+        let native = serde_json::to_string(&OsString::from("report.pdf")).unwrap();
+        let native: Saved = serde_json::from_str(&format!(r#"{{"name": {}}}"#, native)).unwrap();
+        assert_eq!(native.name, "report.pdf");
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn scans_keep_undecodable_names_usable() {
+        let scratch = Scratch::new("undecodable");
+        let name = undecodable("photo");
+        fs::write(scratch.0.join(&name), b"data").unwrap();
+
+        let index = ScanIndex::build(&scratch.0, &ScanOptions::default(), true);
+        let node = index.nodes.iter().find(|node| node.name == name).expect("the file is indexed");
+        assert_eq!(fs::read(&node.path).unwrap(), b"data");
+        fs::remove_file(&node.path).unwrap();
+    }
+
+    #[test]
+    fn scans_reach_files_past_max_path() {
+        let scratch = Scratch::new("long");
+        let mut dir = scratch.0.clone();
+        while dir.as_os_str().len() < 400 {
+            dir.push("a".repeat(50));
+        }
+        fs::create_dir_all(extended(&dir)).unwrap();
+        let file = dir.join("deep.txt");
+        fs::write(extended(&file), b"deep").unwrap();
+
+        let index = ScanIndex::build(&scratch.0, &ScanOptions::default(), true);
+        let node = index.nodes.iter().find(|node| node.path == file).expect("the file is indexed");
+        assert_eq!(node.name, "deep.txt");
+        assert_eq!(fs::read(extended(&node.path)).unwrap(), b"deep");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_paths_get_the_extended_prefix() {
+        assert_eq!(extended(Path::new(r"C:\Users\me")), Path::new(r"\\?\C:\Users\me"));
+        assert_eq!(extended(Path::new(r"C:\")), Path::new(r"\\?\C:\"));
+        assert_eq!(extended(Path::new(r"\\server\share\folder")), Path::new(r"\\?\UNC\server\share\folder"));
+        assert_eq!(extended(Path::new(r"\\?\C:\already")), Path::new(r"\\?\C:\already"));
+        assert_eq!(extended(Path::new(r"relative\path")), Path::new(r"relative\path"));
+        assert_eq!(extended(Path::new(r"C:\a\..\b")), Path::new(r"C:\a\..\b"));
+    }
+}
@@ -0,0 +1,299 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    audit,
+    category::{self, Category},
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths,
+};
+
+const RAW_EXTENSIONS: [&str; 12] = ["cr2", "cr3", "nef", "nrw", "arw", "dng", "raf", "orf", "rw2", "pef", "srw", "raw"];
+const JPEG_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "heic", "heif"];
+/// Shots taken at most this far apart belong to the same burst.
+const BURST_GAP: Duration = Duration::from_secs(1);
+const MIN_BURST: usize = 3;
+const SCREENSHOT_PREFIXES: [&str; 4] = ["screenshot", "screen shot", "captura de tela", "bildschirmfoto"];
+const MAX_ROWS: usize = 200;
+
+type Photo = (PathBuf, u64);
+/// A photo's path, size and modification time.
+type Candidate = (PathBuf, u64, Option<SystemTime>);
+/// A photo's path, size and when it was taken.
+type Shot = (PathBuf, u64, SystemTime);
+
+pub struct RawPair {
+    pub raw: Photo,
+    pub jpeg: Photo,
+}
+
+pub struct Burst {
+    /// In shooting order.
+    pub shots: Vec<Photo>,
+}
+
+impl Burst {
+    /// The largest shot, usually the sharpest, is the one worth keeping.
+    fn keeper(&self) -> Option<&Path> {
+        self.shots.iter().max_by_key(|(_, size)| *size).map(|(path, _)| path.as_path())
+    }
+
+    pub fn reclaimable(&self) -> u64 {
+        let total: u64 = self.shots.iter().map(|(_, size)| size).sum();
+        total - self.shots.iter().map(|(_, size)| *size).max().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+pub struct PhotoReport {
+    pub pairs: Vec<RawPair>,
+    pub bursts: Vec<Burst>,
+    pub screenshots: Vec<Photo>,
+}
+
+fn is_screenshot(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    let in_folder = path.parent().and_then(Path::file_name).is_some_and(|folder| {
+        let folder = folder.to_string_lossy().to_lowercase();
+        folder == "screenshots" || folder == "screen shots"
+    });
+    in_folder || SCREENSHOT_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// When the shot was taken according to its EXIF data, which unlike the modification time
+/// survives copying. The camera's clock has no time zone unless it recorded one, so the time
+/// is only good for comparing shots from the same camera.
+fn capture_time(path: &Path) -> Option<SystemTime> {
+    let file = File::open(path).ok()?;
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()?;
+    let ascii = |tag| match exif.get_field(tag, exif::In::PRIMARY).map(|field| &field.value) {
+        Some(exif::Value::Ascii(values)) => values.first().cloned(),
+        _ => None,
+    };
+    let mut taken = exif::DateTime::from_ascii(&ascii(exif::Tag::DateTimeOriginal)?).ok()?;
+    if let Some(subsec) = ascii(exif::Tag::SubSecTimeOriginal) {
+        let _ = taken.parse_subsec(&subsec);
+    }
+    if !(1..=12).contains(&taken.month) || !(1..=31).contains(&taken.day) {
+        return None;
+    }
+    let seconds = taken.hour as u64 * 3600 + taken.minute as u64 * 60 + taken.second as u64;
+    let local = audit::civil_timestamp(taken.year as i64, taken.month as i64, taken.day as i64, seconds);
+    let utc = local.checked_add_signed(-(taken.offset.unwrap_or(0) as i64 * 60))?;
+    Some(UNIX_EPOCH + Duration::new(utc, taken.nanosecond.unwrap_or(0)))
+}
+
+/// Splits one folder's photos, oldest first, into runs of shots taken in quick succession.
+fn bursts(mut photos: Vec<Shot>) -> Vec<Burst> {
+    photos.sort_by_key(|(_, _, taken)| *taken);
+    let mut bursts = Vec::new();
+    let mut run: Vec<Photo> = Vec::new();
+    let mut last: Option<SystemTime> = None;
+    for (path, size, taken) in photos {
+        let close = last.and_then(|last| taken.duration_since(last).ok()).is_some_and(|gap| gap <= BURST_GAP);
+        if !close && run.len() >= MIN_BURST {
+            bursts.push(Burst { shots: std::mem::take(&mut run) });
+        } else if !close {
+            run.clear();
+        }
+        run.push((path, size));
+        last = Some(taken);
+    }
+    if run.len() >= MIN_BURST {
+        bursts.push(Burst { shots: run });
+    }
+    bursts
+}
+
+/// The photos in each folder of the scan, copied out of the index so the analysis can run
+/// in the background. Only the name is looked at here; reading the files is left to `analyze`.
+fn candidates(index: &ScanIndex) -> Vec<Vec<Candidate>> {
+    index
+        .nodes
+        .iter()
+        .filter(|node| node.is_dir)
+        .map(|dir| {
+            dir.children
+                .iter()
+                .map(|&child| &index.nodes[child])
+                .filter(|node| !node.is_dir)
+                .filter(|node| {
+                    RAW_EXTENSIONS.contains(&category::extension(&node.path).as_str())
+                        || category::classify(&node.path) == Category::Image
+                })
+                .map(|node| (node.path.clone(), node.size, node.modified))
+                .collect::<Vec<_>>()
+        })
+        .filter(|photos| !photos.is_empty())
+        .collect()
+}
+
+/// Looks for RAW files with a JPEG or HEIC sibling of the same name, burst sequences and
+/// screenshots among the photos in the scan.
+fn analyze(folders: Vec<Vec<Candidate>>) -> PhotoReport {
+    let mut report = PhotoReport::default();
+    for photos in folders {
+        let mut raws: HashMap<String, Photo> = HashMap::new();
+        let mut jpegs: HashMap<String, Photo> = HashMap::new();
+        // Burst candidates per extension, so a RAW and its JPEG don't make a burst of two, and
+        // apart from shots without EXIF, whose modification times don't compare with capture times.
+        let mut shots: HashMap<(String, bool), Vec<Shot>> = HashMap::new();
+        for (path, size, modified) in photos {
+            if is_screenshot(&path) {
+                report.screenshots.push((path, size));
+                continue;
+            }
+            let extension = category::extension(&path);
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+            if RAW_EXTENSIONS.contains(&extension.as_str()) {
+                raws.insert(stem, (path.clone(), size));
+            } else if JPEG_EXTENSIONS.contains(&extension.as_str()) {
+                jpegs.insert(stem, (path.clone(), size));
+            }
+            let (taken, from_exif) = match capture_time(&path) {
+                Some(taken) => (Some(taken), true),
+                None => (modified, false),
+            };
+            if let Some(taken) = taken {
+                shots.entry((extension, from_exif)).or_default().push((path, size, taken));
+            }
+        }
+        for (stem, raw) in raws {
+            if let Some(jpeg) = jpegs.remove(&stem) {
+                report.pairs.push(RawPair { raw, jpeg });
+            }
+        }
+        report.bursts.extend(shots.into_values().flat_map(bursts));
+    }
+    report.pairs.sort_by(|a, b| a.raw.0.cmp(&b.raw.0));
+    report.bursts.sort_by_key(|burst| std::cmp::Reverse(burst.reclaimable()));
+    report.screenshots.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    report
+}
+
+fn photo_link(ui: &mut egui::Ui, (path, size): &Photo, navigate: &mut Option<PathBuf>) {
+    let text = format!("{} - {}", paths::display_path(path), i18n::format_bytes(*size));
+    if ui.link(text).on_hover_text(tr("Show in folder")).clicked() {
+        *navigate = path.parent().map(Path::to_path_buf);
+    }
+}
+
+#[derive(Default)]
+pub struct PhotosState {
+    pub open: bool,
+    job: Option<Job<PhotoReport>>,
+    report: Option<PhotoReport>,
+}
+
+impl PhotosState {
+    /// Draws the window and returns a folder to jump to when a file's location is clicked.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>) -> Option<PathBuf> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Photo Library"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr("Finds RAW files shot together with a JPEG, burst sequences and screenshots."))
+                        .color(Color32::GRAY),
+                );
+                if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Analyze"))).clicked() {
+                    let folders = candidates(index);
+                    self.job = Some(Job::spawn(move || analyze(folders)));
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+
+                let raw_size: u64 = report.pairs.iter().map(|pair| pair.raw.1).sum();
+                let jpeg_size: u64 = report.pairs.iter().map(|pair| pair.jpeg.1).sum();
+                let burst_size: u64 = report.bursts.iter().map(Burst::reclaimable).sum();
+                let screenshot_size: u64 = report.screenshots.iter().map(|(_, size)| size).sum();
+                egui::ScrollArea::vertical().max_height(460.0).show(ui, |ui| {
+                    egui::CollapsingHeader::new(trf(
+                        "RAW+JPEG pairs: {}",
+                        &[&i18n::format_count(report.pairs.len() as u64)],
+                    ))
+                    .id_source("photo_pairs")
+                    .show(ui, |ui| {
+                        ui.label(trf(
+                            "Removing the JPEG copies frees {}; removing the RAW files frees {}",
+                            &[&i18n::format_bytes(jpeg_size), &i18n::format_bytes(raw_size)],
+                        ));
+                        for pair in report.pairs.iter().take(MAX_ROWS) {
+                            photo_link(ui, &pair.raw, &mut navigate);
+                            ui.indent(("photo_pair", &pair.raw.0), |ui| photo_link(ui, &pair.jpeg, &mut navigate));
+                        }
+                    });
+
+                    egui::CollapsingHeader::new(trf(
+                        "Burst sequences: {}, {} reclaimable",
+                        &[&i18n::format_count(report.bursts.len() as u64), &i18n::format_bytes(burst_size)],
+                    ))
+                    .id_source("photo_bursts")
+                    .show(ui, |ui| {
+                        for (number, burst) in report.bursts.iter().enumerate().take(MAX_ROWS) {
+                            egui::CollapsingHeader::new(trf(
+                                "{} shots, keeping the largest frees {}",
+                                &[&i18n::format_count(burst.shots.len() as u64), &i18n::format_bytes(burst.reclaimable())],
+                            ))
+                            .id_source(("photo_burst", number))
+                            .show(ui, |ui| {
+                                let keeper = burst.keeper();
+                                for shot in &burst.shots {
+                                    ui.horizontal(|ui| {
+                                        photo_link(ui, shot, &mut navigate);
+                                        if keeper == Some(shot.0.as_path()) {
+                                            ui.label(RichText::new(tr("largest")).small().color(Color32::GREEN));
+                                        }
+                                    });
+                                }
+                            });
+                        }
+                    });
+
+                    egui::CollapsingHeader::new(trf(
+                        "Screenshots: {}, {}",
+                        &[&i18n::format_count(report.screenshots.len() as u64), &i18n::format_bytes(screenshot_size)],
+                    ))
+                    .id_source("photo_screenshots")
+                    .show(ui, |ui| {
+                        for screenshot in report.screenshots.iter().take(MAX_ROWS) {
+                            photo_link(ui, screenshot, &mut navigate);
+                        }
+                    });
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
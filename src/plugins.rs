@@ -0,0 +1,602 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    config,
+    i18n::{self, tr, trf},
+    job::Job,
+    paths, scan,
+};
+
+const SETTINGS_FILE: &str = "plugins.json";
+/// Plugins built against a different version of the interface are refused.
+pub const API_VERSION: u32 = 1;
+const EXTENSION: &str = if cfg!(windows) {
+    "dll"
+} else if cfg!(target_os = "macos") {
+    "dylib"
+} else {
+    "so"
+};
+
+type ApiFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *mut c_char;
+type CallFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+#[cfg(unix)]
+mod library {
+    use std::{
+        ffi::{c_void, CStr, CString},
+        os::unix::ffi::OsStrExt,
+        path::Path,
+    };
+
+    pub struct Library(*mut c_void);
+
+    fn last_error() -> String {
+        let error = unsafe { libc::dlerror() };
+        if error.is_null() {
+            return "unknown error".to_string();
+        }
+        unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned()
+    }
+
+    impl Library {
+        pub fn open(path: &Path) -> Result<Self, String> {
+            let name = CString::new(path.as_os_str().as_bytes()).map_err(|e| format!("Error loading {}: {}", path.display(), e))?;
+            let handle = unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+            if handle.is_null() {
+                return Err(format!("Error loading {}: {}", path.display(), last_error()));
+            }
+            Ok(Library(handle))
+        }
+
+        pub fn symbol(&self, name: &str) -> Option<*mut c_void> {
+            let name = CString::new(name).ok()?;
+            let symbol = unsafe { libc::dlsym(self.0, name.as_ptr()) };
+            (!symbol.is_null()).then_some(symbol)
+        }
+    }
+
+    impl Drop for Library {
+        fn drop(&mut self) {
+            unsafe {
+                libc::dlclose(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod library {
+    use std::{
+        ffi::{c_void, CString},
+        os::windows::ffi::OsStrExt,
+        path::Path,
+    };
+    use winapi::{
+        shared::minwindef::HMODULE,
+        um::libloaderapi::{FreeLibrary, GetProcAddress, LoadLibraryW},
+    };
+
+    pub struct Library(HMODULE);
+
+    impl Library {
+        pub fn open(path: &Path) -> Result<Self, String> {
+            let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+            let handle = unsafe { LoadLibraryW(wide.as_ptr()) };
+            if handle.is_null() {
+                return Err(format!("Error loading {}: {}", path.display(), std::io::Error::last_os_error()));
+            }
+            Ok(Library(handle))
+        }
+
+        pub fn symbol(&self, name: &str) -> Option<*mut c_void> {
+            let name = CString::new(name).ok()?;
+            let symbol = unsafe { GetProcAddress(self.0, name.as_ptr()) };
+            (!symbol.is_null()).then_some(symbol as *mut c_void)
+        }
+    }
+
+    impl Drop for Library {
+        fn drop(&mut self) {
+            unsafe {
+                FreeLibrary(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod library {
+    use std::{ffi::c_void, path::Path};
+
+    pub struct Library;
+
+    impl Library {
+        pub fn open(_path: &Path) -> Result<Self, String> {
+            Err("Plugins aren't supported on this platform".to_string())
+        }
+
+        pub fn symbol(&self, _name: &str) -> Option<*mut c_void> {
+            None
+        }
+    }
+}
+
+// The handle is only used to look up symbols and to unload; the interface requires plugin
+// functions to be callable from any thread.
+unsafe impl Send for library::Library {}
+unsafe impl Sync for library::Library {}
+
+#[derive(Deserialize, Clone)]
+pub struct ReportInfo {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    #[default]
+    Any,
+    File,
+    Dir,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ActionInfo {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub target: Target,
+    /// Actions that only look at files stay available in read-only mode.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// What a plugin says about itself; see the README for the JSON it returns.
+#[derive(Deserialize, Clone)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub reports: Vec<ReportInfo>,
+    #[serde(default)]
+    pub actions: Vec<ActionInfo>,
+}
+
+/// One line of a plugin report. Entries without a size are measured here.
+#[derive(Deserialize)]
+pub struct ReportRow {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Deserialize)]
+struct ActionResult {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+pub struct Plugin {
+    pub file: String,
+    pub manifest: Manifest,
+    report: Option<CallFn>,
+    action: Option<CallFn>,
+    free: FreeFn,
+    // Dropped last so the function pointers above never outlive the library.
+    _library: library::Library,
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Plugin, String> {
+        let library = library::Library::open(path)?;
+        let missing = |name: &str| format!("{} doesn't export {}", path.display(), name);
+        let api = library.symbol("disk_analyzer_plugin_api").ok_or_else(|| missing("disk_analyzer_plugin_api"))?;
+        let api = unsafe { std::mem::transmute::<*mut c_void, ApiFn>(api) };
+        let version = unsafe { api() };
+        if version != API_VERSION {
+            return Err(format!(
+                "{} was built for plugin interface {}, this version supports {}",
+                path.display(),
+                version,
+                API_VERSION
+            ));
+        }
+        let manifest = library
+            .symbol("disk_analyzer_plugin_manifest")
+            .ok_or_else(|| missing("disk_analyzer_plugin_manifest"))?;
+        let manifest = unsafe { std::mem::transmute::<*mut c_void, ManifestFn>(manifest) };
+        let free = library.symbol("disk_analyzer_plugin_free").ok_or_else(|| missing("disk_analyzer_plugin_free"))?;
+        let free = unsafe { std::mem::transmute::<*mut c_void, FreeFn>(free) };
+        let call = |name: &str| library.symbol(name).map(|symbol| unsafe { std::mem::transmute::<*mut c_void, CallFn>(symbol) });
+        let report = call("disk_analyzer_plugin_report");
+        let action = call("disk_analyzer_plugin_action");
+
+        let file = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let json = take_string(unsafe { manifest() }, free).ok_or_else(|| missing("a manifest"))?;
+        let manifest: Manifest =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid manifest in {}: {}", path.display(), e))?;
+        Ok(Plugin { file, manifest, report, action, free, _library: library })
+    }
+
+    /// Passes two strings to a plugin function and returns the string it hands back.
+    fn call(&self, function: Option<CallFn>, id: &str, argument: &str) -> Result<String, String> {
+        let function = function.ok_or_else(|| format!("{} has no such function", self.manifest.name))?;
+        let id = CString::new(id).map_err(|e| e.to_string())?;
+        let argument = CString::new(argument).map_err(|e| e.to_string())?;
+        let result = unsafe { function(id.as_ptr(), argument.as_ptr()) };
+        take_string(result, self.free).ok_or_else(|| format!("{} returned nothing", self.manifest.name))
+    }
+
+    pub fn report(&self, id: &str, root: &Path) -> Result<Vec<ReportRow>, String> {
+        let json = self.call(self.report, id, &root.to_string_lossy())?;
+        let mut rows: Vec<ReportRow> = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid report from {}: {}", self.manifest.name, e))?;
+        rows.retain_mut(|row| {
+            let Ok(metadata) = fs::symlink_metadata(&row.path) else {
+                return false;
+            };
+            if row.size.is_none() {
+                row.size = Some(if metadata.is_dir() {
+                    scan::measure_dir(&row.path, &scan::ScanOptions::default()).size
+                } else {
+                    scan::file_size(&row.path, &metadata)
+                });
+            }
+            true
+        });
+        rows.sort_by_key(|row| std::cmp::Reverse(row.size));
+        Ok(rows)
+    }
+
+    pub fn action(&self, id: &str, path: &Path) -> Result<String, String> {
+        let json = self.call(self.action, id, &path.to_string_lossy())?;
+        let result: ActionResult = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid result from {}: {}", self.manifest.name, e))?;
+        match result.error {
+            Some(error) => Err(error),
+            None => Ok(result.message),
+        }
+    }
+}
+
+/// Copies a string the plugin allocated and hands it back for the plugin to free.
+fn take_string(raw: *mut c_char, free: FreeFn) -> Option<String> {
+    if raw.is_null() {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(raw) }.to_string_lossy().into_owned();
+    unsafe { free(raw) };
+    Some(text)
+}
+
+pub fn plugins_dir() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join("plugins"))
+}
+
+/// Libraries in the plugins folder, by file name.
+fn discover() -> Vec<PathBuf> {
+    let Some(dir) = plugins_dir() else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(Result::ok).map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    found.retain(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case(EXTENSION)));
+    found.sort();
+    found
+}
+
+/// Which plugins the user turned on. Nothing in the plugins folder runs until it is enabled.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Settings {
+    enabled: Vec<String>,
+}
+
+struct ReportWindow {
+    plugin: Arc<Plugin>,
+    report: ReportInfo,
+    open: bool,
+    job: Option<Job<Result<Vec<ReportRow>, String>>>,
+    rows: Option<Result<Vec<ReportRow>, String>>,
+}
+
+#[derive(Default)]
+pub struct PluginsState {
+    pub open: bool,
+    settings: Settings,
+    available: Vec<PathBuf>,
+    loaded: Vec<Arc<Plugin>>,
+    errors: Vec<String>,
+    reports: Vec<ReportWindow>,
+    action_job: Option<Job<Result<String, String>>>,
+    /// Whether the running action may change files, so the listing needs reading again.
+    action_modifies: bool,
+    /// An action picked from a context menu, waiting for the user to confirm it.
+    confirm: Option<(Arc<Plugin>, ActionInfo, PathBuf)>,
+    message: Option<Result<String, String>>,
+}
+
+impl PluginsState {
+    pub fn load() -> Self {
+        let mut state = PluginsState { settings: config::load(SETTINGS_FILE), ..Default::default() };
+        state.reload();
+        state
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+    }
+
+    /// Looks at the plugins folder again and loads what is enabled. Report windows of unloaded
+    /// plugins close with them.
+    fn reload(&mut self) {
+        self.reports.clear();
+        self.loaded.clear();
+        self.errors.clear();
+        self.available = discover();
+        for path in &self.available {
+            if !self.settings.enabled.contains(&Self::file_name(path)) {
+                continue;
+            }
+            match Plugin::load(path) {
+                Ok(plugin) => self.loaded.push(Arc::new(plugin)),
+                Err(error) => self.errors.push(error),
+            }
+        }
+    }
+
+    /// A button per plugin report; returns true when one was clicked.
+    pub fn report_buttons(&mut self, ui: &mut egui::Ui, root: Option<&Path>) -> bool {
+        let mut clicked = false;
+        for plugin in &self.loaded {
+            for report in &plugin.manifest.reports {
+                let button = ui
+                    .add_enabled(root.is_some(), egui::Button::new(&report.title))
+                    .on_hover_text(trf("From the {} plugin", &[&plugin.manifest.name]))
+                    .on_disabled_hover_text(tr("Open a folder first"));
+                if let (true, Some(root)) = (button.clicked(), root) {
+                    let (runner, id, root) = (Arc::clone(plugin), report.id.clone(), root.to_path_buf());
+                    self.reports.push(ReportWindow {
+                        plugin: Arc::clone(plugin),
+                        report: report.clone(),
+                        open: true,
+                        job: Some(Job::spawn(move || runner.report(&id, &root))),
+                        rows: None,
+                    });
+                    clicked = true;
+                }
+            }
+        }
+        clicked
+    }
+
+    /// Adds the plugin actions that apply to an item to its context menu.
+    pub fn context_menu(&mut self, ui: &mut egui::Ui, path: &Path, is_dir: bool, read_only: bool) {
+        for plugin in &self.loaded {
+            for action in &plugin.manifest.actions {
+                let applies = match action.target {
+                    Target::Any => true,
+                    Target::File => !is_dir,
+                    Target::Dir => is_dir,
+                };
+                if !applies || (read_only && !action.read_only) {
+                    continue;
+                }
+                let button = ui
+                    .add_enabled(self.action_job.is_none(), egui::Button::new(&action.label))
+                    .on_hover_text(trf("From the {} plugin", &[&plugin.manifest.name]));
+                if button.clicked() {
+                    ui.close_menu();
+                    self.confirm = Some((Arc::clone(plugin), action.clone(), path.to_path_buf()));
+                }
+            }
+        }
+    }
+
+    /// Asks before running the action picked from a context menu, and starts it once confirmed.
+    fn confirm_action(&mut self, ctx: &egui::Context) {
+        let Some((plugin, action, path)) = &self.confirm else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(tr("Run Plugin Action"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(trf("Run {} from the {} plugin on:", &[&action.label, &plugin.manifest.name]));
+                ui.label(RichText::new(paths::display_path(path)).monospace());
+                if !action.read_only {
+                    ui.label(RichText::new(tr("This action may change or delete files.")).color(Color32::YELLOW));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Run")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if choice.is_none() {
+            return;
+        }
+        let Some((runner, action, path)) = self.confirm.take() else {
+            return;
+        };
+        if choice == Some(true) {
+            self.message = None;
+            self.action_modifies = !action.read_only;
+            self.action_job = Some(Job::spawn(move || runner.action(&action.id, &path)));
+        }
+    }
+
+    /// Draws the manager and any open report windows. Returns true once a plugin action that
+    /// may have changed files has finished.
+    pub fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut finished = false;
+        self.confirm_action(ctx);
+        if let Some(job) = &self.action_job {
+            match job.poll() {
+                Some(result) => {
+                    self.message = Some(result);
+                    self.action_job = None;
+                    self.open = true;
+                    finished = self.action_modifies;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        for window in &mut self.reports {
+            show_report(ctx, window);
+        }
+        self.reports.retain(|window| window.open);
+
+        let mut open = self.open;
+        let mut changed = false;
+        egui::Window::new(tr("Plugins"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(tr(
+                        "Plugins are libraries that add reports and context menu actions. Only enable plugins you trust: they run with the same rights as this program.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                if let Some(dir) = plugins_dir() {
+                    ui.horizontal(|ui| {
+                        ui.label(trf("Plugin folder: {}", &[&paths::display_path(&dir)]));
+                        if ui.button(tr("Refresh")).clicked() {
+                            changed = true;
+                        }
+                    });
+                }
+                if self.action_job.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("Running plugin action..."));
+                    });
+                }
+                match &self.message {
+                    Some(Ok(message)) if !message.is_empty() => {
+                        ui.label(RichText::new(message).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    _ => {}
+                }
+                for error in &self.errors {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                }
+                ui.separator();
+                if self.available.is_empty() {
+                    ui.label(trf("No plugins found. Copy .{} files into the plugin folder.", &[&EXTENSION]));
+                    return;
+                }
+                egui::Grid::new("plugins").striped(true).show(ui, |ui| {
+                    for path in &self.available {
+                        let file = Self::file_name(path);
+                        let mut enabled = self.settings.enabled.contains(&file);
+                        if ui.checkbox(&mut enabled, &file).changed() {
+                            if enabled {
+                                self.settings.enabled.push(file.clone());
+                            } else {
+                                self.settings.enabled.retain(|name| *name != file);
+                            }
+                            changed = true;
+                        }
+                        match self.loaded.iter().find(|plugin| plugin.file == file) {
+                            Some(plugin) => {
+                                let manifest = &plugin.manifest;
+                                ui.label(RichText::new(format!("{} {}", manifest.name, manifest.version)).strong());
+                                ui.label(&manifest.description);
+                            }
+                            None => {
+                                ui.label("");
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        self.open = open;
+        if changed {
+            if let Err(error) = config::save(SETTINGS_FILE, &self.settings) {
+                self.errors.push(error);
+            }
+            self.reload();
+        }
+        finished
+    }
+}
+
+fn show_report(ctx: &egui::Context, window: &mut ReportWindow) {
+    if let Some(job) = &window.job {
+        match job.poll() {
+            Some(rows) => {
+                window.rows = Some(rows);
+                window.job = None;
+            }
+            None => ctx.request_repaint(),
+        }
+    }
+    let mut open = window.open;
+    egui::Window::new(&window.report.title)
+        .id(egui::Id::new(("plugin_report", &window.plugin.file, &window.report.id)))
+        .open(&mut open)
+        .resizable(true)
+        .default_width(620.0)
+        .show(ctx, |ui| {
+            ui.label(RichText::new(trf("From the {} plugin", &[&window.plugin.manifest.name])).color(Color32::GRAY));
+            let rows = match &window.rows {
+                None => {
+                    ui.spinner();
+                    return;
+                }
+                Some(Err(error)) => {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                    return;
+                }
+                Some(Ok(rows)) => rows,
+            };
+            if rows.is_empty() {
+                ui.label(tr("Nothing found."));
+                return;
+            }
+            let total: u64 = rows.iter().filter_map(|row| row.size).sum();
+            ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+            egui::ScrollArea::vertical().max_height(440.0).show(ui, |ui| {
+                egui::Grid::new(("plugin_rows", &window.report.id)).striped(true).show(ui, |ui| {
+                    for row in rows {
+                        ui.label(paths::display_path(&row.path));
+                        ui.label(i18n::format_bytes(row.size.unwrap_or(0)));
+                        ui.label(&row.note);
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+    window.open = open;
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{config, filter::DirectoryFilter, grouping::GroupBy, scan::ScanOptions};
+
+const PROFILES_FILE: &str = "profiles.json";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub scan: ScanOptions,
+    pub show_hidden: bool,
+    pub show_all: bool,
+    pub min_size: u64,
+    #[serde(default)]
+    pub directory_filter: DirectoryFilter,
+    pub sort_by_size: bool,
+    pub highlight_names: Vec<String>,
+    #[serde(default)]
+    pub group_by: GroupBy,
+    pub show_owners: bool,
+    pub age_heatmap: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            scan: ScanOptions::default(),
+            show_hidden: false,
+            show_all: false,
+            min_size: crate::app::scan::MIN_SIZE_FILTER,
+            directory_filter: DirectoryFilter::default(),
+            sort_by_size: true,
+            highlight_names: Vec::new(),
+            group_by: GroupBy::None,
+            show_owners: false,
+            age_heatmap: false,
+        }
+    }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+pub fn builtin() -> Vec<Profile> {
+    vec![
+        Profile::default(),
+        Profile {
+            name: "Developer".to_string(),
+            scan: ScanOptions {
+                excluded_names: names(&[".git", ".hg", ".svn"]),
+                ..ScanOptions::default()
+            },
+            show_hidden: true,
+            highlight_names: names(&["target", "node_modules", "build", "dist", ".venv", "__pycache__"]),
+            ..Profile::default()
+        },
+        Profile {
+            name: "Media".to_string(),
+            group_by: GroupBy::Category,
+            age_heatmap: true,
+            ..Profile::default()
+        },
+        Profile {
+            name: "System admin".to_string(),
+            scan: ScanOptions {
+                one_file_system: true,
+                ..ScanOptions::default()
+            },
+            show_hidden: true,
+            show_all: true,
+            show_owners: true,
+            ..Profile::default()
+        },
+    ]
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ProfileStore {
+    pub user: Vec<Profile>,
+}
+
+impl ProfileStore {
+    pub fn load() -> Self {
+        config::load(PROFILES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(PROFILES_FILE, self)
+    }
+
+    pub fn all(&self) -> Vec<Profile> {
+        let mut profiles = builtin();
+        profiles.extend(self.user.iter().cloned());
+        profiles
+    }
+
+    pub fn upsert(&mut self, profile: Profile) {
+        match self.user.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.user.push(profile),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.user.retain(|p| p.name != name);
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    audit, category, cli, dump,
+    index::{Node, ScanIndex},
+    users_report::csv_field,
+};
+
+pub const EXAMPLE: &str = "size > 1GB and ext = mp4 and modified < 2023-01-01 sort by size desc limit 50";
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Name,
+    Ext,
+    Path,
+    Type,
+    Size,
+    Entries,
+    Modified,
+    Accessed,
+}
+
+impl Field {
+    fn parse(word: &str) -> Result<Field, String> {
+        Ok(match word.to_ascii_lowercase().as_str() {
+            "name" => Field::Name,
+            "ext" | "extension" => Field::Ext,
+            "path" => Field::Path,
+            "type" => Field::Type,
+            "size" => Field::Size,
+            "entries" => Field::Entries,
+            "modified" => Field::Modified,
+            "accessed" => Field::Accessed,
+            _ => return Err(format!("Unknown field: {}", word)),
+        })
+    }
+
+    fn text(&self, node: &Node) -> String {
+        match self {
+            Field::Name => node.name.to_string_lossy().to_lowercase(),
+            Field::Ext => category::extension(&node.path),
+            _ => node.path.to_string_lossy().to_lowercase(),
+        }
+    }
+
+    fn order(&self, a: &Node, b: &Node) -> Ordering {
+        match self {
+            Field::Size => a.size.cmp(&b.size),
+            Field::Entries => a.entries.cmp(&b.entries),
+            Field::Modified => a.modified.cmp(&b.modified),
+            Field::Accessed => a.accessed.cmp(&b.accessed),
+            Field::Type => a.is_dir.cmp(&b.is_dir),
+            _ => self.text(a).cmp(&self.text(b)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// `~`: the text contains the value.
+    Contains,
+}
+
+impl Op {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq | Op::Contains => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+enum Value {
+    Text(String),
+    Number(u64),
+    Time(SystemTime),
+    Dir(bool),
+}
+
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Value),
+}
+
+impl Expr {
+    fn matches(&self, node: &Node) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(node) && right.matches(node),
+            Expr::Or(left, right) => left.matches(node) || right.matches(node),
+            Expr::Not(inner) => !inner.matches(node),
+            Expr::Compare(field, op, value) => match value {
+                Value::Number(number) => {
+                    let actual = if *field == Field::Entries { node.entries } else { node.size };
+                    op.holds(actual.cmp(number))
+                }
+                Value::Time(time) => {
+                    let actual = if *field == Field::Accessed { node.accessed } else { node.modified };
+                    actual.is_some_and(|actual| op.holds(actual.cmp(time)))
+                }
+                Value::Dir(is_dir) => op.holds(node.is_dir.cmp(is_dir)),
+                Value::Text(text) if *op == Op::Contains => field.text(node).contains(text.as_str()),
+                Value::Text(text) => op.holds(field.text(node).as_str().cmp(text.as_str())),
+            },
+        }
+    }
+}
+
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        chars.next();
+        // Only the comparison operators take a following `=`; after anything else it starts
+        // the next token.
+        let followed_by_equals = "!<>".contains(c) && chars.next_if_eq(&'=').is_some();
+        let token = match (c, followed_by_equals) {
+            ('(', _) => Token::Open,
+            (')', _) => Token::Close,
+            ('=', _) => Token::Op(Op::Eq),
+            ('~', _) => Token::Op(Op::Contains),
+            ('!', true) => Token::Op(Op::Ne),
+            ('>', true) => Token::Op(Op::Ge),
+            ('>', false) => Token::Op(Op::Gt),
+            ('<', true) => Token::Op(Op::Le),
+            ('<', false) => Token::Op(Op::Lt),
+            ('"', _) | ('\'', _) => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(next) => text.push(next),
+                        None => return Err("Unclosed quote".to_string()),
+                    }
+                }
+                Token::Quoted(text)
+            }
+            ('!', false) => return Err("Expected != after !".to_string()),
+            _ => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.next_if(|next| !next.is_whitespace() && !"()=!<>~\"'".contains(*next)) {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Dates are YYYY-MM-DD (UTC) from 1970 on; `90d` means ninety days ago.
+fn parse_time(word: &str) -> Result<SystemTime, String> {
+    let invalid = || format!("Invalid date: {} (use YYYY-MM-DD or a number of days like 30d)", word);
+    if let Some(days) = word.strip_suffix('d') {
+        let days: u64 = days.parse().map_err(|_| invalid())?;
+        let seconds = days.checked_mul(DAY_SECS).ok_or_else(invalid)?;
+        return SystemTime::now().checked_sub(Duration::from_secs(seconds)).ok_or_else(invalid);
+    }
+    let parts: Vec<i64> = word.split('-').map(|part| part.parse().map_err(|_| invalid())).collect::<Result<_, _>>()?;
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    // A day past the end of its month, like 2023-02-31, comes back as a different date.
+    let timestamp = audit::civil_timestamp(year, month, day, 0);
+    if audit::civil_date(timestamp) != (year, month, day, 0) {
+        return Err(invalid());
+    }
+    Ok(UNIX_EPOCH + Duration::from_secs(timestamp))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn done(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn word(&mut self, what: &str) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Word(word)) | Some(Token::Quoted(word)) => Ok(word.clone()),
+            _ => Err(format!("Expected {}", what)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat_keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat_keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if matches!(self.tokens.get(self.position), Some(Token::Open)) {
+            self.position += 1;
+            let expr = self.or()?;
+            return match self.next() {
+                Some(Token::Close) => Ok(expr),
+                _ => Err("Expected )".to_string()),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let field = Field::parse(&self.word("a field such as size, name or modified")?)?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => *op,
+            _ => return Err("Expected an operator: = != > >= < <= ~".to_string()),
+        };
+        let word = self.word("a value")?;
+        let value = match field {
+            Field::Size => Value::Number(cli::parse_size(&word)?),
+            Field::Entries => Value::Number(word.parse().map_err(|_| format!("Invalid number: {}", word))?),
+            Field::Modified | Field::Accessed => Value::Time(parse_time(&word)?),
+            Field::Type => match word.to_ascii_lowercase().as_str() {
+                "dir" | "folder" => Value::Dir(true),
+                "file" => Value::Dir(false),
+                _ => return Err(format!("Invalid type: {} (use file or dir)", word)),
+            },
+            Field::Ext => Value::Text(word.trim_start_matches('.').to_lowercase()),
+            Field::Name | Field::Path => Value::Text(word.to_lowercase()),
+        };
+        if op == Op::Contains && !matches!(value, Value::Text(_)) {
+            return Err("~ only works on name, ext and path".to_string());
+        }
+        Ok(Expr::Compare(field, op, value))
+    }
+}
+
+/// A parsed query: `<conditions> [sort by <field> [asc|desc]] [limit <n>]`.
+pub struct Query {
+    filter: Option<Expr>,
+    sort: Option<(Field, bool)>,
+    limit: Option<usize>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, String> {
+        let mut parser = Parser { tokens: tokenize(input)?, position: 0 };
+        let filter = if parser.done() || parser.peek_keyword("sort") || parser.peek_keyword("limit") {
+            None
+        } else {
+            Some(parser.or()?)
+        };
+        let mut sort = None;
+        if parser.eat_keyword("sort") {
+            if !parser.eat_keyword("by") {
+                return Err("Expected by after sort".to_string());
+            }
+            let field = Field::parse(&parser.word("a field to sort by")?)?;
+            let descending = parser.eat_keyword("desc");
+            if !descending {
+                parser.eat_keyword("asc");
+            }
+            sort = Some((field, descending));
+        }
+        let mut limit = None;
+        if parser.eat_keyword("limit") {
+            let word = parser.word("a number")?;
+            limit = Some(word.parse().map_err(|_| format!("Invalid limit: {}", word))?);
+        }
+        if !parser.done() {
+            return Err("Expected and, or, sort by or limit".to_string());
+        }
+        Ok(Query { filter, sort, limit })
+    }
+
+    /// Matching nodes below the root, largest first unless the query sorts otherwise.
+    pub fn run(&self, index: &ScanIndex) -> Vec<usize> {
+        let nodes = &index.nodes;
+        let mut results: Vec<usize> = (1..nodes.len())
+            .filter(|&id| self.filter.as_ref().is_none_or(|filter| filter.matches(&nodes[id])))
+            .collect();
+        match self.sort {
+            Some((field, descending)) => results.sort_by(|&a, &b| {
+                let ordering = field.order(&nodes[a], &nodes[b]);
+                if descending { ordering.reverse() } else { ordering }
+            }),
+            None => results.sort_by_key(|&id| std::cmp::Reverse(nodes[id].size)),
+        }
+        if let Some(limit) = self.limit {
+            results.truncate(limit);
+        }
+        results
+    }
+}
+
+pub fn to_csv(index: &ScanIndex, results: &[usize]) -> String {
+    let mut csv = String::from("path,type,size,entries,modified,accessed\n");
+    for &id in results {
+        let node = &index.nodes[id];
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&node.path.to_string_lossy()),
+            if node.is_dir { "dir" } else { "file" },
+            node.size,
+            node.entries,
+            node.modified.map_or(String::new(), |time| audit::format_timestamp(dump::seconds(time))),
+            node.accessed.map_or(String::new(), |time| audit::format_timestamp(dump::seconds(time))),
+        ));
+    }
+    csv
+}
@@ -0,0 +1,150 @@
+use eframe::egui::{self, Color32, Key, KeyboardShortcut, Modifiers, RichText};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    i18n::{self, tr},
+    index::ScanIndex,
+    paths,
+};
+
+pub const SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::P);
+const MAX_RESULTS: usize = 50;
+
+/// Scores `candidate` against `query` as a case-insensitive subsequence match. Consecutive
+/// characters and matches at the start of a path component score higher; None means no match.
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<i64> {
+    let mut score = 0i64;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous_matched = false;
+    let mut previous = '/';
+    for c in candidate.chars() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(wanted));
+        if matched {
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if matches!(previous, '/' | '\\' | '_' | '-' | '.' | ' ') {
+                score += 10;
+            }
+            query_chars.next();
+        }
+        previous_matched = matched;
+        previous = c;
+    }
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some(score * 100 - candidate.len() as i64)
+}
+
+#[derive(Default)]
+pub struct QuickOpen {
+    pub open: bool,
+    query: String,
+    results: Vec<usize>,
+    selected: usize,
+    focus: bool,
+}
+
+impl QuickOpen {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.results.clear();
+        self.selected = 0;
+        self.focus = self.open;
+    }
+
+    fn update_results(&mut self, index: &ScanIndex, root: &Path) {
+        self.selected = 0;
+        if self.query.trim().is_empty() {
+            self.results.clear();
+            return;
+        }
+        let query: String = self.query.split_whitespace().collect();
+        let mut scored: Vec<(i64, usize)> = (1..index.nodes.len())
+            .filter_map(|id| {
+                let path = &index.nodes[id].path;
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                fuzzy_score(&paths::display_path(relative), &query).map(|score| (score, id))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, id)| (std::cmp::Reverse(score), std::cmp::Reverse(index.nodes[id].size)));
+        self.results = scored.into_iter().take(MAX_RESULTS).map(|(_, id)| id).collect();
+    }
+
+    /// Draws the palette and returns the directory to jump to; picking a file jumps to its folder.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>, root: Option<&Path>) -> Option<PathBuf> {
+        let mut target = None;
+        let mut close = ctx.input(|i| i.key_pressed(Key::Escape));
+        egui::Window::new(tr("Go to Path"))
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let (Some(index), Some(root)) = (index, root) else {
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(tr("Type part of a path"))
+                        .desired_width(f32::INFINITY),
+                );
+                if std::mem::take(&mut self.focus) {
+                    edit.request_focus();
+                }
+                if edit.changed() {
+                    self.update_results(index, root);
+                }
+
+                let (down, up, enter) = ctx.input(|i| {
+                    (i.key_pressed(Key::ArrowDown), i.key_pressed(Key::ArrowUp), i.key_pressed(Key::Enter))
+                });
+                if down && self.selected + 1 < self.results.len() {
+                    self.selected += 1;
+                }
+                if up {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+
+                let mut picked = enter.then_some(self.selected).filter(|&row| row < self.results.len());
+                for (row, &id) in self.results.iter().enumerate() {
+                    let node = &index.nodes[id];
+                    let relative = node.path.strip_prefix(root).unwrap_or(&node.path);
+                    let icon = if node.is_dir { "📁" } else { "📄" };
+                    let text = RichText::new(format!("{} {}", icon, paths::display_path(relative)));
+                    let text = if node.is_dir { text.color(Color32::LIGHT_BLUE) } else { text };
+                    ui.horizontal(|ui| {
+                        let response = ui.selectable_label(row == self.selected, text);
+                        if row == self.selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            picked = Some(row);
+                        }
+                        ui.label(RichText::new(i18n::format_bytes(node.size)).color(Color32::GRAY));
+                    });
+                }
+                if !self.query.trim().is_empty() && self.results.is_empty() {
+                    ui.label(RichText::new(tr("No matches")).color(Color32::GRAY));
+                }
+
+                if let Some(row) = picked {
+                    let node = &index.nodes[self.results[row]];
+                    target = if node.is_dir { Some(node.path.clone()) } else { node.path.parent().map(Path::to_path_buf) };
+                    close = true;
+                }
+            });
+        if close {
+            self.toggle();
+        }
+        target
+    }
+}
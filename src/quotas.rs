@@ -0,0 +1,360 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alerts::{self, Alert, AlertKind, AlertSettings},
+    config, dialogs,
+    i18n::{self, tr, trf},
+    job::Job,
+    notify, scan,
+};
+
+const QUOTAS_FILE: &str = "quotas.json";
+const GIB: u64 = 1024 * 1024 * 1024;
+pub const MONITOR_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct QuotaStore {
+    limits: BTreeMap<PathBuf, u64>,
+    #[serde(default)]
+    pub monitor: bool,
+    #[serde(default)]
+    pub alerts: AlertSettings,
+}
+
+impl QuotaStore {
+    pub fn load() -> Self {
+        config::load(QUOTAS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(QUOTAS_FILE, self)
+    }
+
+    pub fn limit(&self, path: &Path) -> Option<u64> {
+        self.limits.get(path).copied()
+    }
+
+    pub fn over_budget(&self, path: &Path, size: u64) -> bool {
+        self.limit(path).is_some_and(|limit| size > limit)
+    }
+
+    pub fn budgets(&self) -> Vec<(PathBuf, u64)> {
+        self.limits.iter().map(|(path, limit)| (path.clone(), *limit)).collect()
+    }
+}
+
+pub struct QuotaCheck {
+    pub path: PathBuf,
+    pub size: u64,
+    pub limit: u64,
+}
+
+impl QuotaCheck {
+    pub fn breached(&self) -> bool {
+        self.size > self.limit
+    }
+}
+
+/// Measures every budgeted directory and notifies about those that went over since `breached`.
+/// Returns the checks and any alerts or notifications that couldn't be sent.
+fn check(
+    limits: Vec<(PathBuf, u64)>,
+    breached: HashSet<PathBuf>,
+    notify_new: bool,
+    settings: AlertSettings,
+) -> (Vec<QuotaCheck>, Vec<String>) {
+    let mut errors = Vec::new();
+    let measured: Vec<(QuotaCheck, u64)> = limits
+        .into_iter()
+        .map(|(path, limit)| {
+            let measurement = scan::measure_dir(&path, &Default::default());
+            (QuotaCheck { size: measurement.size, path, limit }, measurement.entries)
+        })
+        .collect();
+    if notify_new {
+        for (check, entries) in &measured {
+            let newly_breached = check.breached() && !breached.contains(&check.path);
+            let kind = if newly_breached {
+                AlertKind::OverBudget
+            } else if settings.on_scan_finished {
+                AlertKind::ScanFinished
+            } else {
+                continue;
+            };
+            let alert =
+                Alert { kind, path: check.path.clone(), size: check.size, entries: *entries, budget: Some(check.limit) };
+            if let Err(e) = alerts::send(&settings, &alert, &Default::default()) {
+                errors.push(e);
+            }
+        }
+    }
+    let checks: Vec<QuotaCheck> = measured.into_iter().map(|(check, _)| check).collect();
+    if notify_new {
+        for check in checks.iter().filter(|check| check.breached() && !breached.contains(&check.path)) {
+            let body = trf(
+                "{} is {} over its {} budget",
+                &[
+                    &check.path.display(),
+                    &i18n::format_bytes(check.size - check.limit),
+                    &i18n::format_bytes(check.limit),
+                ],
+            );
+            if let Err(e) = notify::send(tr("Directory over budget"), &body) {
+                errors.push(e);
+            }
+        }
+    }
+    (checks, errors)
+}
+
+pub struct QuotaState {
+    pub open: bool,
+    pub store: QuotaStore,
+    job: Option<Job<(Vec<QuotaCheck>, Vec<String>)>>,
+    test_job: Option<Job<Result<(), String>>>,
+    checks: Vec<QuotaCheck>,
+    last_check: Option<Instant>,
+    new_path: String,
+    new_limit_gib: u64,
+    message: Option<String>,
+}
+
+impl Default for QuotaState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            store: QuotaStore::load(),
+            job: None,
+            test_job: None,
+            checks: Vec::new(),
+            last_check: None,
+            new_path: String::new(),
+            new_limit_gib: 10,
+            message: None,
+        }
+    }
+}
+
+impl QuotaState {
+    pub fn over_budget(&self, path: &Path, size: u64) -> bool {
+        self.store.over_budget(path, size)
+    }
+
+    fn start_check(&mut self, notify_new: bool) {
+        let limits = self.store.budgets();
+        let breached = self
+            .checks
+            .iter()
+            .filter(|check| check.breached())
+            .map(|check| check.path.clone())
+            .collect();
+        let settings = self.store.alerts.clone();
+        self.job = Some(Job::spawn(move || check(limits, breached, notify_new, settings)));
+        self.last_check = Some(Instant::now());
+    }
+
+    fn save(&mut self) {
+        self.message = self.store.save().err();
+    }
+
+    /// Runs the background monitor; called every frame whether or not the window is open.
+    /// Returns the alerts and notifications a finished check failed to send.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some((checks, failed)) => {
+                    self.checks = checks;
+                    errors = failed;
+                    self.job = None;
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(250)),
+            }
+        }
+        if !self.store.monitor || self.store.limits.is_empty() || self.job.is_some() {
+            return errors;
+        }
+        match self.last_check {
+            Some(last) if last.elapsed() < MONITOR_INTERVAL => {
+                ctx.request_repaint_after(MONITOR_INTERVAL - last.elapsed());
+            }
+            // The first pass only records the current state, so a breach that already existed
+            // at startup doesn't notify again.
+            last => self.start_check(last.is_some()),
+        }
+        errors
+    }
+
+    fn show_alerts(&mut self, ui: &mut egui::Ui) {
+        if let Some(job) = &self.test_job {
+            if let Some(result) = job.poll() {
+                self.message = Some(match result {
+                    Ok(()) => tr("Test alert sent").to_string(),
+                    Err(e) => e,
+                });
+                self.test_job = None;
+            }
+        }
+        let mut changed = false;
+        let settings = &mut self.store.alerts;
+        egui::Grid::new("alert_settings").num_columns(2).show(ui, |ui| {
+            let fields = [
+                ("Webhook URL:", &mut settings.webhook_url, "https://hooks.example.com/...", false),
+                ("SMTP server:", &mut settings.smtp_url, "smtps://smtp.example.com:465", false),
+                ("SMTP user:", &mut settings.smtp_user, "", false),
+                ("SMTP password:", &mut settings.smtp_password, "", true),
+                ("From:", &mut settings.mail_from, "disk-analyzer@example.com", false),
+                ("To:", &mut settings.mail_to, "ops@example.com, admin@example.com", false),
+            ];
+            for (label, value, hint, password) in fields {
+                ui.label(tr(label));
+                let edit = egui::TextEdit::singleline(value).hint_text(hint).password(password).desired_width(300.0);
+                changed |= ui.add(edit).lost_focus();
+                ui.end_row();
+            }
+        });
+        changed |= ui
+            .checkbox(&mut settings.on_scan_finished, tr("Also report every scheduled check"))
+            .on_hover_text(tr("Sends the size of each budgeted folder after every check, not only breaches"))
+            .changed();
+        ui.label(tr("Messages ({path}, {host}, {size}, {entries}, {budget}, {over} and {top} are filled in):"));
+        for (template, default) in [
+            (&mut settings.breach_template, alerts::DEFAULT_BREACH_TEMPLATE),
+            (&mut settings.scan_template, alerts::DEFAULT_SCAN_TEMPLATE),
+        ] {
+            let edit = egui::TextEdit::multiline(template).hint_text(default).desired_rows(2).desired_width(f32::INFINITY);
+            changed |= ui.add(edit).lost_focus();
+        }
+        if changed {
+            self.save();
+        }
+
+        ui.horizontal(|ui| {
+            let enabled = self.store.alerts.enabled() && self.test_job.is_none();
+            let test = ui.add_enabled(enabled, egui::Button::new(tr("Send Test Alert")));
+            if test.clicked() {
+                let settings = self.store.alerts.clone();
+                // Reports the first budgeted folder, or the home folder, as a finished scan.
+                let (path, budget) = match self.store.budgets().into_iter().next() {
+                    Some((path, limit)) => (path, Some(limit)),
+                    None => (dirs::home_dir().unwrap_or_default(), None),
+                };
+                self.test_job = Some(Job::spawn(move || {
+                    let measurement = scan::measure_dir(&path, &Default::default());
+                    let alert = Alert {
+                        kind: AlertKind::ScanFinished,
+                        path,
+                        size: measurement.size,
+                        entries: measurement.entries,
+                        budget,
+                    };
+                    alerts::send(&settings, &alert, &Default::default())
+                }));
+            }
+            if self.test_job.is_some() {
+                ui.spinner();
+                ui.ctx().request_repaint_after(Duration::from_millis(250));
+            }
+        });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, current: Option<&Path>) {
+        let mut open = self.open;
+        egui::Window::new(tr("Directory Budgets"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Folder:"));
+                    ui.text_edit_singleline(&mut self.new_path);
+                    if ui.button(tr("Browse")).clicked() {
+                        if let Some(path) = dialogs::pick_folder() {
+                            self.new_path = path.display().to_string();
+                        }
+                    }
+                    if let Some(current) = current {
+                        if ui.button(tr("Current Folder")).clicked() {
+                            self.new_path = current.display().to_string();
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Budget:"));
+                    ui.add(egui::DragValue::new(&mut self.new_limit_gib).suffix(" GiB").clamp_range(1..=u64::MAX));
+                    let valid = Path::new(self.new_path.trim()).is_dir();
+                    if ui.add_enabled(valid, egui::Button::new(tr("Add"))).clicked() {
+                        let path = PathBuf::from(self.new_path.trim());
+                        self.store.limits.insert(path, self.new_limit_gib * GIB);
+                        self.new_path.clear();
+                        self.save();
+                        self.start_check(false);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let monitor = ui
+                        .checkbox(&mut self.store.monitor, tr("Notify when a folder goes over budget"))
+                        .on_hover_text(trf(
+                            "Checks every {} minutes while the app is open",
+                            &[&(MONITOR_INTERVAL.as_secs() / 60)],
+                        ));
+                    if monitor.changed() {
+                        self.save();
+                    }
+                    let idle = self.job.is_none() && !self.store.limits.is_empty();
+                    if ui.add_enabled(idle, egui::Button::new(tr("Check Now"))).clicked() {
+                        self.start_check(true);
+                    }
+                    if self.job.is_some() {
+                        ui.spinner();
+                    }
+                });
+                egui::CollapsingHeader::new(tr("Webhook and Email Alerts")).show(ui, |ui| self.show_alerts(ui));
+                if let Some(message) = &self.message {
+                    ui.label(RichText::new(message).color(Color32::RED));
+                }
+                ui.separator();
+
+                if self.store.limits.is_empty() {
+                    ui.label(tr("No budgets set."));
+                    return;
+                }
+                let mut removed = None;
+                egui::Grid::new("quotas").striped(true).show(ui, |ui| {
+                    ui.label(tr("Folder"));
+                    ui.label(tr("Budget"));
+                    ui.label(tr("Used"));
+                    ui.end_row();
+                    for (path, limit) in &self.store.limits {
+                        ui.label(path.display().to_string());
+                        ui.label(i18n::format_bytes(*limit));
+                        match self.checks.iter().find(|check| &check.path == path) {
+                            Some(check) => {
+                                let color = if check.breached() { Color32::RED } else { Color32::GREEN };
+                                ui.label(RichText::new(i18n::format_bytes(check.size)).color(color));
+                            }
+                            None => {
+                                ui.label("-");
+                            }
+                        }
+                        if ui.button(tr("Remove")).clicked() {
+                            removed = Some(path.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(path) = removed {
+                    self.store.limits.remove(&path);
+                    self.checks.retain(|check| check.path != path);
+                    self.save();
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,273 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::Metadata,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    i18n::{self, tr, trf},
+    job::Job,
+    scan, snapshots, volume,
+};
+
+/// What deleting a set of files and folders would give back.
+#[derive(Clone, Default)]
+pub struct Estimate {
+    pub files: u64,
+    pub logical: u64,
+    /// Bytes on disk, counting hard-linked files once.
+    pub on_disk: u64,
+    /// Bytes of hard-linked files that still have names outside the selection.
+    pub linked: u64,
+    /// Bytes sharing extents with clones, reflink copies or deduplicated data.
+    pub shared: u64,
+    /// Snapshots on the volumes involved; they keep deleted data until they are removed.
+    pub snapshots: usize,
+}
+
+impl Estimate {
+    pub fn reclaimable(&self) -> u64 {
+        self.on_disk.saturating_sub(self.linked + self.shared)
+    }
+}
+
+/// The file's identity and link count, for files with more than one name.
+#[cfg(unix)]
+fn hard_link(metadata: &Metadata) -> Option<((u64, u64), u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.nlink() > 1).then(|| ((metadata.dev(), metadata.ino()), metadata.nlink()))
+}
+
+#[cfg(not(unix))]
+fn hard_link(_metadata: &Metadata) -> Option<((u64, u64), u64)> {
+    None
+}
+
+/// Walks the entries; gives up, with a partial estimate nobody will see, once `cancel` is set.
+pub fn estimate(paths: Vec<PathBuf>, cancel: &AtomicBool) -> Estimate {
+    let mut estimate = Estimate::default();
+    let selected: HashSet<&Path> = paths.iter().map(PathBuf::as_path).collect();
+    // A folder's contents are walked with it, so selected entries inside it don't count twice.
+    let tops = paths.iter().filter(|path| !path.ancestors().skip(1).any(|ancestor| selected.contains(ancestor)));
+    // Size, link count and names seen, per hard-linked file.
+    let mut links: HashMap<(u64, u64), (u64, u64, u64)> = HashMap::new();
+    let mut volumes = HashSet::new();
+    for top in tops {
+        if let Some((fs, mount)) = snapshots::detect(top) {
+            if volumes.insert(mount.mount_point.clone()) {
+                if let Ok((subvolumes, _)) = snapshots::list(fs, &mount) {
+                    estimate.snapshots += subvolumes.iter().filter(|subvolume| subvolume.is_snapshot).count();
+                }
+            }
+        }
+        for entry in WalkDir::new(top).follow_links(false).into_iter().filter_map(Result::ok) {
+            if cancel.load(Ordering::Relaxed) {
+                return estimate;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let size = scan::file_size(entry.path(), &metadata);
+            if let Some((id, count)) = hard_link(&metadata) {
+                let link = links.entry(id).or_insert((size, count, 0));
+                link.2 += 1;
+                if link.2 > 1 {
+                    continue;
+                }
+            }
+            estimate.files += 1;
+            estimate.logical += scan::logical_size(&metadata);
+            estimate.on_disk += size;
+            if let Some(clone) = volume::clone_info(entry.path()).filter(|clone| clone.may_share_blocks) {
+                estimate.shared += size.saturating_sub(clone.private_size);
+            }
+        }
+    }
+    estimate.linked = links.values().filter(|(_, count, seen)| seen < count).map(|(size, _, _)| size).sum();
+    if cfg!(target_os = "macos") {
+        estimate.snapshots += volume::local_snapshots().len();
+    }
+    estimate
+}
+
+pub enum SelectionAction {
+    Delete,
+    Move,
+    Copy,
+}
+
+#[derive(Clone, Copy)]
+pub struct Selected {
+    pub is_dir: bool,
+    pub size: u64,
+    pub logical: u64,
+}
+
+/// How long the selection has to stay unchanged before it's estimated, so ticking several
+/// boxes starts one walk rather than one each.
+const ESTIMATE_DELAY: Duration = Duration::from_millis(400);
+
+/// Entries ticked in the file list, with a running estimate of what deleting them frees.
+#[derive(Default)]
+pub struct Selection {
+    items: BTreeMap<PathBuf, Selected>,
+    job: Option<Job<Estimate>>,
+    /// Stops the walk of a selection that has since changed.
+    cancel: Arc<AtomicBool>,
+    /// When the selection last changed, until it's estimated.
+    changed: Option<Instant>,
+    estimate: Option<Estimate>,
+}
+
+impl Selection {
+    pub fn contains(&self, path: &Path) -> bool {
+        self.items.contains_key(path)
+    }
+
+    pub fn set(&mut self, path: PathBuf, selected: Option<Selected>) {
+        let changed = match selected {
+            Some(selected) => self.items.insert(path, selected).is_none(),
+            None => self.items.remove(&path).is_some(),
+        };
+        if changed {
+            self.stop();
+            self.changed = Some(Instant::now());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.estimate = None;
+        self.job = None;
+        self.changed = None;
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = (&PathBuf, Selected)> {
+        self.items.iter().map(|(path, selected)| (path, *selected))
+    }
+
+    /// Takes the selected entries, emptying the selection.
+    pub fn take(&mut self) -> Vec<(PathBuf, Selected)> {
+        let items = self.items.iter().map(|(path, selected)| (path.clone(), *selected)).collect();
+        self.clear();
+        items
+    }
+
+    /// The summary above the file list; returns what the user asked to do with the selection.
+    pub fn show(&mut self, ui: &mut egui::Ui, read_only: bool) -> Option<SelectionAction> {
+        if self.items.is_empty() {
+            return None;
+        }
+        if let Some(changed) = self.changed {
+            let waited = changed.elapsed();
+            if waited >= ESTIMATE_DELAY {
+                self.changed = None;
+                self.cancel = Arc::default();
+                let (paths, cancel) = (self.items.keys().cloned().collect(), Arc::clone(&self.cancel));
+                self.job = Some(Job::spawn(move || estimate(paths, &cancel)));
+            } else {
+                ui.ctx().request_repaint_after(ESTIMATE_DELAY - waited);
+            }
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(estimate) => {
+                    self.estimate = Some(estimate);
+                    self.job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+
+        let mut action = None;
+        ui.group(|ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(RichText::new(trf("{} selected", &[&i18n::format_count(self.items.len() as u64)])).strong());
+                match &self.estimate {
+                    Some(estimate) => {
+                        ui.label(trf("{} files", &[&i18n::format_count(estimate.files)]));
+                        ui.label(trf("Logical size: {}", &[&i18n::format_bytes(estimate.logical)]));
+                        ui.label(trf("On disk: {}", &[&i18n::format_bytes(estimate.on_disk)]));
+                        let reclaim = RichText::new(trf(
+                            "Estimated reclaim: {}",
+                            &[&i18n::format_bytes(estimate.reclaimable())],
+                        ))
+                        .strong()
+                        .color(Color32::GREEN);
+                        ui.label(reclaim).on_hover_text(tr(
+                            "What deleting the selection would free: its size on disk, less hard-linked files that keep other names and data shared with clones.",
+                        ));
+                    }
+                    // The listed sizes until the walk is done; they count hard links and clones in full.
+                    None => {
+                        let logical: u64 = self.items.values().map(|item| item.logical).sum();
+                        let on_disk: u64 = self.items.values().map(|item| item.size).sum();
+                        ui.label(trf("Logical size: {}", &[&i18n::format_bytes(logical)]));
+                        ui.label(trf("On disk: {}", &[&i18n::format_bytes(on_disk)]));
+                        ui.spinner();
+                    }
+                }
+                if !read_only {
+                    if ui.button(tr("Queue Deletion")).clicked() {
+                        action = Some(SelectionAction::Delete);
+                    }
+                    if ui.button(tr("Move to...")).clicked() {
+                        action = Some(SelectionAction::Move);
+                    }
+                    if ui.button(tr("Copy with verification...")).clicked() {
+                        action = Some(SelectionAction::Copy);
+                    }
+                }
+                if ui.button(tr("Clear Selection")).clicked() {
+                    self.clear();
+                }
+            });
+            let Some(estimate) = &self.estimate else {
+                return;
+            };
+            if estimate.linked > 0 {
+                ui.label(
+                    RichText::new(trf(
+                        "{} stays in use by hard links outside the selection",
+                        &[&i18n::format_bytes(estimate.linked)],
+                    ))
+                    .color(Color32::YELLOW),
+                );
+            }
+            if estimate.shared > 0 {
+                ui.label(
+                    RichText::new(trf(
+                        "{} is shared with cloned or deduplicated copies",
+                        &[&i18n::format_bytes(estimate.shared)],
+                    ))
+                    .color(Color32::YELLOW),
+                );
+            }
+            if estimate.snapshots > 0 {
+                ui.label(
+                    RichText::new(trf(
+                        "{} snapshots on this volume may keep the space in use until they are removed",
+                        &[&estimate.snapshots],
+                    ))
+                    .color(Color32::YELLOW),
+                );
+            }
+        });
+        action
+    }
+}
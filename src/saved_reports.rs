@@ -0,0 +1,402 @@
+use eframe::egui::{self, Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    audit, config, dialogs, dump,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    query::Query,
+    scan::ScanOptions,
+};
+
+const SAVED_REPORTS_FILE: &str = "saved_reports.json";
+/// Rows kept from each run; a query without a limit could otherwise match the whole disk.
+const MAX_ROWS: usize = 1000;
+const MAX_SHOWN: usize = 200;
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum Schedule {
+    #[default]
+    Manual,
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl Schedule {
+    const ALL: [Schedule; 4] = [Schedule::Manual, Schedule::Hourly, Schedule::Daily, Schedule::Weekly];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Schedule::Manual => "Manually",
+            Schedule::Hourly => "Every hour",
+            Schedule::Daily => "Every day",
+            Schedule::Weekly => "Every week",
+        }
+    }
+
+    fn interval(&self) -> Option<u64> {
+        match self {
+            Schedule::Manual => None,
+            Schedule::Hourly => Some(60 * 60),
+            Schedule::Daily => Some(24 * 60 * 60),
+            Schedule::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Row {
+    pub path: PathBuf,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedReport {
+    pub name: String,
+    pub root: PathBuf,
+    pub query: String,
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// The scan options of the first run, kept so later runs compare like with like after the
+    /// options change. Reports saved before this was recorded take the options at their next run.
+    #[serde(default)]
+    pub options: Option<ScanOptions>,
+    #[serde(default)]
+    pub show_hidden: bool,
+    /// Seconds since the epoch of the last finished run.
+    #[serde(default)]
+    pub last_run: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub rows: Vec<Row>,
+    /// Rows the last run found that the one before it didn't, and the other way round. Set
+    /// until the report is marked as seen.
+    #[serde(default)]
+    pub added: usize,
+    #[serde(default)]
+    pub removed: usize,
+    #[serde(default)]
+    pub changed: bool,
+}
+
+impl SavedReport {
+    fn due(&self, now: u64) -> bool {
+        self.schedule
+            .interval()
+            .is_some_and(|interval| self.last_run.is_none_or(|last| now.saturating_sub(last) >= interval))
+    }
+
+    fn update(&mut self, result: Result<Vec<Row>, String>) {
+        // The first run has nothing to compare with, and neither does one after a failure.
+        let comparable = self.last_run.is_some() && self.error.is_none();
+        self.last_run = Some(dump::seconds(SystemTime::now()));
+        match result {
+            Ok(rows) => {
+                if comparable && rows != self.rows {
+                    let before: HashSet<&PathBuf> = self.rows.iter().map(|row| &row.path).collect();
+                    let after: HashSet<&PathBuf> = rows.iter().map(|row| &row.path).collect();
+                    self.added = after.difference(&before).count();
+                    self.removed = before.difference(&after).count();
+                    self.changed = true;
+                }
+                self.rows = rows;
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SavedReportStore {
+    reports: Vec<SavedReport>,
+}
+
+impl SavedReportStore {
+    fn save(&self) -> Result<(), String> {
+        config::save(SAVED_REPORTS_FILE, self)
+    }
+}
+
+/// Scans `root` afresh and runs the query over it.
+fn generate(root: PathBuf, query: String, options: ScanOptions, show_hidden: bool) -> Result<Vec<Row>, String> {
+    let query = Query::parse(&query)?;
+    if !root.is_dir() {
+        return Err(format!("Error reading {}: not a folder", root.display()));
+    }
+    let index = ScanIndex::build(&root, &options, show_hidden);
+    Ok(query
+        .run(&index)
+        .into_iter()
+        .take(MAX_ROWS)
+        .map(|id| {
+            let node = &index.nodes[id];
+            Row { path: node.path.clone(), size: node.size, is_dir: node.is_dir }
+        })
+        .collect())
+}
+
+type Generation = Job<Result<Vec<Row>, String>>;
+
+pub struct SavedReportsState {
+    pub open: bool,
+    store: SavedReportStore,
+    /// The report being regenerated, by name.
+    job: Option<(String, Generation)>,
+    /// Reports asked to run now, ahead of the schedule.
+    pending: Vec<String>,
+    new_name: String,
+    new_root: String,
+    new_query: String,
+    new_schedule: Schedule,
+    message: Option<String>,
+}
+
+impl Default for SavedReportsState {
+    fn default() -> Self {
+        let store: SavedReportStore = config::load(SAVED_REPORTS_FILE);
+        Self {
+            open: false,
+            store,
+            job: None,
+            pending: Vec::new(),
+            new_name: String::new(),
+            new_root: String::new(),
+            new_query: String::new(),
+            new_schedule: Schedule::Daily,
+            message: None,
+        }
+    }
+}
+
+impl SavedReportsState {
+    fn save(&mut self) {
+        self.message = self.store.save().err();
+    }
+
+    /// Reports whose results differ from the run before and haven't been looked at.
+    pub fn changed_count(&self) -> usize {
+        self.store.reports.iter().filter(|report| report.changed).count()
+    }
+
+    /// Opens the window with `query` filled in, ready to be named and saved.
+    pub fn save_query(&mut self, query: &str, root: Option<&Path>) {
+        self.new_query = query.to_string();
+        if let Some(root) = root {
+            self.new_root = root.display().to_string();
+        }
+        self.open = true;
+    }
+
+    /// Regenerates due reports in the background; called every frame whether or not the window
+    /// is open. The given options only apply to reports that haven't recorded their own.
+    pub fn poll(&mut self, ctx: &egui::Context, options: &ScanOptions, show_hidden: bool) {
+        if let Some((name, job)) = &self.job {
+            match job.poll() {
+                Some(result) => {
+                    if let Some(report) = self.store.reports.iter_mut().find(|report| &report.name == name) {
+                        report.update(result);
+                    }
+                    self.job = None;
+                    self.save();
+                }
+                None => {
+                    ctx.request_repaint_after(Duration::from_millis(250));
+                    return;
+                }
+            }
+        }
+        let now = dump::seconds(SystemTime::now());
+        let next = match self.pending.pop() {
+            Some(name) => self.store.reports.iter_mut().find(|report| report.name == name),
+            None => self.store.reports.iter_mut().find(|report| report.due(now)),
+        };
+        if let Some(report) = next {
+            if report.options.is_none() {
+                report.options = Some(options.clone());
+                report.show_hidden = show_hidden;
+            }
+            let (root, query, name) = (report.root.clone(), report.query.clone(), report.name.clone());
+            let (options, show_hidden) = (report.options.clone().unwrap_or_default(), report.show_hidden);
+            self.job = Some((name, Job::spawn(move || generate(root, query, options, show_hidden))));
+        } else if self.store.reports.iter().any(|report| report.schedule != Schedule::Manual) {
+            ctx.request_repaint_after(CHECK_INTERVAL);
+        }
+    }
+
+    fn show_form(&mut self, ui: &mut egui::Ui, current: Option<&Path>) {
+        egui::Grid::new("saved_report_form").num_columns(2).show(ui, |ui| {
+            ui.label(tr("Name:"));
+            ui.add(egui::TextEdit::singleline(&mut self.new_name).hint_text(tr("Old large files")).desired_width(360.0));
+            ui.end_row();
+            ui.label(tr("Folder:"));
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.new_root).desired_width(240.0));
+                if ui.button(tr("Browse")).clicked() {
+                    if let Some(path) = dialogs::pick_folder() {
+                        self.new_root = path.display().to_string();
+                    }
+                }
+                if let Some(current) = current {
+                    if ui.button(tr("Current Folder")).clicked() {
+                        self.new_root = current.display().to_string();
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Query:"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_query)
+                    .hint_text("type = file and modified < 365d sort by size desc limit 50")
+                    .desired_width(360.0)
+                    .code_editor(),
+            );
+            ui.end_row();
+            ui.label(tr("Regenerate:"));
+            egui::ComboBox::from_id_source("saved_report_schedule")
+                .selected_text(tr(self.new_schedule.label()))
+                .show_ui(ui, |ui| {
+                    for schedule in Schedule::ALL {
+                        ui.selectable_value(&mut self.new_schedule, schedule, tr(schedule.label()));
+                    }
+                });
+            ui.end_row();
+        });
+
+        let name = self.new_name.trim().to_string();
+        let parsed = Query::parse(&self.new_query);
+        let exists = self.store.reports.iter().any(|report| report.name == name);
+        let valid = !name.is_empty() && !exists && Path::new(self.new_root.trim()).is_dir() && parsed.is_ok();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(valid, egui::Button::new(tr("Save Report"))).clicked() {
+                self.store.reports.push(SavedReport {
+                    name: name.clone(),
+                    root: PathBuf::from(self.new_root.trim()),
+                    query: self.new_query.trim().to_string(),
+                    schedule: self.new_schedule,
+                    options: None,
+                    show_hidden: false,
+                    last_run: None,
+                    error: None,
+                    rows: Vec::new(),
+                    added: 0,
+                    removed: 0,
+                    changed: false,
+                });
+                self.pending.push(name);
+                self.new_name.clear();
+                self.save();
+            }
+            if exists {
+                ui.label(RichText::new(tr("A report with this name already exists")).color(Color32::GRAY));
+            } else if let (false, Err(error)) = (self.new_query.trim().is_empty(), &parsed) {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+        });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, current: Option<&Path>) {
+        let mut open = self.open;
+        egui::Window::new(tr("Saved Reports"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                egui::CollapsingHeader::new(tr("New Report"))
+                    .default_open(self.store.reports.is_empty() || !self.new_query.is_empty())
+                    .show(ui, |ui| self.show_form(ui, current));
+                if let Some(message) = &self.message {
+                    ui.label(RichText::new(message).color(Color32::RED));
+                }
+                ui.separator();
+
+                if self.store.reports.is_empty() {
+                    ui.label(tr("No saved reports."));
+                    return;
+                }
+                let running = self.job.as_ref().map(|(name, _)| name.clone());
+                let mut removed = None;
+                let mut seen = None;
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    for report in &self.store.reports {
+                        let mut title = RichText::new(&report.name).strong();
+                        if report.changed {
+                            title = title.color(Color32::GOLD);
+                        }
+                        egui::CollapsingHeader::new(title).id_source(("saved_report", &report.name)).show(ui, |ui| {
+                            ui.label(RichText::new(format!("{} - {}", report.root.display(), report.query)).monospace());
+                            ui.horizontal(|ui| {
+                                let last_run = report
+                                    .last_run
+                                    .map_or_else(|| tr("Never").to_string(), audit::format_timestamp);
+                                ui.label(trf("{}. Last run: {}", &[&tr(report.schedule.label()), &last_run]));
+                                if running.as_ref() == Some(&report.name) {
+                                    ui.spinner();
+                                } else if ui.button(tr("Run Now")).clicked() {
+                                    self.pending.push(report.name.clone());
+                                }
+                                if ui.button(tr("Remove")).clicked() {
+                                    removed = Some(report.name.clone());
+                                }
+                            });
+                            if report.changed {
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        RichText::new(trf(
+                                            "Changed since the previous run: {} new, {} gone",
+                                            &[&report.added, &report.removed],
+                                        ))
+                                        .color(Color32::GOLD),
+                                    );
+                                    if ui.button(tr("Mark as Seen")).clicked() {
+                                        seen = Some(report.name.clone());
+                                    }
+                                });
+                            }
+                            if let Some(error) = &report.error {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                            let total: u64 = report.rows.iter().map(|row| row.size).sum();
+                            ui.label(trf(
+                                "{} results, {}",
+                                &[&i18n::format_count(report.rows.len() as u64), &i18n::format_bytes(total)],
+                            ));
+                            egui::Grid::new(("saved_report_rows", &report.name)).striped(true).show(ui, |ui| {
+                                for row in report.rows.iter().take(MAX_SHOWN) {
+                                    let icon = if row.is_dir { "📁" } else { "📄" };
+                                    ui.label(format!("{} {}", icon, row.path.display()));
+                                    ui.label(i18n::format_bytes(row.size));
+                                    ui.end_row();
+                                }
+                            });
+                            if report.rows.len() > MAX_SHOWN {
+                                ui.label(RichText::new(trf("Showing the first {}", &[&MAX_SHOWN])).color(Color32::GRAY));
+                            }
+                        });
+                    }
+                });
+                if let Some(name) = seen {
+                    if let Some(report) = self.store.reports.iter_mut().find(|report| report.name == name) {
+                        report.changed = false;
+                    }
+                    self.save();
+                }
+                if let Some(name) = removed {
+                    self.store.reports.retain(|report| report.name != name);
+                    self.pending.retain(|pending| pending != &name);
+                    self.save();
+                }
+            });
+        self.open = open;
+    }
+}
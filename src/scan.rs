@@ -0,0 +1,442 @@
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fs::{self, Metadata},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{index, telemetry, throttle};
+
+pub const MAX_RESCANS: usize = 2;
+/// Thresholds offered for low-memory mode; the middle one is the default.
+pub const SUMMARY_THRESHOLDS: [u64; 4] = [64 * 1024, 256 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+pub const DEFAULT_SUMMARY_THRESHOLD: u64 = SUMMARY_THRESHOLDS[2];
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanOptions {
+    pub respect_ignores: bool,
+    pub one_file_system: bool,
+    pub excluded_names: Vec<String>,
+    #[serde(default)]
+    pub network_mode: bool,
+    /// Scans with `index::AsyncScanner` on tokio's asynchronous file I/O, for SFTP and cloud mounts.
+    #[serde(default)]
+    pub async_io: bool,
+    /// Low-memory mode: files smaller than this are only counted into their folder instead of
+    /// being kept in the scan index one by one. Zero keeps every file.
+    #[serde(default)]
+    pub summarize_below: u64,
+}
+
+impl ScanOptions {
+    pub fn excludes(&self, name: &OsStr) -> bool {
+        self.excluded_names.iter().any(|excluded| OsStr::new(excluded) == name)
+    }
+
+    /// Whether the chosen scanner lists folders from the scan index instead of reading them
+    /// again, as network and async scans do.
+    pub fn lists_from_index(&self) -> bool {
+        index::scanner(self, false).lists_from_index()
+    }
+
+    pub fn root_device(&self, path: &Path) -> Option<u64> {
+        if self.one_file_system {
+            fs::symlink_metadata(path).ok().and_then(|m| device_id(&m))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn device_id(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+#[cfg(windows)]
+mod attributes {
+    use std::{fs::Metadata, os::windows::fs::MetadataExt};
+    use winapi::um::winnt::{
+        FILE_ATTRIBUTE_COMPRESSED, FILE_ATTRIBUTE_OFFLINE, FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS,
+        FILE_ATTRIBUTE_RECALL_ON_OPEN, FILE_ATTRIBUTE_SPARSE_FILE,
+    };
+
+    pub fn online_only(metadata: &Metadata) -> bool {
+        metadata.file_attributes()
+            & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+            != 0
+    }
+
+    pub fn compressed(metadata: &Metadata) -> bool {
+        metadata.file_attributes() & (FILE_ATTRIBUTE_COMPRESSED | FILE_ATTRIBUTE_SPARSE_FILE) != 0
+    }
+
+    /// "Always keep on this device"; not in every winapi release, hence the literal.
+    pub fn pinned(metadata: &Metadata) -> bool {
+        const FILE_ATTRIBUTE_PINNED: u32 = 0x0008_0000;
+        metadata.file_attributes() & FILE_ATTRIBUTE_PINNED != 0
+    }
+}
+
+/// Cloud placeholders (OneDrive, Dropbox smart sync) that exist locally only as metadata.
+#[cfg(windows)]
+pub fn is_online_only(metadata: &Metadata) -> bool {
+    attributes::online_only(metadata)
+}
+
+/// Files a sync client was told to keep downloaded, which freeing up space leaves alone.
+#[cfg(windows)]
+pub fn is_pinned(metadata: &Metadata) -> bool {
+    attributes::pinned(metadata)
+}
+
+#[cfg(not(windows))]
+pub fn is_pinned(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// Dataless files that File Provider clients (iCloud Drive, OneDrive, Dropbox, Google Drive)
+/// download on first use.
+#[cfg(target_os = "macos")]
+pub fn is_online_only(metadata: &Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn is_online_only(_metadata: &Metadata) -> bool {
+    false
+}
+
+#[cfg(windows)]
+fn compressed_size(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetCompressedFileSizeW;
+
+    let wide: Vec<u16> = crate::paths::extended(path).as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut high = 0u32;
+    let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+    if low == u32::MAX && std::io::Error::last_os_error().raw_os_error() != Some(0) {
+        return None;
+    }
+    Some((u64::from(high) << 32) | u64::from(low))
+}
+
+/// Bytes a file takes on this device: nothing for online-only placeholders, the compressed
+/// size for NTFS-compressed or sparse files, and the logical length otherwise.
+#[cfg(windows)]
+pub fn file_size(path: &Path, metadata: &Metadata) -> u64 {
+    if attributes::online_only(metadata) {
+        0
+    } else if attributes::compressed(metadata) {
+        compressed_size(path).unwrap_or(metadata.len())
+    } else {
+        metadata.len()
+    }
+}
+
+/// Bytes a file takes on this device: nothing for online-only placeholders, and its allocated
+/// blocks when ZFS or APFS compression or holes make them fewer than its length. btrfs reports
+/// blocks before compression, so its compressed files count at their full length.
+#[cfg(unix)]
+pub fn file_size(_path: &Path, metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    if is_online_only(metadata) {
+        return 0;
+    }
+    let allocated = metadata.blocks() * 512;
+    // Some network and FUSE filesystems don't report blocks at all.
+    if allocated == 0 {
+        metadata.len()
+    } else {
+        allocated.min(metadata.len())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn file_size(_path: &Path, metadata: &Metadata) -> u64 {
+    if is_online_only(metadata) {
+        0
+    } else {
+        metadata.len()
+    }
+}
+
+/// Bytes a file holds before transparent compression, i.e. its length. Like `file_size`,
+/// nothing for online-only placeholders, so the two only differ where compression saves space.
+pub fn logical_size(metadata: &Metadata) -> u64 {
+    if is_online_only(metadata) {
+        0
+    } else {
+        metadata.len()
+    }
+}
+
+/// When the entry was last read or written. Many systems don't keep access times up to date
+/// (`noatime` mounts, Windows by default), so they lag behind the modification time, which is
+/// used instead then.
+pub fn last_used(metadata: &Metadata) -> Option<SystemTime> {
+    metadata.accessed().ok().max(metadata.modified().ok())
+}
+
+pub fn crosses_device(metadata: &Metadata, root_device: Option<u64>) -> bool {
+    root_device.is_some() && metadata.is_dir() && device_id(metadata) != root_device
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct IgnoredTotal {
+    pub count: u64,
+    pub size: u64,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Measurement {
+    pub size: u64,
+    /// Bytes before compression; see `logical_size`.
+    pub logical: u64,
+    /// Entries (files, folders and links) below a directory, i.e. the inodes it uses.
+    pub entries: u64,
+    pub changed: bool,
+    pub modified: Option<SystemTime>,
+}
+
+impl Measurement {
+    fn add(&mut self, other: Measurement) {
+        self.size += other.size;
+        self.logical += other.logical;
+        self.entries += other.entries;
+        self.changed |= other.changed;
+        self.touch(other.modified);
+    }
+
+    fn touch(&mut self, modified: Option<SystemTime>) {
+        if modified > self.modified {
+            self.modified = modified;
+        }
+    }
+}
+
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    fs::symlink_metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub fn measure_dir(path: &Path, options: &ScanOptions) -> Measurement {
+    measure_dir_within(path, options, options.root_device(path))
+}
+
+fn measure_dir_within(path: &Path, options: &ScanOptions, root_device: Option<u64>) -> Measurement {
+    let before = modified(path);
+    let mut total = Measurement::default();
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            total.changed = e.kind() == ErrorKind::NotFound;
+            return total;
+        }
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        if options.excludes(&entry.file_name()) {
+            continue;
+        }
+        throttle::pace();
+        match entry.metadata() {
+            Ok(metadata) if crosses_device(&metadata, root_device) => {}
+            Ok(metadata) if metadata.is_dir() => {
+                telemetry::record_entry(0);
+                total.entries += 1;
+                total.add(measure_dir_within(&entry.path(), options, root_device))
+            }
+            Ok(metadata) if metadata.is_file() => {
+                let size = file_size(&entry.path(), &metadata);
+                telemetry::record_entry(size);
+                total.size += size;
+                total.logical += logical_size(&metadata);
+                total.entries += 1;
+                total.touch(metadata.modified().ok());
+            }
+            Ok(_) => total.entries += 1,
+            Err(e) => total.changed |= e.kind() == ErrorKind::NotFound,
+        }
+    }
+
+    if modified(path) != before {
+        total.changed = true;
+    }
+    total
+}
+
+/// Sums the files directly inside `path`, without descending into subdirectories.
+pub fn own_size(path: &Path, options: &ScanOptions) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| !options.excludes(&entry.file_name()))
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+        .filter(|(_, metadata)| metadata.is_file())
+        .map(|(path, metadata)| file_size(&path, &metadata))
+        .sum()
+}
+
+fn ignore_walker(path: &Path, options: &ScanOptions) -> WalkBuilder {
+    skipping_walker(path, options, HashSet::new())
+}
+
+/// Walker that also leaves out the folders in `skip`, e.g. ones restored from a checkpoint.
+fn skipping_walker(path: &Path, options: &ScanOptions, skip: HashSet<PathBuf>) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(path);
+    let excluded = options.excluded_names.clone();
+    builder
+        .hidden(false)
+        .follow_links(false)
+        .same_file_system(options.one_file_system)
+        .filter_entry(move |entry| {
+            !excluded.iter().any(|name| OsStr::new(name) == entry.file_name()) && !skip.contains(entry.path())
+        });
+    builder
+}
+
+pub fn tree_walker(path: &Path, options: &ScanOptions, skip: HashSet<PathBuf>) -> WalkBuilder {
+    let mut builder = skipping_walker(path, options, skip);
+    if !options.respect_ignores {
+        builder
+            .ignore(false)
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .parents(false);
+    }
+    builder
+}
+
+pub fn measure_dir_respecting_ignores(path: &Path, options: &ScanOptions) -> Measurement {
+    let mut total = Measurement::default();
+    for entry in ignore_walker(path, options).build() {
+        match entry {
+            Ok(entry) => {
+                if entry.depth() > 0 {
+                    total.entries += 1;
+                }
+                throttle::pace();
+                if let Ok(metadata) = entry.metadata() {
+                    let size = if metadata.is_file() { file_size(entry.path(), &metadata) } else { 0 };
+                    telemetry::record_entry(size);
+                    if metadata.is_file() {
+                        total.size += size;
+                        total.logical += logical_size(&metadata);
+                        total.touch(metadata.modified().ok());
+                    }
+                }
+            }
+            Err(e) => {
+                total.changed |= e.io_error().is_some_and(|e| e.kind() == ErrorKind::NotFound);
+            }
+        }
+    }
+    total
+}
+
+pub fn unignored_children(dir: &Path, options: &ScanOptions) -> HashSet<PathBuf> {
+    ignore_walker(dir, options)
+        .max_depth(Some(1))
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.depth() == 1)
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// What ignore files hide anywhere below `dir`, which measuring it with `respect_ignores`
+/// leaves out. Each ignored entry counts once, with everything inside it.
+pub fn ignored_within(dir: &Path, options: &ScanOptions) -> IgnoredTotal {
+    let root_device = options.root_device(dir);
+    let mut total = IgnoredTotal::default();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let unignored = unignored_children(&dir, options);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = dir.join(entry.file_name());
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if options.excludes(&entry.file_name()) || crosses_device(&metadata, root_device) {
+                continue;
+            }
+            if unignored.contains(&path) {
+                if metadata.is_dir() {
+                    pending.push(path);
+                }
+            } else if let Some(measurement) = measure_entry(&path, &metadata, &ScanOptions::default()) {
+                total.count += 1;
+                total.size += measurement.size;
+            }
+        }
+    }
+    total
+}
+
+pub fn measure_entry(
+    path: &Path,
+    metadata: &Metadata,
+    options: &ScanOptions,
+) -> Option<Measurement> {
+    if !metadata.is_dir() {
+        telemetry::record_entry(metadata.len());
+        throttle::pace();
+        return match fs::symlink_metadata(path) {
+            Ok(current) => Some(Measurement {
+                size: if current.is_file() { file_size(path, &current) } else { 0 },
+                logical: if current.is_file() { logical_size(&current) } else { 0 },
+                entries: 1,
+                changed: current.len() != metadata.len()
+                    || current.modified().ok() != metadata.modified().ok(),
+                modified: current.modified().ok(),
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(_) => Some(Measurement {
+                size: file_size(path, metadata),
+                logical: logical_size(metadata),
+                entries: 1,
+                changed: false,
+                modified: metadata.modified().ok(),
+            }),
+        };
+    }
+
+    let measure = |path: &Path| {
+        if options.respect_ignores {
+            measure_dir_respecting_ignores(path, options)
+        } else {
+            measure_dir(path, options)
+        }
+    };
+
+    let mut measurement = measure(path);
+    for _ in 0..MAX_RESCANS {
+        if !measurement.changed {
+            break;
+        }
+        if !path.exists() {
+            return None;
+        }
+        measurement = measure(path);
+    }
+    Some(measurement)
+}
@@ -0,0 +1,294 @@
+use eframe::egui::{self, Color32, RichText};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ImmutableString, INT};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+use crate::{
+    category, heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    operations::Action,
+};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+const EXAMPLE: &str = r#"// Sum of *.bak files older than 90 days, grouped by parent folder
+let totals = #{};
+for f in files() {
+    if f.ext == "bak" && f.modified_days > 90 {
+        totals[f.parent] = (totals[f.parent] ?? 0) + f.size;
+    }
+}
+for folder in totals.keys() {
+    print(folder + ": " + format_bytes(totals[folder]));
+}
+"#;
+
+/// A file or folder as scripts see it: a copy taken from the index when the script starts.
+#[derive(Clone)]
+pub struct Entry {
+    path: ImmutableString,
+    name: ImmutableString,
+    ext: ImmutableString,
+    parent: ImmutableString,
+    size: INT,
+    is_dir: bool,
+    modified_days: INT,
+    accessed_days: INT,
+}
+
+/// Whole days since `time`, or -1 when it isn't known.
+fn days(time: Option<SystemTime>) -> INT {
+    heatmap::age(time).map_or(-1, |age| (age.as_secs() / DAY_SECS) as INT)
+}
+
+/// What scripts need of each node, copied out of the index so the rest of the snapshot can
+/// be made in the background.
+type Raw = (PathBuf, bool, u64, Option<SystemTime>, Option<SystemTime>);
+
+fn raw(index: &ScanIndex) -> Vec<Raw> {
+    index.nodes.iter().map(|node| (node.path.clone(), node.is_dir, node.size, node.modified, node.accessed)).collect()
+}
+
+fn snapshot(raw: Vec<Raw>) -> Vec<Entry> {
+    raw.into_iter()
+        .map(|(path, is_dir, size, modified, accessed)| Entry {
+            name: path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned()).into(),
+            ext: if is_dir { ImmutableString::new() } else { category::extension(&path).into() },
+            parent: path.parent().map_or_else(String::new, |parent| parent.to_string_lossy().into_owned()).into(),
+            path: path.to_string_lossy().into_owned().into(),
+            size: size as INT,
+            is_dir,
+            modified_days: days(modified),
+            accessed_days: days(accessed),
+        })
+        .collect()
+}
+
+/// An operation a script asked for; it joins the operations queue once the script ends and
+/// the user has confirmed the whole list.
+pub struct Queued {
+    pub action: Action,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+pub struct ScriptResult {
+    pub queued: Vec<Queued>,
+    pub error: Option<String>,
+    /// The snapshot the script ran on, kept for the next run.
+    entries: Arc<Vec<Entry>>,
+}
+
+fn entries_where(entries: &Arc<Vec<Entry>>, keep: impl Fn(&Entry) -> bool) -> Array {
+    entries.iter().filter(|entry| keep(entry)).cloned().map(Dynamic::from).collect()
+}
+
+fn build_engine(
+    entries: Arc<Vec<Entry>>,
+    output: Arc<Mutex<Vec<String>>>,
+    queued: Arc<Mutex<Vec<Queued>>>,
+    cancel: Arc<AtomicBool>,
+    read_only: bool,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.on_print(move |text| {
+        if let Ok(mut output) = output.lock() {
+            output.push(text.to_string());
+        }
+    });
+    engine.on_progress(move |_| cancel.load(Ordering::Relaxed).then_some(Dynamic::UNIT));
+
+    engine
+        .register_type_with_name::<Entry>("Entry")
+        .register_get("path", |entry: &mut Entry| entry.path.clone())
+        .register_get("name", |entry: &mut Entry| entry.name.clone())
+        .register_get("ext", |entry: &mut Entry| entry.ext.clone())
+        .register_get("parent", |entry: &mut Entry| entry.parent.clone())
+        .register_get("size", |entry: &mut Entry| entry.size)
+        .register_get("is_dir", |entry: &mut Entry| entry.is_dir)
+        .register_get("modified_days", |entry: &mut Entry| entry.modified_days)
+        .register_get("accessed_days", |entry: &mut Entry| entry.accessed_days)
+        .register_fn("to_string", |entry: &mut Entry| entry.path.to_string());
+
+    let all = Arc::clone(&entries);
+    engine.register_fn("entries", move || entries_where(&all, |_| true));
+    let all = Arc::clone(&entries);
+    engine.register_fn("files", move || entries_where(&all, |entry| !entry.is_dir));
+    let all = entries;
+    engine.register_fn("dirs", move || entries_where(&all, |entry| entry.is_dir));
+    engine.register_fn("format_bytes", |bytes: INT| i18n::format_bytes(bytes.max(0) as u64));
+
+    let queue = move |action: Action, entry: &Entry| -> Result<(), Box<EvalAltResult>> {
+        if read_only {
+            return Err(tr("Disabled in read-only mode").into());
+        }
+        if let Ok(mut queued) = queued.lock() {
+            queued.push(Queued {
+                action,
+                path: PathBuf::from(entry.path.as_str()),
+                is_dir: entry.is_dir,
+                size: entry.size.max(0) as u64,
+            });
+        }
+        Ok(())
+    };
+    let delete = queue.clone();
+    engine.register_fn("delete", move |entry: Entry| delete(Action::Delete, &entry));
+    let compress = queue.clone();
+    engine.register_fn("compress", move |entry: Entry| compress(Action::Compress, &entry));
+    engine.register_fn("move_to", move |entry: Entry, destination: ImmutableString| {
+        queue(Action::Move(PathBuf::from(destination.as_str())), &entry)
+    });
+    engine
+}
+
+fn run(
+    script: String,
+    entries: Arc<Vec<Entry>>,
+    output: Arc<Mutex<Vec<String>>>,
+    cancel: Arc<AtomicBool>,
+    read_only: bool,
+) -> ScriptResult {
+    let queued = Arc::new(Mutex::new(Vec::new()));
+    let engine = build_engine(Arc::clone(&entries), output, Arc::clone(&queued), Arc::clone(&cancel), read_only);
+    let error = engine.run(&script).err().map(|error| {
+        if cancel.load(Ordering::Relaxed) {
+            tr("Stopped").to_string()
+        } else {
+            error.to_string()
+        }
+    });
+    // Nothing is queued from a script that failed halfway.
+    let queued = match (&error, queued.lock()) {
+        (None, Ok(mut queued)) => queued.drain(..).collect(),
+        _ => Vec::new(),
+    };
+    ScriptResult { queued, error, entries }
+}
+
+/// The console panel: a script editor, its printed output and the operations it queued.
+pub struct ConsoleState {
+    script: String,
+    output: Arc<Mutex<Vec<String>>>,
+    cancel: Arc<AtomicBool>,
+    job: Option<Job<ScriptResult>>,
+    /// The index as scripts see it, made on the first run after the index changes.
+    entries: Option<Arc<Vec<Entry>>>,
+    error: Option<String>,
+    /// Operations the last run asked for.
+    queued: usize,
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        Self {
+            script: EXAMPLE.to_string(),
+            output: Arc::default(),
+            cancel: Arc::default(),
+            job: None,
+            entries: None,
+            error: None,
+            queued: 0,
+        }
+    }
+}
+
+impl ConsoleState {
+    /// Drops the snapshot after the index changes.
+    pub fn invalidate(&mut self) {
+        self.entries = None;
+    }
+
+    /// Draws the panel and returns the operations the last script asked for, to be confirmed
+    /// before they're queued.
+    pub fn show(&mut self, ui: &mut egui::Ui, index: Option<&ScanIndex>, read_only: bool) -> Vec<Queued> {
+        let mut queued = Vec::new();
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(result) => {
+                    self.entries = Some(result.entries);
+                    queued = result.queued;
+                    self.queued = queued.len();
+                    self.error = result.error;
+                    self.job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let running = self.job.is_some();
+            let run_button = ui
+                .add_enabled(!running && index.is_some(), egui::Button::new(tr("Run Script")))
+                .on_disabled_hover_text(tr("Available once the tree has been scanned"));
+            if let (true, Some(index)) = (run_button.clicked(), index) {
+                if let Ok(mut output) = self.output.lock() {
+                    output.clear();
+                }
+                self.error = None;
+                self.queued = 0;
+                self.cancel = Arc::default();
+                let script = self.script.clone();
+                let (output, cancel) = (Arc::clone(&self.output), Arc::clone(&self.cancel));
+                self.job = Some(match self.entries.clone() {
+                    Some(entries) => Job::spawn(move || run(script, entries, output, cancel, read_only)),
+                    None => {
+                        let raw = raw(index);
+                        Job::spawn(move || run(script, Arc::new(snapshot(raw)), output, cancel, read_only))
+                    }
+                });
+            }
+            if running {
+                ui.spinner();
+                if ui.button(tr("Stop")).clicked() {
+                    self.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+            if ui.button(tr("Clear Output")).clicked() {
+                if let Ok(mut output) = self.output.lock() {
+                    output.clear();
+                }
+                self.error = None;
+            }
+        });
+        ui.label(
+            RichText::new(tr(
+                "Scripts use Rhai. entries(), files() and dirs() list the scan; each entry has path, name, ext, parent, size, is_dir, modified_days and accessed_days. delete(entry), compress(entry) and move_to(entry, folder) ask for operations, which are listed for confirmation before they're queued.",
+            ))
+            .small()
+            .color(Color32::GRAY),
+        );
+        ui.add(
+            egui::TextEdit::multiline(&mut self.script)
+                .code_editor()
+                .desired_rows(10)
+                .desired_width(f32::INFINITY),
+        );
+        ui.separator();
+        if let Some(error) = &self.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+        if self.queued > 0 {
+            ui.label(trf(
+                "Asked for {} operations; confirm them to queue them",
+                &[&i18n::format_count(self.queued as u64)],
+            ));
+        }
+        egui::ScrollArea::vertical().stick_to_bottom(true).auto_shrink([false; 2]).show(ui, |ui| {
+            if let Ok(output) = self.output.lock() {
+                for line in output.iter() {
+                    ui.label(RichText::new(line).monospace());
+                }
+            }
+        });
+        queued
+    }
+}
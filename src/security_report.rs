@@ -0,0 +1,302 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    dialogs,
+    i18n::{self, tr, trf},
+    job::Job,
+    owner, scan,
+    users_report::csv_field,
+};
+
+const MAX_ITEMS: usize = 500;
+const WORLD_WRITABLE: u32 = 0o002;
+const STICKY: u32 = 0o1000;
+const SETGID: u32 = 0o2000;
+const SETUID: u32 = 0o4000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Finding {
+    WorldWritable,
+    Setuid,
+    Setgid,
+    NoOwner,
+}
+
+impl Finding {
+    pub const ALL: [Finding; 4] = [Finding::WorldWritable, Finding::Setuid, Finding::Setgid, Finding::NoOwner];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Finding::WorldWritable => "World-writable directories",
+            Finding::Setuid => "Setuid files",
+            Finding::Setgid => "Setgid files",
+            Finding::NoOwner => "Files without an owner",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Finding::WorldWritable => {
+                "Any account can add files here. Sizes include everything inside; the sticky bit at least stops users from deleting each other's files."
+            }
+            Finding::Setuid => "These run with the rights of their owner, whoever starts them.",
+            Finding::Setgid => "These run with the rights of their group, whoever starts them.",
+            Finding::NoOwner => {
+                "Owned by user IDs with no local account, usually left behind by deleted users. A new account given that ID would own them."
+            }
+        }
+    }
+
+    fn csv_name(&self) -> &'static str {
+        match self {
+            Finding::WorldWritable => "world_writable",
+            Finding::Setuid => "setuid",
+            Finding::Setgid => "setgid",
+            Finding::NoOwner => "no_owner",
+        }
+    }
+
+    fn position(&self) -> usize {
+        Finding::ALL.iter().position(|finding| finding == self).unwrap_or_default()
+    }
+}
+
+pub struct Item {
+    pub path: PathBuf,
+    pub size: u64,
+    pub mode: u32,
+    pub uid: Option<u32>,
+}
+
+#[derive(Default)]
+pub struct Category {
+    pub count: u64,
+    pub size: u64,
+    /// The first `MAX_ITEMS` found; `count` and `size` cover all of them.
+    pub items: Vec<Item>,
+}
+
+impl Category {
+    fn add(&mut self, item: Item) {
+        self.count += 1;
+        self.size += item.size;
+        if self.items.len() < MAX_ITEMS {
+            self.items.push(item);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SecurityReport {
+    pub root: PathBuf,
+    pub categories: [Category; 4],
+}
+
+impl SecurityReport {
+    pub fn category(&self, finding: Finding) -> &Category {
+        &self.categories[finding.position()]
+    }
+}
+
+/// Walks `root` once without leaving its file system, reading each entry's owner and
+/// permission bits.
+pub fn compute(root: &Path) -> SecurityReport {
+    let mut report = SecurityReport { root: root.to_path_buf(), ..SecurityReport::default() };
+    // World-writable folders the walk is inside of: their depth and, while under `MAX_ITEMS`,
+    // their item's index. Files below them add to their size.
+    let mut open: Vec<(usize, Option<usize>)> = Vec::new();
+    let world_writable = Finding::WorldWritable.position();
+    for entry in WalkDir::new(root).follow_links(false).same_file_system(true).into_iter().filter_map(Result::ok) {
+        while open.last().is_some_and(|&(depth, _)| depth >= entry.depth()) {
+            open.pop();
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Some(mode) = owner::mode(&metadata) else {
+            continue;
+        };
+        let uid = owner::owner_id(&metadata);
+        let item = |size| Item { path: entry.path().to_path_buf(), size, mode, uid };
+        if metadata.is_dir() {
+            if mode & WORLD_WRITABLE != 0 {
+                let category = &mut report.categories[world_writable];
+                let index = (category.items.len() < MAX_ITEMS).then_some(category.items.len());
+                category.add(item(0));
+                open.push((entry.depth(), index));
+            }
+            continue;
+        }
+        if !metadata.is_file() {
+            continue;
+        }
+        let size = scan::file_size(entry.path(), &metadata);
+        let category = &mut report.categories[world_writable];
+        category.size += if open.is_empty() { 0 } else { size };
+        for &(_, index) in &open {
+            if let Some(index) = index {
+                category.items[index].size += size;
+            }
+        }
+        if mode & SETUID != 0 {
+            report.categories[Finding::Setuid.position()].add(item(size));
+        }
+        if mode & SETGID != 0 {
+            report.categories[Finding::Setgid.position()].add(item(size));
+        }
+        if uid.is_some_and(|uid| !owner::known_owner(uid)) {
+            report.categories[Finding::NoOwner.position()].add(item(size));
+        }
+    }
+    report
+}
+
+pub fn to_csv(report: &SecurityReport) -> String {
+    let mut csv = String::from("finding,path,size,mode,owner\n");
+    for finding in Finding::ALL {
+        for item in &report.category(finding).items {
+            csv.push_str(&format!(
+                "{},{},{},{:04o},{}\n",
+                finding.csv_name(),
+                csv_field(&item.path.to_string_lossy()),
+                item.size,
+                item.mode,
+                csv_field(&owner::display_owner(item.uid)),
+            ));
+        }
+    }
+    csv
+}
+
+#[derive(Default)]
+pub struct SecurityReportState {
+    pub open: bool,
+    root: String,
+    job: Option<Job<SecurityReport>>,
+    report: Option<SecurityReport>,
+    message: Option<String>,
+}
+
+impl SecurityReportState {
+    pub fn show(&mut self, ctx: &egui::Context, current: Option<&Path>) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if self.root.is_empty() {
+            self.root = current.map(|path| path.display().to_string()).unwrap_or_else(|| "/".to_string());
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Security Report"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                if !cfg!(unix) {
+                    ui.label(tr("Permission bits and user IDs are only available on Unix-like systems."));
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.root);
+                    if ui.button(tr("Browse")).clicked() {
+                        if let Some(path) = dialogs::pick_folder() {
+                            self.root = path.display().to_string();
+                        }
+                    }
+                    if let Some(current) = current {
+                        if ui.button(tr("Current Folder")).clicked() {
+                            self.root = current.display().to_string();
+                        }
+                    }
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Run Report"))).clicked() {
+                        let root = PathBuf::from(&self.root);
+                        self.job = Some(Job::spawn(move || compute(&root)));
+                    }
+                    if ui.add_enabled(self.report.is_some(), egui::Button::new(tr("Export CSV"))).clicked() {
+                        if let (Some(report), Some(path)) = (&self.report, dialogs::save_file("security.csv")) {
+                            self.message = Some(match fs::write(&path, to_csv(report)) {
+                                Ok(()) => path.display().to_string(),
+                                Err(e) => format!("Error writing {}: {}", path.display(), e),
+                            });
+                        }
+                    }
+                });
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+
+                egui::Grid::new("security_summary").striped(true).show(ui, |ui| {
+                    ui.label(tr("Finding"));
+                    ui.label(tr("Count"));
+                    ui.label(tr("Size"));
+                    ui.end_row();
+                    for finding in Finding::ALL {
+                        let category = report.category(finding);
+                        let color = if category.count > 0 { Color32::YELLOW } else { Color32::GREEN };
+                        ui.label(tr(finding.label()));
+                        ui.label(RichText::new(i18n::format_count(category.count)).color(color));
+                        ui.label(i18n::format_bytes(category.size));
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for finding in Finding::ALL {
+                        let category = report.category(finding);
+                        if category.count == 0 {
+                            continue;
+                        }
+                        egui::CollapsingHeader::new(format!(
+                            "{} ({})",
+                            tr(finding.label()),
+                            i18n::format_count(category.count)
+                        ))
+                        .id_source(("security_finding", finding.csv_name()))
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr(finding.description())).color(Color32::GRAY));
+                            egui::Grid::new(("security_items", finding.csv_name())).striped(true).show(ui, |ui| {
+                                for item in &category.items {
+                                    let relative = item.path.strip_prefix(&report.root).unwrap_or(&item.path);
+                                    ui.label(relative.display().to_string());
+                                    ui.label(i18n::format_bytes(item.size));
+                                    let mut mode = format!("{:04o}", item.mode);
+                                    if finding == Finding::WorldWritable && item.mode & STICKY != 0 {
+                                        mode = format!("{} {}", mode, tr("(sticky)"));
+                                    }
+                                    ui.label(RichText::new(mode).monospace());
+                                    ui.label(owner::display_owner(item.uid));
+                                    ui.end_row();
+                                }
+                            });
+                            if category.count > category.items.len() as u64 {
+                                ui.label(
+                                    RichText::new(trf("Showing the first {}", &[&category.items.len()]))
+                                        .color(Color32::GRAY),
+                                );
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+    }
+}
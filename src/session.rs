@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, time::Instant};
+
+use crate::{
+    app::scan::{CacheEntry, FileInfo},
+    config, scan,
+};
+
+const SESSION_FILE: &str = "session.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct CachedListing {
+    pub path: PathBuf,
+    pub file_list: Vec<FileInfo>,
+    pub total_size: u64,
+    pub ignored: scan::IgnoredTotal,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub root_path: Option<PathBuf>,
+    pub current_path: Option<PathBuf>,
+    pub scroll_offset: f32,
+    pub show_tree: bool,
+    pub expanded: Vec<PathBuf>,
+    pub listings: Vec<CachedListing>,
+}
+
+impl Session {
+    /// Returns the last saved session, if it had a directory open.
+    pub fn load() -> Option<Self> {
+        let session: Session = config::load(SESSION_FILE);
+        session.root_path.is_some().then_some(session)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(SESSION_FILE, self)
+    }
+
+    pub fn capture_cache(&mut self, cache: &HashMap<PathBuf, CacheEntry>) {
+        self.listings = cache
+            .iter()
+            .map(|(path, entry)| CachedListing {
+                path: path.clone(),
+                file_list: entry.file_list.clone(),
+                total_size: entry.total_size,
+                ignored: entry.ignored,
+            })
+            .collect();
+    }
+
+    /// Rebuilds the directory cache so resuming doesn't rescan; entries count as fresh
+    /// until the next manual refresh or the usual cache expiry.
+    pub fn take_cache(&mut self) -> HashMap<PathBuf, CacheEntry> {
+        std::mem::take(&mut self.listings)
+            .into_iter()
+            .map(|listing| {
+                (listing.path, CacheEntry {
+                    file_list: listing.file_list,
+                    total_size: listing.total_size,
+                    ignored: listing.ignored,
+                    timestamp: Instant::now(),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,263 @@
+use eframe::egui::{self, Color32, RichText};
+use image::{imageops::FilterType, DynamicImage};
+use rayon::prelude::*;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    category::{self, Category},
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    job::Job,
+    paths, throttle,
+};
+
+/// Hashes differing in at most this many of their 64 bits count as the same picture by default.
+pub const DEFAULT_DISTANCE: u32 = 8;
+const MAX_DISTANCE: u32 = 20;
+/// Seek positions tried for a video frame, so clips shorter than the first still get one.
+const FRAME_OFFSETS: [&str; 2] = ["5", "0"];
+
+/// Difference hash: shrinks the picture to 9x8 grey pixels and records whether each pixel is
+/// brighter than its right-hand neighbour. Re-encoding or resizing barely changes the bits.
+fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y).0[0] < small.get_pixel(x + 1, y).0[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// Grabs a single frame with ffmpeg, which has to be on the PATH.
+fn video_frame(path: &Path) -> Option<DynamicImage> {
+    FRAME_OFFSETS.iter().find_map(|offset| {
+        let output = Command::new("ffmpeg")
+            .args(["-v", "error", "-ss", offset, "-i"])
+            .arg(path)
+            .args(["-frames:v", "1", "-f", "image2pipe", "-vcodec", "png", "-"])
+            .output()
+            .ok()?;
+        if !output.status.success() || output.stdout.is_empty() {
+            return None;
+        }
+        image::load_from_memory(&output.stdout).ok()
+    })
+}
+
+fn media_hash(path: &Path, category: Category) -> Option<u64> {
+    let image = match category {
+        Category::Image => image::open(paths::extended(path)).ok()?,
+        Category::Video => video_frame(path)?,
+        _ => return None,
+    };
+    Some(dhash(&image))
+}
+
+pub struct SimilarGroup {
+    /// Largest first; the largest copy is usually the one worth keeping.
+    pub files: Vec<(PathBuf, u64)>,
+}
+
+impl SimilarGroup {
+    /// Bytes freed by keeping only the largest copy.
+    pub fn reclaimable(&self) -> u64 {
+        self.files.iter().skip(1).map(|(_, size)| size).sum()
+    }
+}
+
+pub struct SimilarReport {
+    pub groups: Vec<SimilarGroup>,
+    pub hashed: usize,
+    /// Files that couldn't be decoded, e.g. unsupported formats or videos without ffmpeg.
+    pub skipped: usize,
+}
+
+fn find_root(parents: &mut [usize], mut item: usize) -> usize {
+    while parents[item] != item {
+        parents[item] = parents[parents[item]];
+        item = parents[item];
+    }
+    item
+}
+
+/// Hashes every image and video, then links any two whose hashes are within `max_distance` bits.
+pub fn find_similar(files: Vec<(PathBuf, u64, Category)>, max_distance: u32, done: &AtomicUsize) -> SimilarReport {
+    let total = files.len();
+    let hashed: Vec<(PathBuf, u64, u64)> = throttle::run_limited(|| {
+        files
+            .into_par_iter()
+            .filter_map(|(path, size, category)| {
+                let hash = media_hash(&path, category);
+                done.fetch_add(1, Ordering::Relaxed);
+                Some((path, size, hash?))
+            })
+            .collect()
+    });
+
+    let mut parents: Vec<usize> = (0..hashed.len()).collect();
+    for i in 0..hashed.len() {
+        for j in i + 1..hashed.len() {
+            if (hashed[i].2 ^ hashed[j].2).count_ones() <= max_distance {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); hashed.len()];
+    for i in 0..hashed.len() {
+        let root = find_root(&mut parents, i);
+        members[root].push(i);
+    }
+    let mut groups: Vec<SimilarGroup> = members
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            let mut files: Vec<(PathBuf, u64)> =
+                group.into_iter().map(|i| (hashed[i].0.clone(), hashed[i].1)).collect();
+            files.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+            SimilarGroup { files }
+        })
+        .collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.reclaimable()));
+
+    SimilarReport { groups, hashed: hashed.len(), skipped: total - hashed.len() }
+}
+
+pub struct SimilarState {
+    pub open: bool,
+    max_distance: u32,
+    done: Arc<AtomicUsize>,
+    total: usize,
+    job: Option<Job<SimilarReport>>,
+    report: Option<SimilarReport>,
+}
+
+impl Default for SimilarState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            max_distance: DEFAULT_DISTANCE,
+            done: Arc::default(),
+            total: 0,
+            job: None,
+            report: None,
+        }
+    }
+}
+
+impl SimilarState {
+    /// Draws the window and returns a folder to jump to when a file's location is clicked.
+    pub fn show(&mut self, ctx: &egui::Context, index: Option<&ScanIndex>) -> Option<PathBuf> {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(report) => {
+                    self.report = Some(report);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut navigate = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Similar Media"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let Some(index) = index else {
+                    ui.spinner();
+                    ui.label(tr("Indexing..."));
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr("Finds re-encoded and resized copies of images and videos. Video frames need ffmpeg."))
+                        .color(Color32::GRAY),
+                );
+                ui.horizontal(|ui| {
+                    ui.label(tr("Tolerance:"));
+                    ui.add(egui::Slider::new(&mut self.max_distance, 0..=MAX_DISTANCE).suffix(tr(" bits")));
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Find Similar"))).clicked() {
+                        let files: Vec<(PathBuf, u64, Category)> = index
+                            .nodes
+                            .iter()
+                            .filter(|node| !node.is_dir && !node.online_only)
+                            .map(|node| (node.path.clone(), node.size, category::classify_file(&node.path, node.size)))
+                            .filter(|(_, _, category)| matches!(category, Category::Image | Category::Video))
+                            .collect();
+                        self.total = files.len();
+                        self.done = Arc::default();
+                        let done = Arc::clone(&self.done);
+                        let max_distance = self.max_distance;
+                        self.job = Some(Job::spawn(move || find_similar(files, max_distance, &done)));
+                    }
+                });
+
+                if self.job.is_some() {
+                    let done = self.done.load(Ordering::Relaxed);
+                    let fraction = if self.total == 0 { 1.0 } else { done as f32 / self.total as f32 };
+                    ui.add(egui::ProgressBar::new(fraction).text(trf(
+                        "{} of {} files",
+                        &[&i18n::format_count(done as u64), &i18n::format_count(self.total as u64)],
+                    )));
+                    return;
+                }
+                let Some(report) = &self.report else {
+                    return;
+                };
+                let reclaimable: u64 = report.groups.iter().map(SimilarGroup::reclaimable).sum();
+                ui.label(trf(
+                    "{} groups of similar files; keeping only the largest of each frees {}",
+                    &[&i18n::format_count(report.groups.len() as u64), &i18n::format_bytes(reclaimable)],
+                ));
+                ui.label(trf("Compared {} files", &[&i18n::format_count(report.hashed as u64)]));
+                if report.skipped > 0 {
+                    ui.label(
+                        RichText::new(trf("{} files could not be decoded", &[&i18n::format_count(report.skipped as u64)]))
+                            .color(Color32::GRAY),
+                    );
+                }
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for (number, group) in report.groups.iter().enumerate() {
+                        egui::CollapsingHeader::new(trf(
+                            "Group {} - {} files, {} reclaimable",
+                            &[
+                                &(number + 1),
+                                &i18n::format_count(group.files.len() as u64),
+                                &i18n::format_bytes(group.reclaimable()),
+                            ],
+                        ))
+                        .id_source(("similar_group", number))
+                        .show(ui, |ui| {
+                            for (position, (path, size)) in group.files.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    let text = format!("{} - {}", paths::display_path(path), i18n::format_bytes(*size));
+                                    if ui.link(text).on_hover_text(tr("Show in folder")).clicked() {
+                                        navigate = path.parent().map(Path::to_path_buf);
+                                    }
+                                    if position == 0 {
+                                        ui.label(RichText::new(tr("largest")).small().color(Color32::GREEN));
+                                    }
+                                });
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+        navigate
+    }
+}
@@ -0,0 +1,517 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process::Command,
+};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    job::Job,
+    mounts::{self, Mount},
+};
+
+pub const SNAPSHOT_WARNING: &str = "This filesystem shares data between snapshots. Deleting files \
+may not free any space until the snapshots that still reference them are removed.";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SnapshotFs {
+    Btrfs,
+    Zfs,
+}
+
+impl SnapshotFs {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapshotFs::Btrfs => "btrfs",
+            SnapshotFs::Zfs => "ZFS",
+        }
+    }
+}
+
+pub struct Subvolume {
+    pub name: String,
+    pub is_snapshot: bool,
+    pub referenced: Option<u64>,
+    pub exclusive: Option<u64>,
+}
+
+/// Subvolumes or snapshots, plus a note when sizes couldn't be read (e.g. btrfs quotas are off).
+type Listing = Result<(Vec<Subvolume>, Option<String>), String>;
+type Report = Option<(SnapshotFs, Listing)>;
+
+pub fn detect(path: &Path) -> Option<(SnapshotFs, Mount)> {
+    let mount = mounts::mount_of(path)?;
+    let fs = match mount.fs_type.as_str() {
+        "btrfs" => SnapshotFs::Btrfs,
+        "zfs" => SnapshotFs::Zfs,
+        _ => return None,
+    };
+    Some((fs, mount))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Parses `btrfs subvolume list` lines into (id, path).
+fn btrfs_subvolumes(output: &str) -> Vec<(u64, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let id = line.strip_prefix("ID ")?.split_whitespace().next()?.parse().ok()?;
+            let (_, path) = line.split_once(" path ")?;
+            Some((id, path.to_string()))
+        })
+        .collect()
+}
+
+fn list_btrfs(mount: &Mount) -> Listing {
+    let mount_point = mount.mount_point.to_string_lossy();
+    let all = btrfs_subvolumes(&run("btrfs", &["subvolume", "list", &mount_point])?);
+    let snapshots: HashSet<u64> = btrfs_subvolumes(&run("btrfs", &["subvolume", "list", "-s", &mount_point])?)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    let (qgroups, note) = match run("btrfs", &["qgroup", "show", "--raw", &mount_point]) {
+        Ok(output) => {
+            let sizes: HashMap<u64, (u64, u64)> = output
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.split_whitespace();
+                    let id = fields.next()?.strip_prefix("0/")?.parse().ok()?;
+                    let referenced = fields.next()?.parse().ok()?;
+                    let exclusive = fields.next()?.parse().ok()?;
+                    Some((id, (referenced, exclusive)))
+                })
+                .collect();
+            (sizes, None)
+        }
+        Err(e) => (HashMap::new(), Some(e)),
+    };
+
+    let subvolumes = all
+        .into_iter()
+        .map(|(id, name)| Subvolume {
+            is_snapshot: snapshots.contains(&id),
+            referenced: qgroups.get(&id).map(|sizes| sizes.0),
+            exclusive: qgroups.get(&id).map(|sizes| sizes.1),
+            name,
+        })
+        .collect();
+    Ok((subvolumes, note))
+}
+
+fn list_zfs(mount: &Mount) -> Listing {
+    let output = run(
+        "zfs",
+        &["list", "-H", "-p", "-t", "snapshot", "-o", "name,used,referenced", "-r", &mount.device],
+    )?;
+    let snapshots = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.to_string();
+            let exclusive = fields.next()?.parse().ok();
+            let referenced = fields.next()?.parse().ok();
+            Some(Subvolume { name, is_snapshot: true, referenced, exclusive })
+        })
+        .collect();
+    Ok((snapshots, None))
+}
+
+pub fn list(fs: SnapshotFs, mount: &Mount) -> Listing {
+    match fs {
+        SnapshotFs::Btrfs => list_btrfs(mount),
+        SnapshotFs::Zfs => list_zfs(mount),
+    }
+}
+
+/// Volume-wide snapshots the OS keeps on its own: Time Machine local snapshots on macOS and
+/// Volume Shadow Copies on Windows. Neither shows up in file totals, but both hold on to the
+/// space of deleted files.
+pub struct VolumeSnapshots {
+    pub volume: String,
+    /// Snapshot names or ids with their creation time, when known.
+    pub snapshots: Vec<(String, Option<String>)>,
+    /// Space the snapshots use, and the most they may use. macOS reports neither.
+    pub used: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+pub fn system_snapshots_name() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("Time Machine local snapshots")
+    } else if cfg!(windows) {
+        Some("Volume Shadow Copies")
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_snapshots() -> Result<Vec<VolumeSnapshots>, String> {
+    let mut volumes = vec!["/".to_string()];
+    if let Ok(entries) = std::fs::read_dir("/Volumes") {
+        volumes.extend(entries.filter_map(Result::ok).map(|entry| entry.path().to_string_lossy().into_owned()));
+    }
+    let mut found = Vec::new();
+    for volume in volumes {
+        let Ok(output) = run("tmutil", &["listlocalsnapshots", &volume]) else {
+            continue;
+        };
+        // Names look like com.apple.TimeMachine.2024-05-01-101500.local.
+        let snapshots: Vec<(String, Option<String>)> = output
+            .lines()
+            .filter(|line| line.starts_with("com.apple."))
+            .map(|name| {
+                let date = name.split('.').find(|part| part.len() == 17 && part.starts_with("20")).map(str::to_string);
+                (name.to_string(), date)
+            })
+            .collect();
+        if !snapshots.is_empty() {
+            found.push(VolumeSnapshots { volume, snapshots, used: None, limit: None });
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(windows)]
+pub fn system_snapshots() -> Result<Vec<VolumeSnapshots>, String> {
+    const SCRIPT: &str = "$letters = @{}; \
+        Get-CimInstance Win32_Volume | ForEach-Object { $letters[$_.DeviceID] = $_.DriveLetter }; \
+        Get-CimInstance Win32_ShadowStorage | ForEach-Object { \
+            \"storage`t$($letters[$_.Volume.DeviceID])`t$($_.UsedSpace)`t$($_.MaxSpace)\" }; \
+        Get-CimInstance Win32_ShadowCopy | ForEach-Object { \
+            \"copy`t$($letters[$_.VolumeName])`t$($_.ID)`t$($_.InstallDate.ToString('yyyy-MM-dd HH:mm'))\" }";
+    let output = run("powershell", &["-NoProfile", "-Command", SCRIPT])?;
+    let mut volumes: Vec<VolumeSnapshots> = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.trim().split('\t').collect();
+        let [kind, volume, first, second] = fields[..] else {
+            continue;
+        };
+        let index = match volumes.iter().position(|entry| entry.volume == volume) {
+            Some(index) => index,
+            None => {
+                volumes.push(VolumeSnapshots { volume: volume.to_string(), snapshots: Vec::new(), used: None, limit: None });
+                volumes.len() - 1
+            }
+        };
+        let entry = &mut volumes[index];
+        match kind {
+            "storage" => {
+                entry.used = first.parse().ok();
+                // The maximum is all of u64 when shadow storage is unbounded.
+                entry.limit = second.parse().ok().filter(|&limit| limit != u64::MAX);
+            }
+            "copy" => entry.snapshots.push((first.to_string(), Some(second.to_string()))),
+            _ => {}
+        }
+    }
+    Ok(volumes)
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn system_snapshots() -> Result<Vec<VolumeSnapshots>, String> {
+    Ok(Vec::new())
+}
+
+/// Commands that free snapshot space on a volume, with whether they need administrator rights.
+fn thin_commands(volume: &str) -> Vec<(&'static str, Vec<String>, bool)> {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+    if cfg!(target_os = "macos") {
+        vec![
+            ("Thin as much as possible", args(&["tmutil", "thinlocalsnapshots", volume, "999999999999", "4"]), false),
+            ("Delete all", args(&["tmutil", "deletelocalsnapshots", volume]), true),
+        ]
+    } else if cfg!(windows) {
+        let volume = volume.trim_end_matches('\\');
+        vec![
+            ("Delete the oldest", args(&["vssadmin", "delete", "shadows", &format!("/for={}", volume), "/oldest", "/quiet"]), true),
+            (
+                "Limit to 5% of the volume",
+                args(&["vssadmin", "resize", "shadowstorage", &format!("/for={}", volume), &format!("/on={}", volume), "/maxsize=5%"]),
+                true,
+            ),
+        ]
+    } else {
+        Vec::new()
+    }
+}
+
+fn settings_command() -> Option<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        Some(("Open Time Machine Settings", &["open", "x-apple.systempreferences:com.apple.Time-Machine-Settings.extension"]))
+    } else if cfg!(windows) {
+        Some(("Open System Protection", &["SystemPropertiesProtection.exe"]))
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct SnapshotState {
+    pub open: bool,
+    job: Option<Job<Report>>,
+    result: Option<Report>,
+    system_job: Option<Job<Result<Vec<VolumeSnapshots>, String>>>,
+    system: Option<Result<Vec<VolumeSnapshots>, String>>,
+    thin_job: Option<Job<audit::Audited<String>>>,
+    message: Option<Result<String, String>>,
+    /// A thinning command waiting for the user to confirm it, with its label.
+    confirm: Option<(&'static str, Vec<String>)>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl SnapshotState {
+    pub fn show(&mut self, ctx: &egui::Context, root: Option<&Path>, read_only: bool) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(result) => {
+                    self.result = Some(result);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if system_snapshots_name().is_some() && self.system.is_none() && self.system_job.is_none() {
+            self.system_job = Some(Job::spawn(system_snapshots));
+        }
+        if let Some(job) = &self.system_job {
+            match job.poll() {
+                Some(result) => {
+                    self.system = Some(result);
+                    self.system_job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.thin_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = Some(result);
+                    self.thin_job = None;
+                    self.system_job = Some(Job::spawn(system_snapshots));
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut thin = None;
+        let mut open = self.open;
+        egui::Window::new(tr("Snapshots"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                if let Some(name) = system_snapshots_name() {
+                    self.render_system(ui, name, read_only, &mut thin);
+                    ui.separator();
+                }
+                let Some(root) = root else {
+                    ui.label(tr("Select a directory first."));
+                    return;
+                };
+                if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Run Report"))).clicked() {
+                    let root = root.to_path_buf();
+                    self.job = Some(Job::spawn(move || {
+                        detect(&root).map(|(fs, mount)| (fs, list(fs, &mount)))
+                    }));
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.result {
+                    None => {}
+                    Some(None) => {
+                        ui.label(tr("This folder is not on a btrfs or ZFS filesystem."));
+                    }
+                    Some(Some((fs, listing))) => {
+                        ui.label(RichText::new(trf("Filesystem: {}", &[&fs.name()])).strong());
+                        ui.label(RichText::new(tr(SNAPSHOT_WARNING)).color(Color32::YELLOW));
+                        match listing {
+                            Ok((subvolumes, note)) => {
+                                if let Some(note) = note {
+                                    ui.label(RichText::new(trf("Sizes unavailable: {}", &[note])).color(Color32::GRAY));
+                                }
+                                render_subvolumes(ui, subvolumes);
+                            }
+                            Err(error) => {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                        }
+                    }
+                }
+            });
+        self.open = open;
+
+        if thin.is_some() {
+            self.confirm = thin;
+        }
+        if let Some(command) = self.confirm_thinning(ctx) {
+            self.message = None;
+            self.thin_job = Some(Job::spawn(move || {
+                let line = command.join(" ");
+                let result = run(&command[0], &command[1..].iter().map(String::as_str).collect::<Vec<&str>>())
+                    .map(|_| line.clone());
+                let audited = audit::record(audit::CLEAN, &line, None, result.as_ref().err());
+                (result, audited)
+            }));
+        }
+    }
+
+    /// Shows the pending thinning command until the user runs or drops it; returns it once
+    /// confirmed.
+    fn confirm_thinning(&mut self, ctx: &egui::Context) -> Option<Vec<String>> {
+        let (label, command) = self.confirm.as_ref()?;
+        let mut choice = None;
+        egui::Window::new(tr(label))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(command.join(" ")).monospace());
+                ui.label(
+                    RichText::new(tr("Removed snapshots are gone for good; nothing can be restored from them afterwards."))
+                        .color(Color32::YELLOW),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Run")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => self.confirm.take().map(|(_, command)| command),
+            Some(false) => {
+                self.confirm = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn render_system(
+        &self,
+        ui: &mut egui::Ui,
+        name: &'static str,
+        read_only: bool,
+        thin: &mut Option<(&'static str, Vec<String>)>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(tr(name)).strong());
+            if let Some((label, command)) = settings_command() {
+                if ui.button(tr(label)).clicked() {
+                    let _ = Command::new(command[0]).args(&command[1..]).spawn();
+                }
+            }
+        });
+        if self.system_job.is_some() || self.thin_job.is_some() {
+            ui.spinner();
+            return;
+        }
+        match &self.message {
+            Some(Ok(command)) => {
+                ui.label(RichText::new(trf("Ran {}", &[command])).color(Color32::GREEN));
+            }
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+            None => {}
+        }
+        let volumes = match &self.system {
+            Some(Ok(volumes)) => volumes,
+            Some(Err(error)) => {
+                ui.label(RichText::new(error).color(Color32::RED));
+                return;
+            }
+            None => return,
+        };
+        if volumes.is_empty() {
+            ui.label(tr("No volume has snapshots."));
+            return;
+        }
+        ui.label(
+            RichText::new(tr("They explain free space that is lower than the files add up to."))
+                .color(Color32::GRAY),
+        );
+        for volume in volumes {
+            let title = match volume.used {
+                Some(used) => trf(
+                    "{}: {} snapshots using {}",
+                    &[&volume.volume, &volume.snapshots.len(), &i18n::format_bytes(used)],
+                ),
+                None => trf("{}: {} snapshots", &[&volume.volume, &volume.snapshots.len()]),
+            };
+            egui::CollapsingHeader::new(title).id_source(("system_snapshots", &volume.volume)).show(ui, |ui| {
+                if let Some(limit) = volume.limit {
+                    ui.label(trf("Limited to {}", &[&i18n::format_bytes(limit)]));
+                } else if volume.used.is_none() {
+                    ui.label(
+                        RichText::new(tr("The system doesn't report how much space each snapshot holds."))
+                            .color(Color32::GRAY),
+                    );
+                }
+                for (snapshot, created) in &volume.snapshots {
+                    ui.label(format!("{}  {}", created.as_deref().unwrap_or("-"), snapshot));
+                }
+                for (label, command, needs_admin) in thin_commands(&volume.volume) {
+                    ui.horizontal(|ui| {
+                        let line = command.join(" ");
+                        let line = if needs_admin && cfg!(unix) { format!("sudo {}", line) } else { line };
+                        ui.label(tr(label));
+                        ui.label(RichText::new(&line).monospace());
+                        if ui.button(tr("Copy")).clicked() {
+                            ui.output_mut(|output| output.copied_text = line.clone());
+                        }
+                        let run = ui
+                            .add_enabled(!read_only && !needs_admin, egui::Button::new(tr("Run")))
+                            .on_disabled_hover_text(if read_only {
+                                tr("Disabled in read-only mode")
+                            } else {
+                                tr("Needs administrator rights; copy the command instead")
+                            });
+                        if run.clicked() {
+                            *thin = Some((label, command));
+                        }
+                    });
+                }
+            });
+        }
+    }
+}
+
+fn render_subvolumes(ui: &mut egui::Ui, subvolumes: &[Subvolume]) {
+    let size = |size: Option<u64>| size.map_or("-".to_string(), i18n::format_bytes);
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("snapshots").striped(true).show(ui, |ui| {
+            ui.label(tr("Name"));
+            ui.label(tr("Referenced"));
+            ui.label(tr("Exclusive"));
+            ui.end_row();
+            for subvolume in subvolumes {
+                let icon = if subvolume.is_snapshot { "📷" } else { "📦" };
+                ui.label(format!("{} {}", icon, subvolume.name));
+                ui.label(size(subvolume.referenced));
+                ui.label(size(subvolume.exclusive));
+                ui.end_row();
+            }
+        });
+    });
+}
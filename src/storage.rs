@@ -0,0 +1,331 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    category::{self, Category},
+    i18n::{self, tr, trf},
+    job::Job,
+    mounts, paths, scan, throttle, volume, windows_cleanup,
+};
+
+const BAR_HEIGHT: f32 = 18.0;
+const FREE_COLOR: Color32 = Color32::from_gray(60);
+
+/// The groups operating systems use in their storage settings.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StorageCategory {
+    System,
+    Apps,
+    Documents,
+    Pictures,
+    Video,
+    Music,
+    Temporary,
+    Other,
+}
+
+impl StorageCategory {
+    pub const ALL: [StorageCategory; 8] = [
+        StorageCategory::System,
+        StorageCategory::Apps,
+        StorageCategory::Documents,
+        StorageCategory::Pictures,
+        StorageCategory::Video,
+        StorageCategory::Music,
+        StorageCategory::Temporary,
+        StorageCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageCategory::System => "System",
+            StorageCategory::Apps => "Apps",
+            StorageCategory::Documents => "Documents",
+            StorageCategory::Pictures => "Pictures",
+            StorageCategory::Video => "Video",
+            StorageCategory::Music => "Music",
+            StorageCategory::Temporary => "Temporary",
+            StorageCategory::Other => "Other",
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            StorageCategory::System => Color32::from_rgb(130, 130, 150),
+            StorageCategory::Apps => Color32::from_rgb(90, 140, 230),
+            StorageCategory::Documents => Color32::from_rgb(240, 190, 70),
+            StorageCategory::Pictures => Color32::from_rgb(80, 190, 120),
+            StorageCategory::Video => Color32::from_rgb(220, 90, 90),
+            StorageCategory::Music => Color32::from_rgb(180, 110, 220),
+            StorageCategory::Temporary => Color32::from_rgb(240, 140, 60),
+            StorageCategory::Other => Color32::from_rgb(90, 200, 210),
+        }
+    }
+
+    fn of_file(path: &Path, size: u64) -> Self {
+        match category::classify_file(path, size) {
+            Category::Video => StorageCategory::Video,
+            Category::Audio => StorageCategory::Music,
+            Category::Image => StorageCategory::Pictures,
+            Category::Document => StorageCategory::Documents,
+            Category::Executable => StorageCategory::Apps,
+            Category::Archive | Category::Code | Category::Other => StorageCategory::Other,
+        }
+    }
+}
+
+/// Folders and files whose whole contents belong to one category, whatever the file types.
+/// A nested entry overrides its parent, so `/var/lib/flatpak` counts as apps inside `/var/lib`.
+fn known_locations() -> HashMap<PathBuf, StorageCategory> {
+    use StorageCategory::*;
+
+    let mut locations: Vec<(StorageCategory, PathBuf)> = [
+        (System, "/usr"),
+        (System, "/boot"),
+        (System, "/etc"),
+        (System, "/lib"),
+        (System, "/lib64"),
+        (System, "/bin"),
+        (System, "/sbin"),
+        (System, "/var/lib"),
+        (System, "/var/log"),
+        (System, "/swapfile"),
+        (System, "/System"),
+        (System, "/Library"),
+        (System, "/private/var"),
+        (Apps, "/opt"),
+        (Apps, "/usr/local"),
+        (Apps, "/snap"),
+        (Apps, "/var/lib/flatpak"),
+        (Apps, "/var/lib/snapd"),
+        (Apps, "/Applications"),
+        (Temporary, "/tmp"),
+        (Temporary, "/var/tmp"),
+        (Temporary, "/var/cache"),
+        (Temporary, "/private/var/folders"),
+    ]
+    .into_iter()
+    .map(|(category, path)| (category, PathBuf::from(path)))
+    .collect();
+
+    if let Some(home) = dirs::home_dir() {
+        locations.push((Apps, home.join("Applications")));
+        locations.push((Apps, home.join(".local/share/flatpak")));
+        locations.push((Temporary, home.join(".local/share/Trash")));
+    }
+    let user_dirs = [
+        (Temporary, dirs::cache_dir()),
+        (Documents, dirs::document_dir()),
+        (Pictures, dirs::picture_dir()),
+        (Video, dirs::video_dir()),
+        (Music, dirs::audio_dir()),
+    ];
+    locations.extend(user_dirs.into_iter().filter_map(|(category, path)| Some((category, path?))));
+    locations.push((Temporary, std::env::temp_dir()));
+
+    if cfg!(windows) {
+        let drive = windows_cleanup::system_drive();
+        let windows = windows_cleanup::windows_dir();
+        locations.push((System, windows.clone()));
+        for file in ["pagefile.sys", "hiberfil.sys", "swapfile.sys"] {
+            locations.push((System, drive.join(file)));
+        }
+        locations.push((System, drive.join("System Volume Information")));
+        locations.push((Apps, drive.join("Program Files")));
+        locations.push((Apps, drive.join("Program Files (x86)")));
+        locations.extend(dirs::data_local_dir().map(|dir| (Apps, dir.join("Programs"))));
+        locations.push((Temporary, windows.join("Temp")));
+        locations.push((Temporary, drive.join("$Recycle.Bin")));
+    }
+    locations.into_iter().map(|(category, path)| (path, category)).collect()
+}
+
+/// Mounted disks, one per device; where mounts can't be listed, the drive holding the home
+/// folder.
+pub fn drives() -> Vec<PathBuf> {
+    let mut drives = mounts::disks();
+    if drives.is_empty() {
+        drives.extend(dirs::home_dir().and_then(|home| home.ancestors().last().map(Path::to_path_buf)));
+    }
+    drives
+}
+
+pub struct Breakdown {
+    pub sizes: BTreeMap<StorageCategory, u64>,
+    /// Total and available space of the drive.
+    pub capacity: Option<(u64, u64)>,
+}
+
+fn walk(
+    dir: &Path,
+    inherited: Option<StorageCategory>,
+    locations: &HashMap<PathBuf, StorageCategory>,
+    root_device: Option<u64>,
+    sizes: &mut BTreeMap<StorageCategory, u64>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        throttle::pace();
+        let path = entry.path();
+        let category = locations.get(&path).copied().or(inherited);
+        match entry.metadata() {
+            Ok(metadata) if scan::crosses_device(&metadata, root_device) => {}
+            Ok(metadata) if metadata.is_dir() => walk(&path, category, locations, root_device, sizes),
+            Ok(metadata) if metadata.is_file() => {
+                let size = scan::file_size(&path, &metadata);
+                let category = category.unwrap_or_else(|| StorageCategory::of_file(&path, size));
+                *sizes.entry(category).or_default() += size;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sorts everything on the drive into categories. Space in use that no readable file
+/// accounts for (file system metadata, reserved blocks, folders we can't open) counts as
+/// system, as the storage settings do.
+pub fn analyze(drive: &Path) -> Breakdown {
+    let locations = known_locations();
+    let root_device = fs::symlink_metadata(drive).ok().and_then(|metadata| scan::device_id(&metadata));
+    let mut sizes = BTreeMap::new();
+    walk(drive, locations.get(drive).copied(), &locations, root_device, &mut sizes);
+
+    let capacity = volume::info(drive).map(|info| (info.total, info.available));
+    if let Some((total, available)) = capacity {
+        let counted: u64 = sizes.values().sum();
+        let unaccounted = total.saturating_sub(available).saturating_sub(counted);
+        *sizes.entry(StorageCategory::System).or_default() += unaccounted;
+    }
+    Breakdown { sizes, capacity }
+}
+
+fn stacked_bar(ui: &mut egui::Ui, breakdown: &Breakdown) {
+    let width = ui.available_width().max(200.0);
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, BAR_HEIGHT), egui::Sense::hover());
+    let used: u64 = breakdown.sizes.values().sum();
+    let scale = breakdown.capacity.map_or(used, |(total, _)| total.max(used)).max(1) as f32;
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, FREE_COLOR);
+
+    let mut left = rect.left();
+    let mut hovered = None;
+    for category in StorageCategory::ALL {
+        let size = breakdown.sizes.get(&category).copied().unwrap_or_default();
+        if size == 0 {
+            continue;
+        }
+        let segment = egui::Rect::from_min_max(
+            egui::pos2(left, rect.top()),
+            egui::pos2(left + rect.width() * size as f32 / scale, rect.bottom()),
+        );
+        painter.rect_filled(segment, 0.0, category.color());
+        if response.hover_pos().is_some_and(|pos| segment.x_range().contains(pos.x)) {
+            hovered = Some((category, size));
+        }
+        left = segment.right();
+    }
+    let text = match hovered {
+        Some((category, size)) => trf(
+            "{}: {} ({})",
+            &[&tr(category.label()), &i18n::format_bytes(size), &format!("{:.1}%", size as f32 * 100.0 / scale)],
+        ),
+        None => trf("Free: {}", &[&i18n::format_bytes(breakdown.capacity.map_or(0, |(_, available)| available))]),
+    };
+    response.on_hover_text(text);
+}
+
+fn legend(ui: &mut egui::Ui, breakdown: &Breakdown) {
+    ui.horizontal_wrapped(|ui| {
+        for category in StorageCategory::ALL {
+            let size = breakdown.sizes.get(&category).copied().unwrap_or_default();
+            if size == 0 {
+                continue;
+            }
+            ui.label(RichText::new("■").color(category.color()));
+            ui.label(format!("{} {}", tr(category.label()), i18n::format_bytes(size)));
+            ui.add_space(8.0);
+        }
+        if let Some((_, available)) = breakdown.capacity {
+            ui.label(RichText::new("■").color(FREE_COLOR));
+            ui.label(format!("{} {}", tr("Free"), i18n::format_bytes(available)));
+        }
+    });
+}
+
+#[derive(Default)]
+pub struct StorageState {
+    pub open: bool,
+    drives: Option<Vec<PathBuf>>,
+    breakdowns: HashMap<PathBuf, Breakdown>,
+    jobs: HashMap<PathBuf, Job<Breakdown>>,
+}
+
+impl StorageState {
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut finished = Vec::new();
+        for (drive, job) in &self.jobs {
+            match job.poll() {
+                Some(breakdown) => finished.push((drive.clone(), breakdown)),
+                None => ctx.request_repaint(),
+            }
+        }
+        for (drive, breakdown) in finished {
+            self.jobs.remove(&drive);
+            self.breakdowns.insert(drive, breakdown);
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Storage Categories"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(tr(
+                        "Sorts each drive the way the system storage settings do, by known locations first and file type otherwise. Analyzing reads the whole drive.",
+                    ))
+                    .color(Color32::GRAY),
+                );
+                let drives = self.drives.get_or_insert_with(drives);
+                let mut requested = None;
+                for drive in drives.iter() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(paths::display_path(drive)).strong());
+                        if let Some(info) = volume::info(drive) {
+                            ui.label(trf(
+                                "{} used of {}",
+                                &[
+                                    &i18n::format_bytes(info.total.saturating_sub(info.available)),
+                                    &i18n::format_bytes(info.total),
+                                ],
+                            ));
+                        }
+                        if self.jobs.contains_key(drive) {
+                            ui.spinner();
+                        } else {
+                            let label = if self.breakdowns.contains_key(drive) { tr("Refresh") } else { tr("Analyze") };
+                            if ui.button(label).clicked() {
+                                requested = Some(drive.clone());
+                            }
+                        }
+                    });
+                    if let Some(breakdown) = self.breakdowns.get(drive) {
+                        stacked_bar(ui, breakdown);
+                        legend(ui, breakdown);
+                    }
+                }
+                if let Some(drive) = requested {
+                    let path = drive.clone();
+                    self.jobs.insert(drive, Job::spawn(move || analyze(&path)));
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,211 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    category::{self, Category},
+    heatmap,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    paths,
+};
+
+/// Folders that tools recreate on demand and that tend to pile up across projects.
+const GENERATED_FOLDERS: [&str; 8] =
+    ["node_modules", ".git", "target", "__pycache__", ".venv", "venv", ".gradle", ".terraform"];
+const STALE_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+/// Findings about less than this share of the folder aren't worth a sentence.
+const MIN_SHARE: f64 = 0.05;
+const MIN_LARGEST_SHARE: f64 = 0.2;
+const BREAKDOWN_ENTRIES: usize = 5;
+
+fn category_noun(category: Category) -> Option<&'static str> {
+    Some(match category {
+        Category::Video => "video files",
+        Category::Audio => "audio files",
+        Category::Image => "images",
+        Category::Document => "documents",
+        Category::Archive => "archives",
+        Category::Code => "source code",
+        Category::Executable => "programs and libraries",
+        Category::Other => return None,
+    })
+}
+
+#[derive(Default)]
+struct Totals {
+    categories: BTreeMap<Category, u64>,
+    /// Size and count of the outermost folder of each generated kind.
+    generated: BTreeMap<&'static str, (u64, u64)>,
+    stale: (u64, u64),
+    unreachable: u64,
+    largest: Option<usize>,
+}
+
+fn tally(index: &ScanIndex, dir: usize) -> Totals {
+    let mut totals = Totals::default();
+    let mut pending: Vec<(usize, u8)> = index.nodes[dir].children.iter().map(|&child| (child, 0)).collect();
+    for &child in &index.nodes[dir].children {
+        if totals.largest.is_none_or(|largest| index.nodes[child].size > index.nodes[largest].size) {
+            totals.largest = Some(child);
+        }
+    }
+    // Each bit marks a generated folder kind already entered, so nested copies count once.
+    while let Some((id, inside)) = pending.pop() {
+        let node = &index.nodes[id];
+        if !node.is_dir {
+            *totals.categories.entry(category::classify_file(&node.path, node.size)).or_default() += node.size;
+            if heatmap::age(node.modified).is_some_and(|age| age > STALE_AGE) {
+                totals.stale.0 += node.size;
+                totals.stale.1 += 1;
+            }
+            continue;
+        }
+        if node.unreachable {
+            totals.unreachable += 1;
+        }
+        let mut inside = inside;
+        if let Some(kind) = GENERATED_FOLDERS.iter().position(|&name| name == node.name) {
+            if inside & (1 << kind) == 0 {
+                let entry = totals.generated.entry(GENERATED_FOLDERS[kind]).or_default();
+                entry.0 += node.size;
+                entry.1 += 1;
+                inside |= 1 << kind;
+            }
+        }
+        pending.extend(node.children.iter().map(|&child| (child, inside)));
+    }
+    totals
+}
+
+fn percent(part: u64, total: u64) -> String {
+    format!("{:.0}%", part as f64 * 100.0 / total.max(1) as f64)
+}
+
+/// The folder's largest entries and their shares of it, for tooltips that help decide
+/// whether a folder is worth opening.
+pub fn show_breakdown(ui: &mut egui::Ui, index: &ScanIndex, dir: usize) {
+    let node = &index.nodes[dir];
+    if node.size == 0 {
+        ui.label(RichText::new(tr("This folder is empty.")).color(Color32::GRAY));
+        return;
+    }
+    egui::Grid::new("breakdown").num_columns(4).show(ui, |ui| {
+        for &child in node.children.iter().take(BREAKDOWN_ENTRIES) {
+            let child = &index.nodes[child];
+            let share = child.size as f32 / node.size as f32;
+            let name = paths::display_name(&child.name);
+            ui.label(if child.is_dir { format!("{}/", name) } else { name.into_owned() });
+            ui.label(i18n::format_bytes(child.size));
+            ui.add(egui::ProgressBar::new(share).desired_width(60.0));
+            ui.label(percent(child.size, node.size));
+            ui.end_row();
+        }
+    });
+    let rest = node.children.len().saturating_sub(BREAKDOWN_ENTRIES) as u64 + node.small_files;
+    if rest > 0 {
+        ui.label(RichText::new(trf("and {} more", &[&i18n::format_count(rest)])).small().color(Color32::GRAY));
+    }
+}
+
+/// Plain-language conclusions about what fills the folder, most significant first.
+pub fn findings(index: &ScanIndex, dir: usize) -> Vec<String> {
+    let total = index.nodes[dir].size;
+    if total == 0 {
+        return vec![tr("This folder is empty.").to_string()];
+    }
+    let totals = tally(index, dir);
+    let significant = |size: u64| size as f64 >= total as f64 * MIN_SHARE;
+    let mut findings = Vec::new();
+
+    let mut categories: Vec<(Category, u64)> = totals.categories.into_iter().collect();
+    categories.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+    for (category, size) in categories.into_iter().filter(|&(_, size)| significant(size)).take(3) {
+        if let Some(noun) = category_noun(category) {
+            findings.push(trf(
+                "{} of this folder is {} ({})",
+                &[&percent(size, total), &tr(noun), &i18n::format_bytes(size)],
+            ));
+        }
+    }
+
+    if let Some(largest) = totals.largest.map(|id| &index.nodes[id]) {
+        if largest.size as f64 >= total as f64 * MIN_LARGEST_SHARE {
+            findings.push(trf(
+                "The largest item, {}, takes {} on its own ({})",
+                &[
+                    &paths::display_name(&largest.name),
+                    &percent(largest.size, total),
+                    &i18n::format_bytes(largest.size),
+                ],
+            ));
+        }
+    }
+
+    let mut generated: Vec<(&str, (u64, u64))> = totals.generated.into_iter().collect();
+    generated.sort_by_key(|&(_, (size, _))| std::cmp::Reverse(size));
+    for (name, (size, count)) in generated.into_iter().filter(|&(_, (size, _))| significant(size)) {
+        findings.push(if count == 1 {
+            trf("A {} folder takes {}", &[&name, &i18n::format_bytes(size)])
+        } else {
+            trf(
+                "{} folders account for {} across {} projects",
+                &[&name, &i18n::format_bytes(size), &i18n::format_count(count)],
+            )
+        });
+    }
+
+    let (stale_size, stale_count) = totals.stale;
+    if significant(stale_size) {
+        findings.push(trf(
+            "Files untouched for over a year total {} ({} files, {} of this folder)",
+            &[&i18n::format_bytes(stale_size), &i18n::format_count(stale_count), &percent(stale_size, total)],
+        ));
+    }
+
+    if totals.unreachable > 0 {
+        findings.push(trf(
+            "{} folders couldn't be read, so the real total may be higher",
+            &[&i18n::format_count(totals.unreachable)],
+        ));
+    }
+    if findings.is_empty() {
+        findings.push(tr("No single kind of file or folder stands out here.").to_string());
+    }
+    findings
+}
+
+#[derive(Default)]
+pub struct SummaryState {
+    findings: Option<(PathBuf, Vec<String>)>,
+}
+
+impl SummaryState {
+    /// Drops the cached findings after the index changes.
+    pub fn invalidate(&mut self) {
+        self.findings = None;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, index: Option<&ScanIndex>, current: Option<&Path>) {
+        let (Some(index), Some(current)) = (index, current) else {
+            ui.label(RichText::new(tr("Available once the tree has been scanned")).color(Color32::GRAY));
+            return;
+        };
+        let Some(dir) = index.find_dir(current) else {
+            return;
+        };
+        if self.findings.as_ref().is_none_or(|(path, _)| path != current) {
+            self.findings = Some((current.to_path_buf(), findings(index, dir)));
+        }
+        let Some((_, findings)) = &self.findings else {
+            return;
+        };
+        ui.label(RichText::new(tr("What uses the space here")).strong());
+        for finding in findings {
+            ui.label(format!("• {}", finding));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::config;
+
+const TAGS_FILE: &str = "tags.json";
+
+pub const PRESET_TAGS: [&str; 3] = ["keep", "review", "delete later"];
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PathTags {
+    pub tags: BTreeSet<String>,
+    pub note: String,
+}
+
+impl PathTags {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.trim().is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct TagStore {
+    entries: BTreeMap<PathBuf, PathTags>,
+}
+
+impl TagStore {
+    pub fn load() -> Self {
+        config::load(TAGS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::save(TAGS_FILE, self)
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&PathTags> {
+        self.entries.get(path)
+    }
+
+    pub fn has_tag(&self, path: &Path, tag: &str) -> bool {
+        self.get(path).is_some_and(|entry| entry.tags.contains(tag))
+    }
+
+    pub fn set(&mut self, path: &Path, tags: PathTags) {
+        if tags.is_empty() {
+            self.entries.remove(path);
+        } else {
+            self.entries.insert(path.to_path_buf(), tags);
+        }
+    }
+
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        let mut tags: BTreeSet<String> = PRESET_TAGS.iter().map(|t| t.to_string()).collect();
+        for entry in self.entries.values() {
+            tags.extend(entry.tags.iter().cloned());
+        }
+        tags
+    }
+}
+
+pub fn tag_color(tag: &str) -> eframe::egui::Color32 {
+    use eframe::egui::Color32;
+    match tag {
+        "keep" => Color32::GREEN,
+        "review" => Color32::YELLOW,
+        "delete later" => Color32::LIGHT_RED,
+        _ => Color32::LIGHT_GRAY,
+    }
+}
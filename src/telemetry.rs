@@ -0,0 +1,190 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    scan::{self, ScanOptions},
+};
+
+const BENCH_RUNS: usize = 2;
+
+struct Counters {
+    entries: AtomicU64,
+    bytes: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    active_jobs: AtomicUsize,
+}
+
+static COUNTERS: Counters = Counters {
+    entries: AtomicU64::new(0),
+    bytes: AtomicU64::new(0),
+    cache_hits: AtomicU64::new(0),
+    cache_misses: AtomicU64::new(0),
+    active_jobs: AtomicUsize::new(0),
+};
+
+pub fn record_entry(bytes: u64) {
+    COUNTERS.entries.fetch_add(1, Ordering::Relaxed);
+    COUNTERS.bytes.fetch_add(bytes, Ordering::Relaxed);
+}
+
+pub fn record_cache(hit: bool) {
+    let counter = if hit { &COUNTERS.cache_hits } else { &COUNTERS.cache_misses };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn job_started() {
+    COUNTERS.active_jobs.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn job_finished() {
+    COUNTERS.active_jobs.fetch_sub(1, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Snapshot {
+    pub entries: u64,
+    pub bytes: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub active_jobs: usize,
+}
+
+impl Snapshot {
+    pub fn take() -> Self {
+        Self {
+            entries: COUNTERS.entries.load(Ordering::Relaxed),
+            bytes: COUNTERS.bytes.load(Ordering::Relaxed),
+            cache_hits: COUNTERS.cache_hits.load(Ordering::Relaxed),
+            cache_misses: COUNTERS.cache_misses.load(Ordering::Relaxed),
+            active_jobs: COUNTERS.active_jobs.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        (total > 0).then(|| self.cache_hits as f64 / total as f64)
+    }
+}
+
+pub fn available_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Live throughput figures for the diagnostics overlay, recomputed about once a second.
+pub struct Diagnostics {
+    last: Snapshot,
+    last_at: Instant,
+    entries_per_sec: f64,
+    bytes_per_sec: f64,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            last: Snapshot::take(),
+            last_at: Instant::now(),
+            entries_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+        }
+    }
+}
+
+impl Diagnostics {
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let now = Snapshot::take();
+        let elapsed = self.last_at.elapsed().as_secs_f64();
+        if elapsed >= 1.0 {
+            self.entries_per_sec = (now.entries - self.last.entries) as f64 / elapsed;
+            self.bytes_per_sec = (now.bytes - self.last.bytes) as f64 / elapsed;
+            self.last = now;
+            self.last_at = Instant::now();
+        }
+
+        egui::Area::new("diagnostics")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -40.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let text = |text: String| RichText::new(text).monospace().color(Color32::LIGHT_GRAY);
+                    ui.label(text(trf("{} entries/s", &[&i18n::format_count(self.entries_per_sec as u64)])));
+                    ui.label(text(trf("{}/s measured", &[&i18n::format_bytes(self.bytes_per_sec as u64)])));
+                    ui.label(text(trf(
+                        "{} of {} threads busy",
+                        &[&now.active_jobs, &available_threads()],
+                    )));
+                    ui.label(text(match now.cache_hit_rate() {
+                        Some(rate) => trf(
+                            "Cache hits: {}% ({} / {})",
+                            &[&((rate * 100.0).round() as u64), &now.cache_hits, &(now.cache_hits + now.cache_misses)],
+                        ),
+                        None => tr("Cache hits: -").to_string(),
+                    }));
+                    ui.label(text(trf("{} entries total", &[&i18n::format_count(now.entries)])));
+                });
+            });
+        ctx.request_repaint_after(Duration::from_secs(1));
+    }
+}
+
+/// Times full scans of `path` and prints a breakdown; the first run is usually cold.
+pub fn run_bench(path: &Path, options: &ScanOptions, show_hidden: bool) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+    println!("Benchmarking {} ({} threads available)", path.display(), available_threads());
+
+    for run in 1..=BENCH_RUNS {
+        let before = Snapshot::take();
+        let started = Instant::now();
+        let index = ScanIndex::build(path, options, show_hidden);
+        let walk = started.elapsed();
+        let walked = Snapshot::take();
+
+        let started = Instant::now();
+        let entries = fs::read_dir(path).map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+        let mut children = 0u64;
+        for entry in entries.filter_map(Result::ok) {
+            if let Ok(metadata) = entry.metadata() {
+                scan::measure_entry(&entry.path(), &metadata, options);
+                children += 1;
+            }
+        }
+        let listing = started.elapsed();
+        let listed = Snapshot::take();
+
+        let walk_entries = walked.entries - before.entries;
+        println!("Run {}:", run);
+        println!(
+            "  full walk      {:>10.3}s  {} entries, {} ({} entries/s)",
+            walk.as_secs_f64(),
+            i18n::format_count(walk_entries),
+            i18n::format_bytes(index.nodes.first().map_or(0, |root| root.size)),
+            i18n::format_count(per_second(walk_entries, walk))
+        );
+        println!(
+            "  top-level list {:>10.3}s  {} children, {} entries measured ({} entries/s)",
+            listing.as_secs_f64(),
+            i18n::format_count(children),
+            i18n::format_count(listed.entries - walked.entries),
+            i18n::format_count(per_second(listed.entries - walked.entries, listing))
+        );
+    }
+    Ok(())
+}
+
+fn per_second(count: u64, elapsed: Duration) -> u64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        (count as f64 / seconds) as u64
+    } else {
+        count
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::IsTerminal;
+
+use crate::{
+    i18n::{self, trf},
+    index::ScanIndex,
+    paths,
+};
+
+const BAR_WIDTH: usize = 24;
+const MAX_NAME_WIDTH: usize = 60;
+/// Entries smaller than this share of the root are folded into one line per folder.
+const MIN_SHARE: f64 = 0.01;
+const PARTIAL_BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(format!("Invalid value for --color: {} (use auto, always or never)", other)),
+        }
+    }
+
+    /// Auto colors only a terminal and honours the `NO_COLOR` convention.
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+pub struct TreeOptions {
+    pub depth: usize,
+    pub color: ColorChoice,
+    /// Smallest first, so with a long listing the biggest entries end up nearest the prompt.
+    pub reverse: bool,
+}
+
+struct Line {
+    prefix: String,
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+fn share_bar(fraction: f64) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (BAR_WIDTH * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if let Some(partial) = (eighths % 8).checked_sub(1) {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+    format!("{:<width$}", bar, width = BAR_WIDTH)
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+fn collect(index: &ScanIndex, id: usize, prefix: &str, depth: usize, options: &TreeOptions, lines: &mut Vec<Line>) {
+    if depth >= options.depth {
+        return;
+    }
+    let threshold = (index.nodes[0].size as f64 * MIN_SHARE) as u64;
+    let (mut shown, folded): (Vec<usize>, Vec<usize>) =
+        index.nodes[id].children.iter().partition(|&&child| index.nodes[child].size >= threshold);
+    if options.reverse {
+        shown.reverse();
+    }
+    let folded_size: u64 = folded.iter().map(|&child| index.nodes[child].size).sum::<u64>() + index.nodes[id].small_size;
+    let folded_count = folded.len() as u64 + index.nodes[id].small_files;
+
+    let mut rows: Vec<Option<usize>> = shown.into_iter().map(Some).collect();
+    if folded_count > 0 {
+        if options.reverse {
+            rows.insert(0, None);
+        } else {
+            rows.push(None);
+        }
+    }
+    let count = rows.len();
+    for (position, row) in rows.into_iter().enumerate() {
+        let last = position + 1 == count;
+        let branch = if last { "└── " } else { "├── " };
+        let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+        match row {
+            Some(child) => {
+                let node = &index.nodes[child];
+                lines.push(Line {
+                    prefix: format!("{}{}", prefix, branch),
+                    name: paths::display_name(&node.name).into_owned(),
+                    is_dir: node.is_dir,
+                    size: node.size,
+                });
+                if node.is_dir {
+                    collect(index, child, &child_prefix, depth + 1, options, lines);
+                }
+            }
+            None => lines.push(Line {
+                prefix: format!("{}{}", prefix, branch),
+                name: trf("({} smaller entries)", &[&i18n::format_count(folded_count)]),
+                is_dir: false,
+                size: folded_size,
+            }),
+        }
+    }
+}
+
+/// Renders the scan like `dust`: an indented tree sorted by size, with each entry's share of
+/// the root as a percentage and a bar.
+pub fn render(index: &ScanIndex, options: &TreeOptions) -> String {
+    let Some(root) = index.nodes.first() else {
+        return String::new();
+    };
+    let mut lines = vec![Line {
+        prefix: String::new(),
+        name: root.path.display().to_string(),
+        is_dir: true,
+        size: root.size,
+    }];
+    collect(index, 0, "", 0, options, &mut lines);
+
+    let color = options.color.enabled();
+    let name_width = lines
+        .iter()
+        .map(|line| line.prefix.chars().count() + line.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_NAME_WIDTH);
+    let mut output = String::new();
+    for line in &lines {
+        let name = truncate(&line.name, name_width.saturating_sub(line.prefix.chars().count()).max(1));
+        let padding = name_width.saturating_sub(line.prefix.chars().count() + name.chars().count());
+        let fraction = if root.size == 0 { 0.0 } else { line.size as f64 / root.size as f64 };
+        let (name, bar) = if color {
+            let name = if line.is_dir { format!("\x1b[1;34m{}\x1b[0m", name) } else { name };
+            // Green for small shares through yellow to red for the biggest.
+            let shade = if fraction >= 0.5 { 31 } else if fraction >= 0.1 { 33 } else { 32 };
+            (name, format!("\x1b[{}m{}\x1b[0m", shade, share_bar(fraction)))
+        } else {
+            (name, share_bar(fraction))
+        };
+        output.push_str(&format!(
+            "{}{}{} {:>11} {:>4.0}% │{}│\n",
+            line.prefix,
+            name,
+            " ".repeat(padding),
+            i18n::format_bytes(line.size),
+            fraction * 100.0,
+            bar
+        ));
+    }
+    output
+}
@@ -0,0 +1,110 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::telemetry;
+
+/// Limits for background scans; zero means no limit.
+static MAX_WORKERS: AtomicUsize = AtomicUsize::new(0);
+static MAX_OPERATIONS_PER_SECOND: AtomicU64 = AtomicU64::new(0);
+static LOW_PRIORITY: AtomicBool = AtomicBool::new(false);
+/// The earliest moment the next file system operation may start when a rate is set.
+static NEXT_SLOT: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn configure(max_workers: usize, max_operations_per_second: u64, low_priority: bool) {
+    MAX_WORKERS.store(max_workers, Ordering::Relaxed);
+    MAX_OPERATIONS_PER_SECOND.store(max_operations_per_second, Ordering::Relaxed);
+    LOW_PRIORITY.store(low_priority, Ordering::Relaxed);
+}
+
+/// Caps a worker count at the configured maximum.
+pub fn workers(default: usize) -> usize {
+    match MAX_WORKERS.load(Ordering::Relaxed) {
+        0 => default,
+        limit => limit.min(default),
+    }
+}
+
+/// Waits until the operations-per-second budget allows another file system call. Callers on
+/// every thread share one budget.
+pub fn pace() {
+    let per_second = MAX_OPERATIONS_PER_SECOND.load(Ordering::Relaxed);
+    if per_second == 0 {
+        return;
+    }
+    let interval = Duration::from_nanos(1_000_000_000 / per_second);
+    let slot = {
+        let Ok(mut next) = NEXT_SLOT.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let slot = next.map_or(now, |next| next.max(now));
+        *next = Some(slot + interval);
+        slot
+    };
+    let wait = slot.saturating_duration_since(Instant::now());
+    if !wait.is_zero() {
+        thread::sleep(wait);
+    }
+}
+
+/// Moves the calling thread to idle I/O and lowest CPU priority when low-priority scanning is
+/// on. Called at the start of every background worker.
+pub fn enter_background() {
+    if LOW_PRIORITY.load(Ordering::Relaxed) {
+        lower_current_thread();
+    }
+}
+
+/// Runs rayon work on a pool sized by the worker limit, with background priority applied.
+pub fn run_limited<R: Send>(work: impl FnOnce() -> R + Send) -> R {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers(telemetry::available_threads()))
+        .start_handler(|_| enter_background())
+        .build();
+    match pool {
+        Ok(pool) => pool.install(work),
+        Err(_) => work(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lower_current_thread() {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+    const LOWEST_NICE: libc::c_int = 19;
+    // On Linux both calls treat `who == 0` as the calling thread, not the whole process.
+    unsafe {
+        libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT);
+        libc::setpriority(libc::PRIO_PROCESS, 0, LOWEST_NICE);
+    }
+}
+
+/// The background QoS class throttles both CPU and disk access for the thread.
+#[cfg(target_os = "macos")]
+fn lower_current_thread() {
+    unsafe {
+        libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_BACKGROUND, 0);
+    }
+}
+
+/// Background mode lowers the thread's CPU, I/O and memory priority together.
+#[cfg(windows)]
+fn lower_current_thread() {
+    use winapi::um::{
+        processthreadsapi::{GetCurrentThread, SetThreadPriority},
+        winbase::THREAD_MODE_BACKGROUND_BEGIN,
+    };
+    unsafe {
+        SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN as i32);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn lower_current_thread() {}
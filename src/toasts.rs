@@ -0,0 +1,92 @@
+use eframe::egui::{self, Color32, RichText};
+use std::time::{Duration, Instant};
+
+use crate::{a11y, error::Error, i18n::tr};
+
+/// How long a notification stays up while the pointer is away and its details are closed.
+const LIFETIME: Duration = Duration::from_secs(8);
+const WIDTH: f32 = 360.0;
+/// Older notifications make way for newer ones past this many.
+const MAX_TOASTS: usize = 5;
+
+struct Toast {
+    id: u64,
+    message: String,
+    details: String,
+    /// How many times the same error came in while this was up.
+    count: usize,
+    shown_at: Instant,
+}
+
+/// Notifications stacked in the bottom-right corner. They don't block the window and go away
+/// on their own; the Errors panel keeps the full list.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn error(&mut self, error: &Error) {
+        let (message, details) = (error.to_string(), error.details());
+        if let Some(toast) = self.toasts.iter_mut().find(|toast| toast.message == message && toast.details == details) {
+            toast.count += 1;
+            toast.shown_at = Instant::now();
+            return;
+        }
+        self.toasts.push(Toast { id: self.next_id, message, details, count: 1, shown_at: Instant::now() });
+        self.next_id += 1;
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if self.toasts.is_empty() {
+            return;
+        }
+        let mut dismissed = Vec::new();
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -32.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(WIDTH);
+                for toast in &mut self.toasts {
+                    let mut reading = false;
+                    let frame = egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new("⚠").color(Color32::RED));
+                            ui.add(egui::Label::new(&toast.message).wrap(true));
+                            if toast.count > 1 {
+                                ui.label(RichText::new(format!("×{}", toast.count)).color(Color32::GRAY));
+                            }
+                            if a11y::icon_button(ui, "✖", tr("Dismiss")).clicked() {
+                                dismissed.push(toast.id);
+                            }
+                        });
+                        if toast.details.is_empty() {
+                            return;
+                        }
+                        let details = egui::CollapsingHeader::new(tr("Details"))
+                            .id_source(("toast", toast.id))
+                            .show(ui, |ui| {
+                                ui.label(RichText::new(&toast.details).monospace().small());
+                                if ui.small_button(tr("Copy")).clicked() {
+                                    let text = format!("{}\n{}", toast.message, toast.details);
+                                    ui.output_mut(|output| output.copied_text = text);
+                                }
+                            });
+                        reading = details.openness > 0.0;
+                    });
+                    if reading || ui.rect_contains_pointer(frame.response.rect) {
+                        toast.shown_at = Instant::now();
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+        self.toasts.retain(|toast| !dismissed.contains(&toast.id) && toast.shown_at.elapsed() < LIFETIME);
+        if !self.toasts.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    audit,
+    error::Error,
+    i18n::{self, tr, trf},
+    job::Job,
+    operations::Action,
+    paths, scan,
+};
+
+/// Items listed for putting back; the rest are still counted in the totals.
+const MAX_LISTED: usize = 200;
+
+/// The trash (or Recycle Bin) belonging to one volume.
+pub struct TrashLocation {
+    pub volume: PathBuf,
+    pub size: u64,
+    pub items: u64,
+}
+
+/// Something in the trash that can be put back where it came from.
+pub struct TrashedItem {
+    pub path: PathBuf,
+    pub original: PathBuf,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+    use std::{
+        fs::OpenOptions,
+        io::Write,
+        os::unix::{
+            ffi::{OsStrExt, OsStringExt},
+            fs::{DirBuilderExt, MetadataExt},
+        },
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use crate::mounts;
+
+    fn uid() -> u32 {
+        unsafe { libc::getuid() }
+    }
+
+    fn home_trash() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("Trash"))
+    }
+
+    /// Per-volume trash directories from the freedesktop.org trash spec.
+    fn volume_trashes(mount_point: &Path) -> [PathBuf; 2] {
+        [
+            mount_point.join(format!(".Trash-{}", uid())),
+            mount_point.join(".Trash").join(uid().to_string()),
+        ]
+    }
+
+    fn all_trashes() -> Vec<(PathBuf, PathBuf)> {
+        let mut trashes = Vec::new();
+        if let Some(home) = home_trash() {
+            let volume = mounts::mount_of(&home).map_or_else(|| PathBuf::from("/"), |mount| mount.mount_point);
+            trashes.push((volume, home));
+        }
+        for mount in mounts::all() {
+            for trash in volume_trashes(&mount.mount_point) {
+                if trash.join("files").is_dir() && !trashes.iter().any(|(_, known)| *known == trash) {
+                    trashes.push((mount.mount_point.clone(), trash));
+                }
+            }
+        }
+        trashes
+    }
+
+    pub fn locations() -> Vec<TrashLocation> {
+        all_trashes()
+            .into_iter()
+            .filter_map(|(volume, trash)| {
+                let files = trash.join("files");
+                let items = fs::read_dir(&files).ok()?.count() as u64;
+                let size = scan::measure_dir(&files, &Default::default()).size;
+                Some(TrashLocation { volume, size, items })
+            })
+            .collect()
+    }
+
+    fn percent_decode(text: &str) -> PathBuf {
+        let bytes = text.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut position = 0;
+        while position < bytes.len() {
+            let escaped = bytes.get(position + 1..position + 3).and_then(|hex| {
+                std::str::from_utf8(hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            });
+            match (bytes[position], escaped) {
+                (b'%', Some(byte)) => {
+                    decoded.push(byte);
+                    position += 3;
+                }
+                (byte, _) => {
+                    decoded.push(byte);
+                    position += 1;
+                }
+            }
+        }
+        PathBuf::from(std::ffi::OsString::from_vec(decoded))
+    }
+
+    /// The items in every trash with a readable `.trashinfo`. Paths recorded in a volume's
+    /// trash are relative to that volume.
+    pub fn trashed() -> Vec<TrashedItem> {
+        let mut items = Vec::new();
+        for (volume, trash) in all_trashes() {
+            let Ok(entries) = fs::read_dir(trash.join("info")) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let info_name = entry.file_name();
+                let Some(name) = info_name.as_bytes().strip_suffix(b".trashinfo") else {
+                    continue;
+                };
+                let path = trash.join("files").join(std::ffi::OsStr::from_bytes(name));
+                let Ok(metadata) = fs::symlink_metadata(&path) else {
+                    continue;
+                };
+                let Some(original) = fs::read_to_string(entry.path())
+                    .ok()
+                    .and_then(|info| info.lines().find_map(|line| line.strip_prefix("Path=").map(percent_decode)))
+                else {
+                    continue;
+                };
+                let size = match metadata.is_dir() {
+                    true => scan::measure_dir(&path, &Default::default()).size,
+                    false => metadata.len(),
+                };
+                let original = if original.is_absolute() { original } else { volume.join(original) };
+                items.push(TrashedItem { path, original, size, is_dir: metadata.is_dir() });
+            }
+        }
+        items.sort_by(|a, b| a.original.cmp(&b.original));
+        items
+    }
+
+    /// Removes the `.trashinfo` of an item that has left the trash.
+    pub fn forget(path: &Path) -> Result<(), Error> {
+        let (Some(files), Some(name)) = (path.parent(), path.file_name()) else {
+            return Ok(());
+        };
+        let mut info_name = name.to_os_string();
+        info_name.push(".trashinfo");
+        let info = files.with_file_name("info").join(info_name);
+        match fs::remove_file(&info) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Write { path: info, source: e }),
+            _ => Ok(()),
+        }
+    }
+
+    fn device(path: &Path) -> Option<u64> {
+        fs::metadata(path).ok().map(|metadata| metadata.dev())
+    }
+
+    /// The home trash when `path` lives on the same device, otherwise `$topdir/.Trash-$uid`.
+    fn trash_for(path: &Path) -> Result<PathBuf, String> {
+        let home = home_trash().ok_or("No home trash directory")?;
+        let parent = path.parent().unwrap_or(path);
+        if device(parent).is_some() && device(home.parent().unwrap_or(&home)) == device(parent) {
+            return Ok(home);
+        }
+        let mount = mounts::mount_of(parent).ok_or("Couldn't find the volume to trash into")?;
+        let [trash, _] = volume_trashes(&mount.mount_point);
+        Ok(trash)
+    }
+
+    fn percent_encode(path: &Path) -> String {
+        path.as_os_str()
+            .as_bytes()
+            .iter()
+            .map(|&byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    fn deletion_date() -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let (year, month, day, seconds) = audit::civil_date(now);
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+
+    pub fn move_to_trash(path: &Path) -> Result<(), Error> {
+        let path = std::path::absolute(path).map_err(|source| Error::Read { path: path.to_path_buf(), source })?;
+        let trash = trash_for(&path)?;
+        let (files, info) = (trash.join("files"), trash.join("info"));
+        for dir in [&files, &info] {
+            fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .map_err(|source| Error::Write { path: dir.clone(), source })?;
+        }
+
+        let name = path.file_name().ok_or(Error::NoName("move to the trash"))?;
+        // Creating the .trashinfo file exclusively reserves the name against other trashers.
+        let mut attempt = 0;
+        let (trashed_name, mut info_file) = loop {
+            let mut candidate = name.to_os_string();
+            if attempt > 0 {
+                candidate.push(format!(".{}", attempt));
+            }
+            let mut info_name = candidate.clone();
+            info_name.push(".trashinfo");
+            match OpenOptions::new().write(true).create_new(true).open(info.join(&info_name)) {
+                Ok(file) if !files.join(&candidate).exists() => break (candidate, file),
+                Ok(_) => {
+                    let _ = fs::remove_file(info.join(&info_name));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(source) => return Err(Error::Write { path: info.join(info_name), source }),
+            }
+            attempt += 1;
+        };
+        let mut info_name = trashed_name.clone();
+        info_name.push(".trashinfo");
+        let info_path = info.join(info_name);
+
+        let written = writeln!(
+            info_file,
+            "[Trash Info]\nPath={}\nDeletionDate={}",
+            percent_encode(&path),
+            deletion_date()
+        );
+        let result = written
+            .map_err(|source| Error::Write { path: info_path.clone(), source })
+            .and_then(|()| {
+                fs::rename(&path, files.join(&trashed_name)).map_err(|source| Error::Trash { path, source })
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(info_path);
+        }
+        result
+    }
+
+    pub fn empty() -> Result<(), String> {
+        for (_, trash) in all_trashes() {
+            for dir in ["files", "info", "expunged"] {
+                let Ok(entries) = fs::read_dir(trash.join(dir)) else {
+                    continue;
+                };
+                for entry in entries.filter_map(Result::ok) {
+                    let path = entry.path();
+                    let result = match entry.file_type() {
+                        Ok(file_type) if file_type.is_dir() => fs::remove_dir_all(&path),
+                        _ => fs::remove_file(&path),
+                    };
+                    result.map_err(|e| format!("Error removing {}: {}", paths::display_path(&path), e))?;
+                }
+            }
+            let _ = fs::remove_file(trash.join("directorysizes"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    fn finder(script: &str) -> Result<(), String> {
+        let output = Command::new("osascript")
+            .args(["-e", script])
+            .output()
+            .map_err(|e| format!("Error running osascript: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    pub fn locations() -> Vec<TrashLocation> {
+        let uid = unsafe { libc::getuid() };
+        let mut trashes: Vec<(PathBuf, PathBuf)> = dirs::home_dir()
+            .map(|home| (PathBuf::from("/"), home.join(".Trash")))
+            .into_iter()
+            .collect();
+        if let Ok(volumes) = fs::read_dir("/Volumes") {
+            for volume in volumes.filter_map(Result::ok) {
+                let trash = volume.path().join(".Trashes").join(uid.to_string());
+                if trash.is_dir() {
+                    trashes.push((volume.path(), trash));
+                }
+            }
+        }
+        trashes
+            .into_iter()
+            .filter_map(|(volume, trash)| {
+                let items = fs::read_dir(&trash).ok()?.filter_map(Result::ok).filter(|e| e.file_name() != ".DS_Store").count();
+                let size = scan::measure_dir(&trash, &Default::default()).size;
+                Some(TrashLocation { volume, size, items: items as u64 })
+            })
+            .collect()
+    }
+
+    /// Goes through Finder so "Put Back" keeps working.
+    pub fn move_to_trash(path: &Path) -> Result<(), Error> {
+        let path = std::path::absolute(path).map_err(|source| Error::Read { path: path.to_path_buf(), source })?;
+        let quoted = path.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+        finder(&format!("tell application \"Finder\" to delete POSIX file \"{}\"", quoted)).map_err(Error::from)
+    }
+
+    pub fn empty() -> Result<(), String> {
+        finder("tell application \"Finder\" to empty trash")
+    }
+
+    /// Finder's Put Back covers this.
+    pub fn trashed() -> Vec<TrashedItem> {
+        Vec::new()
+    }
+
+    pub fn forget(_path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use std::{os::windows::ffi::OsStrExt, ptr};
+    use winapi::um::shellapi::{
+        SHEmptyRecycleBinW, SHFileOperationW, SHQueryRecycleBinW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION,
+        FOF_NOERRORUI, FOF_SILENT, FO_DELETE, SHERB_NOCONFIRMATION, SHERB_NOPROGRESSUI, SHERB_NOSOUND,
+        SHFILEOPSTRUCTW, SHQUERYRBINFO,
+    };
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    pub fn locations() -> Vec<TrashLocation> {
+        (b'A'..=b'Z')
+            .map(|letter| PathBuf::from(format!(r"{}:\", letter as char)))
+            .filter(|drive| drive.exists())
+            .filter_map(|drive| {
+                let root = wide(&drive);
+                let mut info: SHQUERYRBINFO = unsafe { std::mem::zeroed() };
+                info.cbSize = std::mem::size_of::<SHQUERYRBINFO>() as u32;
+                let result = unsafe { SHQueryRecycleBinW(root.as_ptr(), &mut info) };
+                (result == 0).then(|| TrashLocation {
+                    volume: drive,
+                    size: info.i64Size.max(0) as u64,
+                    items: info.i64NumItems.max(0) as u64,
+                })
+            })
+            .collect()
+    }
+
+    pub fn move_to_trash(path: &Path) -> Result<(), Error> {
+        let path = std::path::absolute(path).map_err(|source| Error::Read { path: path.to_path_buf(), source })?;
+        // pFrom is a list of paths ended by an extra NUL.
+        let mut from = wide(&path);
+        from.push(0);
+        let mut operation: SHFILEOPSTRUCTW = unsafe { std::mem::zeroed() };
+        operation.wFunc = FO_DELETE as u32;
+        operation.pFrom = from.as_ptr();
+        operation.fFlags = FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI;
+        let result = unsafe { SHFileOperationW(&mut operation) };
+        if result != 0 || operation.fAnyOperationsAborted != 0 {
+            return Err(Error::Other(format!("Error moving {} to the Recycle Bin (code {:#x})", path.display(), result)));
+        }
+        Ok(())
+    }
+
+    pub fn empty() -> Result<(), String> {
+        let flags = SHERB_NOCONFIRMATION | SHERB_NOPROGRESSUI | SHERB_NOSOUND;
+        let result = unsafe { SHEmptyRecycleBinW(ptr::null_mut(), ptr::null(), flags) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(format!("Error emptying the Recycle Bin: HRESULT {:#010x}", result))
+        }
+    }
+
+    /// The Recycle Bin's own Restore covers this.
+    pub fn trashed() -> Vec<TrashedItem> {
+        Vec::new()
+    }
+
+    pub fn forget(_path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::*;
+
+    pub fn locations() -> Vec<TrashLocation> {
+        Vec::new()
+    }
+
+    pub fn move_to_trash(_path: &Path) -> Result<(), Error> {
+        Err("Moving to the trash is not supported on this platform".into())
+    }
+
+    pub fn empty() -> Result<(), String> {
+        Err("Emptying the trash is not supported on this platform".to_string())
+    }
+
+    pub fn trashed() -> Vec<TrashedItem> {
+        Vec::new()
+    }
+
+    pub fn forget(_path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+pub use platform::{empty, forget, locations, move_to_trash, trashed};
+
+/// Trash sizes per volume for the details panel, with an "Empty Trash" action.
+#[derive(Default)]
+pub struct TrashState {
+    job: Option<Job<(Vec<TrashLocation>, Vec<TrashedItem>)>>,
+    locations: Option<Vec<TrashLocation>>,
+    trashed: Vec<TrashedItem>,
+    confirm_empty: bool,
+    empty_job: Option<Job<audit::Audited<()>>>,
+    error: Option<String>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl TrashState {
+    pub fn invalidate(&mut self) {
+        self.locations = None;
+    }
+
+    /// Draws the trash sizes and returns the items the user chose to put back, as tasks for
+    /// the operations queue.
+    pub fn show(&mut self, ui: &mut egui::Ui, read_only: bool) -> Vec<(Action, PathBuf, bool, u64)> {
+        let mut restored = Vec::new();
+        if self.locations.is_none() && self.job.is_none() && self.empty_job.is_none() {
+            self.job = Some(Job::spawn(|| (locations(), trashed())));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some((locations, trashed)) => {
+                    self.locations = Some(locations);
+                    self.trashed = trashed;
+                    self.job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+        if let Some(job) = &self.empty_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.error = result.err();
+                    self.empty_job = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+
+        ui.separator();
+        ui.label(tr("Trash:"));
+        let Some(locations) = &self.locations else {
+            ui.spinner();
+            return restored;
+        };
+        if let Some(error) = &self.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+        for location in locations {
+            ui.label(trf(
+                "{} on {} ({} items)",
+                &[
+                    &i18n::format_bytes(location.size),
+                    &paths::display_path(&location.volume),
+                    &i18n::format_count(location.items),
+                ],
+            ));
+        }
+        let size: u64 = locations.iter().map(|location| location.size).sum();
+        let items: u64 = locations.iter().map(|location| location.items).sum();
+        if items == 0 {
+            ui.label(RichText::new(tr("The trash is empty.")).color(Color32::GRAY));
+            return restored;
+        }
+        ui.label(
+            RichText::new(tr("Files in the trash still use disk space until it is emptied."))
+                .small()
+                .color(Color32::GRAY),
+        );
+        if !self.trashed.is_empty() {
+            egui::CollapsingHeader::new(trf(
+                "Put back ({})",
+                &[&i18n::format_count(self.trashed.len() as u64)],
+            ))
+            .id_source("trashed_items")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for item in self.trashed.iter().take(MAX_LISTED) {
+                        ui.horizontal(|ui| {
+                            let button = ui
+                                .add_enabled(!read_only, egui::Button::new(tr("Restore")).small())
+                                .on_disabled_hover_text(tr("Disabled in read-only mode"));
+                            if button.clicked() {
+                                let action = Action::Restore { target: item.original.clone(), replace: false };
+                                restored.push((action, item.path.clone(), item.is_dir, item.size));
+                            }
+                            ui.label(format!(
+                                "{} ({})",
+                                paths::display_path(&item.original),
+                                i18n::format_bytes(item.size)
+                            ));
+                        });
+                    }
+                });
+            });
+        }
+
+        if !self.confirm_empty {
+            let button = ui
+                .add_enabled(!read_only && self.empty_job.is_none(), egui::Button::new(tr("Empty Trash")))
+                .on_disabled_hover_text(tr("Disabled in read-only mode"));
+            if button.clicked() {
+                self.confirm_empty = true;
+            }
+            return restored;
+        }
+        ui.label(RichText::new(trf(
+            "Permanently delete {} items ({}) from the trash?",
+            &[&i18n::format_count(items), &i18n::format_bytes(size)],
+        )).color(Color32::YELLOW));
+        ui.horizontal(|ui| {
+            if ui.button(tr("Empty Trash")).clicked() {
+                self.confirm_empty = false;
+                self.error = None;
+                self.locations = None;
+                self.empty_job = Some(Job::spawn(move || {
+                    let result = empty();
+                    let audited = audit::record(audit::CLEAN, "Trash", Some(size), result.as_ref().err());
+                    (result, audited)
+                }));
+            }
+            if ui.button(tr("Cancel")).clicked() {
+                self.confirm_empty = false;
+            }
+        });
+        restored
+    }
+}
@@ -0,0 +1,111 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    a11y,
+    i18n::{self, tr, trf},
+    filter::SizeFilter,
+    index::ScanIndex,
+    paths,
+    quotas::QuotaStore,
+};
+
+const INDENT: f32 = 16.0;
+
+struct TreeView<'a> {
+    index: &'a ScanIndex,
+    expanded: &'a mut HashSet<PathBuf>,
+    current: Option<&'a Path>,
+    filter: SizeFilter,
+    quotas: &'a QuotaStore,
+    selected: Option<PathBuf>,
+}
+
+/// Draws the scanned hierarchy; only expanded nodes have their children laid out.
+/// Returns the directory the user picked, if any.
+pub fn render(
+    ui: &mut egui::Ui,
+    index: &ScanIndex,
+    expanded: &mut HashSet<PathBuf>,
+    current: Option<&Path>,
+    filter: SizeFilter,
+    quotas: &QuotaStore,
+) -> Option<PathBuf> {
+    let mut view = TreeView { index, expanded, current, filter, quotas, selected: None };
+    egui::ScrollArea::both()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            if !index.nodes.is_empty() {
+                view.render_node(ui, 0, 0);
+            }
+        });
+    view.selected
+}
+
+/// Describes the files low-memory mode counted into a folder without indexing them.
+pub fn small_files_label(count: u64, size: u64) -> String {
+    format!(
+        "📦 {}",
+        trf("{} small files - {}", &[&i18n::format_count(count), &i18n::format_bytes(size)])
+    )
+}
+
+impl TreeView<'_> {
+    fn render_node(&mut self, ui: &mut egui::Ui, id: usize, depth: usize) {
+        let node = &self.index.nodes[id];
+        let is_open = self.expanded.contains(&node.path);
+        ui.horizontal(|ui| {
+            ui.add_space(depth as f32 * INDENT);
+            if node.is_dir && !node.children.is_empty() {
+                let (icon, label) = if is_open { ("⏷", tr("Collapse")) } else { ("⏵", tr("Expand")) };
+                if a11y::labeled(ui.small_button(icon), label).clicked() {
+                    if is_open {
+                        self.expanded.remove(&node.path);
+                    } else {
+                        self.expanded.insert(node.path.clone());
+                    }
+                }
+            } else {
+                ui.add_space(INDENT + ui.spacing().button_padding.x);
+            }
+
+            let icon = if node.is_dir { "📁" } else { "📄" };
+            let text = format!("{} {} - {}", icon, paths::display_name(&node.name), i18n::format_bytes(node.size));
+            if node.is_dir {
+                let color = if self.quotas.over_budget(&node.path, node.size) {
+                    Color32::RED
+                } else {
+                    Color32::LIGHT_BLUE
+                };
+                let text = RichText::new(text).color(color);
+                if ui.selectable_label(self.current == Some(node.path.as_path()), text).clicked() {
+                    self.selected = Some(node.path.clone());
+                }
+            } else {
+                ui.label(text);
+            }
+            if node.unreachable {
+                ui.label(RichText::new("⚠").color(Color32::RED))
+                    .on_hover_text(tr("Unreachable or timed out; size is incomplete"));
+            }
+        });
+
+        if is_open {
+            for &child in &node.children {
+                let node = &self.index.nodes[child];
+                if !self.filter.hides(node.is_dir, node.size, || self.index.own_size(child)) {
+                    self.render_node(ui, child, depth + 1);
+                }
+            }
+            if node.small_files > 0 {
+                ui.horizontal(|ui| {
+                    ui.add_space((depth + 1) as f32 * INDENT + INDENT + ui.spacing().button_padding.x);
+                    ui.label(RichText::new(small_files_label(node.small_files, node.small_size)).color(Color32::GRAY));
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use eframe::egui::{self, Color32, ColorImage, Rect, Sense, Stroke, TextureHandle};
+use std::path::PathBuf;
+
+use crate::{
+    category, icons,
+    i18n::{self, tr, trf},
+    index::ScanIndex,
+    paths, summary,
+};
+
+/// Entries that would get fewer pixels than this are merged into one block per folder.
+const MIN_AREA: f32 = 4.0;
+/// Gap around a folder's contents, in pixels, so folders read as frames.
+const PADDING: f32 = 1.0;
+/// Side of the hit-testing grid's cells, in pixels.
+const CELL: usize = 16;
+
+/// One rectangle of the map, in pixels of the cached texture.
+struct Tile {
+    node: usize,
+    rect: Rect,
+    depth: usize,
+    /// Count and size of the entries merged into this block; `node` is then their folder.
+    other: Option<(u64, u64)>,
+}
+
+/// The map for one folder at one size, drawn once into a texture. Each frame then paints a
+/// single image, however many entries it shows.
+struct Cache {
+    focus: usize,
+    size: [usize; 2],
+    texture: TextureHandle,
+    tiles: Vec<Tile>,
+    /// Tiles overlapping each grid cell, so hovering doesn't look through all of them.
+    grid: Vec<Vec<u32>>,
+    columns: usize,
+}
+
+impl Cache {
+    /// The innermost tile under `pixel`.
+    fn tile_at(&self, pixel: egui::Pos2) -> Option<&Tile> {
+        if pixel.x < 0.0 || pixel.y < 0.0 {
+            return None;
+        }
+        let (column, row) = (pixel.x as usize / CELL, pixel.y as usize / CELL);
+        if column >= self.columns {
+            return None;
+        }
+        self.grid
+            .get(row * self.columns + column)?
+            .iter()
+            .map(|&tile| &self.tiles[tile as usize])
+            .filter(|tile| tile.rect.contains(pixel))
+            .max_by_key(|tile| tile.depth)
+    }
+}
+
+/// Sizes as nested rectangles, each with an area in proportion to its size.
+#[derive(Default)]
+pub struct Treemap {
+    /// Kept as a path so the zoom survives rescans; the root when unset or gone.
+    focus: Option<PathBuf>,
+    cache: Option<Cache>,
+    /// The size the panel had last frame; a new size is only drawn once it stops changing.
+    last_size: [usize; 2],
+}
+
+impl Treemap {
+    /// Drops the drawn map after the index changes.
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+
+    /// Returns a folder to show in the file list.
+    pub fn show(&mut self, ui: &mut egui::Ui, index: &ScanIndex) -> Option<PathBuf> {
+        let focus = self.focus.as_deref().and_then(|path| index.find_dir(path)).unwrap_or(0);
+        let mut open = None;
+        ui.horizontal(|ui| {
+            let parent = index.nodes[focus].parent;
+            if ui.add_enabled(parent.is_some(), egui::Button::new(tr("⬆ Zoom out"))).clicked() {
+                self.zoom(index, parent);
+            }
+            if ui.add_enabled(focus != 0, egui::Button::new(tr("Whole tree"))).clicked() {
+                self.focus = None;
+            }
+            if ui.button(tr("Show in file list")).clicked() {
+                open = Some(index.nodes[focus].path.clone());
+            }
+            ui.label(format!(
+                "{} ({})",
+                index.nodes[focus].path.display(),
+                i18n::format_bytes(index.nodes[focus].size)
+            ));
+        });
+        if index.nodes[focus].size == 0 {
+            ui.label(egui::RichText::new(tr("Nothing to show in an empty folder")).color(Color32::GRAY));
+            return open;
+        }
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click());
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let size = [
+            (rect.width() * pixels_per_point).round() as usize,
+            (rect.height() * pixels_per_point).round() as usize,
+        ];
+        if size[0] == 0 || size[1] == 0 {
+            return open;
+        }
+        // While the panel is being resized the old map is stretched to fit, rather than
+        // redrawn every frame.
+        let stale = self.cache.as_ref().is_none_or(|cache| cache.focus != focus || cache.size != size);
+        let resizing = self.cache.as_ref().is_some_and(|cache| cache.focus == focus) && size != self.last_size;
+        self.last_size = size;
+        if stale && !resizing {
+            self.cache = Some(build(ui.ctx(), index, focus, size));
+        } else if stale {
+            ui.ctx().request_repaint();
+        }
+        let Some(cache) = &self.cache else {
+            return open;
+        };
+        let painter = ui.painter_at(rect);
+        let uv = Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        painter.image(cache.texture.id(), rect, uv, Color32::WHITE);
+
+        let scale = egui::vec2(cache.size[0] as f32 / rect.width(), cache.size[1] as f32 / rect.height());
+        let to_pixel = |pos: egui::Pos2| ((pos - rect.min) * scale).to_pos2();
+        let to_screen = |pixels: Rect| {
+            Rect::from_min_max(rect.min + pixels.min.to_vec2() / scale, rect.min + pixels.max.to_vec2() / scale)
+        };
+        let hovered = response.hover_pos().and_then(|pos| cache.tile_at(to_pixel(pos)));
+        if let Some(tile) = hovered {
+            painter.rect_stroke(to_screen(tile.rect), 0.0, Stroke::new(1.5, Color32::WHITE));
+        }
+
+        let mut zoom = None;
+        if response.secondary_clicked() {
+            zoom = Some(index.nodes[focus].parent);
+        } else if response.clicked() {
+            if let Some(tile) = hovered {
+                // Files and merged blocks zoom to the folder holding them.
+                let node = &index.nodes[tile.node];
+                let folder = if node.is_dir { tile.node } else { node.parent.unwrap_or(0) };
+                if folder != focus {
+                    zoom = Some(Some(folder));
+                }
+            }
+        }
+        if let Some(tile) = hovered {
+            let total = index.nodes[focus].size;
+            response.on_hover_ui_at_pointer(|ui| {
+                let node = &index.nodes[tile.node];
+                match tile.other {
+                    Some((count, size)) => {
+                        let title = trf("{} smaller entries", &[&i18n::format_count(count)]);
+                        ui.label(egui::RichText::new(title).strong());
+                        ui.label(trf(
+                            "{}, {} of {}",
+                            &[
+                                &i18n::format_bytes(size),
+                                &percent(size, total),
+                                &paths::display_name(&index.nodes[focus].name),
+                            ],
+                        ));
+                        ui.label(
+                            egui::RichText::new(trf("in {}", &[&paths::display_name(&node.name)]))
+                                .small()
+                                .color(Color32::GRAY),
+                        );
+                    }
+                    None => {
+                        ui.label(egui::RichText::new(paths::display_name(&node.name)).strong());
+                        ui.label(trf(
+                            "{}, {} of {}",
+                            &[
+                                &i18n::format_bytes(node.size),
+                                &percent(node.size, total),
+                                &paths::display_name(&index.nodes[focus].name),
+                            ],
+                        ));
+                        if node.is_dir {
+                            ui.separator();
+                            summary::show_breakdown(ui, index, tile.node);
+                        }
+                    }
+                }
+            });
+        }
+        if let Some(id) = zoom {
+            self.zoom(index, id);
+        }
+        open
+    }
+
+    fn zoom(&mut self, index: &ScanIndex, id: Option<usize>) {
+        self.focus = id.filter(|&id| id != 0).map(|id| index.nodes[id].path.clone());
+    }
+}
+
+fn build(ctx: &egui::Context, index: &ScanIndex, focus: usize, size: [usize; 2]) -> Cache {
+    let area = Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(size[0] as f32, size[1] as f32));
+    let tiles = layout(index, focus, area);
+    let mut image = ColorImage::new(size, Color32::from_gray(30));
+    // Tiles come parent first, so each one is drawn over the folder holding it.
+    for tile in &tiles {
+        fill(&mut image, tile.rect, color(index, tile));
+        let (width, height) = (tile.rect.width(), tile.rect.height());
+        if !index.nodes[tile.node].is_dir && width >= 3.0 && height >= 3.0 {
+            let shade = color(index, tile).linear_multiply(0.7);
+            let (min, max) = (tile.rect.min, tile.rect.max);
+            fill(&mut image, Rect::from_min_max(egui::pos2(max.x - 1.0, min.y), max), shade);
+            fill(&mut image, Rect::from_min_max(egui::pos2(min.x, max.y - 1.0), max), shade);
+        }
+    }
+    let texture = ctx.load_texture("treemap", image, egui::TextureOptions::NEAREST);
+
+    let columns = size[0].div_ceil(CELL);
+    let mut grid = vec![Vec::new(); columns * size[1].div_ceil(CELL)];
+    for (id, tile) in tiles.iter().enumerate() {
+        let (left, top) = (tile.rect.min.x as usize / CELL, tile.rect.min.y as usize / CELL);
+        let right = ((tile.rect.max.x as usize).saturating_sub(1) / CELL).min(columns - 1);
+        let bottom = (tile.rect.max.y as usize).saturating_sub(1) / CELL;
+        for row in top..=bottom {
+            for column in left..=right {
+                if let Some(cell) = grid.get_mut(row * columns + column) {
+                    cell.push(id as u32);
+                }
+            }
+        }
+    }
+    Cache { focus, size, texture, tiles, grid, columns }
+}
+
+/// Lays out `focus` over `area` with each folder's entries inside it. Entries too small to
+/// see, and the files a scan only counted, become one block at the end of their folder.
+fn layout(index: &ScanIndex, focus: usize, area: Rect) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    let mut pending = vec![(focus, area, 0)];
+    while let Some((id, rect, depth)) = pending.pop() {
+        tiles.push(Tile { node: id, rect, depth, other: None });
+        let node = &index.nodes[id];
+        let inner = if depth == 0 { rect } else { rect.shrink(PADDING) };
+        if !node.is_dir || node.size == 0 || inner.width() < 2.0 || inner.height() < 2.0 {
+            continue;
+        }
+        let scale = inner.area() / node.size as f32;
+        let mut entries: Vec<(Option<usize>, u64)> = Vec::new();
+        let (mut other_count, mut other_size) = (node.small_files, node.small_size);
+        let mut merging = false;
+        // Children are sorted largest first, so everything after the first tiny one is tiny.
+        for &child in &node.children {
+            let size = index.nodes[child].size;
+            if size == 0 {
+                continue;
+            }
+            merging |= size as f32 * scale < MIN_AREA;
+            if merging {
+                other_count += 1;
+                other_size += size;
+            } else {
+                entries.push((Some(child), size));
+            }
+        }
+        if other_size > 0 {
+            entries.push((None, other_size));
+        }
+        let sizes: Vec<u64> = entries.iter().map(|&(_, size)| size).collect();
+        for ((entry, _), rect) in entries.into_iter().zip(squarify(&sizes, inner)) {
+            match entry {
+                Some(child) => pending.push((child, rect, depth + 1)),
+                None => tiles.push(Tile { node: id, rect, depth: depth + 1, other: Some((other_count, other_size)) }),
+            }
+        }
+    }
+    tiles
+}
+
+/// Splits `rect` among `sizes`, largest first, in rows along its shorter side, starting a
+/// new row whenever adding to the current one would make its rectangles less square.
+fn squarify(sizes: &[u64], rect: Rect) -> Vec<Rect> {
+    let mut rects = Vec::with_capacity(sizes.len());
+    let mut rest = rect;
+    let mut remaining: f64 = sizes.iter().sum::<u64>() as f64;
+    let mut start = 0;
+    while start < sizes.len() && remaining > 0.0 {
+        let scale = rest.area() as f64 / remaining;
+        let side = rest.width().min(rest.height()) as f64;
+        if side <= 0.0 {
+            break;
+        }
+        let worst = |largest: u64, smallest: u64, sum: f64| {
+            let (row, side) = (sum * scale, side * side);
+            f64::max(side * largest as f64 * scale / (row * row), row * row / (side * smallest as f64 * scale))
+        };
+        let mut end = start + 1;
+        let mut sum = sizes[start] as f64;
+        let mut ratio = worst(sizes[start], sizes[start], sum);
+        while end < sizes.len() {
+            let next = worst(sizes[start], sizes[end], sum + sizes[end] as f64);
+            if next > ratio {
+                break;
+            }
+            ratio = next;
+            sum += sizes[end] as f64;
+            end += 1;
+        }
+        let thickness = (sum * scale / side) as f32;
+        let mut offset = 0.0;
+        for &size in &sizes[start..end] {
+            let length = (size as f64 * scale / thickness as f64) as f32;
+            rects.push(if rest.width() >= rest.height() {
+                Rect::from_min_size(egui::pos2(rest.left(), rest.top() + offset), egui::vec2(thickness, length))
+            } else {
+                Rect::from_min_size(egui::pos2(rest.left() + offset, rest.top()), egui::vec2(length, thickness))
+            });
+            offset += length;
+        }
+        if rest.width() >= rest.height() {
+            rest.min.x += thickness;
+        } else {
+            rest.min.y += thickness;
+        }
+        remaining -= sum;
+        start = end;
+    }
+    rects
+}
+
+/// Folders darken with depth so their frames stay visible; files take their category's
+/// colour from the extension alone, since sniffing every file would stall redraws.
+fn color(index: &ScanIndex, tile: &Tile) -> Color32 {
+    let node = &index.nodes[tile.node];
+    if tile.other.is_some() {
+        Color32::from_gray(90)
+    } else if node.unreachable || node.online_only {
+        Color32::from_gray(120)
+    } else if node.is_dir {
+        Color32::from_gray(70 - tile.depth.min(6) as u8 * 6)
+    } else {
+        icons::color(category::classify(&node.path))
+    }
+}
+
+fn fill(image: &mut ColorImage, rect: Rect, color: Color32) {
+    let [width, height] = image.size;
+    let (left, right) = ((rect.min.x.round() as usize).min(width), (rect.max.x.round() as usize).min(width));
+    let (top, bottom) = ((rect.min.y.round() as usize).min(height), (rect.max.y.round() as usize).min(height));
+    for row in top..bottom {
+        image.pixels[row * width + left..row * width + right].fill(color);
+    }
+}
+
+fn percent(part: u64, total: u64) -> String {
+    format!("{:.1}%", part as f64 * 100.0 / total.max(1) as f64)
+}
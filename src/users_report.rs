@@ -0,0 +1,264 @@
+use eframe::egui;
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    dialogs,
+    i18n::{self, tr},
+    job::Job,
+    owner, scan,
+};
+
+#[derive(Clone)]
+pub struct UserUsage {
+    pub user: String,
+    pub home: Option<PathBuf>,
+    pub home_size: u64,
+    pub owned_size: u64,
+    pub owned_files: u64,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortColumn {
+    User,
+    HomeSize,
+    OwnedSize,
+    OwnedFiles,
+}
+
+pub fn default_root() -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(r"C:\Users")
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from("/Users")
+    } else {
+        PathBuf::from("/home")
+    }
+}
+
+impl UserUsage {
+    fn new(user: String) -> Self {
+        Self {
+            user,
+            home: None,
+            home_size: 0,
+            owned_size: 0,
+            owned_files: 0,
+        }
+    }
+}
+
+fn home_user(name: &str, metadata: &fs::Metadata) -> String {
+    match owner::owner_id(metadata) {
+        Some(uid) => owner::display_owner(Some(uid)),
+        None => name.to_string(),
+    }
+}
+
+/// Attributes every file under `root` both to the home directory it lives in and to the
+/// account that owns it, so data dropped into someone else's home still shows up.
+pub fn compute(root: &Path) -> Vec<UserUsage> {
+    let mut users: HashMap<String, UserUsage> = HashMap::new();
+    let mut homes: HashMap<OsString, String> = HashMap::new();
+
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_dir() {
+                continue;
+            }
+            let user = home_user(&entry.file_name().to_string_lossy(), &metadata);
+            users.entry(user.clone()).or_insert_with(|| UserUsage::new(user.clone())).home = Some(entry.path());
+            homes.insert(entry.file_name(), user);
+        }
+    }
+
+    for entry in WalkDir::new(root).follow_links(false).into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let size = scan::file_size(entry.path(), &metadata);
+        let home = entry
+            .path()
+            .strip_prefix(root)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .and_then(|first| homes.get(first.as_os_str()));
+        if let Some(home) = home {
+            if let Some(usage) = users.get_mut(home) {
+                usage.home_size += size;
+            }
+        }
+
+        let owner = match owner::owner_id(&metadata) {
+            Some(uid) => owner::display_owner(Some(uid)),
+            None => match home {
+                Some(home) => home.clone(),
+                None => continue,
+            },
+        };
+        let usage = users.entry(owner.clone()).or_insert_with(|| UserUsage::new(owner));
+        usage.owned_size += size;
+        usage.owned_files += 1;
+    }
+
+    users.into_values().collect()
+}
+
+pub fn to_csv(users: &[UserUsage]) -> String {
+    let mut csv = String::from("user,home,home_bytes,owned_bytes,owned_files\n");
+    for usage in users {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&usage.user),
+            csv_field(&usage.home.as_ref().map(|h| h.display().to_string()).unwrap_or_default()),
+            usage.home_size,
+            usage.owned_size,
+            usage.owned_files
+        ));
+    }
+    csv
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub struct UsersReportState {
+    pub open: bool,
+    root: String,
+    job: Option<Job<Vec<UserUsage>>>,
+    users: Vec<UserUsage>,
+    sort: SortColumn,
+    descending: bool,
+    message: Option<String>,
+}
+
+impl Default for UsersReportState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            root: default_root().display().to_string(),
+            job: None,
+            users: Vec::new(),
+            sort: SortColumn::OwnedSize,
+            descending: true,
+            message: None,
+        }
+    }
+}
+
+impl UsersReportState {
+    fn sort(&mut self) {
+        let sort = self.sort;
+        self.users.sort_by(|a, b| match sort {
+            SortColumn::User => a.user.to_lowercase().cmp(&b.user.to_lowercase()),
+            SortColumn::HomeSize => a.home_size.cmp(&b.home_size),
+            SortColumn::OwnedSize => a.owned_size.cmp(&b.owned_size),
+            SortColumn::OwnedFiles => a.owned_files.cmp(&b.owned_files),
+        });
+        if self.descending {
+            self.users.reverse();
+        }
+    }
+
+    fn header(&mut self, ui: &mut egui::Ui, label: &str, column: SortColumn) {
+        let arrow = match (self.sort == column, self.descending) {
+            (true, true) => " ⏷",
+            (true, false) => " ⏶",
+            _ => "",
+        };
+        if ui.button(format!("{}{}", label, arrow)).clicked() {
+            if self.sort == column {
+                self.descending = !self.descending;
+            } else {
+                self.sort = column;
+                self.descending = column != SortColumn::User;
+            }
+            self.sort();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(users) => {
+                    self.users = users;
+                    self.job = None;
+                    self.sort();
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Usage by User"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(650.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.root);
+                    if ui.button(tr("Browse")).clicked() {
+                        if let Some(path) = dialogs::pick_folder() {
+                            self.root = path.display().to_string();
+                        }
+                    }
+                    if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Run Report"))).clicked() {
+                        let root = PathBuf::from(&self.root);
+                        self.job = Some(Job::spawn(move || compute(&root)));
+                    }
+                    if ui.add_enabled(!self.users.is_empty(), egui::Button::new(tr("Export CSV"))).clicked() {
+                        if let Some(path) = dialogs::save_file("users.csv") {
+                            self.message = Some(match fs::write(&path, to_csv(&self.users)) {
+                                Ok(()) => path.display().to_string(),
+                                Err(e) => format!("Error writing {}: {}", path.display(), e),
+                            });
+                        }
+                    }
+                });
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+                if self.job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("users_report").striped(true).show(ui, |ui| {
+                        self.header(ui, tr("User"), SortColumn::User);
+                        ui.label(tr("Home"));
+                        self.header(ui, tr("Home size"), SortColumn::HomeSize);
+                        self.header(ui, tr("Owned"), SortColumn::OwnedSize);
+                        self.header(ui, tr("Files"), SortColumn::OwnedFiles);
+                        ui.end_row();
+
+                        for usage in &self.users {
+                            ui.label(&usage.user);
+                            ui.label(usage.home.as_ref().map(|h| h.display().to_string()).unwrap_or_default());
+                            ui.label(i18n::format_bytes(usage.home_size));
+                            ui.label(i18n::format_bytes(usage.owned_size));
+                            ui.label(i18n::format_count(usage.owned_files));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+    }
+}
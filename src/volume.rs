@@ -0,0 +1,275 @@
+use std::path::Path;
+
+pub const PURGEABLE_NOTE: &str = "Finder counts purgeable space (local Time Machine snapshots, \
+optimized iCloud files and system caches) as available, so it reports more free space than the \
+volume itself. Cloned files share their data, so deleting one frees only its private part.";
+
+pub struct VolumeInfo {
+    pub total: u64,
+    pub available: u64,
+    pub fs_type: Option<String>,
+    pub inodes: Option<Inodes>,
+}
+
+#[derive(Clone, Copy)]
+pub struct Inodes {
+    pub total: u64,
+    pub free: u64,
+}
+
+impl Inodes {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    pub fn fraction_used(&self) -> f32 {
+        self.used() as f32 / self.total.max(1) as f32
+    }
+}
+
+#[cfg(unix)]
+fn statvfs(path: &Path) -> Option<libc::statvfs> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat)
+}
+
+// The statvfs field types differ between platforms, so the casts aren't always no-ops.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn space(path: &Path) -> Option<(u64, u64)> {
+    let stat = statvfs(path)?;
+    let block = stat.f_frsize as u64;
+    Some((stat.f_blocks as u64 * block, stat.f_bavail as u64 * block))
+}
+
+/// Inode capacity of the filesystem holding `path`. Filesystems that allocate inodes
+/// dynamically (btrfs, ZFS) report zero and give None.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+pub fn inodes(path: &Path) -> Option<Inodes> {
+    let stat = statvfs(path)?;
+    (stat.f_files > 0).then_some(Inodes {
+        total: stat.f_files as u64,
+        free: stat.f_ffree as u64,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn inodes(_path: &Path) -> Option<Inodes> {
+    None
+}
+
+#[cfg(windows)]
+fn space(path: &Path) -> Option<(u64, u64)> {
+    use std::{os::windows::ffi::OsStrExt, ptr};
+    use winapi::um::{fileapi::GetDiskFreeSpaceExW, winnt::ULARGE_INTEGER};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let mut total: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, ptr::null_mut()) };
+    (ok != 0).then(|| unsafe { (*total.QuadPart(), *available.QuadPart()) })
+}
+
+pub fn info(path: &Path) -> Option<VolumeInfo> {
+    let (total, available) = space(path)?;
+    Some(VolumeInfo {
+        total,
+        available,
+        fs_type: crate::mounts::mount_of(path).map(|mount| mount.fs_type),
+        inodes: inodes(path),
+    })
+}
+
+pub struct CloneInfo {
+    pub private_size: u64,
+    pub may_share_blocks: bool,
+}
+
+/// Asks APFS how much of a file is private to it and whether it may share blocks with clones.
+#[cfg(target_os = "macos")]
+pub fn clone_info(path: &Path) -> Option<CloneInfo> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+    const EF_MAY_SHARE_BLOCKS: u64 = 0x1;
+
+    #[repr(C, packed(4))]
+    struct Reply {
+        length: u32,
+        private_size: libc::off_t,
+        ext_flags: u64,
+    }
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut request: libc::attrlist = unsafe { std::mem::zeroed() };
+    request.bitmapcount = libc::ATTR_BIT_MAP_COUNT;
+    request.forkattr = libc::ATTR_CMNEXT_PRIVATESIZE | libc::ATTR_CMNEXT_EXT_FLAGS;
+    let mut reply: Reply = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::getattrlist(
+            path.as_ptr(),
+            &mut request as *mut libc::attrlist as *mut libc::c_void,
+            &mut reply as *mut Reply as *mut libc::c_void,
+            std::mem::size_of::<Reply>(),
+            libc::FSOPT_NOFOLLOW | libc::FSOPT_ATTR_CMN_EXTENDED,
+        )
+    };
+    let (private_size, ext_flags) = (reply.private_size, reply.ext_flags);
+    (result == 0).then_some(CloneInfo {
+        private_size: private_size.max(0) as u64,
+        may_share_blocks: ext_flags & EF_MAY_SHARE_BLOCKS != 0,
+    })
+}
+
+/// Asks for the file's extent map and counts the extents not shared with other files, such as
+/// reflink copies and deduplicated data on btrfs and XFS.
+#[cfg(target_os = "linux")]
+pub fn clone_info(path: &Path) -> Option<CloneInfo> {
+    use std::os::unix::io::AsRawFd;
+    const FS_IOC_FIEMAP: u32 = 0xC020_660B;
+    const FIEMAP_EXTENT_LAST: u32 = 0x1;
+    const FIEMAP_EXTENT_SHARED: u32 = 0x2000;
+    const EXTENTS: usize = 64;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Request {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; EXTENTS],
+    }
+
+    let file = std::fs::File::open(path).ok()?;
+    let (mut private_size, mut may_share_blocks) = (0, false);
+    let mut start = 0;
+    loop {
+        let mut request = Request {
+            start,
+            length: u64::MAX - start,
+            flags: 0,
+            mapped_extents: 0,
+            extent_count: EXTENTS as u32,
+            reserved: 0,
+            extents: [Extent::default(); EXTENTS],
+        };
+        if unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP as _, &mut request as *mut Request) } != 0 {
+            return None;
+        }
+        let mapped = &request.extents[..(request.mapped_extents as usize).min(EXTENTS)];
+        for extent in mapped {
+            if extent.flags & FIEMAP_EXTENT_SHARED != 0 {
+                may_share_blocks = true;
+            } else {
+                private_size += extent.length;
+            }
+        }
+        match mapped.last() {
+            Some(last) if last.flags & FIEMAP_EXTENT_LAST == 0 => start = last.logical + last.length,
+            _ => break,
+        }
+    }
+    Some(CloneInfo { private_size, may_share_blocks })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn clone_info(_path: &Path) -> Option<CloneInfo> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub fn local_snapshots() -> Vec<String> {
+    std::process::Command::new("tmutil")
+        .args(["listlocalsnapshots", "/"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| line.starts_with("com.apple."))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn local_snapshots() -> Vec<String> {
+    Vec::new()
+}
+
+/// Space macOS can free on its own when an app needs it: what it offers for important
+/// use minus what the volume has free.
+#[cfg(target_os = "macos")]
+pub fn purgeable(path: &Path, available: u64) -> Option<u64> {
+    use objc::{class, msg_send, rc::autoreleasepool, runtime::Object, sel, sel_impl};
+    use std::{ffi::CString, os::unix::ffi::OsStrExt, ptr};
+
+    #[link(name = "Foundation", kind = "framework")]
+    extern "C" {}
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let key = CString::new("NSURLVolumeAvailableCapacityForImportantUsageKey").ok()?;
+    autoreleasepool(|| unsafe {
+        let path: *mut Object = msg_send![class!(NSString), stringWithUTF8String: path.as_ptr()];
+        let url: *mut Object = msg_send![class!(NSURL), fileURLWithPath: path];
+        let key: *mut Object = msg_send![class!(NSString), stringWithUTF8String: key.as_ptr()];
+        let mut value: *mut Object = ptr::null_mut();
+        let error: *mut *mut Object = ptr::null_mut();
+        let found: objc::runtime::BOOL = msg_send![url, getResourceValue: &mut value forKey: key error: error];
+        if found == objc::runtime::NO || value.is_null() {
+            return None;
+        }
+        let important: i64 = msg_send![value, longLongValue];
+        Some((important.max(0) as u64).saturating_sub(available))
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn purgeable(_path: &Path, _available: u64) -> Option<u64> {
+    None
+}
+
+/// Volume and clone figures for the details dialog, gathered once per listing on a worker.
+pub struct VolumeDetails {
+    pub volume: Option<VolumeInfo>,
+    pub purgeable: Option<u64>,
+    pub cloned_files: u64,
+    pub shared_bytes: u64,
+    pub local_snapshots: usize,
+}
+
+pub fn details<'a>(dir: &Path, files: impl Iterator<Item = (&'a Path, u64)>) -> VolumeDetails {
+    let volume = info(dir);
+    let mut details = VolumeDetails {
+        purgeable: volume.as_ref().and_then(|volume| purgeable(dir, volume.available)),
+        volume,
+        cloned_files: 0,
+        shared_bytes: 0,
+        local_snapshots: local_snapshots().len(),
+    };
+    for (path, size) in files {
+        if let Some(clone) = clone_info(path).filter(|clone| clone.may_share_blocks) {
+            details.cloned_files += 1;
+            details.shared_bytes += size.saturating_sub(clone.private_size);
+        }
+    }
+    details
+}
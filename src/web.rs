@@ -0,0 +1,372 @@
+use eframe::egui::{self, Color32, RichText};
+use js_sys::{Function, Promise, Reflect};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    backend::{Backend, Entry, Listing},
+    error::Error,
+    i18n::{self, tr, trf},
+    icicle::Icicle,
+    index::{Node, ScanIndex},
+    layout::Panel,
+    paths, scan,
+    treemap::Treemap,
+};
+
+const CANVAS_ID: &str = "disk_analyzer_canvas";
+
+/// A folder read through the File System Access API. Browsers only hand out file handles
+/// asynchronously, so the whole tree is read once up front and listed from memory.
+pub struct PickedFolder {
+    root: PathBuf,
+    children: HashMap<PathBuf, Vec<Entry>>,
+    /// The same tree for the charts shared with the desktop app.
+    index: ScanIndex,
+}
+
+fn node(path: &Path, is_dir: bool, size: u64, modified: Option<SystemTime>, parent: Option<usize>) -> Node {
+    Node {
+        path: path.to_path_buf(),
+        name: path.file_name().unwrap_or(path.as_os_str()).to_os_string(),
+        size,
+        logical: size,
+        entries: 0,
+        is_dir,
+        modified,
+        accessed: None,
+        unreachable: false,
+        online_only: false,
+        parent,
+        children: Vec::new(),
+        small_files: 0,
+        small_size: 0,
+    }
+}
+
+/// Arranges the picked folders and files into a scan index, parents before children.
+fn build_index(root: &Path, dirs: &[PathBuf], files: &[(PathBuf, u64, Option<SystemTime>)]) -> ScanIndex {
+    let mut dirs: Vec<&PathBuf> = dirs.iter().filter(|dir| dir.as_path() != root).collect();
+    dirs.sort_by_key(|dir| dir.components().count());
+    let mut nodes = vec![node(root, true, 0, None, None)];
+    let mut ids: HashMap<&Path, usize> = HashMap::from([(root, 0)]);
+    for dir in dirs {
+        let Some(&parent) = dir.parent().and_then(|parent| ids.get(parent)) else {
+            continue;
+        };
+        ids.insert(dir.as_path(), nodes.len());
+        nodes[parent].children.push(nodes.len());
+        nodes.push(node(dir, true, 0, None, Some(parent)));
+    }
+    for (path, size, modified) in files {
+        let Some(&parent) = path.parent().and_then(|parent| ids.get(parent)) else {
+            continue;
+        };
+        nodes[parent].children.push(nodes.len());
+        nodes.push(node(path, false, *size, *modified, Some(parent)));
+    }
+    ScanIndex::from_unfinished(nodes)
+}
+
+impl PickedFolder {
+    fn new(root: PathBuf, dirs: Vec<PathBuf>, files: Vec<(PathBuf, u64, Option<SystemTime>)>) -> Self {
+        let mut children: HashMap<PathBuf, Vec<Entry>> = HashMap::new();
+        let entry = |path: &Path, is_dir: bool, size: u64, modified: Option<SystemTime>| Entry {
+            path: path.to_path_buf(),
+            name: path.file_name().unwrap_or_default().to_os_string(),
+            is_dir,
+            size,
+            logical: size,
+            own_size: size,
+            entries: if is_dir { 0 } else { 1 },
+            modified,
+            accessed: None,
+            changed: false,
+            owner: None,
+            online_only: false,
+        };
+        let mut folders: HashMap<PathBuf, Entry> =
+            dirs.iter().map(|dir| (dir.clone(), entry(dir, true, 0, None))).collect();
+        for dir in &dirs {
+            for ancestor in dir.ancestors().skip(1) {
+                if let Some(folder) = folders.get_mut(ancestor) {
+                    folder.entries += 1;
+                }
+            }
+        }
+        for (path, size, modified) in &files {
+            if let Some(parent) = path.parent().and_then(|parent| folders.get_mut(parent)) {
+                parent.own_size += size;
+            }
+            for ancestor in path.ancestors().skip(1) {
+                if let Some(folder) = folders.get_mut(ancestor) {
+                    folder.size += size;
+                    folder.logical += size;
+                    folder.entries += 1;
+                }
+            }
+            if let Some(parent) = path.parent() {
+                children.entry(parent.to_path_buf()).or_default().push(entry(path, false, *size, *modified));
+            }
+        }
+        for dir in &dirs {
+            if let (Some(parent), Some(folder)) = (dir.parent(), folders.get(dir)) {
+                if dir != &root {
+                    children.entry(parent.to_path_buf()).or_default().push(folder.clone());
+                }
+            }
+        }
+        let index = build_index(&root, &dirs, &files);
+        Self { root, children, index }
+    }
+}
+
+impl Backend for PickedFolder {
+    fn list(&self, dir: &Path, options: &scan::ScanOptions, show_hidden: bool) -> Result<Listing, Error> {
+        let entries = self
+            .children
+            .get(dir)
+            .into_iter()
+            .flatten()
+            .filter(|entry| show_hidden || !paths::is_dotfile(&entry.name))
+            .filter(|entry| !options.excludes(entry.path.file_name().unwrap_or_default()))
+            .cloned()
+            .collect();
+        Ok(Listing { entries, ignored: scan::IgnoredTotal::default() })
+    }
+}
+
+fn js_error(value: JsValue) -> String {
+    let message = Reflect::get(&value, &"message".into()).ok().and_then(|message| message.as_string());
+    message.or_else(|| value.as_string()).unwrap_or_else(|| format!("{:?}", value))
+}
+
+fn property(target: &JsValue, name: &str) -> JsValue {
+    Reflect::get(target, &name.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Calls a method without arguments and waits for the promise it returns.
+async fn call_async(target: &JsValue, method: &str) -> Result<JsValue, String> {
+    let function: Function = property(target, method)
+        .dyn_into()
+        .map_err(|_| format!("Error reading folder: {} is not available", method))?;
+    let promise: Promise = function
+        .call0(target)
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| format!("Error reading folder: {} did not return a promise", method))?;
+    JsFuture::from(promise).await.map_err(js_error)
+}
+
+/// Asks the user for a folder and reads every file size below it.
+async fn pick_folder() -> Result<PickedFolder, String> {
+    let window = web_sys::window().ok_or("No browser window")?;
+    if property(&window, "showDirectoryPicker").is_undefined() {
+        return Err(tr("This browser can't open folders; try a Chromium-based browser such as Chrome or Edge").to_string());
+    }
+    let handle = call_async(&window, "showDirectoryPicker").await?;
+    let root = PathBuf::from(property(&handle, "name").as_string().unwrap_or_default());
+
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    let mut pending = vec![(root.clone(), handle)];
+    while let Some((dir, handle)) = pending.pop() {
+        let Ok(values) = property(&handle, "values").dyn_into::<Function>() else {
+            continue;
+        };
+        let iterator = values.call0(&handle).map_err(js_error)?;
+        loop {
+            let step = call_async(&iterator, "next").await?;
+            if property(&step, "done").as_bool().unwrap_or(true) {
+                break;
+            }
+            let child = property(&step, "value");
+            let path = dir.join(property(&child, "name").as_string().unwrap_or_default());
+            if property(&child, "kind").as_string().as_deref() == Some("directory") {
+                pending.push((path, child));
+                continue;
+            }
+            // Files the browser refuses to open (e.g. locked by another program) are skipped.
+            let Ok(file) = call_async(&child, "getFile").await else {
+                continue;
+            };
+            let size = property(&file, "size").as_f64().unwrap_or(0.0) as u64;
+            let modified = property(&file, "lastModified")
+                .as_f64()
+                .map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64));
+            files.push((path, size, modified));
+        }
+        dirs.push(dir);
+    }
+    Ok(PickedFolder::new(root, dirs, files))
+}
+
+type Pending = Rc<RefCell<Option<Result<PickedFolder, String>>>>;
+
+/// The browser version of the analyzer: browse a picked folder by size as a list, or in the
+/// treemap and icicle chart the desktop app draws. Deleting, the reports and everything else
+/// that needs the local filesystem stay desktop-only.
+struct WebApp {
+    folder: Option<PickedFolder>,
+    current: PathBuf,
+    show_hidden: bool,
+    pending: Option<Pending>,
+    error: Option<String>,
+    /// The list, treemap or icicle chart.
+    view: Panel,
+    treemap: Treemap,
+    icicle: Icicle,
+}
+
+impl Default for WebApp {
+    fn default() -> Self {
+        Self {
+            folder: None,
+            current: PathBuf::new(),
+            show_hidden: false,
+            pending: None,
+            error: None,
+            view: Panel::Files,
+            treemap: Treemap::default(),
+            icicle: Icicle::default(),
+        }
+    }
+}
+
+impl WebApp {
+    fn start_picking(&mut self, ctx: &egui::Context) {
+        let pending: Pending = Rc::default();
+        let result = Rc::clone(&pending);
+        let ctx = ctx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            *result.borrow_mut() = Some(pick_folder().await);
+            ctx.request_repaint();
+        });
+        self.pending = Some(pending);
+        self.error = None;
+    }
+
+    fn poll_picking(&mut self) {
+        let Some(result) = self.pending.as_ref().and_then(|pending| pending.borrow_mut().take()) else {
+            return;
+        };
+        self.pending = None;
+        match result {
+            Ok(folder) => {
+                self.current = folder.root.clone();
+                self.folder = Some(folder);
+                self.treemap = Treemap::default();
+                self.icicle = Icicle::default();
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn show_listing(&mut self, ui: &mut egui::Ui) {
+        let Some(folder) = &self.folder else {
+            ui.label(tr("Choose a folder to see what takes up its space. Nothing leaves your browser."));
+            return;
+        };
+        let mut listing = match folder.list(&self.current, &scan::ScanOptions::default(), self.show_hidden) {
+            Ok(listing) => listing,
+            Err(e) => {
+                ui.label(RichText::new(e.to_string()).color(Color32::RED));
+                return;
+            }
+        };
+        listing.entries.sort_by_key(|entry| std::cmp::Reverse(entry.size));
+        let total: u64 = listing.entries.iter().map(|entry| entry.size).sum();
+
+        let mut navigate = None;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.current != folder.root, egui::Button::new("⬆")).clicked() {
+                navigate = self.current.parent().map(Path::to_path_buf);
+            }
+            ui.label(RichText::new(self.current.display().to_string()).strong());
+            ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+        });
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("web_listing").striped(true).num_columns(3).show(ui, |ui| {
+                for entry in &listing.entries {
+                    if entry.is_dir {
+                        if ui.link(format!("📁 {}", paths::display_name(&entry.name))).clicked() {
+                            navigate = Some(entry.path.clone());
+                        }
+                    } else {
+                        ui.label(format!("📄 {}", paths::display_name(&entry.name)));
+                    }
+                    ui.label(i18n::format_bytes(entry.size));
+                    let fraction = if total == 0 { 0.0 } else { entry.size as f32 / total as f32 };
+                    ui.add(egui::ProgressBar::new(fraction).desired_width(160.0));
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(path) = navigate {
+            self.current = path;
+        }
+    }
+}
+
+impl eframe::App for WebApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_picking();
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(self.pending.is_none(), egui::Button::new(tr("Choose Folder..."))).clicked() {
+                    self.start_picking(ctx);
+                }
+                ui.checkbox(&mut self.show_hidden, tr("Show Hidden"));
+                if self.pending.is_some() {
+                    ui.spinner();
+                    ui.label(tr("Reading folder..."));
+                }
+            });
+            if let Some(error) = &self.error {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                for view in [Panel::Files, Panel::Treemap, Panel::Icicle] {
+                    ui.selectable_value(&mut self.view, view, tr(view.label()));
+                }
+            });
+            let opened = match (&self.folder, self.view) {
+                (Some(folder), Panel::Treemap) => self.treemap.show(ui, &folder.index),
+                (Some(folder), Panel::Icicle) => self.icicle.show(ui, &folder.index),
+                _ => {
+                    self.show_listing(ui);
+                    None
+                }
+            };
+            if let Some(path) = opened {
+                self.current = path;
+                self.view = Panel::Files;
+            }
+        });
+    }
+}
+
+/// Mounts the app on the page's canvas; see index.html.
+pub fn start() {
+    wasm_bindgen_futures::spawn_local(async {
+        let result = eframe::WebRunner::new()
+            .start(
+                CANVAS_ID,
+                eframe::WebOptions::default(),
+                Box::new(|_cc| Box::<WebApp>::default()),
+            )
+            .await;
+        if let Err(e) = result {
+            web_sys::console::error_1(&e);
+        }
+    });
+}
@@ -0,0 +1,315 @@
+use eframe::egui::{self, Color32, RichText};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    audit,
+    i18n::{self, tr, trf},
+    job::Job,
+    scan, trash,
+};
+
+#[derive(Clone, Copy)]
+pub enum Cleanup {
+    /// Removes what it can inside the folder; files other programs hold open are left alone.
+    TempContents,
+    EmptyRecycleBin,
+    /// A system tool that does the cleanup itself.
+    Command(&'static [&'static str]),
+}
+
+pub struct CleanupItem {
+    pub label: &'static str,
+    pub path: Option<PathBuf>,
+    pub size: u64,
+    pub cleanup: Cleanup,
+    pub needs_admin: bool,
+}
+
+impl CleanupItem {
+    pub fn command_line(&self) -> Option<String> {
+        match self.cleanup {
+            Cleanup::Command(command) => Some(command.join(" ")),
+            _ => None,
+        }
+    }
+}
+
+pub fn system_drive() -> PathBuf {
+    let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+    PathBuf::from(format!(r"{}\", drive))
+}
+
+pub fn windows_dir() -> PathBuf {
+    std::env::var_os("SystemRoot").map_or_else(|| system_drive().join("Windows"), PathBuf::from)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    scan::measure_dir(path, &Default::default()).size
+}
+
+pub fn scan_items() -> Vec<CleanupItem> {
+    let windows = windows_dir();
+    let temp = std::env::temp_dir();
+    let update_cache = windows.join(r"SoftwareDistribution\Download");
+    let delivery_optimization =
+        windows.join(r"ServiceProfiles\NetworkService\AppData\Local\Microsoft\Windows\DeliveryOptimization\Cache");
+    let windows_old = system_drive().join("Windows.old");
+    let hibernation = system_drive().join("hiberfil.sys");
+
+    let mut items = vec![
+        CleanupItem {
+            label: "Temporary files",
+            size: dir_size(&temp),
+            path: Some(temp),
+            cleanup: Cleanup::TempContents,
+            needs_admin: false,
+        },
+        CleanupItem {
+            label: "Recycle Bin",
+            path: None,
+            size: trash::locations().iter().map(|location| location.size).sum(),
+            cleanup: Cleanup::EmptyRecycleBin,
+            needs_admin: false,
+        },
+        CleanupItem {
+            label: "Windows Update cache",
+            size: dir_size(&update_cache),
+            path: Some(update_cache),
+            // Microsoft's documented reset: the service has to be stopped while its cache is removed.
+            cleanup: Cleanup::Command(&[
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                r"Stop-Service wuauserv; Remove-Item $env:SystemRoot\SoftwareDistribution\Download\* -Recurse -Force; Start-Service wuauserv",
+            ]),
+            needs_admin: true,
+        },
+        CleanupItem {
+            label: "Delivery Optimization files",
+            size: dir_size(&delivery_optimization),
+            path: Some(delivery_optimization),
+            cleanup: Cleanup::Command(&[
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                "Delete-DeliveryOptimizationCache -Force",
+            ]),
+            needs_admin: true,
+        },
+        CleanupItem {
+            label: "Previous Windows installation",
+            size: dir_size(&windows_old),
+            path: Some(windows_old),
+            // Disk Cleanup asks for elevation itself and is the supported way to drop Windows.old.
+            cleanup: Cleanup::Command(&["cleanmgr"]),
+            needs_admin: false,
+        },
+        CleanupItem {
+            label: "Hibernation file",
+            size: fs::metadata(&hibernation).map_or(0, |metadata| metadata.len()),
+            path: Some(hibernation),
+            cleanup: Cleanup::Command(&["powercfg", "/hibernate", "off"]),
+            needs_admin: true,
+        },
+    ];
+    items.retain(|item| item.size > 0);
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+/// Deletes each entry of the temp folder separately so files in use just get skipped.
+fn clean_temp(temp: &Path) -> Result<String, String> {
+    let entries = fs::read_dir(temp).map_err(|e| trf("Error reading {}: {}", &[&temp.display(), &e]))?;
+    let (mut removed, mut skipped) = (0u64, 0u64);
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let result = match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => fs::remove_dir_all(&path),
+            _ => fs::remove_file(&path),
+        };
+        match result {
+            Ok(()) => removed += 1,
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok(trf("Removed {} items from {} ({} in use were skipped)", &[&removed, &temp.display(), &skipped]))
+}
+
+fn run_command(command: &[&str]) -> Result<String, String> {
+    let (program, args) = command.split_first().ok_or(tr("Empty command"))?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| trf("Error running {}: {}", &[program, &e]))?;
+    if output.status.success() {
+        Ok(trf("Ran {}", &[&command.join(" ")]))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() { trf("{} failed with {}", &[program, &output.status]) } else { stderr })
+    }
+}
+
+fn run_cleanup(cleanup: Cleanup, path: Option<PathBuf>) -> Result<String, String> {
+    match cleanup {
+        Cleanup::TempContents => clean_temp(&path.ok_or(tr("No folder to clean"))?),
+        Cleanup::EmptyRecycleBin => trash::empty().map(|()| tr("Emptied the Recycle Bin").to_string()),
+        Cleanup::Command(command) => run_command(command),
+    }
+}
+
+#[derive(Default)]
+pub struct WindowsCleanupState {
+    pub open: bool,
+    job: Option<Job<Vec<CleanupItem>>>,
+    items: Option<Vec<CleanupItem>>,
+    clean_job: Option<Job<audit::Audited<String>>>,
+    message: Option<Result<String, String>>,
+    /// A cleanup waiting for the user to confirm it.
+    confirm: Option<(Cleanup, Option<PathBuf>, &'static str, u64)>,
+    /// Why the last finished run could not be written to the audit log.
+    pub audit_error: Option<String>,
+}
+
+impl WindowsCleanupState {
+    pub fn show(&mut self, ctx: &egui::Context, read_only: bool) {
+        if self.items.is_none() && self.job.is_none() {
+            self.job = Some(Job::spawn(scan_items));
+        }
+        if let Some(job) = &self.job {
+            match job.poll() {
+                Some(items) => {
+                    self.items = Some(items);
+                    self.job = None;
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+        if let Some(job) = &self.clean_job {
+            match job.poll() {
+                Some((result, audited)) => {
+                    self.audit_error = audited.err();
+                    self.message = Some(result);
+                    self.clean_job = None;
+                    self.job = Some(Job::spawn(scan_items));
+                }
+                None => ctx.request_repaint(),
+            }
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("Windows Cleanup"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                if self.job.is_some() || self.clean_job.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                match &self.message {
+                    Some(Ok(message)) => {
+                        ui.label(RichText::new(message).color(Color32::GREEN));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+                    None => {}
+                }
+                let Some(items) = &self.items else {
+                    return;
+                };
+                if items.is_empty() {
+                    ui.label(tr("Nothing to clean up."));
+                    return;
+                }
+
+                let total: u64 = items.iter().map(|item| item.size).sum();
+                ui.label(trf("Total: {}", &[&i18n::format_bytes(total)]));
+                let mut clean = None;
+                egui::Grid::new("windows_cleanup").striped(true).show(ui, |ui| {
+                    for item in items {
+                        let size = ui.label(RichText::new(tr(item.label)).strong());
+                        if let Some(path) = &item.path {
+                            size.on_hover_text(path.display().to_string());
+                        }
+                        ui.label(i18n::format_bytes(item.size));
+                        match item.command_line() {
+                            Some(command) => {
+                                ui.label(RichText::new(&command).monospace());
+                                if ui.button(tr("Copy")).clicked() {
+                                    ui.output_mut(|output| output.copied_text = command);
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                                ui.label("");
+                            }
+                        }
+                        let run = ui
+                            .add_enabled(!read_only && !item.needs_admin, egui::Button::new(tr("Clean")))
+                            .on_disabled_hover_text(if read_only {
+                                tr("Disabled in read-only mode")
+                            } else {
+                                tr("Needs administrator rights; run the command from an elevated prompt")
+                            });
+                        if run.clicked() {
+                            clean = Some((item.cleanup, item.path.clone(), item.label, item.size));
+                        }
+                        ui.end_row();
+                    }
+                });
+                if clean.is_some() {
+                    self.confirm = clean;
+                }
+            });
+        self.open = open;
+
+        if let Some((cleanup, path, label, size)) = self.confirm_cleanup(ctx) {
+            self.message = None;
+            self.clean_job = Some(Job::spawn(move || {
+                let result = run_cleanup(cleanup, path);
+                let audited = audit::record(audit::CLEAN, label, Some(size), result.as_ref().err());
+                (result, audited)
+            }));
+        }
+    }
+
+    /// Shows the pending cleanup until the user runs or drops it; returns it once confirmed.
+    fn confirm_cleanup(&mut self, ctx: &egui::Context) -> Option<(Cleanup, Option<PathBuf>, &'static str, u64)> {
+        let (_, path, label, size) = self.confirm.as_ref()?;
+        let mut choice = None;
+        egui::Window::new(tr("Clean Up"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(trf("Clean up {}?", &[&tr(label)]));
+                if let Some(path) = path {
+                    ui.label(RichText::new(path.display().to_string()).monospace());
+                }
+                ui.label(
+                    RichText::new(trf("This permanently removes about {}.", &[&i18n::format_bytes(*size)]))
+                        .color(Color32::YELLOW),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Clean")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => self.confirm.take(),
+            Some(false) => {
+                self.confirm = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    fs::{self, Metadata, OpenOptions},
+    io::{Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{error::Error, operations::Progress, paths};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+pub const SSD_WARNING: &str = "Overwriting is not guaranteed to destroy data on SSDs, \
+flash drives, copy-on-write filesystems (btrfs, ZFS, APFS) or files that have \
+snapshots or backups. Use full-disk encryption for those.";
+
+/// Overwrites every file under `path` with zeros, then removes it. Links are removed without
+/// touching what they point to, and files with other hard links are refused before anything
+/// is overwritten, since zeroing them would destroy the data behind the other names too.
+pub fn wipe_path(path: &Path, is_dir: bool, progress: &Progress) -> Result<(), Error> {
+    let error = |source| Error::Delete { path: path.to_path_buf(), source };
+    let root = paths::extended(path);
+    let metadata = fs::symlink_metadata(&root).map_err(error)?;
+    if metadata.file_type().is_symlink() {
+        // Folder links on Windows can only be removed as folders.
+        return fs::remove_file(&root)
+            .or_else(|e| if is_dir { fs::remove_dir(&root) } else { Err(e) })
+            .map_err(error);
+    }
+    if !metadata.is_dir() {
+        if metadata.is_file() {
+            refuse_hard_links(path, &metadata)?;
+            overwrite_file(&root, progress)?;
+        }
+        return fs::remove_file(&root).map_err(error);
+    }
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in WalkDir::new(&root).follow_links(false).follow_root_links(false) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            refuse_hard_links(entry.path(), &entry.metadata()?)?;
+            files.push(entry.into_path());
+        }
+    }
+    for file in &files {
+        overwrite_file(file, progress)?;
+    }
+    fs::remove_dir_all(&root).map_err(error)
+}
+
+#[cfg(unix)]
+fn refuse_hard_links(path: &Path, metadata: &Metadata) -> Result<(), Error> {
+    use std::os::unix::fs::MetadataExt;
+    if metadata.nlink() > 1 {
+        return Err(Error::Other(format!(
+            "{} has other hard links; wiping it would destroy their data too",
+            paths::display_path(path)
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn refuse_hard_links(_path: &Path, _metadata: &Metadata) -> Result<(), Error> {
+    Ok(())
+}
+
+fn overwrite_file(path: &Path, progress: &Progress) -> Result<(), Error> {
+    let error = |source| Error::Write { path: path.to_path_buf(), source };
+    let mut options = OpenOptions::new();
+    options.write(true);
+    // The walk saw a regular file; don't follow a link swapped in since then.
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_NOFOLLOW);
+    let mut file = options.open(path).map_err(error)?;
+    let len = file.metadata().map_err(error)?.len();
+    file.seek(SeekFrom::Start(0)).map_err(error)?;
+
+    let zeros = vec![0u8; CHUNK_SIZE];
+    let mut remaining = len;
+    while remaining > 0 {
+        progress.checkpoint()?;
+        let chunk = remaining.min(CHUNK_SIZE as u64) as usize;
+        file.write_all(&zeros[..chunk]).map_err(error)?;
+        remaining -= chunk as u64;
+        progress.advance(chunk as u64);
+    }
+    file.sync_all().map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn wiping_links_leaves_their_targets_alone() {
+        use std::os::unix::fs::symlink;
+
+        let dir = std::env::temp_dir().join(format!("disk-analyzer-wipe-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let outside = dir.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("file.txt"), "keep").unwrap();
+        let wiped = dir.join("wiped");
+        fs::create_dir_all(&wiped).unwrap();
+        symlink(outside.join("file.txt"), wiped.join("file link")).unwrap();
+        symlink(&outside, wiped.join("folder link")).unwrap();
+        symlink(outside.join("file.txt"), dir.join("file link")).unwrap();
+        symlink(&outside, dir.join("folder link")).unwrap();
+
+        let progress = Progress::default();
+        wipe_path(&dir.join("file link"), false, &progress).unwrap();
+        wipe_path(&dir.join("folder link"), true, &progress).unwrap();
+        wipe_path(&wiped, true, &progress).unwrap();
+
+        assert!(!wiped.exists());
+        assert!(fs::symlink_metadata(dir.join("file link")).is_err());
+        assert!(fs::symlink_metadata(dir.join("folder link")).is_err());
+        assert_eq!(fs::read_to_string(outside.join("file.txt")).unwrap(), "keep");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}